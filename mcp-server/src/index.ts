@@ -8,7 +8,7 @@
  * Tool naming philosophy:
  * - SESSION tools: aspy_stats, aspy_events, aspy_window, aspy_sessions
  * - MEMORY tools: aspy_recall (primary), aspy_recall_* (specialized)
- * - LIFETIME tools: aspy_lifetime, aspy_embeddings
+ * - LIFETIME tools: aspy_lifetime, aspy_files, aspy_embeddings
  */
 
 import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
@@ -276,6 +276,37 @@ interface LifetimeStats {
   by_tool: ToolStats[];
 }
 
+interface FileStats {
+  path: string;
+  reads: number;
+  edits: number;
+  writes: number;
+  searches: number;
+  total: number;
+  sessions: number;
+  last_touched: string;
+}
+
+interface FileSessionActivity {
+  session_id: string | null;
+  user_id: string | null;
+  first_touched: string;
+  last_touched: string;
+  reads: number;
+  edits: number;
+  writes: number;
+  paths: string[];
+}
+
+interface RepeatedRead {
+  session_id: string;
+  path: string;
+  reads: number;
+  redundant_reads: number;
+  modifications: number;
+  last_read: string | null;
+}
+
 interface EmbeddingStatusResponse {
   [key: string]: unknown;
   enabled: boolean;
@@ -1061,6 +1092,122 @@ server.registerTool(
   }
 );
 
+// Tool: aspy_files - File activity across sessions
+server.registerTool(
+  "aspy_files",
+  {
+    title: "File Activity",
+    description:
+      "Which files you read/edit most, which past sessions touched a file, and files re-read without changes (wasted context). Pass `path` to see sessions that touched that file.",
+    inputSchema: {
+      path: z
+        .string()
+        .optional()
+        .describe("File path or suffix (e.g. 'src/main.rs') to list sessions that touched it"),
+      days: z
+        .number()
+        .min(1)
+        .max(365)
+        .default(7)
+        .describe("Only include the last N days"),
+      op: z
+        .enum(["read", "edit", "write", "search"])
+        .optional()
+        .describe("Only count one operation"),
+      limit: z.number().min(1).max(100).default(10).describe("Maximum results per list"),
+    },
+  },
+  async ({ path, days = 7, op, limit = 10 }) => {
+    const userId = getUserId();
+    if (!userId) {
+      return {
+        content: [
+          {
+            type: "text" as const,
+            text: "Error: Cannot determine user identity. Ensure ANTHROPIC_API_KEY is set.",
+          },
+        ],
+        isError: true,
+      };
+    }
+
+    if (path) {
+      const params = new URLSearchParams({ path, user: userId, limit: String(limit) });
+      const result = await fetchApi<FileSessionActivity[]>(
+        `/api/lifestats/files/sessions?${params}`
+      );
+      if (!result.ok) {
+        return {
+          content: [{ type: "text" as const, text: `Error: ${result.error.error}` }],
+          isError: true,
+        };
+      }
+
+      const lines = [`📁 **Sessions that touched ${path}**\n`];
+      if (result.data.length === 0) {
+        lines.push("No recorded activity for this file.");
+      }
+      for (const s of result.data) {
+        lines.push(
+          `- ${s.session_id ?? "(unknown session)"}: ${s.reads} reads, ${s.edits} edits, ${s.writes} writes (last ${s.last_touched.split("T")[0]})`
+        );
+      }
+
+      return {
+        content: [
+          { type: "text" as const, text: lines.join("\n") },
+          { type: "text" as const, text: JSON.stringify(result.data, null, 2) },
+        ],
+      };
+    }
+
+    const params = new URLSearchParams({
+      user: userId,
+      days: String(days),
+      limit: String(limit),
+    });
+    const topParams = new URLSearchParams(params);
+    if (op) {
+      topParams.set("op", op);
+    }
+
+    const [top, repeated] = await Promise.all([
+      fetchApi<FileStats[]>(`/api/lifestats/files/top?${topParams}`),
+      fetchApi<RepeatedRead[]>(`/api/lifestats/files/repeated-reads?${params}`),
+    ]);
+
+    if (!top.ok) {
+      return {
+        content: [{ type: "text" as const, text: `Error: ${top.error.error}` }],
+        isError: true,
+      };
+    }
+
+    const lines = [`📁 **File Activity (last ${days} days)**\n`];
+    for (const f of top.data) {
+      lines.push(
+        `- ${f.path}: ${f.reads} reads, ${f.edits} edits, ${f.writes} writes across ${f.sessions} sessions`
+      );
+    }
+
+    const repeatedReads = repeated.ok ? repeated.data : [];
+    if (repeatedReads.length > 0) {
+      lines.push("\n**Re-read without changes:**");
+      for (const r of repeatedReads) {
+        lines.push(`- ${r.path}: ${r.redundant_reads} redundant of ${r.reads} reads`);
+      }
+    }
+
+    const data = { top_files: top.data, repeated_reads: repeatedReads };
+    return {
+      content: [
+        { type: "text" as const, text: lines.join("\n") },
+        { type: "text" as const, text: JSON.stringify(data, null, 2) },
+      ],
+    };
+  }
+);
+
 // Tool: aspy_embeddings - Embedding indexer status
 server.registerTool(
   "aspy_embeddings",
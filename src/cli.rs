@@ -2,15 +2,19 @@
 //
 // Provides subcommands for configuration management:
 // - config --show: Display effective configuration
+// - config --check: Validate config file (non-zero exit on errors)
+// - config --schema: Print JSON Schema for config.toml
 // - config --reset: Regenerate config file with defaults
 // - config --edit: Open config file in $EDITOR
 // - config --update: Merge new defaults into existing config (with diff preview)
 // - config --init: Interactive setup wizard
 
-use crate::config::{Config, VERSION};
+use crate::config::{Config, PROJECT_CONFIG_FILE, VERSION};
+use crate::config_schema::{self, Severity};
 use crate::theme::list_bundled_themes;
 use clap::{Parser, Subcommand};
 use std::io::Write;
+use std::path::PathBuf;
 use std::process::Command;
 
 /// Aspy - Observability proxy for Claude Code
@@ -31,6 +35,10 @@ pub enum Commands {
         #[arg(long)]
         show: bool,
 
+        /// With --show: merge the .aspy.toml for this project directory
+        #[arg(long, value_name = "PATH", requires = "show")]
+        project: Option<PathBuf>,
+
         /// Reset config file to defaults
         #[arg(long)]
         reset: bool,
@@ -47,6 +55,14 @@ pub enum Commands {
         #[arg(long)]
         path: bool,
 
+        /// Validate config file (exits non-zero on errors)
+        #[arg(long)]
+        check: bool,
+
+        /// Print JSON Schema for config.toml (for editor autocompletion)
+        #[arg(long)]
+        schema: bool,
+
         /// Interactive setup wizard
         #[arg(long)]
         init: bool,
@@ -62,6 +78,48 @@ pub enum Commands {
         #[arg(long)]
         reindex: bool,
     },
+
+    /// Analyze tool call history
+    Tools {
+        /// Show failure patterns, rejections and slowest calls
+        #[arg(long)]
+        insights: bool,
+
+        /// Only include the last N days
+        #[arg(long)]
+        days: Option<u32>,
+
+        /// Only include sessions for this user ID
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Maximum entries per section
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Link sessions to the git commits they contributed to
+    GitLink {
+        /// Repository to scan (default: current directory)
+        #[arg(long)]
+        repo: Option<PathBuf>,
+
+        /// Only consider sessions and commits from the last N days
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+
+        /// Only include sessions for this user ID
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Show links without storing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Maximum commits to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
 }
 
 /// Handle CLI commands. Returns true if a command was handled (exit after).
@@ -71,16 +129,26 @@ pub fn handle_cli() -> bool {
     match cli.command {
         Some(Commands::Config {
             show,
+            project,
             reset,
             edit,
             update,
             path,
+            check,
+            schema,
             init,
         }) => {
             if path {
                 handle_config_path();
+            } else if check {
+                handle_config_check();
+            } else if schema {
+                handle_config_schema();
             } else if show {
-                handle_config_show();
+                match project {
+                    Some(dir) => handle_config_show_project(&dir),
+                    None => handle_config_show(),
+                }
             } else if reset {
                 handle_config_reset();
             } else if edit {
@@ -96,10 +164,13 @@ pub fn handle_cli() -> bool {
                 println!("Options:");
                 println!("  --init    Interactive setup wizard (recommended for first-time setup)");
                 println!("  --show    Display effective configuration");
+                println!("            (--project <PATH> merges that project's .aspy.toml)");
                 println!("  --edit    Open config file in $EDITOR");
                 println!("  --update  Update config structure (preserves values, shows diff)");
                 println!("  --reset   Reset config file to defaults");
                 println!("  --path    Show config file path");
+                println!("  --check   Validate config file (exit 1 on errors)");
+                println!("  --schema  Print JSON Schema for editor autocompletion");
             }
             true
         }
@@ -131,6 +202,38 @@ pub fn handle_cli() -> bool {
             }
             true
         }
+        Some(Commands::Tools {
+            insights,
+            days,
+            user,
+            limit,
+        }) => {
+            if insights {
+                handle_tools_insights(days, user.as_deref(), limit);
+            } else {
+                // No flag provided, show help
+                println!("Usage: aspy tools [OPTIONS]");
+                println!();
+                println!("Analyze tool calls recorded in the lifestats database.");
+                println!();
+                println!("Options:");
+                println!("  --insights   Show failure patterns, rejections and slowest calls");
+                println!("  --days <N>   Only include the last N days");
+                println!("  --user <ID>  Only include sessions for this user ID");
+                println!("  --limit <N>  Maximum entries per section (default: 10)");
+            }
+            true
+        }
+        Some(Commands::GitLink {
+            repo,
+            days,
+            user,
+            dry_run,
+            limit,
+        }) => {
+            handle_git_link(repo, days, user.as_deref(), dry_run, limit);
+            true
+        }
         None => false, // No subcommand, run normal proxy
     }
 }
@@ -145,6 +248,73 @@ fn handle_config_path() {
     }
 }
 
+/// Validate config.toml strictly, printing one line per problem
+fn handle_config_check() {
+    let Some(path) = Config::config_path() else {
+        eprintln!("Error: Could not determine config path");
+        std::process::exit(1);
+    };
+    if !path.exists() {
+        println!("No config file at {} (using defaults)", path.display());
+        return;
+    }
+    let source = match std::fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error: Failed to read {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let diagnostics = config_schema::check(&source);
+    // User presets are separate files; report the ones that won't load
+    let broken_presets: Vec<_> = crate::tui::preset::list_presets()
+        .into_iter()
+        .filter_map(|entry| Some((entry.path?, entry.error?)))
+        .collect();
+    let config_errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - config_errors;
+    let errors = config_errors + broken_presets.len();
+
+    for diagnostic in &diagnostics {
+        match diagnostic.line {
+            Some(line) => println!("{}:{}: {}", path.display(), line, diagnostic),
+            None => println!("{}: {}", path.display(), diagnostic),
+        }
+    }
+    for (preset_path, error) in &broken_presets {
+        println!("{}: error: {}", preset_path.display(), error);
+    }
+    if diagnostics.is_empty() && broken_presets.is_empty() {
+        println!("✓ {} is valid", path.display());
+    } else {
+        println!();
+        println!(
+            "{} error{}, {} warning{}",
+            errors,
+            if errors == 1 { "" } else { "s" },
+            warnings,
+            if warnings == 1 { "" } else { "s" }
+        );
+    }
+    if errors > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn handle_config_schema() {
+    match serde_json::to_string_pretty(&config_schema::json_schema()) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn handle_config_show() {
     let config = Config::from_env();
 
@@ -152,6 +322,7 @@ fn handle_config_show() {
     println!();
     println!("theme = {:?}", config.theme);
     println!("use_theme_background = {}", config.use_theme_background);
+    println!("mouse = {}", config.mouse);
     println!("context_limit = {}", config.context_limit);
     println!("bind_addr = {:?}", config.bind_addr.to_string());
     println!("log_dir = {:?}", config.log_dir.display().to_string());
@@ -167,6 +338,18 @@ fn handle_config_show() {
         "context_warning_thresholds = {:?}",
         config.augmentation.context_warning_thresholds
     );
+    println!();
+    println!("[tokens]");
+    println!("counter = {:?}", config.tokens.counter.as_str());
+    println!("count_tokens_api = {}", config.tokens.count_tokens_api);
+    println!("calibration = {}", config.tokens.calibration);
+    if !config.keybindings.is_empty() {
+        println!();
+        println!("[keybindings]");
+        for (action, keys) in &config.keybindings {
+            println!("{} = {:?}", action, keys);
+        }
+    }
 
     // Show source info
     println!();
@@ -179,6 +362,40 @@ fn handle_config_show() {
     }
 }
 
+/// Show the full merged config for a project directory
+///
+/// Printed as complete TOML since overlays can touch any section, though
+/// only per-request settings (transformers, augmentation) take effect.
+fn handle_config_show_project(dir: &std::path::Path) {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let Some(root) = Config::find_project_root(&dir) else {
+        eprintln!(
+            "No {} found in {} or its parents",
+            PROJECT_CONFIG_FILE,
+            dir.display()
+        );
+        std::process::exit(1);
+    };
+
+    let config = match Config::for_project(Config::load_file_value().as_ref(), &root) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!("# Effective configuration for {}", root.display());
+    println!("# (env > .aspy.toml > global file > defaults)");
+    println!("# Sources: {}", root.join(PROJECT_CONFIG_FILE).display());
+    if let Some(path) = Config::config_path().filter(|p| p.exists()) {
+        println!("#          {}", path.display());
+    }
+    println!("# Only [transformers] and [augmentation] are applied per project.");
+    println!();
+    print!("{}", config.to_toml());
+}
+
 fn handle_config_reset() {
     let Some(path) = Config::config_path() else {
         eprintln!("Error: Could not determine config path");
@@ -827,3 +1044,227 @@ fn handle_embeddings_reindex_db(config: &Config) {
         }
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Tools Commands
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn handle_tools_insights(days: Option<u32>, user: Option<&str>, limit: usize) {
+    let config = Config::from_env();
+
+    // Try API first (proxy holds the pooled connection), then fall back to the DB
+    if let Some(insights) = try_api_tool_insights(&config, days, user, limit) {
+        print_tool_insights(&insights, "Live");
+        return;
+    }
+
+    use crate::pipeline::lifestats_query::LifestatsQuery;
+
+    let db_path = &config.lifestats.db_path;
+    if !db_path.exists() {
+        eprintln!("Error: Database not found at {}", db_path.display());
+        eprintln!("Run aspy normally first to create the database.");
+        std::process::exit(1);
+    }
+
+    let insights =
+        LifestatsQuery::new(db_path).and_then(|q| q.get_tool_insights(user, days, limit));
+    match insights {
+        Ok(insights) => print_tool_insights(&insights, "Offline"),
+        Err(e) => {
+            eprintln!("Error querying tool insights: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Try to get insights from running proxy API
+fn try_api_tool_insights(
+    config: &Config,
+    days: Option<u32>,
+    user: Option<&str>,
+    limit: usize,
+) -> Option<crate::tool_analytics::ToolInsights> {
+    let url = format!("http://{}/api/lifestats/tools/insights", config.bind_addr);
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(2))
+        .build()
+        .ok()?;
+
+    let mut query: Vec<(&str, String)> = vec![("limit", limit.to_string())];
+    if let Some(days) = days {
+        query.push(("days", days.to_string()));
+    }
+    if let Some(user) = user {
+        query.push(("user", user.to_string()));
+    }
+
+    let response = client.get(&url).query(&query).send().ok()?;
+    if response.status().is_success() {
+        response.json().ok()
+    } else {
+        None
+    }
+}
+
+fn print_tool_insights(insights: &crate::tool_analytics::ToolInsights, source: &str) {
+    println!("Tool Insights ({})", source);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+    let failure_pct = if insights.calls_analyzed > 0 {
+        insights.failed_calls as f64 / insights.calls_analyzed as f64 * 100.0
+    } else {
+        0.0
+    };
+    println!("  Calls:      {}", insights.calls_analyzed);
+    println!(
+        "  Failed:     {} ({:.1}%), {} rejected by user",
+        insights.failed_calls, failure_pct, insights.rejected_calls
+    );
+    println!();
+
+    println!("  Repeated Failures");
+    println!("  ──────────────────────────────────────────────────────────────────────────");
+    if insights.repeated_failures.is_empty() {
+        println!("  (none)");
+    }
+    for f in &insights.repeated_failures {
+        println!("  {:>3}x {:<10} {}", f.attempts, f.tool_name, f.signature);
+        println!("       └ {}", f.last_error);
+    }
+    println!();
+
+    println!(
+        "  Edit Mismatches ({} total)",
+        insights.total_edit_mismatches
+    );
+    println!("  ──────────────────────────────────────────────────────────────────────────");
+    if insights.edit_mismatches.is_empty() {
+        println!("  (none)");
+    }
+    for m in &insights.edit_mismatches {
+        println!(
+            "  {:<10} {}",
+            m.kind.as_str(),
+            m.file_path.as_deref().unwrap_or("(unknown file)")
+        );
+        if let Some(old) = &m.old_string {
+            println!("       └ {}", old);
+        }
+    }
+    println!();
+
+    println!("  Bash Failures ({} total)", insights.total_bash_failures);
+    println!("  ──────────────────────────────────────────────────────────────────────────");
+    if insights.bash_failures.is_empty() {
+        println!("  (none)");
+    }
+    for b in &insights.bash_failures {
+        let code = b
+            .exit_code
+            .map(|c| format!("exit {}", c))
+            .unwrap_or_else(|| "error".to_string());
+        println!("  {:<10} {}", code, b.command);
+    }
+    println!();
+
+    println!("  Rejections by Tool");
+    println!("  ──────────────────────────────────────────────────────────────────────────");
+    if insights.rejections_by_tool.is_empty() {
+        println!("  (none)");
+    }
+    for r in &insights.rejections_by_tool {
+        println!(
+            "  {:<12} {:>4} of {:<5} ({:.1}%)",
+            r.tool_name, r.rejections, r.calls, r.rejection_rate_pct
+        );
+    }
+    println!();
+
+    println!("  Slowest Calls");
+    println!("  ──────────────────────────────────────────────────────────────────────────");
+    if insights.slowest_calls.is_empty() {
+        println!("  (none)");
+    }
+    for c in &insights.slowest_calls {
+        println!(
+            "  {:>8.1}s {:<10} {}",
+            c.duration_ms as f64 / 1000.0,
+            c.tool_name,
+            c.input
+        );
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Git Commands
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn handle_git_link(
+    repo: Option<PathBuf>,
+    days: u32,
+    user: Option<&str>,
+    dry_run: bool,
+    limit: usize,
+) {
+    let config = Config::from_env();
+
+    let db_path = &config.lifestats.db_path;
+    if !db_path.exists() {
+        eprintln!("Error: Database not found at {}", db_path.display());
+        eprintln!("Run aspy normally first to create the database.");
+        std::process::exit(1);
+    }
+
+    let repo = repo.unwrap_or_else(|| PathBuf::from("."));
+    match crate::git_link::link_repo(db_path, &repo, days, user, dry_run) {
+        Ok(summary) => print_git_link(&summary, dry_run, limit),
+        Err(e) => {
+            eprintln!("Error linking sessions to commits: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_git_link(summary: &crate::git_link::GitLinkSummary, dry_run: bool, limit: usize) {
+    let costs = crate::git_link::commit_costs(&summary.links);
+
+    println!("Git Link ({})", summary.repo_root.display());
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+    println!("  Sessions scanned: {}", summary.sessions_scanned);
+    println!("  Commits scanned:  {}", summary.commits_scanned);
+    println!(
+        "  Links:            {} ({} commits){}",
+        summary.links.len(),
+        costs.len(),
+        if dry_run { ", dry run: not stored" } else { "" }
+    );
+    println!();
+
+    println!("  Cost per Commit");
+    println!("  ──────────────────────────────────────────────────────────────────────────");
+    if costs.is_empty() {
+        println!("  (none)");
+    }
+    for c in costs.iter().take(limit) {
+        let hash: String = c.commit_hash.chars().take(8).collect();
+        let date = c.committed_at.get(..10).unwrap_or(&c.committed_at);
+        println!(
+            "  {} {} ${:>8.4} {:>2} sess  {}",
+            hash, date, c.cost_usd, c.sessions, c.subject
+        );
+    }
+
+    if !costs.is_empty() {
+        let total: f64 = costs.iter().map(|c| c.cost_usd).sum();
+        println!();
+        println!(
+            "  Total: ${:.4} across {} commits (avg ${:.4})",
+            total,
+            costs.len(),
+            total / costs.len() as f64
+        );
+    }
+}
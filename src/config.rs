@@ -2,17 +2,22 @@
 //
 // Configuration is loaded in order of precedence:
 // 1. Environment variables (highest priority)
-// 2. Config file (~/.config/aspy/config.toml)
-// 3. Built-in defaults (lowest priority)
+// 2. Project overlay (.aspy.toml in the project root, per-request decisions only)
+// 3. Config file (~/.config/aspy/config.toml)
+// 4. Built-in defaults (lowest priority)
 
-use serde::Deserialize;
-use std::collections::HashMap;
+use crate::tokens::CounterKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Version info
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Per-project overlay file, looked up from the project directory upwards
+pub const PROJECT_CONFIG_FILE: &str = ".aspy.toml";
+
 /// Feature flags for optional modules (opt-out: default enabled)
 #[derive(Debug, Clone)]
 pub struct Features {
@@ -247,6 +252,113 @@ pub struct Transformers {
 
     /// Compact enhancer configuration (enhances compaction prompts with session context)
     pub compact_enhancer: Option<crate::proxy::transformation::CompactEnhancerConfig>,
+
+    /// Context enricher configuration (injects past context from lifestats search)
+    pub context_enricher: Option<crate::proxy::transformation::ContextEnricherConfig>,
+}
+
+/// Response transformation settings
+///
+/// Response transformers rewrite API responses in flight (streamed or buffered):
+/// redacting text, blocking or rewriting tool calls, stripping thinking blocks.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResponseTransformers {
+    /// Master kill-switch; when false no response transformers run
+    pub enabled: bool,
+
+    /// Redactor configuration (masks regex matches in assistant text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redactor: Option<crate::proxy::response_transformation::RedactorConfig>,
+
+    /// Tool guard configuration (blocks or rewrites tool calls by input pattern)
+    #[serde(rename = "tool-guard", skip_serializing_if = "Option::is_none")]
+    pub tool_guard: Option<crate::proxy::response_transformation::ToolGuardConfig>,
+
+    /// Thinking stripper configuration (removes thinking blocks)
+    #[serde(rename = "thinking-stripper", skip_serializing_if = "Option::is_none")]
+    pub thinking_stripper: Option<crate::proxy::response_transformation::ThinkingStripperConfig>,
+
+    /// Tool annotator configuration (inserts a note before tool calls)
+    #[serde(rename = "tool-annotator", skip_serializing_if = "Option::is_none")]
+    pub tool_annotator: Option<crate::proxy::response_transformation::ToolAnnotatorConfig>,
+}
+
+/// Token counting settings
+///
+/// Controls which counter sizes transformer/augmenter injections and whether
+/// local estimates are calibrated against actual API usage.
+#[derive(Debug, Clone)]
+pub struct TokensConfig {
+    /// Counter for local estimates: heuristic (default) or bpe
+    pub counter: CounterKind,
+
+    /// Fetch exact counts from upstream /v1/messages/count_tokens in the background
+    /// Costs one extra API call per request, so opt-in.
+    pub count_tokens_api: bool,
+
+    /// Compare estimates with actual ApiUsage and track drift
+    pub calibration: bool,
+
+    /// Number of count_tokens results to cache
+    pub cache_size: usize,
+}
+
+impl Default for TokensConfig {
+    fn default() -> Self {
+        Self {
+            counter: CounterKind::Heuristic,
+            count_tokens_api: false, // Opt-in (extra API call per request)
+            calibration: false,      // Opt-in
+            cache_size: 256,
+        }
+    }
+}
+
+/// Session handoff document settings
+///
+/// Handoff docs summarize a session from lifestats (goals, files, TODOs,
+/// decisions, errors) so the next session - or the post-compaction Claude -
+/// can pick up where the last one stopped. Requires lifestats.
+#[derive(Debug, Clone)]
+pub struct HandoffConfig {
+    /// Whether handoff docs are generated
+    pub enabled: bool,
+    /// Directory handoff docs are written to (one subdirectory per user)
+    pub dir: PathBuf,
+    /// Append the current session's handoff to compaction prompts
+    /// (requires the compact-enhancer transformer)
+    pub inject_on_compact: bool,
+}
+
+impl Default for HandoffConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Opt-in feature
+            dir: PathBuf::from("./data/handoffs"),
+            inject_on_compact: true,
+        }
+    }
+}
+
+/// Image and document attachment capture settings
+///
+/// Base64 `image`/`document` blocks are written once to a content-addressed
+/// store and replaced in logs and lifestats with a small reference.
+#[derive(Debug, Clone)]
+pub struct AttachmentsConfig {
+    /// Whether attachments are extracted from logged requests
+    pub enabled: bool,
+    /// Directory of the content-addressed store (files named by SHA-256)
+    pub dir: PathBuf,
+}
+
+impl Default for AttachmentsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: PathBuf::from("./data/attachments"),
+        }
+    }
 }
 
 /// Lifetime statistics storage configuration
@@ -260,6 +372,12 @@ pub struct LifestatsConfig {
     pub store_thinking: bool,
     /// Whether to store full tool inputs/outputs
     pub store_tool_io: bool,
+    /// Whether to record file paths touched by tool calls
+    pub track_file_activity: bool,
+    /// Whether to record the todo timeline from TodoWrite calls
+    pub track_todos: bool,
+    /// Whether to record the working directory and git branch from the system prompt
+    pub capture_git_context: bool,
     /// Maximum thinking block size to store (bytes)
     pub max_thinking_size: usize,
     /// Retention period in days (0 = forever)
@@ -279,6 +397,9 @@ impl Default for LifestatsConfig {
             db_path: PathBuf::from("./data/lifestats.db"),
             store_thinking: true,
             store_tool_io: true,
+            track_file_activity: true,
+            track_todos: true,
+            capture_git_context: false, // Opt-in: records cwd and branch
             max_thinking_size: 100_000, // ~100KB per thinking block
             retention_days: 90,
             channel_buffer: 10_000, // Buffer before backpressure
@@ -604,9 +725,15 @@ pub struct Config {
     /// Use theme's background color (true) or terminal's default (false)
     pub use_theme_background: bool,
 
+    /// Mouse capture in the TUI (off lets the terminal select text)
+    pub mouse: bool,
+
     /// Layout preset name: "classic", "reasoning", "debug"
     pub preset: String,
 
+    /// Key overrides per TUI action ([keybindings] section, see tui::keymap)
+    pub keybindings: BTreeMap<String, Vec<String>>,
+
     /// Feature flags for optional modules
     pub features: Features,
 
@@ -628,13 +755,48 @@ pub struct Config {
     /// Request transformation settings
     pub transformers: Transformers,
 
+    /// Response transformation settings
+    pub response_transformers: ResponseTransformers,
+
+    /// Token counting and calibration settings
+    pub tokens: TokensConfig,
+
+    /// Session handoff document settings
+    pub handoff: HandoffConfig,
+
+    /// Attachment capture settings
+    pub attachments: AttachmentsConfig,
+
     /// OpenTelemetry export configuration
     pub otel: OtelConfig,
 
+    /// Alert rules ([[alerts]] sections)
+    pub alerts: Vec<crate::pipeline::alerts::AlertRule>,
+
+    /// Tool-use policy ([policy] section)
+    pub policy: crate::proxy::policy::PolicyConfig,
+
     /// Client and provider configuration for multi-user routing
     pub clients: ClientsConfig,
 }
 
+/// Keys for one action in [keybindings]: `"x"` or `["x", "ctrl+x"]`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FileKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<FileKeys> for Vec<String> {
+    fn from(keys: FileKeys) -> Self {
+        match keys {
+            FileKeys::One(key) => vec![key],
+            FileKeys::Many(keys) => keys,
+        }
+    }
+}
+
 /// Feature flags as loaded from config file
 #[derive(Debug, Deserialize, Default)]
 struct FileFeatures {
@@ -666,6 +828,9 @@ struct FileLifestatsConfig {
     db_path: Option<String>,
     store_thinking: Option<bool>,
     store_tool_io: Option<bool>,
+    track_file_activity: Option<bool>,
+    track_todos: Option<bool>,
+    capture_git_context: Option<bool>,
     max_thinking_size: Option<usize>,
     retention_days: Option<u32>,
     channel_buffer: Option<usize>,
@@ -704,6 +869,44 @@ struct FileTransformers {
     tag_editor: Option<crate::proxy::transformation::TagEditorConfig>,
     #[serde(rename = "compact-enhancer")]
     compact_enhancer: Option<crate::proxy::transformation::CompactEnhancerConfig>,
+    #[serde(rename = "context-enricher")]
+    context_enricher: Option<crate::proxy::transformation::ContextEnricherConfig>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct FileResponseTransformers {
+    enabled: Option<bool>,
+    redactor: Option<crate::proxy::response_transformation::RedactorConfig>,
+    #[serde(rename = "tool-guard")]
+    tool_guard: Option<crate::proxy::response_transformation::ToolGuardConfig>,
+    #[serde(rename = "thinking-stripper")]
+    thinking_stripper: Option<crate::proxy::response_transformation::ThinkingStripperConfig>,
+    #[serde(rename = "tool-annotator")]
+    tool_annotator: Option<crate::proxy::response_transformation::ToolAnnotatorConfig>,
+}
+
+/// Handoff config as loaded from file
+#[derive(Debug, Deserialize, Default)]
+struct FileHandoffConfig {
+    enabled: Option<bool>,
+    dir: Option<String>,
+    inject_on_compact: Option<bool>,
+}
+
+/// Attachment capture config as loaded from file
+#[derive(Debug, Deserialize, Default)]
+struct FileAttachmentsConfig {
+    enabled: Option<bool>,
+    dir: Option<String>,
+}
+
+/// Token counting config as loaded from file
+#[derive(Debug, Deserialize, Default)]
+struct FileTokensConfig {
+    counter: Option<String>,
+    count_tokens_api: Option<bool>,
+    calibration: Option<bool>,
+    cache_size: Option<usize>,
 }
 
 /// OpenTelemetry config as loaded from file
@@ -724,8 +927,13 @@ struct FileConfig {
     log_dir: Option<String>,
     theme: Option<String>,
     use_theme_background: Option<bool>,
+    mouse: Option<bool>,
     preset: Option<String>,
 
+    /// Optional [keybindings] section
+    #[serde(default)]
+    keybindings: BTreeMap<String, FileKeys>,
+
     /// Optional [features] section
     features: Option<FileFeatures>,
 
@@ -747,9 +955,27 @@ struct FileConfig {
     /// Optional [transformers] section
     transformers: Option<FileTransformers>,
 
+    /// Optional [response_transformers] section
+    response_transformers: Option<FileResponseTransformers>,
+
+    /// Optional [tokens] section
+    tokens: Option<FileTokensConfig>,
+
+    /// Optional [handoff] section
+    handoff: Option<FileHandoffConfig>,
+    /// Optional [attachments] section
+    attachments: Option<FileAttachmentsConfig>,
+
     /// Optional [otel] section (OpenTelemetry export)
     otel: Option<FileOtelConfig>,
 
+    /// Optional [[alerts]] rules
+    #[serde(default)]
+    alerts: Vec<crate::pipeline::alerts::AlertRule>,
+
+    /// Optional [policy] section
+    policy: Option<crate::proxy::policy::PolicyConfig>,
+
     /// Optional [clients.X] sections for multi-user routing
     #[serde(default)]
     clients: HashMap<String, ClientConfig>,
@@ -799,48 +1025,129 @@ impl Config {
     /// a broken config should fail fast with a clear error, not silently
     /// fall back to defaults while the user debugs the wrong thing.
     fn load_file_config() -> FileConfig {
-        let Some(path) = Self::config_path() else {
+        let Some(value) = Self::load_file_value() else {
             return FileConfig::default();
         };
+        match value.try_into() {
+            Ok(config) => config,
+            Err(e) => config_error(
+                "Failed to parse configuration file",
+                &Self::config_path().unwrap_or_default(),
+                &e,
+            ),
+        }
+    }
+
+    /// Load the global config file as a raw TOML table (None if absent)
+    ///
+    /// Kept raw so project overlays can be merged in before parsing.
+    ///
+    /// # Panics
+    /// If the file exists but is not valid TOML or cannot be read.
+    pub(crate) fn load_file_value() -> Option<toml::Value> {
+        let path = Self::config_path()?;
 
         match std::fs::read_to_string(&path) {
-            Ok(contents) => {
-                match toml::from_str(&contents) {
-                    Ok(config) => config,
-                    Err(e) => {
-                        // Fatal error - config exists but is invalid
-                        // Print a clear, actionable error message
-                        eprintln!(
-                            "\n╔══════════════════════════════════════════════════════════════╗"
-                        );
-                        eprintln!(
-                            "║  CONFIG ERROR - Failed to parse configuration file          ║"
-                        );
-                        eprintln!(
-                            "╚══════════════════════════════════════════════════════════════╝\n"
-                        );
-                        eprintln!("  File: {}\n", path.display());
-                        eprintln!("  Error: {}\n", e);
-                        eprintln!("  Tip: Run `aspy config --show` to validate your config");
-                        eprintln!("       Or delete the file to regenerate defaults\n");
-                        std::process::exit(1);
+            Ok(contents) => match toml::from_str(&contents) {
+                Ok(value) => Some(value),
+                // Fatal error - config exists but is invalid
+                Err(e) => config_error("Failed to parse configuration file", &path, &e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                // File doesn't exist - that's fine, use defaults
+                None
+            }
+            // File exists but can't be read (permissions, etc.)
+            Err(e) => config_error("Cannot read configuration file", &path, &e),
+        }
+    }
+
+    /// Serialize key overrides to a [keybindings] section
+    fn keybindings_to_toml(&self) -> String {
+        if self.keybindings.is_empty() {
+            // Show example comments when nothing is rebound
+            return r#"
+# [keybindings]
+# copy_jsonl = "J"
+# toggle_zoom = ["z", "ctrl+z"]
+# view_history = []
+"#
+            .to_string();
+        }
+
+        let mut output = String::from("\n[keybindings]\n");
+        for (action, keys) in &self.keybindings {
+            output.push_str(&format!("{} = {:?}\n", action, keys));
+        }
+        output
+    }
+
+    /// Serialize alert rules to [[alerts]] sections
+    fn alerts_to_toml(&self) -> String {
+        use crate::pipeline::alerts::AlertCondition;
+
+        if self.alerts.is_empty() {
+            // Show example comments when no alerts configured
+            return r#"
+# [[alerts]]
+# name = "Context filling up"
+# type = "context_percent"
+# above = 85
+# sinks = ["toast", "bell"]
+# cooldown_secs = 600
+#
+# [[alerts]]
+# name = "Spend"
+# type = "cost_per_hour"
+# above_usd = 5.0
+# sinks = ["command"]
+# command = "notify-send 'aspy' \"$(jq -r .message)\""
+"#
+            .to_string();
+        }
+
+        let mut output = String::from("\n");
+        for rule in &self.alerts {
+            output.push_str("[[alerts]]\n");
+            output.push_str(&format!("name = {:?}\n", rule.name));
+            output.push_str(&format!("type = \"{}\"\n", rule.condition.as_str()));
+            match &rule.condition {
+                AlertCondition::ErrorStatus { min_status } => {
+                    output.push_str(&format!("min_status = {}\n", min_status));
+                }
+                AlertCondition::ContextPercent { above } => {
+                    output.push_str(&format!("above = {:?}\n", above));
+                }
+                AlertCondition::CostPerHour { above_usd } => {
+                    output.push_str(&format!("above_usd = {:?}\n", above_usd));
+                }
+                AlertCondition::ToolFailureStreak { count } => {
+                    output.push_str(&format!("count = {}\n", count));
+                }
+                AlertCondition::RateLimitRemaining {
+                    requests_below,
+                    tokens_below,
+                } => {
+                    if let Some(n) = requests_below {
+                        output.push_str(&format!("requests_below = {}\n", n));
+                    }
+                    if let Some(n) = tokens_below {
+                        output.push_str(&format!("tokens_below = {}\n", n));
                     }
                 }
             }
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                // File doesn't exist - that's fine, use defaults
-                FileConfig::default()
+            let sinks: Vec<&str> = rule.sinks.iter().map(|s| s.as_str()).collect();
+            output.push_str(&format!("sinks = {:?}\n", sinks));
+            if let Some(command) = &rule.command {
+                output.push_str(&format!("command = {:?}\n", command));
             }
-            Err(e) => {
-                // File exists but can't be read (permissions, etc.)
-                eprintln!("\n╔══════════════════════════════════════════════════════════════╗");
-                eprintln!("║  CONFIG ERROR - Cannot read configuration file              ║");
-                eprintln!("╚══════════════════════════════════════════════════════════════╝\n");
-                eprintln!("  File: {}\n", path.display());
-                eprintln!("  Error: {}\n", e);
-                std::process::exit(1);
+            if let Some(url) = &rule.webhook_url {
+                output.push_str(&format!("webhook_url = {:?}\n", url));
             }
+            output.push_str(&format!("cooldown_secs = {}\n", rule.cooldown_secs));
+            output.push('\n');
         }
+        output
     }
 
     /// Serialize clients HashMap to TOML sections
@@ -1063,9 +1370,97 @@ enabled = true
             }
         }
 
+        // Serialize context-enricher if configured
+        if let Some(ref enricher) = self.transformers.context_enricher {
+            output.push_str(&format!(
+                r#"
+# ─────────────────────────────────────────────────────────────────────────────
+# CONTEXT ENRICHER
+# ─────────────────────────────────────────────────────────────────────────────
+# Searches lifestats for the prompt and injects past context as <aspy-context>.
+
+[transformers.context-enricher]
+enabled = {}
+top_k = {}
+max_tokens = {}
+first_turn = {}
+every_turns = {}
+min_prompt_chars = {}
+"#,
+                enricher.enabled,
+                enricher.top_k,
+                enricher.max_tokens,
+                enricher.first_turn,
+                enricher.every_turns,
+                enricher.min_prompt_chars,
+            ));
+            if !enricher.patterns.is_empty() {
+                let patterns: Vec<String> = enricher
+                    .patterns
+                    .iter()
+                    .map(|p| toml::Value::String(p.clone()).to_string())
+                    .collect();
+                output.push_str(&format!("patterns = [{}]\n", patterns.join(", ")));
+            }
+        }
+
         output
     }
 
+    /// Serialize response transformers to a [response_transformers] section
+    fn response_transformers_to_toml(&self) -> String {
+        #[derive(Serialize)]
+        struct Section<'a> {
+            response_transformers: &'a ResponseTransformers,
+        }
+
+        toml::to_string(&Section {
+            response_transformers: &self.response_transformers,
+        })
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to serialize response transformers: {}", e);
+            "[response_transformers]\nenabled = false\n".to_string()
+        })
+    }
+
+    /// Serialize policy to a [policy] section (commented example when empty)
+    fn policy_to_toml(&self) -> String {
+        #[derive(Serialize)]
+        struct Section<'a> {
+            policy: &'a crate::proxy::policy::PolicyConfig,
+        }
+
+        if !self.policy.enabled && self.policy.rules.is_empty() {
+            return r#"# [policy]
+# enabled = true
+# mode = "rewrite"
+# default = "allow"
+#
+# [[policy.rules]]
+# name = "no-recursive-delete"
+# effect = "deny"
+# tools = ["Bash"]
+# commands = ["rm\\s+-rf\\s+/"]
+#
+# [[policy.rules]]
+# name = "secrets"
+# effect = "deny"
+# tools = ["Read", "Edit", "Write"]
+# paths = ["**/.env", "~/.ssh/**"]
+# mode = "remind"
+"#
+            .to_string();
+        }
+
+        toml::to_string(&Section {
+            policy: &self.policy,
+        })
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to serialize policy: {}", e);
+            "[policy]\nenabled = false\n".to_string()
+        })
+    }
+
     /// Serialize config to TOML string (single source of truth for format)
     pub fn to_toml(&self) -> String {
         format!(
@@ -1078,7 +1473,10 @@ theme = "{theme}"
 # Use theme's background color (true) or terminal's default (false)
 use_theme_background = {use_bg}
 
-# Layout preset: classic, reasoning, debug
+# Mouse support (enabling it disables the terminal's own text selection)
+mouse = {mouse}
+
+# Layout preset: classic, reasoning, debug (or a file in ~/.config/aspy/presets)
 preset = "{preset}"
 
 # Context window limit for the gauge
@@ -1101,6 +1499,13 @@ stats = {stats}
 context_warning = {ctx_warn}
 context_warning_thresholds = {thresholds:?}
 
+# Token counting (sizes transformer/augmenter injections)
+[tokens]
+counter = "{tokens_counter}"  # heuristic, bpe
+count_tokens_api = {tokens_count_api}  # Exact counts via /v1/messages/count_tokens (extra API call per request)
+calibration = {tokens_calibration}  # Compare estimates with actual usage (GET /api/tokens/calibration)
+cache_size = {tokens_cache_size}
+
 # Logging configuration (RUST_LOG env var overrides)
 [logging]
 level = "{log_level}"
@@ -1116,12 +1521,28 @@ enabled = {lifestats_enabled}
 db_path = "{lifestats_db_path}"
 store_thinking = {lifestats_store_thinking}
 store_tool_io = {lifestats_store_tool_io}
+track_file_activity = {lifestats_track_file_activity}
+track_todos = {lifestats_track_todos}
+capture_git_context = {lifestats_capture_git_context}  # Record working directory and git branch from the system prompt
 max_thinking_size = {lifestats_max_thinking_size}
 retention_days = {lifestats_retention_days}
 channel_buffer = {lifestats_channel_buffer}
 batch_size = {lifestats_batch_size}
 flush_interval_secs = {lifestats_flush_interval_secs}
 
+# Session handoff docs (requires lifestats): written on /api/session/end and
+# context compaction, served by GET /api/session/handoff/<user_id>
+[handoff]
+enabled = {handoff_enabled}
+dir = "{handoff_dir}"
+inject_on_compact = {handoff_inject}  # Append to compaction prompts (needs compact-enhancer)
+
+# Image/document attachments: base64 blocks are stored once by SHA-256 and
+# replaced with references in session logs and lifestats
+[attachments]
+enabled = {attachments_enabled}
+dir = "{attachments_dir}"
+
 # ─────────────────────────────────────────────────────────────────────────────
 # SEMANTIC SEARCH EMBEDDINGS (Optional)
 # ─────────────────────────────────────────────────────────────────────────────
@@ -1192,8 +1613,47 @@ enabled = {transformers_enabled}
 # Compaction Enhancer - inject continuity guidance when Claude Code runs /compact
 # [transformers.compact-enhancer]
 # enabled = true
+#
+# Context Enricher - inject relevant past context from lifestats (requires lifestats)
+# [transformers.context-enricher]
+# enabled = true
+# top_k = 5             # Matches to retrieve
+# max_tokens = 1500     # Budget for the <aspy-context> block
+# first_turn = true     # Enrich the first prompt of each session
+# every_turns = 0       # Also every N prompts (0 = never)
+# patterns = ["(?i)last time|previously|remember when"]
 {transformers_section}
 # ─────────────────────────────────────────────────────────────────────────────
+# RESPONSE TRANSFORMERS (Optional)
+# ─────────────────────────────────────────────────────────────────────────────
+# Rewrite API responses in flight (streaming and buffered). Aspy still records
+# the original response; only the client sees the transformed one.
+#
+# Example: Mask secrets in assistant text
+# [response_transformers.redactor]
+# enabled = true
+# patterns = ["sk-ant-[A-Za-z0-9_-]+"]
+# replacement = "[REDACTED]"
+#
+# Example: Turn destructive Bash calls into a text notice (action: block, rewrite)
+# [response_transformers.tool-guard]
+# enabled = true
+# [[response_transformers.tool-guard.rules]]
+# tool = "Bash"
+# field = "command"
+# pattern = "rm\\s+-rf"
+# action = "block"
+#
+# Example: Remove thinking blocks / note selected tool calls
+# [response_transformers.thinking-stripper]
+# enabled = true
+# [response_transformers.tool-annotator]
+# enabled = true
+# tools = ["Bash"]
+# template = "▸ {{tool}}: {{summary}}"
+
+{response_transformers_section}
+# ─────────────────────────────────────────────────────────────────────────────
 # OPENTELEMETRY EXPORT (Optional)
 # ─────────────────────────────────────────────────────────────────────────────
 # Export telemetry to Azure Application Insights or other OTel-compatible backends.
@@ -1206,6 +1666,29 @@ enabled = {otel_enabled}
 {otel_connection_string}service_name = "{otel_service_name}"
 service_version = "{otel_service_version}"
 
+# ─────────────────────────────────────────────────────────────────────────────
+# KEYBINDINGS (Optional)
+# ─────────────────────────────────────────────────────────────────────────────
+# Rebind TUI actions: action = "key" or ["key", ...]; [] unbinds. Keys are
+# characters (case-sensitive), named keys (Enter, Esc, Tab, Up, PageDown, F1)
+# or chords (ctrl+d, alt+Enter). Action names are in docs/views.md; conflicts
+# are reported at startup and by `aspy config --check`.
+{keybindings_section}
+# ─────────────────────────────────────────────────────────────────────────────
+# ALERTS (Optional)
+# ─────────────────────────────────────────────────────────────────────────────
+# Rules match events and notify sinks: toast, bell, command (alert JSON on
+# stdin), webhook (HTTP POST). Types: error_status, context_percent,
+# cost_per_hour, tool_failure_streak, rate_limit_remaining.
+{alerts_section}
+# ─────────────────────────────────────────────────────────────────────────────
+# TOOL-USE POLICY (Optional)
+# ─────────────────────────────────────────────────────────────────────────────
+# Allow/deny tool calls by name, path globs (Read/Edit/Write), command regexes
+# (Bash) and domains (WebFetch). First matching rule wins. Denials are enforced
+# per mode: rewrite (replace the call with an explanation), remind (inject a
+# system-reminder on the next request) or audit (record only).
+{policy_section}
 # ─────────────────────────────────────────────────────────────────────────────
 # MULTI-CLIENT ROUTING (Optional)
 # ─────────────────────────────────────────────────────────────────────────────
@@ -1222,6 +1705,7 @@ service_version = "{otel_service_version}"
 "#,
             theme = self.theme,
             use_bg = self.use_theme_background,
+            mouse = self.mouse,
             preset = self.preset,
             limit = self.context_limit,
             bind = self.bind_addr,
@@ -1231,6 +1715,15 @@ service_version = "{otel_service_version}"
             stats = self.features.stats,
             ctx_warn = self.augmentation.context_warning,
             thresholds = self.augmentation.context_warning_thresholds,
+            handoff_enabled = self.handoff.enabled,
+            handoff_dir = self.handoff.dir.display(),
+            handoff_inject = self.handoff.inject_on_compact,
+            attachments_enabled = self.attachments.enabled,
+            attachments_dir = self.attachments.dir.display(),
+            tokens_counter = self.tokens.counter.as_str(),
+            tokens_count_api = self.tokens.count_tokens_api,
+            tokens_calibration = self.tokens.calibration,
+            tokens_cache_size = self.tokens.cache_size,
             log_level = self.logging.level,
             log_file_enabled = self.logging.file_enabled,
             log_file_dir = self.logging.file_dir.display(),
@@ -1240,6 +1733,9 @@ service_version = "{otel_service_version}"
             lifestats_db_path = self.lifestats.db_path.display(),
             lifestats_store_thinking = self.lifestats.store_thinking,
             lifestats_store_tool_io = self.lifestats.store_tool_io,
+            lifestats_track_file_activity = self.lifestats.track_file_activity,
+            lifestats_track_todos = self.lifestats.track_todos,
+            lifestats_capture_git_context = self.lifestats.capture_git_context,
             lifestats_max_thinking_size = self.lifestats.max_thinking_size,
             lifestats_retention_days = self.lifestats.retention_days,
             lifestats_channel_buffer = self.lifestats.channel_buffer,
@@ -1288,6 +1784,7 @@ service_version = "{otel_service_version}"
             embed_max_content = self.embeddings.max_content_length,
             transformers_enabled = self.transformers.enabled,
             transformers_section = self.transformers_to_toml(),
+            response_transformers_section = self.response_transformers_to_toml(),
             otel_enabled = self.otel.enabled,
             otel_connection_string = self
                 .otel
@@ -1300,6 +1797,9 @@ service_version = "{otel_service_version}"
                 }),
             otel_service_name = self.otel.service_name,
             otel_service_version = self.otel.service_version,
+            keybindings_section = self.keybindings_to_toml(),
+            alerts_section = self.alerts_to_toml(),
+            policy_section = self.policy_to_toml(),
             clients_section = self.clients_to_toml(),
             providers_section = self.providers_to_toml(),
         )
@@ -1322,10 +1822,85 @@ service_version = "{otel_service_version}"
         std::fs::write(&path, self.to_toml())
     }
 
+    /// Find the project root for a directory: the nearest ancestor with a `.aspy.toml`
+    pub fn find_project_root(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .find(|d| d.join(PROJECT_CONFIG_FILE).is_file())
+            .map(Path::to_path_buf)
+    }
+
+    /// Load configuration for a project: the global file with the project's
+    /// `.aspy.toml` merged over it (tables merge key by key, everything else
+    /// - including arrays like tag-editor rules - is replaced)
+    ///
+    /// `global` is the raw global file (see [`Self::load_file_value`]). Unlike the
+    /// global file, a broken overlay is an error rather than a fatal exit: it
+    /// is loaded while the proxy is serving traffic.
+    pub fn for_project(global: Option<&toml::Value>, root: &Path) -> anyhow::Result<Self> {
+        use anyhow::Context;
+
+        let path = root.join(PROJECT_CONFIG_FILE);
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let overlay: toml::Value = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let mut merged = global
+            .cloned()
+            .unwrap_or_else(|| toml::Value::Table(Default::default()));
+        merge_toml(&mut merged, overlay);
+
+        let file: FileConfig = merged
+            .try_into()
+            .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+        Ok(Self::from_file(file))
+    }
+
+    /// Build configuration from config.toml contents, returning errors instead of exiting
+    ///
+    /// Used for hot reload, where a typo must not take down the proxy. `None`
+    /// means there is no config file. Also returns the raw file table (for
+    /// project overlays and change detection).
+    pub fn from_source(source: Option<&str>) -> anyhow::Result<(Self, Option<toml::Value>)> {
+        use anyhow::Context;
+
+        let raw = match source {
+            Some(source) => Some(toml::from_str::<toml::Value>(source).with_context(|| {
+                format!(
+                    "Failed to parse {}",
+                    Self::config_path().unwrap_or_default().display()
+                )
+            })?),
+            None => None,
+        };
+
+        let config = match &raw {
+            Some(value) => Self::from_value(value.clone())?,
+            None => Self::from_file(FileConfig::default()),
+        };
+        Ok((config, raw))
+    }
+
+    /// Build a config from a parsed config file (env overrides still apply)
+    pub fn from_value(value: toml::Value) -> anyhow::Result<Self> {
+        use anyhow::Context;
+
+        let file: FileConfig = value.try_into().context("Invalid configuration")?;
+        // from_file panics on a bad bind address (fatal at startup); check it first
+        if let Some(addr) = &file.bind_addr {
+            addr.parse::<SocketAddr>()
+                .with_context(|| format!("Invalid bind_addr: {}", addr))?;
+        }
+        Ok(Self::from_file(file))
+    }
+
     /// Load configuration: file -> env vars -> defaults
     pub fn from_env() -> Self {
-        let file = Self::load_file_config();
+        Self::from_file(Self::load_file_config())
+    }
 
+    /// Build configuration from a parsed file, applying env vars and defaults
+    fn from_file(file: FileConfig) -> Self {
         // Bind address: env > file > default
         let bind_addr = std::env::var("ASPY_BIND")
             .ok()
@@ -1373,9 +1948,19 @@ service_version = "{otel_service_version}"
         // Use theme background: file > default (true = use theme's bg color)
         let use_theme_background = file.use_theme_background.unwrap_or(true);
 
+        // Mouse capture: file > default (true)
+        let mouse = file.mouse.unwrap_or(false);
+
         // Preset: file > default ("classic")
         let preset = file.preset.unwrap_or_else(|| "classic".to_string());
 
+        // Keybindings: file only, validated when the TUI builds its keymap
+        let keybindings = file
+            .keybindings
+            .into_iter()
+            .map(|(action, keys)| (action, keys.into()))
+            .collect();
+
         // Feature flags: file config only (env vars would be verbose)
         // Default: enabled (opt-out pattern)
         let file_features = file.features.unwrap_or_default();
@@ -1429,6 +2014,13 @@ service_version = "{otel_service_version}"
             store_tool_io: file_lifestats
                 .store_tool_io
                 .unwrap_or(defaults.store_tool_io),
+            track_file_activity: file_lifestats
+                .track_file_activity
+                .unwrap_or(defaults.track_file_activity),
+            track_todos: file_lifestats.track_todos.unwrap_or(defaults.track_todos),
+            capture_git_context: file_lifestats
+                .capture_git_context
+                .unwrap_or(defaults.capture_git_context),
             max_thinking_size: file_lifestats
                 .max_thinking_size
                 .unwrap_or(defaults.max_thinking_size),
@@ -1444,6 +2036,33 @@ service_version = "{otel_service_version}"
                 .unwrap_or(defaults.flush_interval_secs),
         };
 
+        // Handoff settings: file config only
+        let file_handoff = file.handoff.unwrap_or_default();
+        let handoff_defaults = HandoffConfig::default();
+        let handoff = HandoffConfig {
+            enabled: file_handoff.enabled.unwrap_or(handoff_defaults.enabled),
+            dir: file_handoff
+                .dir
+                .map(PathBuf::from)
+                .unwrap_or(handoff_defaults.dir),
+            inject_on_compact: file_handoff
+                .inject_on_compact
+                .unwrap_or(handoff_defaults.inject_on_compact),
+        };
+
+        // Attachment settings: file config only
+        let file_attachments = file.attachments.unwrap_or_default();
+        let attachments_defaults = AttachmentsConfig::default();
+        let attachments = AttachmentsConfig {
+            enabled: file_attachments
+                .enabled
+                .unwrap_or(attachments_defaults.enabled),
+            dir: file_attachments
+                .dir
+                .map(PathBuf::from)
+                .unwrap_or(attachments_defaults.dir),
+        };
+
         // Embeddings settings: file config + env var for API key
         // API key precedence: ASPY_EMBEDDINGS_API_KEY env var > config file
         let file_embeddings = file.embeddings.unwrap_or_default();
@@ -1497,6 +2116,34 @@ service_version = "{otel_service_version}"
             enabled: file_transformers.enabled.unwrap_or(false),
             tag_editor: file_transformers.tag_editor,
             compact_enhancer: file_transformers.compact_enhancer,
+            context_enricher: file_transformers.context_enricher,
+        };
+
+        // Response transformers: file config only
+        let file_response = file.response_transformers.unwrap_or_default();
+        let response_transformers = ResponseTransformers {
+            enabled: file_response.enabled.unwrap_or(false),
+            redactor: file_response.redactor,
+            tool_guard: file_response.tool_guard,
+            thinking_stripper: file_response.thinking_stripper,
+            tool_annotator: file_response.tool_annotator,
+        };
+
+        // Token counting settings: file config only
+        let file_tokens = file.tokens.unwrap_or_default();
+        let tokens_defaults = TokensConfig::default();
+        let tokens = TokensConfig {
+            counter: file_tokens
+                .counter
+                .map(|s| CounterKind::from_str(&s))
+                .unwrap_or(tokens_defaults.counter),
+            count_tokens_api: file_tokens
+                .count_tokens_api
+                .unwrap_or(tokens_defaults.count_tokens_api),
+            calibration: file_tokens
+                .calibration
+                .unwrap_or(tokens_defaults.calibration),
+            cache_size: file_tokens.cache_size.unwrap_or(tokens_defaults.cache_size),
         };
 
         // OpenTelemetry settings: file config + env var for connection string
@@ -1539,7 +2186,9 @@ service_version = "{otel_service_version}"
             context_limit,
             theme,
             use_theme_background,
+            mouse,
             preset,
+            keybindings,
             features,
             augmentation,
             logging,
@@ -1547,7 +2196,13 @@ service_version = "{otel_service_version}"
             embeddings,
             translation,
             transformers,
+            response_transformers,
+            tokens,
+            handoff,
+            attachments,
             otel,
+            alerts: file.alerts,
+            policy: file.policy.unwrap_or_default(),
             clients,
         }
     }
@@ -1564,7 +2219,9 @@ impl Default for Config {
             context_limit: 147_000,
             theme: "Spy Dark".to_string(),
             use_theme_background: true,
+            mouse: false, // Opt-in: capture breaks terminal text selection
             preset: "classic".to_string(),
+            keybindings: BTreeMap::new(),
             features: Features::default(),
             augmentation: Augmentation::default(),
             logging: LoggingConfig::default(),
@@ -1572,7 +2229,13 @@ impl Default for Config {
             embeddings: EmbeddingsConfig::default(),
             translation: Translation::default(),
             transformers: Transformers::default(),
+            response_transformers: ResponseTransformers::default(),
+            tokens: TokensConfig::default(),
+            handoff: HandoffConfig::default(),
+            attachments: AttachmentsConfig::default(),
             otel: OtelConfig::default(),
+            alerts: Vec::new(),
+            policy: crate::proxy::policy::PolicyConfig::default(),
             clients: ClientsConfig::default(),
         }
     }
@@ -1702,6 +2365,36 @@ impl Config {
             "Request editing",
         ));
 
+        // Response transformation: optional (in-flight response rewriting)
+        let response_active = self.response_transformers.enabled
+            && (self
+                .response_transformers
+                .redactor
+                .as_ref()
+                .is_some_and(|c| c.enabled)
+                || self
+                    .response_transformers
+                    .tool_guard
+                    .as_ref()
+                    .is_some_and(|c| c.enabled)
+                || self
+                    .response_transformers
+                    .thinking_stripper
+                    .as_ref()
+                    .is_some_and(|c| c.enabled)
+                || self
+                    .response_transformers
+                    .tool_annotator
+                    .as_ref()
+                    .is_some_and(|c| c.enabled));
+        features.push(FeatureDefinition::optional(
+            "response_transformers",
+            "responses",
+            FeatureCategory::Pipeline,
+            response_active,
+            "Response rewriting",
+        ));
+
         // OpenTelemetry: configurable (requires connection string and --features otel)
         let otel_def = if self.otel.is_configured() {
             FeatureDefinition::configurable(
@@ -1723,6 +2416,71 @@ impl Config {
         };
         features.push(otel_def);
 
+        // Alerts: configurable (needs [[alerts]] rules)
+        let alerts_def = FeatureDefinition::configurable(
+            "alerts",
+            "alerts",
+            FeatureCategory::Pipeline,
+            !self.alerts.is_empty(),
+            "Alert rules",
+        );
+        features.push(if self.alerts.is_empty() {
+            alerts_def
+        } else {
+            alerts_def.with_detail(format!("{} rules", self.alerts.len()))
+        });
+
+        // Policy: configurable (needs [policy] rules)
+        let policy_def = FeatureDefinition::configurable(
+            "policy",
+            "policy",
+            FeatureCategory::Pipeline,
+            self.policy.enabled,
+            "Tool-use policy",
+        );
+        features.push(if self.policy.enabled {
+            policy_def.with_detail(format!(
+                "{} rules, {}",
+                self.policy.rules.len(),
+                self.policy.mode.as_str()
+            ))
+        } else {
+            policy_def
+        });
+
+        // Context enricher: optional (needs transformers + lifestats for retrieval)
+        features.push(FeatureDefinition::optional(
+            "context_enricher",
+            "context",
+            FeatureCategory::Pipeline,
+            self.transformers.enabled
+                && self.lifestats.enabled
+                && self
+                    .transformers
+                    .context_enricher
+                    .as_ref()
+                    .is_some_and(|c| c.enabled),
+            "Past-context injection",
+        ));
+
+        // Handoff: optional (needs lifestats for session history)
+        features.push(FeatureDefinition::optional(
+            "handoff",
+            "handoff",
+            FeatureCategory::Pipeline,
+            self.handoff.enabled && self.lifestats.enabled,
+            "Session handoff",
+        ));
+
+        // Attachments: optional (on by default)
+        features.push(FeatureDefinition::optional(
+            "attachments",
+            "attachments",
+            FeatureCategory::Pipeline,
+            self.attachments.enabled,
+            "Attachment store",
+        ));
+
         // Routing: configurable (needs client definitions)
         features.push(FeatureDefinition::configurable(
             "routing",
@@ -1740,10 +2498,91 @@ impl Config {
 // Tests
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// Print a clear, actionable config error and exit
+fn config_error(headline: &str, path: &Path, error: &dyn std::fmt::Display) -> ! {
+    eprintln!("\n╔══════════════════════════════════════════════════════════════╗");
+    eprintln!("║  CONFIG ERROR - {:<45}║", headline);
+    eprintln!("╚══════════════════════════════════════════════════════════════╝\n");
+    eprintln!("  File: {}\n", path.display());
+    eprintln!("  Error: {}\n", error);
+    eprintln!("  Tip: Run `aspy config --show` to validate your config");
+    eprintln!("       Or delete the file to regenerate defaults\n");
+    std::process::exit(1);
+}
+
+/// Merge `overlay` into `base`: tables recursively, other values replaced
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Project overlays merge tables key by key but replace arrays wholesale,
+    /// so a project's tag-editor rules are its own rather than appended.
+    #[test]
+    fn test_project_overlay_merge() {
+        let global: toml::Value = toml::from_str(
+            r#"
+            context_limit = 100000
+            [augmentation]
+            context_warning = true
+            context_warning_thresholds = [60, 80]
+            [transformers]
+            enabled = true
+            [transformers.tag-editor]
+            enabled = true
+            rules = [{ type = "remove", tag = "system-reminder", pattern = "global" }]
+            "#,
+        )
+        .unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::write(
+            root.join(PROJECT_CONFIG_FILE),
+            r#"
+            [augmentation]
+            context_warning_thresholds = [90]
+            [transformers.tag-editor]
+            rules = [{ type = "remove", tag = "system-reminder", pattern = "project" }]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Config::find_project_root(&root.join("a/b")),
+            Some(root.clone())
+        );
+
+        let config = Config::for_project(Some(&global), &root).unwrap();
+        assert_eq!(config.context_limit, 100_000);
+        assert!(config.augmentation.context_warning);
+        assert_eq!(config.augmentation.context_warning_thresholds, vec![90]);
+        assert!(config.transformers.enabled);
+        let editor = config.transformers.tag_editor.unwrap();
+        assert!(editor.enabled);
+        assert_eq!(editor.rules.len(), 1);
+        assert!(format!("{:?}", editor.rules[0]).contains("project"));
+
+        std::fs::write(root.join(PROJECT_CONFIG_FILE), "[augmentation\n").unwrap();
+        assert!(Config::for_project(Some(&global), &root).is_err());
+    }
+
     /// Verify that serialized config can be parsed back.
     /// This catches TOML syntax errors like using `[array.property]`
     /// instead of dotted keys for array-of-tables elements.
@@ -1815,6 +2654,138 @@ mod tests {
         assert_eq!(tag_editor.rules.len(), 2, "Should have 2 rules");
     }
 
+    /// Test round-trip with alert rules (flattened condition + sinks)
+    #[test]
+    fn test_config_roundtrip_with_alerts() {
+        use crate::pipeline::alerts::{AlertCondition, AlertRule, AlertSink};
+
+        let config = Config {
+            alerts: vec![
+                AlertRule {
+                    name: "Context \"high\"".to_string(),
+                    condition: AlertCondition::ContextPercent { above: 85.0 },
+                    sinks: vec![AlertSink::Toast, AlertSink::Bell],
+                    command: None,
+                    webhook_url: None,
+                    cooldown_secs: 600,
+                },
+                AlertRule {
+                    name: "Rate limits".to_string(),
+                    condition: AlertCondition::RateLimitRemaining {
+                        requests_below: Some(5),
+                        tokens_below: None,
+                    },
+                    sinks: vec![AlertSink::Command, AlertSink::Webhook],
+                    command: Some("jq -r '.message' >> \"$HOME/alerts.log\"".to_string()),
+                    webhook_url: Some("https://hooks.example.com/aspy".to_string()),
+                    cooldown_secs: 0,
+                },
+            ],
+            ..Config::default()
+        };
+
+        let toml_str = config.to_toml();
+        let parsed: FileConfig = toml::from_str(&toml_str).unwrap_or_else(|e| {
+            panic!(
+                "Config with alerts should round-trip.\nTOML:\n{}\nError: {}",
+                toml_str, e
+            )
+        });
+        assert_eq!(parsed.alerts, config.alerts);
+    }
+
+    /// Policy rules (and the commented example when unset) must round-trip.
+    #[test]
+    fn test_config_roundtrip_with_policy() {
+        use crate::proxy::policy::{EnforcementMode, PolicyConfig, PolicyEffect, PolicyRule};
+
+        let parsed: FileConfig = toml::from_str(&Config::default().to_toml()).unwrap();
+        assert!(parsed.policy.is_none());
+
+        let policy = PolicyConfig {
+            enabled: true,
+            mode: EnforcementMode::Remind,
+            default: PolicyEffect::Allow,
+            rules: vec![PolicyRule {
+                name: "no-root-delete".to_string(),
+                effect: PolicyEffect::Deny,
+                tools: vec!["Bash".to_string()],
+                paths: Vec::new(),
+                commands: vec![r"rm\s+-rf\s+/".to_string()],
+                domains: Vec::new(),
+                message: None,
+                mode: Some(EnforcementMode::Rewrite),
+            }],
+        };
+        let config = Config {
+            policy: policy.clone(),
+            ..Config::default()
+        };
+
+        let toml_str = config.to_toml();
+        let parsed: FileConfig = toml::from_str(&toml_str).unwrap_or_else(|e| {
+            panic!(
+                "Config with policy should round-trip.\nTOML:\n{}\nError: {}",
+                toml_str, e
+            )
+        });
+        assert_eq!(parsed.policy, Some(policy));
+    }
+
+    /// Response transformers (including regex escapes and rule arrays) must round-trip.
+    #[test]
+    fn test_config_roundtrip_with_response_transformers() {
+        use crate::proxy::response_transformation::{
+            GuardAction, GuardRuleConfig, RedactorConfig, ThinkingStripperConfig, ToolGuardConfig,
+        };
+
+        let config = Config {
+            response_transformers: ResponseTransformers {
+                enabled: true,
+                redactor: Some(RedactorConfig {
+                    enabled: true,
+                    patterns: vec![r"sk-ant-[A-Za-z0-9_\-]+".to_string()],
+                    replacement: "[REDACTED]".to_string(),
+                }),
+                tool_guard: Some(ToolGuardConfig {
+                    enabled: true,
+                    rules: vec![GuardRuleConfig {
+                        tool: "Bash".to_string(),
+                        field: "command".to_string(),
+                        pattern: r"rm\s+-rf".to_string(),
+                        action: GuardAction::Rewrite,
+                        replacement: Some("rm -ri".to_string()),
+                        message: None,
+                    }],
+                }),
+                thinking_stripper: Some(ThinkingStripperConfig { enabled: true }),
+                tool_annotator: None,
+            },
+            ..Config::default()
+        };
+
+        let toml_str = config.to_toml();
+        let parsed: FileConfig = toml::from_str(&toml_str).unwrap_or_else(|e| {
+            panic!(
+                "Config with response transformers should round-trip.\nTOML:\n{}\nError: {}",
+                toml_str, e
+            )
+        });
+        let section = parsed
+            .response_transformers
+            .expect("response_transformers section should be present");
+        assert_eq!(section.enabled, Some(true));
+        assert_eq!(
+            section.redactor.unwrap().patterns,
+            vec![r"sk-ant-[A-Za-z0-9_\-]+".to_string()]
+        );
+        let rule = &section.tool_guard.unwrap().rules[0];
+        assert_eq!(rule.pattern, r"rm\s+-rf");
+        assert_eq!(rule.action, GuardAction::Rewrite);
+        assert!(section.thinking_stripper.unwrap().enabled);
+        assert!(section.tool_annotator.is_none());
+    }
+
     /// EXHAUSTIVE TEST: Ensures every transformer field is serialized to TOML.
     ///
     /// When you add a new transformer:
@@ -1830,7 +2801,8 @@ mod tests {
     #[test]
     fn test_all_transformers_have_toml_serialization() {
         use crate::proxy::transformation::{
-            CompactEnhancerConfig, PositionConfig, RuleConfig, TagEditorConfig,
+            CompactEnhancerConfig, ContextEnricherConfig, PositionConfig, RuleConfig,
+            TagEditorConfig,
         };
 
         // ─────────────────────────────────────────────────────────────────────
@@ -1854,6 +2826,14 @@ mod tests {
         // Compact enhancer with minimal valid config
         config.transformers.compact_enhancer = Some(CompactEnhancerConfig { enabled: true });
 
+        // Context enricher with a trigger pattern (exercises string escaping)
+        config.transformers.context_enricher = Some(ContextEnricherConfig {
+            enabled: true,
+            top_k: 3,
+            patterns: vec!["(?i)last time|\\bbefore\\b".to_string()],
+            ..Default::default()
+        });
+
         // ─────────────────────────────────────────────────────────────────────
         // STEP 2: Generate TOML output
         // ─────────────────────────────────────────────────────────────────────
@@ -1880,6 +2860,14 @@ mod tests {
             toml_str
         );
 
+        assert!(
+            toml_str.contains("[transformers.context-enricher]"),
+            "context-enricher missing from TOML output!\n\
+             Did you forget to serialize it in transformers_to_toml()?\n\
+             TOML output:\n{}",
+            toml_str
+        );
+
         // ─────────────────────────────────────────────────────────────────────
         // STEP 4: Verify round-trip works (catches TOML syntax errors)
         // ─────────────────────────────────────────────────────────────────────
@@ -1910,6 +2898,14 @@ mod tests {
             .compact_enhancer
             .expect("compact_enhancer should be present");
         assert!(compact.enabled, "compact_enhancer.enabled should be true");
+
+        // Verify context-enricher
+        let enricher = transformers
+            .context_enricher
+            .expect("context_enricher should be present");
+        assert!(enricher.enabled, "context_enricher.enabled should be true");
+        assert_eq!(enricher.top_k, 3);
+        assert_eq!(enricher.patterns, vec!["(?i)last time|\\bbefore\\b"]);
     }
 
     /// Ensures the DEFAULT template includes commented examples for all transformers.
@@ -1935,6 +2931,12 @@ mod tests {
             "compact-enhancer not documented in default template!\n\
              Add a commented example so users can discover this feature."
         );
+
+        assert!(
+            toml_str.contains("# [transformers.context-enricher]"),
+            "context-enricher not documented in default template!\n\
+             Add a commented example so users can discover this feature."
+        );
     }
 
     /// EXHAUSTIVE TEST: Ensures every augmentation field is serialized to TOML.
@@ -2007,6 +3009,24 @@ mod tests {
         assert_eq!(aug.context_warning_thresholds, Some(vec![50, 75, 90]));
     }
 
+    /// Ensures every [tokens] field is serialized and survives a round-trip.
+    #[test]
+    fn test_tokens_config_roundtrip() {
+        let mut config = Config::default();
+        config.tokens.counter = CounterKind::Bpe;
+        config.tokens.count_tokens_api = true;
+        config.tokens.calibration = true;
+        config.tokens.cache_size = 64;
+
+        let toml_str = config.to_toml();
+        let parsed: FileConfig = toml::from_str(&toml_str).expect("tokens config should parse");
+        let tokens = parsed.tokens.expect("tokens section should be present");
+        assert_eq!(tokens.counter.as_deref(), Some("bpe"));
+        assert_eq!(tokens.count_tokens_api, Some(true));
+        assert_eq!(tokens.calibration, Some(true));
+        assert_eq!(tokens.cache_size, Some(64));
+    }
+
     /// EXHAUSTIVE TEST: Ensures every feature flag is serialized to TOML.
     ///
     /// When you add a new feature flag:
@@ -0,0 +1,1733 @@
+//! Config file schema, strict validation and JSON Schema export
+//!
+//! serde defaults anything it doesn't recognise, so a misspelled key in
+//! config.toml is silently ignored. [`check`] walks the file against
+//! [`schema`] - the declared shape of every section - and reports unknown
+//! keys, wrong types, bad enum values and out-of-range numbers with the line
+//! they are on. It then loads the file for real and cross-checks what a
+//! schema can't express: clients naming missing providers, unset `key_env`
+//! variables, regexes that don't compile.
+//!
+//! The same schema is exported as JSON Schema (`aspy config --schema`) so
+//! editors with TOML language support can autocomplete and lint the file.
+//!
+//! When adding a config key, declare it in [`schema`] too; the tests check
+//! that `Config::to_toml` output and a sample of every section pass.
+
+use crate::config::{AuthMethod, Config, ProviderAuth};
+use crate::proxy::transformation::{PositionConfig, RuleConfig};
+use crate::tui::keymap::{Keymap, REGISTRY};
+use serde_json::{json, Map, Value as JsonValue};
+use std::fmt;
+use std::ops::Range;
+use toml::Value;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Schema
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Expected shape of a config value
+pub enum Shape {
+    Bool,
+    Integer {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    String,
+    /// One of a fixed set of strings
+    Enum(&'static [&'static str]),
+    /// Built-in names plus names found at runtime (user presets)
+    Named {
+        builtin: &'static [&'static str],
+        user: fn() -> Vec<String>,
+    },
+    Array(Box<Node>),
+    /// Table with fixed keys
+    Table(Vec<Field>),
+    /// Table with free-form keys (client IDs, model names)
+    Map(Box<Node>),
+    /// Table whose keys depend on a discriminator (`type = "inject"`)
+    Tagged {
+        tag: &'static str,
+        common: Vec<Field>,
+        variants: Vec<(&'static str, Vec<Field>)>,
+    },
+    /// Any one of several shapes
+    OneOf(Vec<Node>),
+}
+
+/// A schema node: shape plus description
+pub struct Node {
+    shape: Shape,
+    doc: &'static str,
+}
+
+/// A key in a table
+pub struct Field {
+    name: &'static str,
+    node: Node,
+    required: bool,
+}
+
+fn node(doc: &'static str, shape: Shape) -> Node {
+    Node { shape, doc }
+}
+
+fn boolean(doc: &'static str) -> Node {
+    node(doc, Shape::Bool)
+}
+
+fn string(doc: &'static str) -> Node {
+    node(doc, Shape::String)
+}
+
+fn integer(doc: &'static str, min: i64, max: Option<i64>) -> Node {
+    node(
+        doc,
+        Shape::Integer {
+            min: Some(min),
+            max,
+        },
+    )
+}
+
+fn number(doc: &'static str, min: f64, max: Option<f64>) -> Node {
+    node(
+        doc,
+        Shape::Number {
+            min: Some(min),
+            max,
+        },
+    )
+}
+
+fn one_of(doc: &'static str, values: &'static [&'static str]) -> Node {
+    node(doc, Shape::Enum(values))
+}
+
+fn named(doc: &'static str, builtin: &'static [&'static str], user: fn() -> Vec<String>) -> Node {
+    node(doc, Shape::Named { builtin, user })
+}
+
+fn array(doc: &'static str, item: Node) -> Node {
+    node(doc, Shape::Array(Box::new(item)))
+}
+
+fn strings(doc: &'static str) -> Node {
+    array(doc, string(""))
+}
+
+fn table(doc: &'static str, fields: Vec<Field>) -> Node {
+    node(doc, Shape::Table(fields))
+}
+
+fn field(name: &'static str, node: Node) -> Field {
+    Field {
+        name,
+        node,
+        required: false,
+    }
+}
+
+fn required(name: &'static str, node: Node) -> Field {
+    Field {
+        name,
+        node,
+        required: true,
+    }
+}
+
+/// A transformer table with only an on/off switch
+fn enabled_only(doc: &'static str) -> Node {
+    table(
+        doc,
+        vec![field("enabled", boolean("Enable this transformer"))],
+    )
+}
+
+fn auth() -> Node {
+    table(
+        "Authentication for upstream requests",
+        vec![
+            field(
+                "method",
+                one_of(
+                    "passthrough forwards the client's headers",
+                    &["passthrough", "bearer", "x_api_key", "basic", "header"],
+                ),
+            ),
+            field("key", string("API key (prefer key_env)")),
+            field(
+                "key_env",
+                string("Environment variable holding the API key"),
+            ),
+            field("header_name", string("Header for method = \"header\"")),
+            field(
+                "strip_incoming",
+                boolean("Drop the client's auth headers (default: true unless passthrough)"),
+            ),
+        ],
+    )
+}
+
+fn when() -> Field {
+    field(
+        "when",
+        table(
+            "Conditions (all must match; `|` separates alternatives)",
+            vec![
+                field("turn_number", string("\"=1\", \">5\", \"every:3\"")),
+                field("has_tool_results", string("\">0\", \"=0\"")),
+                field("client_id", string("\"dev-1|foundry\"")),
+            ],
+        ),
+    )
+}
+
+fn tag_rule() -> Node {
+    let position = node(
+        "Where to inject",
+        Shape::OneOf(vec![
+            one_of("", &["start", "end"]),
+            table(
+                "Before the first block matching pattern",
+                vec![required(
+                    "before",
+                    table("", vec![required("pattern", string("Regex"))]),
+                )],
+            ),
+            table(
+                "After the last block matching pattern",
+                vec![required(
+                    "after",
+                    table("", vec![required("pattern", string("Regex"))]),
+                )],
+            ),
+        ]),
+    );
+    node(
+        "Rule applied to XML-style tags in user messages",
+        Shape::Tagged {
+            tag: "type",
+            common: vec![
+                required(
+                    "tag",
+                    string("Tag this rule targets, e.g. \"system-reminder\""),
+                ),
+                when(),
+            ],
+            variants: vec![
+                (
+                    "inject",
+                    vec![
+                        required("content", string("Content to inject")),
+                        field("position", position),
+                    ],
+                ),
+                ("remove", vec![required("pattern", string("Regex"))]),
+                (
+                    "replace",
+                    vec![
+                        required("pattern", string("Regex")),
+                        required("replacement", string("Replacement ($1, $2 capture groups)")),
+                    ],
+                ),
+            ],
+        },
+    )
+}
+
+fn alert_rule() -> Node {
+    node(
+        "Alert rule",
+        Shape::Tagged {
+            tag: "type",
+            common: vec![
+                required("name", string("Shown in toasts and payloads")),
+                field(
+                    "sinks",
+                    array(
+                        "Where to deliver",
+                        one_of("", &["toast", "bell", "command", "webhook"]),
+                    ),
+                ),
+                field("command", string("Shell command for the command sink")),
+                field("webhook_url", string("URL for the webhook sink")),
+                field(
+                    "cooldown_secs",
+                    integer("Minimum seconds between firings", 0, None),
+                ),
+            ],
+            variants: vec![
+                (
+                    "error_status",
+                    vec![field(
+                        "min_status",
+                        integer("Lowest status that fires", 100, Some(599)),
+                    )],
+                ),
+                (
+                    "context_percent",
+                    vec![required(
+                        "above",
+                        number("Context usage percent", 0.0, Some(100.0)),
+                    )],
+                ),
+                (
+                    "cost_per_hour",
+                    vec![required(
+                        "above_usd",
+                        number("Spend over the last hour", 0.0, None),
+                    )],
+                ),
+                (
+                    "tool_failure_streak",
+                    vec![required(
+                        "count",
+                        integer("Consecutive failed tool calls", 1, None),
+                    )],
+                ),
+                (
+                    "rate_limit_remaining",
+                    vec![
+                        field("requests_below", integer("", 0, None)),
+                        field("tokens_below", integer("", 0, None)),
+                    ],
+                ),
+            ],
+        },
+    )
+}
+
+/// The declared shape of config.toml
+pub fn schema() -> Node {
+    let enforcement = &["rewrite", "remind", "audit"];
+    let effect = &["allow", "deny"];
+
+    table(
+        "aspy configuration",
+        vec![
+            field("theme", string("Theme name (see the theme selector)")),
+            field(
+                "use_theme_background",
+                boolean("Use the theme's background color"),
+            ),
+            field(
+                "mouse",
+                boolean("Mouse support in the TUI (disable for terminal text selection)"),
+            ),
+            field(
+                "preset",
+                named(
+                    "Layout preset (built-in, or a file in ~/.config/aspy/presets)",
+                    &[
+                        "classic",
+                        "reasoning",
+                        "debug",
+                        "minimal",
+                        "focus",
+                        "compact",
+                        "balanced",
+                    ],
+                    crate::tui::preset::user_preset_names,
+                ),
+            ),
+            field(
+                "keybindings",
+                table(
+                    "Keys per TUI action (a key or list of keys; [] unbinds)",
+                    REGISTRY
+                        .iter()
+                        .map(|def| {
+                            field(
+                                def.name,
+                                node(def.help, Shape::OneOf(vec![string(""), strings("")])),
+                            )
+                        })
+                        .collect(),
+                ),
+            ),
+            field(
+                "context_limit",
+                integer("Context window limit for the gauge", 1, None),
+            ),
+            field(
+                "bind_addr",
+                string("Proxy bind address, e.g. \"127.0.0.1:8080\""),
+            ),
+            field("api_url", string("Upstream API URL")),
+            field("log_dir", string("Directory for session logs")),
+            field(
+                "features",
+                table(
+                    "Feature flags",
+                    vec![
+                        field("storage", boolean("Write session logs")),
+                        field("thinking_panel", boolean("Show the thinking panel")),
+                        field("stats", boolean("Track statistics")),
+                    ],
+                ),
+            ),
+            field(
+                "augmentation",
+                table(
+                    "Response augmentation",
+                    vec![
+                        field("context_warning", boolean("Inject context usage warnings")),
+                        field(
+                            "context_warning_thresholds",
+                            array(
+                                "Percentages that trigger a warning",
+                                integer("", 1, Some(100)),
+                            ),
+                        ),
+                    ],
+                ),
+            ),
+            field(
+                "tokens",
+                table(
+                    "Token counting",
+                    vec![
+                        field("counter", one_of("Local estimator", &["heuristic", "bpe"])),
+                        field("count_tokens_api", boolean("Exact counts via count_tokens")),
+                        field("calibration", boolean("Track estimate drift")),
+                        field("cache_size", integer("Cached count entries", 0, None)),
+                    ],
+                ),
+            ),
+            field(
+                "logging",
+                table(
+                    "Logging (RUST_LOG overrides)",
+                    vec![
+                        field(
+                            "level",
+                            one_of("Log level", &["trace", "debug", "info", "warn", "error"]),
+                        ),
+                        field("file_enabled", boolean("Also log to files")),
+                        field("file_dir", string("Log file directory")),
+                        field(
+                            "file_rotation",
+                            one_of("Log file rotation", &["hourly", "daily", "never"]),
+                        ),
+                        field("file_prefix", string("Log file name prefix")),
+                    ],
+                ),
+            ),
+            field(
+                "lifestats",
+                table(
+                    "Lifetime statistics (SQLite)",
+                    vec![
+                        field("enabled", boolean("Store lifetime statistics")),
+                        field("db_path", string("Database path")),
+                        field("store_thinking", boolean("Store thinking blocks")),
+                        field("store_tool_io", boolean("Store tool inputs and outputs")),
+                        field("track_file_activity", boolean("Record file activity")),
+                        field("track_todos", boolean("Record TodoWrite plans")),
+                        field(
+                            "capture_git_context",
+                            boolean("Record git branch and commit"),
+                        ),
+                        field(
+                            "max_thinking_size",
+                            integer("Bytes kept per thinking block", 0, None),
+                        ),
+                        field(
+                            "retention_days",
+                            integer("Days to keep (0 = forever)", 0, None),
+                        ),
+                        field("channel_buffer", integer("Event channel capacity", 1, None)),
+                        field("batch_size", integer("Rows per write batch", 1, None)),
+                        field(
+                            "flush_interval_secs",
+                            integer("Seconds between flushes", 1, None),
+                        ),
+                    ],
+                ),
+            ),
+            field(
+                "handoff",
+                table(
+                    "Session handoff docs (requires lifestats)",
+                    vec![
+                        field("enabled", boolean("Write handoff docs")),
+                        field("dir", string("Output directory")),
+                        field("inject_on_compact", boolean("Append to compaction prompts")),
+                    ],
+                ),
+            ),
+            field(
+                "attachments",
+                table(
+                    "Image/document attachment store",
+                    vec![
+                        field("enabled", boolean("Store attachments by SHA-256")),
+                        field("dir", string("Store directory")),
+                    ],
+                ),
+            ),
+            field(
+                "embeddings",
+                table(
+                    "Semantic search embeddings",
+                    vec![
+                        field(
+                            "provider",
+                            one_of("Embedding provider", &["none", "local", "remote"]),
+                        ),
+                        field("model", string("Model name")),
+                        field("api_base", string("API base URL (remote)")),
+                        field("api_version", string("API version query parameter")),
+                        field("auth_method", one_of("Remote auth", &["bearer", "api-key"])),
+                        field("api_key", string("API key (env vars take precedence)")),
+                        field(
+                            "poll_interval_secs",
+                            integer("Indexer poll interval", 1, None),
+                        ),
+                        field("batch_size", integer("Documents per request", 1, None)),
+                        field("batch_delay_ms", integer("Delay between batches", 0, None)),
+                        field(
+                            "max_content_length",
+                            integer("Characters embedded per document", 1, None),
+                        ),
+                    ],
+                ),
+            ),
+            field(
+                "translation",
+                table(
+                    "OpenAI ↔ Anthropic translation",
+                    vec![
+                        field("enabled", boolean("Enable translation")),
+                        field("auto_detect", boolean("Detect the request format")),
+                        field(
+                            "model_mapping",
+                            node(
+                                "Source model → target model",
+                                Shape::Map(Box::new(string(""))),
+                            ),
+                        ),
+                    ],
+                ),
+            ),
+            field(
+                "transformers",
+                table(
+                    "Request transformers",
+                    vec![
+                        field("enabled", boolean("Master switch")),
+                        field(
+                            "tag-editor",
+                            table(
+                                "Edit XML-style tags in user messages",
+                                vec![
+                                    field("enabled", boolean("Enable this transformer")),
+                                    field("rules", array("Rules, applied in order", tag_rule())),
+                                ],
+                            ),
+                        ),
+                        field(
+                            "compact-enhancer",
+                            table(
+                                "Continuity guidance for /compact",
+                                vec![required("enabled", boolean("Enable this transformer"))],
+                            ),
+                        ),
+                        field(
+                            "context-enricher",
+                            table(
+                                "Inject past context from lifestats",
+                                vec![
+                                    field("enabled", boolean("Enable this transformer")),
+                                    field("top_k", integer("Matches to retrieve", 1, None)),
+                                    field(
+                                        "max_tokens",
+                                        integer("Budget for <aspy-context>", 1, None),
+                                    ),
+                                    field("first_turn", boolean("Enrich the first prompt")),
+                                    field(
+                                        "every_turns",
+                                        integer("Also every N prompts (0 = never)", 0, None),
+                                    ),
+                                    field("patterns", strings("Regexes that trigger retrieval")),
+                                    field(
+                                        "min_prompt_chars",
+                                        integer("Shorter prompts are skipped", 0, None),
+                                    ),
+                                ],
+                            ),
+                        ),
+                    ],
+                ),
+            ),
+            field(
+                "response_transformers",
+                table(
+                    "Response transformers",
+                    vec![
+                        field("enabled", boolean("Master switch")),
+                        field(
+                            "redactor",
+                            table(
+                                "Mask regex matches in assistant text",
+                                vec![
+                                    field("enabled", boolean("Enable this transformer")),
+                                    field("patterns", strings("Regexes to mask")),
+                                    field("replacement", string("Replacement text")),
+                                ],
+                            ),
+                        ),
+                        field(
+                            "tool-guard",
+                            table(
+                                "Block or rewrite tool calls by input pattern",
+                                vec![
+                                    field("enabled", boolean("Enable this transformer")),
+                                    field(
+                                        "rules",
+                                        array(
+                                            "Rules (first match wins)",
+                                            table(
+                                                "Guard rule",
+                                                vec![
+                                                    required("tool", string("Tool name, or \"*\"")),
+                                                    field("field", string("Input field to test")),
+                                                    required("pattern", string("Regex")),
+                                                    field(
+                                                        "action",
+                                                        one_of("", &["block", "rewrite"]),
+                                                    ),
+                                                    field("replacement", string("For rewrite")),
+                                                    field("message", string("For block")),
+                                                ],
+                                            ),
+                                        ),
+                                    ),
+                                ],
+                            ),
+                        ),
+                        field("thinking-stripper", enabled_only("Remove thinking blocks")),
+                        field(
+                            "tool-annotator",
+                            table(
+                                "Note before selected tool calls",
+                                vec![
+                                    field("enabled", boolean("Enable this transformer")),
+                                    field("tools", strings("Tool names (empty = all)")),
+                                    field("template", string("Annotation template")),
+                                ],
+                            ),
+                        ),
+                    ],
+                ),
+            ),
+            field(
+                "otel",
+                table(
+                    "OpenTelemetry export",
+                    vec![
+                        field("enabled", boolean("Export telemetry")),
+                        field(
+                            "connection_string",
+                            string("Application Insights connection string"),
+                        ),
+                        field("service_name", string("Service name")),
+                        field("service_version", string("Service version")),
+                    ],
+                ),
+            ),
+            field("alerts", array("Alert rules ([[alerts]])", alert_rule())),
+            field(
+                "policy",
+                table(
+                    "Tool-use policy",
+                    vec![
+                        field("enabled", boolean("Enforce the policy")),
+                        field("mode", one_of("Default enforcement", enforcement)),
+                        field("default", one_of("Effect when no rule matches", effect)),
+                        field(
+                            "rules",
+                            array(
+                                "Rules (first match wins)",
+                                table(
+                                    "Policy rule",
+                                    vec![
+                                        required("name", string("Rule name")),
+                                        required("effect", one_of("", effect)),
+                                        field("tools", strings("Tool names (* wildcards)")),
+                                        field("paths", strings("Path globs for file tools")),
+                                        field("commands", strings("Command regexes for Bash")),
+                                        field("domains", strings("Host patterns for WebFetch")),
+                                        field("message", string("Explanation on denial")),
+                                        field("mode", one_of("Enforcement override", enforcement)),
+                                    ],
+                                ),
+                            ),
+                        ),
+                    ],
+                ),
+            ),
+            field(
+                "clients",
+                node(
+                    "Named clients, routed by URL path prefix",
+                    Shape::Map(Box::new(table(
+                        "Client",
+                        vec![
+                            required("name", string("Display name")),
+                            required("provider", string("Provider ID ([providers.<id>])")),
+                            field("tags", strings("Tags")),
+                            field("auth", auth()),
+                        ],
+                    ))),
+                ),
+            ),
+            field(
+                "providers",
+                node(
+                    "Provider backends",
+                    Shape::Map(Box::new(table(
+                        "Provider",
+                        vec![
+                            required("base_url", string("Upstream API URL")),
+                            field("name", string("Display name")),
+                            field("api_format", one_of("API format", &["anthropic", "openai"])),
+                            field("auth", auth()),
+                        ],
+                    ))),
+                ),
+            ),
+        ],
+    )
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Diagnostics
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One step in a path through the config
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A problem found in the config file
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Dotted path, e.g. `transformers.tag-editor.rules[0].pattern`
+    pub path: String,
+    /// 1-based line in the file (None if it couldn't be located)
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// The diagnostic prefixed with its line, e.g. `line 12: error: …`
+    pub fn located(&self) -> String {
+        match self.line {
+            Some(line) => format!("line {}: {}", line, self),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: ", severity)?;
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Diagnostics collected against paths, located in the source afterwards
+#[derive(Default)]
+struct Findings {
+    items: Vec<(Severity, Vec<Segment>, String)>,
+}
+
+impl Findings {
+    fn error(&mut self, path: &[Segment], message: impl Into<String>) {
+        self.items
+            .push((Severity::Error, path.to_vec(), message.into()));
+    }
+
+    fn warning(&mut self, path: &[Segment], message: impl Into<String>) {
+        self.items
+            .push((Severity::Warning, path.to_vec(), message.into()));
+    }
+
+    fn error_count(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|(severity, ..)| *severity == Severity::Error)
+            .count()
+    }
+}
+
+fn key(path: &[Segment], name: &str) -> Vec<Segment> {
+    let mut path = path.to_vec();
+    path.push(Segment::Key(name.to_string()));
+    path
+}
+
+fn index(path: &[Segment], i: usize) -> Vec<Segment> {
+    let mut path = path.to_vec();
+    path.push(Segment::Index(i));
+    path
+}
+
+fn display_path(path: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(k) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(k);
+            }
+            Segment::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+fn kind_name(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "float",
+        Value::Boolean(_) => "boolean",
+        Value::Datetime(_) => "datetime",
+        Value::Array(_) => "array",
+        Value::Table(_) => "table",
+    }
+}
+
+/// Levenshtein distance, for "did you mean" suggestions
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(current)
+            };
+            prev = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// Closest candidate within a typo's reach
+fn suggest<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let normalized = word.replace('_', "-").to_lowercase();
+    candidates
+        .map(|c| (distance(&normalized, &c.replace('_', "-")), c))
+        .filter(|(d, c)| *d <= (c.len() / 3).max(1))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Schema walk
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn walk(node: &Node, value: &Value, path: &[Segment], out: &mut Findings) {
+    let mismatch = |out: &mut Findings, expected: &str| {
+        out.error(
+            path,
+            format!("expected {}, found {}", expected, kind_name(value)),
+        );
+    };
+
+    match &node.shape {
+        Shape::Bool => {
+            if !value.is_bool() {
+                mismatch(out, "boolean");
+            }
+        }
+        Shape::String => {
+            if !value.is_str() {
+                mismatch(out, "string");
+            }
+        }
+        Shape::Integer { min, max } => match value.as_integer() {
+            Some(n) => check_range(
+                n as f64,
+                min.map(|m| m as f64),
+                max.map(|m| m as f64),
+                path,
+                out,
+            ),
+            None => mismatch(out, "integer"),
+        },
+        Shape::Number { min, max } => {
+            match value.as_float().or(value.as_integer().map(|n| n as f64)) {
+                Some(n) => check_range(n, *min, *max, path, out),
+                None => mismatch(out, "number"),
+            }
+        }
+        Shape::Enum(values) => match value.as_str() {
+            Some(s) if values.contains(&s) => {}
+            Some(s) => {
+                let hint = suggest(s, values.iter().copied())
+                    .map(|v| format!(" (did you mean `{}`?)", v))
+                    .unwrap_or_default();
+                out.error(
+                    path,
+                    format!("`{}` is not one of: {}{}", s, values.join(", "), hint),
+                );
+            }
+            None => mismatch(out, "string"),
+        },
+        Shape::Named { builtin, user } => match value.as_str() {
+            Some(s) if builtin.contains(&s) => {}
+            Some(s) => {
+                let user = user();
+                if user.iter().any(|name| name == s) {
+                    return;
+                }
+                let known: Vec<&str> = builtin
+                    .iter()
+                    .copied()
+                    .chain(user.iter().map(String::as_str))
+                    .collect();
+                let hint = suggest(s, known.iter().copied())
+                    .map(|v| format!(" (did you mean `{}`?)", v))
+                    .unwrap_or_default();
+                out.error(
+                    path,
+                    format!("`{}` is not one of: {}{}", s, known.join(", "), hint),
+                );
+            }
+            None => mismatch(out, "string"),
+        },
+        Shape::Array(item) => match value.as_array() {
+            Some(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    walk(item, v, &index(path, i), out);
+                }
+            }
+            None => mismatch(out, "array"),
+        },
+        Shape::Map(item) => match value.as_table() {
+            Some(entries) => {
+                for (k, v) in entries {
+                    walk(item, v, &key(path, k), out);
+                }
+            }
+            None => mismatch(out, "table"),
+        },
+        Shape::Table(fields) => match value.as_table() {
+            Some(entries) => walk_fields(fields.iter(), &[], entries, path, out),
+            None => mismatch(out, "table"),
+        },
+        Shape::Tagged {
+            tag,
+            common,
+            variants,
+        } => {
+            let Some(entries) = value.as_table() else {
+                return mismatch(out, "table");
+            };
+            let names = || variants.iter().map(|(name, _)| *name);
+            let variant = match entries.get(*tag) {
+                None => {
+                    out.error(
+                        path,
+                        format!(
+                            "missing required key `{}` (one of: {})",
+                            tag,
+                            names().collect::<Vec<_>>().join(", ")
+                        ),
+                    );
+                    return;
+                }
+                Some(Value::String(s)) => match variants.iter().find(|(name, _)| name == s) {
+                    Some((_, fields)) => fields,
+                    None => {
+                        let hint = suggest(s, names())
+                            .map(|v| format!(" (did you mean `{}`?)", v))
+                            .unwrap_or_default();
+                        out.error(
+                            &key(path, tag),
+                            format!(
+                                "`{}` is not one of: {}{}",
+                                s,
+                                names().collect::<Vec<_>>().join(", "),
+                                hint
+                            ),
+                        );
+                        return;
+                    }
+                },
+                Some(other) => {
+                    out.error(
+                        &key(path, tag),
+                        format!("expected string, found {}", kind_name(other)),
+                    );
+                    return;
+                }
+            };
+            walk_fields(
+                common.iter().chain(variant.iter()),
+                &[tag],
+                entries,
+                path,
+                out,
+            );
+        }
+        Shape::OneOf(options) => {
+            // Report against whichever option the value comes closest to
+            let best = options
+                .iter()
+                .map(|option| {
+                    let mut findings = Findings::default();
+                    walk(option, value, path, &mut findings);
+                    findings
+                })
+                .min_by_key(Findings::error_count);
+            if let Some(best) = best {
+                out.items.extend(best.items);
+            }
+        }
+    }
+}
+
+fn check_range(n: f64, min: Option<f64>, max: Option<f64>, path: &[Segment], out: &mut Findings) {
+    match (min, max) {
+        (Some(min), Some(max)) if n < min || n > max => {
+            out.error(path, format!("{} is out of range ({} to {})", n, min, max))
+        }
+        (Some(min), None) if n < min => {
+            out.error(path, format!("{} is below the minimum of {}", n, min))
+        }
+        _ => {}
+    }
+}
+
+fn walk_fields<'a>(
+    fields: impl Iterator<Item = &'a Field> + Clone,
+    extra_keys: &[&str],
+    entries: &toml::map::Map<String, Value>,
+    path: &[Segment],
+    out: &mut Findings,
+) {
+    for (k, v) in entries {
+        if extra_keys.contains(&k.as_str()) {
+            continue;
+        }
+        match fields.clone().find(|f| f.name == k) {
+            Some(f) => walk(&f.node, v, &key(path, k), out),
+            None => {
+                let names: Vec<&str> = fields
+                    .clone()
+                    .map(|f| f.name)
+                    .chain(extra_keys.iter().copied())
+                    .collect();
+                let message = match suggest(k, names.iter().copied()) {
+                    Some(close) => format!("unknown key (did you mean `{}`?)", close),
+                    None => format!("unknown key (expected one of: {})", names.join(", ")),
+                };
+                out.error(&key(path, k), message);
+            }
+        }
+    }
+    for f in fields.filter(|f| f.required) {
+        if !entries.contains_key(f.name) {
+            out.error(path, format!("missing required key `{}`", f.name));
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Cross-reference checks (on the loaded config)
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn path_of(parts: &[&str]) -> Vec<Segment> {
+    parts.iter().map(|p| Segment::Key(p.to_string())).collect()
+}
+
+fn check_regex(pattern: &str, path: &[Segment], out: &mut Findings) {
+    if let Err(e) = regex::Regex::new(pattern) {
+        // The last line of a regex error is the reason (the rest is a caret diagram)
+        let text = e.to_string();
+        let reason = text.lines().last().unwrap_or_default();
+        out.error(
+            path,
+            format!(
+                "invalid regex `{}`: {}",
+                pattern,
+                reason.trim_start_matches("error: ")
+            ),
+        );
+    }
+}
+
+fn check_auth(auth: &ProviderAuth, path: &[Segment], out: &mut Findings) {
+    if auth.method == AuthMethod::Passthrough {
+        return;
+    }
+    let method = auth.method.as_str();
+    match (&auth.key_env, &auth.key) {
+        (None, None) => out.error(path, format!("`{}` auth needs `key` or `key_env`", method)),
+        (Some(var), fallback) => {
+            let set = std::env::var(var).is_ok_and(|v| !v.is_empty());
+            if !set && fallback.is_none() {
+                out.error(
+                    &key(path, "key_env"),
+                    format!("environment variable `{}` is not set", var),
+                );
+            } else if !set {
+                out.warning(
+                    &key(path, "key_env"),
+                    format!("environment variable `{}` is not set; using `key`", var),
+                );
+            }
+        }
+        (None, Some(_)) => {}
+    }
+    if auth.method == AuthMethod::Header && auth.header_name.is_none() {
+        out.warning(path, "`header` auth without `header_name` sends x-api-key");
+    }
+}
+
+fn cross_check(config: &Config, out: &mut Findings) {
+    // Clients → providers
+    let clients = &config.clients;
+    let mut provider_ids: Vec<&str> = clients.providers.keys().map(String::as_str).collect();
+    provider_ids.sort();
+    let mut client_ids: Vec<&String> = clients.clients.keys().collect();
+    client_ids.sort();
+    for id in client_ids {
+        let client = &clients.clients[id];
+        let path = path_of(&["clients", id]);
+        if !clients.providers.contains_key(&client.provider) {
+            let message = if provider_ids.is_empty() {
+                format!(
+                    "unknown provider `{}` (no [providers] defined)",
+                    client.provider
+                )
+            } else {
+                let hint = suggest(&client.provider, provider_ids.iter().copied())
+                    .map(|p| format!("did you mean `{}`?", p))
+                    .unwrap_or_else(|| format!("defined: {}", provider_ids.join(", ")));
+                format!("unknown provider `{}` ({})", client.provider, hint)
+            };
+            out.error(&key(&path, "provider"), message);
+        }
+        if let Some(auth) = &client.auth {
+            check_auth(auth, &key(&path, "auth"), out);
+        }
+    }
+    for id in &provider_ids {
+        let provider = &clients.providers[*id];
+        let path = path_of(&["providers", id]);
+        if !provider.base_url.starts_with("http://") && !provider.base_url.starts_with("https://") {
+            out.error(
+                &key(&path, "base_url"),
+                format!("`{}` is not an http(s) URL", provider.base_url),
+            );
+        }
+        if !clients.clients.values().any(|c| &c.provider == id) {
+            out.warning(&path, "not used by any client");
+        }
+        if let Some(auth) = &provider.auth {
+            check_auth(auth, &key(&path, "auth"), out);
+        }
+    }
+
+    if !config.api_url.starts_with("http://") && !config.api_url.starts_with("https://") {
+        out.error(
+            &path_of(&["api_url"]),
+            format!("`{}` is not an http(s) URL", config.api_url),
+        );
+    }
+
+    // Regexes
+    let transformers = &config.transformers;
+    if let Some(tag_editor) = &transformers.tag_editor {
+        for (i, rule) in tag_editor.rules.iter().enumerate() {
+            let path = index(&path_of(&["transformers", "tag-editor", "rules"]), i);
+            match rule {
+                RuleConfig::Remove { pattern, .. } | RuleConfig::Replace { pattern, .. } => {
+                    check_regex(pattern, &key(&path, "pattern"), out)
+                }
+                RuleConfig::Inject { position, .. } => match position {
+                    PositionConfig::Before { pattern } => check_regex(
+                        pattern,
+                        &path_of_in(&path, &["position", "before", "pattern"]),
+                        out,
+                    ),
+                    PositionConfig::After { pattern } => check_regex(
+                        pattern,
+                        &path_of_in(&path, &["position", "after", "pattern"]),
+                        out,
+                    ),
+                    PositionConfig::Start | PositionConfig::End => {}
+                },
+            }
+        }
+    }
+    if let Some(enricher) = &transformers.context_enricher {
+        for (i, pattern) in enricher.patterns.iter().enumerate() {
+            let path = index(
+                &path_of(&["transformers", "context-enricher", "patterns"]),
+                i,
+            );
+            check_regex(pattern, &path, out);
+        }
+    }
+    let response = &config.response_transformers;
+    if let Some(redactor) = &response.redactor {
+        for (i, pattern) in redactor.patterns.iter().enumerate() {
+            let path = index(
+                &path_of(&["response_transformers", "redactor", "patterns"]),
+                i,
+            );
+            check_regex(pattern, &path, out);
+        }
+    }
+    if let Some(guard) = &response.tool_guard {
+        for (i, rule) in guard.rules.iter().enumerate() {
+            let path = index(
+                &path_of(&["response_transformers", "tool-guard", "rules"]),
+                i,
+            );
+            check_regex(&rule.pattern, &key(&path, "pattern"), out);
+        }
+    }
+    for (i, rule) in config.policy.rules.iter().enumerate() {
+        let path = index(&path_of(&["policy", "rules"]), i);
+        for (j, command) in rule.commands.iter().enumerate() {
+            check_regex(command, &index(&key(&path, "commands"), j), out);
+        }
+    }
+
+    // Key chords and conflicts
+    for problem in Keymap::from_config(&config.keybindings).1 {
+        out.error(&path_of(&["keybindings", &problem.action]), problem.message);
+    }
+
+    // Alert sinks
+    for (i, rule) in config.alerts.iter().enumerate() {
+        if let Err(e) = rule.validate() {
+            out.error(&index(&path_of(&["alerts"]), i), e.to_string());
+        }
+    }
+
+    // Switched on but won't run
+    let sub_enabled = [
+        (
+            "tag-editor",
+            transformers.tag_editor.as_ref().is_some_and(|t| t.enabled),
+        ),
+        (
+            "compact-enhancer",
+            transformers
+                .compact_enhancer
+                .as_ref()
+                .is_some_and(|t| t.enabled),
+        ),
+        (
+            "context-enricher",
+            transformers
+                .context_enricher
+                .as_ref()
+                .is_some_and(|t| t.enabled),
+        ),
+    ];
+    if !transformers.enabled {
+        for (name, _) in sub_enabled.iter().filter(|(_, on)| *on) {
+            out.warning(
+                &path_of(&["transformers", name, "enabled"]),
+                "has no effect while [transformers] enabled = false",
+            );
+        }
+    }
+    if !config.lifestats.enabled {
+        if config.handoff.enabled {
+            out.warning(
+                &path_of(&["handoff", "enabled"]),
+                "requires [lifestats] enabled = true",
+            );
+        }
+        if sub_enabled[2].1 {
+            out.warning(
+                &path_of(&["transformers", "context-enricher", "enabled"]),
+                "requires [lifestats] enabled = true",
+            );
+        }
+    }
+}
+
+fn path_of_in(base: &[Segment], parts: &[&str]) -> Vec<Segment> {
+    let mut path = base.to_vec();
+    path.extend(path_of(parts));
+    path
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Source locations
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Span of the deepest part of `path` found in the document
+fn locate(doc: &toml_edit::ImDocument<&str>, path: &[Segment]) -> Option<Range<usize>> {
+    enum Cursor<'a> {
+        Item(&'a toml_edit::Item),
+        Table(&'a toml_edit::Table),
+        Value(&'a toml_edit::Value),
+    }
+
+    let mut cursor = Cursor::Item(doc.as_item());
+    let mut span = None;
+    for segment in path {
+        let next = match segment {
+            Segment::Key(k) => {
+                let table: Option<&dyn toml_edit::TableLike> = match cursor {
+                    Cursor::Item(item) => item.as_table_like(),
+                    Cursor::Table(table) => Some(table),
+                    Cursor::Value(value) => value
+                        .as_inline_table()
+                        .map(|t| t as &dyn toml_edit::TableLike),
+                };
+                table
+                    .and_then(|t| t.get_key_value(k))
+                    .map(|(key, item)| (Cursor::Item(item), key.span().or_else(|| item.span())))
+            }
+            Segment::Index(i) => match cursor {
+                Cursor::Item(item) => match item.as_array_of_tables() {
+                    Some(tables) => tables.get(*i).map(|t| (Cursor::Table(t), t.span())),
+                    None => item
+                        .as_array()
+                        .and_then(|a| a.get(*i))
+                        .map(|v| (Cursor::Value(v), v.span())),
+                },
+                Cursor::Value(value) => value
+                    .as_array()
+                    .and_then(|a| a.get(*i))
+                    .map(|v| (Cursor::Value(v), v.span())),
+                Cursor::Table(_) => None,
+            },
+        };
+        let Some((next, next_span)) = next else {
+            break;
+        };
+        cursor = next;
+        span = next_span.or(span);
+    }
+    span
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Entry points
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Validate config file contents, errors first then in file order
+pub fn check(source: &str) -> Vec<Diagnostic> {
+    let doc = match toml_edit::ImDocument::parse(source) {
+        Ok(doc) => doc,
+        Err(e) => {
+            let line = e.span().map(|span| line_of(source, span.start));
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                path: String::new(),
+                line,
+                message: format!("TOML syntax: {}", e.message()),
+            }];
+        }
+    };
+    let value: Value = match toml::from_str(source) {
+        Ok(value) => value,
+        Err(e) => {
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                path: String::new(),
+                line: e.span().map(|span| line_of(source, span.start)),
+                message: format!("TOML syntax: {}", e.message()),
+            }]
+        }
+    };
+
+    let mut findings = Findings::default();
+    walk(&schema(), &value, &[], &mut findings);
+
+    // Only a structurally valid file can be loaded for cross-checks
+    if findings.error_count() == 0 {
+        match Config::from_value(value) {
+            Ok(config) => cross_check(&config, &mut findings),
+            Err(e) => findings.error(&[], format!("{:#}", e)),
+        }
+    }
+
+    let mut diagnostics: Vec<Diagnostic> = findings
+        .items
+        .into_iter()
+        .map(|(severity, path, message)| Diagnostic {
+            severity,
+            line: locate(&doc, &path).map(|span| line_of(source, span.start)),
+            path: display_path(&path),
+            message,
+        })
+        .collect();
+    diagnostics.sort_by_key(|d| (d.severity == Severity::Warning, d.line.unwrap_or(0)));
+    diagnostics
+}
+
+/// Read config.toml and validate it
+///
+/// Returns the file contents (`None` when there is no file) with their
+/// diagnostics. Startup and hot reload both go through here.
+pub fn check_config_file() -> anyhow::Result<(Option<String>, Vec<Diagnostic>)> {
+    use anyhow::Context;
+
+    let Some(path) = Config::config_path() else {
+        return Ok((None, Vec::new()));
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(source) => {
+            let diagnostics = check(&source);
+            Ok((Some(source), diagnostics))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok((None, Vec::new())),
+        Err(e) => Err(e).with_context(|| format!("Cannot read {}", path.display())),
+    }
+}
+
+/// Log each diagnostic as a warning
+pub fn log_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        tracing::warn!("config.toml {}", diagnostic.located());
+    }
+}
+
+/// JSON Schema (draft-07) for config.toml
+pub fn json_schema() -> JsonValue {
+    let mut root = to_json(&schema());
+    if let Some(object) = root.as_object_mut() {
+        object.insert(
+            "$schema".to_string(),
+            json!("http://json-schema.org/draft-07/schema#"),
+        );
+        object.insert("title".to_string(), json!("aspy config.toml"));
+    }
+    root
+}
+
+fn fields_to_json<'a>(
+    fields: impl Iterator<Item = &'a Field>,
+) -> (Map<String, JsonValue>, Vec<&'a str>) {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for f in fields {
+        properties.insert(f.name.to_string(), to_json(&f.node));
+        if f.required {
+            required.push(f.name);
+        }
+    }
+    (properties, required)
+}
+
+fn to_json(node: &Node) -> JsonValue {
+    let mut schema = match &node.shape {
+        Shape::Bool => json!({ "type": "boolean" }),
+        Shape::String => json!({ "type": "string" }),
+        Shape::Integer { min, max } => {
+            let mut s = json!({ "type": "integer" });
+            if let Some(min) = min {
+                s["minimum"] = json!(min);
+            }
+            if let Some(max) = max {
+                s["maximum"] = json!(max);
+            }
+            s
+        }
+        Shape::Number { min, max } => {
+            let mut s = json!({ "type": "number" });
+            if let Some(min) = min {
+                s["minimum"] = json!(min);
+            }
+            if let Some(max) = max {
+                s["maximum"] = json!(max);
+            }
+            s
+        }
+        Shape::Enum(values) => json!({ "type": "string", "enum": values }),
+        // Built-ins complete in editors; any other name may be a user file
+        Shape::Named { builtin, .. } => json!({
+            "anyOf": [{ "type": "string", "enum": builtin }, { "type": "string" }]
+        }),
+        Shape::Array(item) => json!({ "type": "array", "items": to_json(item) }),
+        Shape::Map(item) => json!({ "type": "object", "additionalProperties": to_json(item) }),
+        Shape::Table(fields) => {
+            let (properties, required) = fields_to_json(fields.iter());
+            let mut s = json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            });
+            if !required.is_empty() {
+                s["required"] = json!(required);
+            }
+            s
+        }
+        Shape::Tagged {
+            tag,
+            common,
+            variants,
+        } => {
+            let one_of: Vec<JsonValue> = variants
+                .iter()
+                .map(|(name, fields)| {
+                    let (mut properties, mut required) =
+                        fields_to_json(common.iter().chain(fields.iter()));
+                    properties.insert(tag.to_string(), json!({ "const": name }));
+                    required.insert(0, tag);
+                    json!({
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                        "additionalProperties": false,
+                    })
+                })
+                .collect();
+            json!({ "type": "object", "oneOf": one_of })
+        }
+        Shape::OneOf(options) => {
+            json!({ "oneOf": options.iter().map(to_json).collect::<Vec<_>>() })
+        }
+    };
+    if !node.doc.is_empty() {
+        schema["description"] = json!(node.doc);
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<Diagnostic> {
+        check(source)
+            .into_iter()
+            .filter(|d| d.severity == Severity::Error)
+            .collect()
+    }
+
+    /// The generated default file is checked at startup and on every reload,
+    /// so it must not produce a single diagnostic (unknown keys included)
+    #[test]
+    fn test_default_config_is_clean() {
+        let diagnostics = check(&Config::default().to_toml());
+        assert!(
+            !diagnostics
+                .iter()
+                .any(|d| d.message.starts_with("unknown key")),
+            "{:?}",
+            diagnostics
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    /// Every section and key the config supports must pass the schema
+    #[test]
+    fn test_full_config_is_clean() {
+        let source = r#"
+theme = "Dracula"
+preset = "reasoning"
+context_limit = 150000
+
+[augmentation]
+context_warning_thresholds = [80, 95]
+
+[lifestats]
+enabled = true
+
+[handoff]
+enabled = true
+
+[keybindings]
+copy_jsonl = "J"
+toggle_zoom = ["z", "ctrl+z"]
+view_history = []
+
+[translation.model_mapping]
+"gpt-4" = "claude-sonnet-4-20250514"
+
+[transformers]
+enabled = true
+
+[transformers.tag-editor]
+enabled = true
+
+[[transformers.tag-editor.rules]]
+type = "inject"
+tag = "aspy-context"
+content = "hi"
+position = { before = { pattern = "^Note" } }
+when = { turn_number = "=1" }
+
+[[transformers.tag-editor.rules]]
+type = "replace"
+tag = "system-reminder"
+pattern = "version (\\d+)"
+replacement = "v$1"
+
+[transformers.compact-enhancer]
+enabled = true
+
+[transformers.context-enricher]
+enabled = true
+patterns = ["(?i)last time"]
+
+[response_transformers.tool-guard]
+enabled = true
+[[response_transformers.tool-guard.rules]]
+tool = "Bash"
+pattern = "rm\\s+-rf"
+action = "block"
+
+[[alerts]]
+name = "Context"
+type = "context_percent"
+above = 85
+sinks = ["toast", "bell"]
+
+[policy]
+enabled = true
+mode = "remind"
+[[policy.rules]]
+name = "no-rm"
+effect = "deny"
+tools = ["Bash"]
+commands = ["rm\\s+-rf\\s+/"]
+
+[clients.dev]
+name = "Dev"
+provider = "anthropic"
+
+[providers.anthropic]
+base_url = "https://api.anthropic.com"
+api_format = "anthropic"
+auth = { method = "x_api_key", key = "sk-test" }
+"#;
+        let diagnostics = check(source);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_typos_point_to_lines() {
+        let source = "theme = \"Nord\"\n\
+            preset = \"reasonin\"\n\
+            \n\
+            [transformers.tag-editor]\n\
+            enabled = true\n\
+            [[transformers.tag-editor.rules]]\n\
+            type = \"remove\"\n\
+            tag = \"system-reminder\"\n\
+            patern = \"debug\"\n\
+            \n\
+            [augmentation]\n\
+            context_warning_thresholds = [80, 120]\n";
+        let diagnostics = errors(source);
+
+        let find = |path: &str| {
+            diagnostics
+                .iter()
+                .find(|d| d.path == path)
+                .unwrap_or_else(|| panic!("no diagnostic for {}: {:?}", path, diagnostics))
+        };
+        let preset = find("preset");
+        assert_eq!(preset.line, Some(2));
+        assert!(preset.message.contains("did you mean `reasoning`"));
+
+        let typo = find("transformers.tag-editor.rules[0].patern");
+        assert_eq!(typo.line, Some(9));
+        assert!(typo.message.contains("did you mean `pattern`"));
+
+        let missing = find("transformers.tag-editor.rules[0]");
+        assert_eq!(missing.line, Some(6));
+        assert!(missing.message.contains("missing required key `pattern`"));
+
+        let threshold = find("augmentation.context_warning_thresholds[1]");
+        assert_eq!(threshold.line, Some(12));
+    }
+
+    #[test]
+    fn test_cross_references() {
+        let source = "[clients.dev]\n\
+            name = \"Dev\"\n\
+            provider = \"anthropc\"\n\
+            \n\
+            [providers.anthropic]\n\
+            base_url = \"https://api.anthropic.com\"\n\
+            auth = { method = \"bearer\", key_env = \"ASPY_TEST_UNSET_KEY\" }\n\
+            \n\
+            [response_transformers.redactor]\n\
+            patterns = [\"sk-(\"]\n\
+            \n\
+            [keybindings]\n\
+            copy_readable = [\"y\", \"ctrl+\"]\n\
+            help = \"q\"\n";
+        let diagnostics = errors(source);
+        let has = |line: usize, text: &str| {
+            diagnostics
+                .iter()
+                .any(|d| d.line == Some(line) && d.to_string().contains(text))
+        };
+
+        assert!(
+            has(3, "unknown provider `anthropc` (did you mean `anthropic`?)"),
+            "{:?}",
+            diagnostics
+        );
+        assert!(has(7, "ASPY_TEST_UNSET_KEY"), "{:?}", diagnostics);
+        assert!(has(10, "invalid regex"), "{:?}", diagnostics);
+        assert!(has(13, "empty key"), "{:?}", diagnostics);
+        assert!(has(14, "`q` is also bound to quit"), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_json_schema_shape() {
+        let schema = json_schema();
+        assert_eq!(schema["additionalProperties"], json!(false));
+        let rules = &schema["properties"]["transformers"]["properties"]["tag-editor"]["properties"]
+            ["rules"]["items"];
+        assert_eq!(rules["oneOf"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            schema["properties"]["logging"]["properties"]["file_rotation"]["enum"],
+            json!(["hourly", "daily", "never"])
+        );
+    }
+}
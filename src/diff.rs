@@ -0,0 +1,847 @@
+//! Structural diffs between requests and between sessions
+//!
+//! Answers "why did this request cost 3x more than the last one?" by
+//! comparing two Anthropic request bodies section by section:
+//!
+//! - **Parameters**: model, max_tokens, thinking, tool_choice, ... (any
+//!   top-level key other than the three below)
+//! - **System prompt**: blocks compared by position
+//! - **Tools**: matched by name - added, removed, or changed definitions
+//! - **Messages**: the shared prefix is skipped; what follows is listed as
+//!   removed (only in A) and added (only in B)
+//! - **Tokens**: estimated per section, with deltas
+//!
+//! `cache_control` markers are ignored - Claude Code moves them every turn,
+//! which would otherwise mark every message as changed.
+//!
+//! Session diffs compare two JSON summaries of aggregate stats with the same
+//! [`json_diff`], so the TUI (past sessions) and `/api/diff` (live sessions)
+//! can each supply whatever numbers they have.
+//!
+//! [`unified_diff`] is a plain line diff for text (Edit tool `old_string` vs
+//! `new_string` in the detail view).
+
+use crate::tokens::{estimate_json_tokens, estimate_tokens};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Characters kept when summarizing a message or value
+const SUMMARY_CHARS: usize = 100;
+
+/// Largest LCS table for a line diff (old lines x new lines, after trimming
+/// the common prefix and suffix); bigger inputs diff as one replacement
+const LCS_CELLS: usize = 1 << 20;
+
+/// How a value differs between A and B
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Added => "+",
+            Self::Removed => "-",
+            Self::Changed => "~",
+        }
+    }
+}
+
+/// One differing leaf (or whole subtree, for added/removed keys)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JsonChange {
+    /// Dotted path, array indices in brackets: `thinking.budget_tokens`, `[2].text`
+    pub path: String,
+    pub kind: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+/// Structural diff of two JSON values
+///
+/// Objects are compared by key, arrays by index; anything else is a leaf.
+pub fn json_diff(a: &Value, b: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_into(&mut changes, String::new(), a, b);
+    changes
+}
+
+fn join_key(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn diff_into(changes: &mut Vec<JsonChange>, path: String, a: &Value, b: &Value) {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, av) in a {
+                let child = join_key(&path, key);
+                match b.get(key) {
+                    Some(bv) => diff_into(changes, child, av, bv),
+                    None => changes.push(JsonChange {
+                        path: child,
+                        kind: ChangeKind::Removed,
+                        before: Some(av.clone()),
+                        after: None,
+                    }),
+                }
+            }
+            for (key, bv) in b {
+                if !a.contains_key(key) {
+                    changes.push(JsonChange {
+                        path: join_key(&path, key),
+                        kind: ChangeKind::Added,
+                        before: None,
+                        after: Some(bv.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{}[{}]", path, i);
+                match (a.get(i), b.get(i)) {
+                    (Some(av), Some(bv)) => diff_into(changes, child, av, bv),
+                    (Some(av), None) => changes.push(JsonChange {
+                        path: child,
+                        kind: ChangeKind::Removed,
+                        before: Some(av.clone()),
+                        after: None,
+                    }),
+                    (None, Some(bv)) => changes.push(JsonChange {
+                        path: child,
+                        kind: ChangeKind::Added,
+                        before: None,
+                        after: Some(bv.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if a != b => changes.push(JsonChange {
+            path,
+            kind: ChangeKind::Changed,
+            before: Some(a.clone()),
+            after: Some(b.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Copy of a value without `cache_control` keys (at any depth)
+fn strip_cache_control(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(k, _)| k.as_str() != "cache_control")
+                .map(|(k, v)| (k.clone(), strip_cache_control(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(strip_cache_control).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Estimated tokens per request section
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionTokens {
+    pub section: &'static str,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl SectionTokens {
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+/// A system prompt block that differs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemChange {
+    pub index: usize,
+    pub kind: ChangeKind,
+    pub tokens_before: u64,
+    pub tokens_after: u64,
+    /// Start of the block's text (B's, unless removed)
+    pub summary: String,
+}
+
+/// A tool definition that differs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolChange {
+    pub name: String,
+    pub kind: ChangeKind,
+    /// Definition changes (only for `Changed`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<JsonChange>,
+}
+
+/// A message only present in one of the requests
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MessageSummary {
+    pub index: usize,
+    pub role: String,
+    pub tokens: u64,
+    /// Content block types and the start of the text
+    pub summary: String,
+}
+
+/// Section-by-section comparison of two request bodies
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RequestDiff {
+    /// Top-level parameter changes (model, max_tokens, thinking, ...)
+    pub parameters: Vec<JsonChange>,
+    pub system: Vec<SystemChange>,
+    pub tools: Vec<ToolChange>,
+    /// Messages identical in both (from the start)
+    pub messages_shared: usize,
+    pub messages_removed: Vec<MessageSummary>,
+    pub messages_added: Vec<MessageSummary>,
+    pub tokens: Vec<SectionTokens>,
+}
+
+impl RequestDiff {
+    /// Compare request A (before) with request B (after)
+    pub fn between(a: &Value, b: &Value) -> Self {
+        let a = strip_cache_control(a);
+        let b = strip_cache_control(b);
+
+        let (system_a, system_b) = (system_blocks(&a), system_blocks(&b));
+        let (tools_a, tools_b) = (tool_map(&a), tool_map(&b));
+        let (messages_a, messages_b) = (array(&a, "messages"), array(&b, "messages"));
+
+        let messages_shared = messages_a
+            .iter()
+            .zip(messages_b)
+            .take_while(|(x, y)| x == y)
+            .count();
+
+        let tokens = vec![
+            SectionTokens {
+                section: "system",
+                before: system_a.iter().map(|s| estimate_tokens(s) as u64).sum(),
+                after: system_b.iter().map(|s| estimate_tokens(s) as u64).sum(),
+            },
+            SectionTokens {
+                section: "tools",
+                before: tools_a
+                    .values()
+                    .map(|t| estimate_json_tokens(t) as u64)
+                    .sum(),
+                after: tools_b
+                    .values()
+                    .map(|t| estimate_json_tokens(t) as u64)
+                    .sum(),
+            },
+            SectionTokens {
+                section: "messages",
+                before: messages_a
+                    .iter()
+                    .map(|m| estimate_json_tokens(m) as u64)
+                    .sum(),
+                after: messages_b
+                    .iter()
+                    .map(|m| estimate_json_tokens(m) as u64)
+                    .sum(),
+            },
+        ];
+
+        Self {
+            parameters: json_diff(&parameters(&a), &parameters(&b)),
+            system: diff_system(&system_a, &system_b),
+            tools: diff_tools(&tools_a, &tools_b),
+            messages_shared,
+            messages_removed: summarize_messages(messages_a, messages_shared),
+            messages_added: summarize_messages(messages_b, messages_shared),
+            tokens,
+        }
+    }
+
+    /// Whether the requests are identical (ignoring cache markers)
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+            && self.system.is_empty()
+            && self.tools.is_empty()
+            && self.messages_removed.is_empty()
+            && self.messages_added.is_empty()
+    }
+
+    /// Estimated total tokens before and after
+    pub fn total_tokens(&self) -> (u64, u64) {
+        self.tokens
+            .iter()
+            .fold((0, 0), |(a, b), s| (a + s.before, b + s.after))
+    }
+
+    /// Render as Markdown (TUI modal)
+    pub fn to_markdown(&self, label_a: &str, label_b: &str) -> String {
+        let mut md = format!(
+            "## ⇄ Request Diff\n\n**A:** {}  \n**B:** {}\n\n",
+            label_a, label_b
+        );
+
+        let (before, after) = self.total_tokens();
+        md.push_str("### Tokens (estimated)\n\n");
+        for s in &self.tokens {
+            md.push_str(&format!(
+                "- **{}:** {} → {} ({})\n",
+                s.section,
+                s.before,
+                s.after,
+                signed(s.delta())
+            ));
+        }
+        md.push_str(&format!(
+            "- **total:** {} → {} ({})\n\n",
+            before,
+            after,
+            signed(after as i64 - before as i64)
+        ));
+
+        if self.is_empty() {
+            md.push_str("_No differences (ignoring cache_control markers)._\n");
+            return md;
+        }
+
+        if !self.parameters.is_empty() {
+            md.push_str("### Parameters\n\n");
+            for c in &self.parameters {
+                md.push_str(&format_change(c));
+            }
+            md.push('\n');
+        }
+
+        if !self.system.is_empty() {
+            md.push_str("### System Prompt\n\n");
+            for s in &self.system {
+                md.push_str(&format!(
+                    "- `{}` block {}: {} → {} tokens — {}\n",
+                    s.kind.symbol(),
+                    s.index,
+                    s.tokens_before,
+                    s.tokens_after,
+                    s.summary
+                ));
+            }
+            md.push('\n');
+        }
+
+        if !self.tools.is_empty() {
+            md.push_str("### Tools\n\n");
+            for t in &self.tools {
+                md.push_str(&format!("- `{}` **{}**\n", t.kind.symbol(), t.name));
+                for c in &t.changes {
+                    md.push_str("  ");
+                    md.push_str(&format_change(c));
+                }
+            }
+            md.push('\n');
+        }
+
+        md.push_str(&format!(
+            "### Messages\n\n{} shared message(s)\n\n",
+            self.messages_shared
+        ));
+        for (symbol, messages) in [("-", &self.messages_removed), ("+", &self.messages_added)] {
+            for m in messages {
+                md.push_str(&format!(
+                    "- `{}` [{}] **{}** ({} tokens) {}\n",
+                    symbol, m.index, m.role, m.tokens, m.summary
+                ));
+            }
+        }
+        md
+    }
+}
+
+/// Top-level keys other than the sections diffed separately
+fn parameters(body: &Value) -> Value {
+    match body {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(k, _)| !matches!(k.as_str(), "system" | "tools" | "messages"))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        ),
+        _ => Value::Object(Map::new()),
+    }
+}
+
+fn array<'a>(body: &'a Value, key: &str) -> &'a [Value] {
+    body.get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// System prompt as text blocks (a plain string is one block)
+fn system_blocks(body: &Value) -> Vec<String> {
+    match body.get("system") {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .map(|b| match b.get("text").and_then(Value::as_str) {
+                Some(text) => text.to_string(),
+                None => b.to_string(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn tool_map(body: &Value) -> BTreeMap<String, Value> {
+    array(body, "tools")
+        .iter()
+        .map(|t| {
+            let name = t
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("(unnamed)")
+                .to_string();
+            (name, t.clone())
+        })
+        .collect()
+}
+
+fn diff_system(a: &[String], b: &[String]) -> Vec<SystemChange> {
+    (0..a.len().max(b.len()))
+        .filter_map(|i| {
+            let (kind, text) = match (a.get(i), b.get(i)) {
+                (Some(x), Some(y)) if x == y => return None,
+                (Some(_), Some(y)) => (ChangeKind::Changed, y),
+                (Some(x), None) => (ChangeKind::Removed, x),
+                (None, Some(y)) => (ChangeKind::Added, y),
+                (None, None) => return None,
+            };
+            Some(SystemChange {
+                index: i,
+                kind,
+                tokens_before: a.get(i).map_or(0, |s| estimate_tokens(s) as u64),
+                tokens_after: b.get(i).map_or(0, |s| estimate_tokens(s) as u64),
+                summary: excerpt(text),
+            })
+        })
+        .collect()
+}
+
+fn diff_tools(a: &BTreeMap<String, Value>, b: &BTreeMap<String, Value>) -> Vec<ToolChange> {
+    let removed = a
+        .keys()
+        .filter(|name| !b.contains_key(*name))
+        .map(|name| ToolChange {
+            name: name.clone(),
+            kind: ChangeKind::Removed,
+            changes: Vec::new(),
+        });
+    let added_or_changed = b.iter().filter_map(|(name, tb)| match a.get(name) {
+        None => Some(ToolChange {
+            name: name.clone(),
+            kind: ChangeKind::Added,
+            changes: Vec::new(),
+        }),
+        Some(ta) if ta != tb => Some(ToolChange {
+            name: name.clone(),
+            kind: ChangeKind::Changed,
+            changes: json_diff(ta, tb),
+        }),
+        Some(_) => None,
+    });
+    removed.chain(added_or_changed).collect()
+}
+
+fn summarize_messages(messages: &[Value], skip: usize) -> Vec<MessageSummary> {
+    messages
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(index, m)| MessageSummary {
+            index,
+            role: m
+                .get("role")
+                .and_then(Value::as_str)
+                .unwrap_or("?")
+                .to_string(),
+            tokens: estimate_json_tokens(m) as u64,
+            summary: summarize_content(m.get("content")),
+        })
+        .collect()
+}
+
+/// `tool_use Bash, tool_result · "first text..."`
+fn summarize_content(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => excerpt(text),
+        Some(Value::Array(blocks)) => {
+            let mut kinds = Vec::new();
+            let mut text = None;
+            for block in blocks {
+                let kind = block.get("type").and_then(Value::as_str).unwrap_or("?");
+                match (kind, block.get("name").and_then(Value::as_str)) {
+                    ("tool_use", Some(name)) => kinds.push(format!("tool_use {}", name)),
+                    _ => kinds.push(kind.to_string()),
+                }
+                if text.is_none() {
+                    text = block.get("text").and_then(Value::as_str);
+                }
+            }
+            kinds.dedup();
+            match text {
+                Some(text) => format!("{} · {}", kinds.join(", "), excerpt(text)),
+                None => kinds.join(", "),
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+/// First line of text, shortened to `SUMMARY_CHARS`
+fn excerpt(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    let mut short: String = line.chars().take(SUMMARY_CHARS).collect();
+    if short.len() < line.len() {
+        short.push('…');
+    }
+    format!("\"{}\"", short)
+}
+
+fn signed(n: i64) -> String {
+    if n > 0 {
+        format!("+{}", n)
+    } else {
+        n.to_string()
+    }
+}
+
+fn short_value(value: &Value) -> String {
+    let text = value.to_string();
+    let mut short: String = text.chars().take(SUMMARY_CHARS).collect();
+    if short.len() < text.len() {
+        short.push('…');
+    }
+    short
+}
+
+/// Markdown list item for one JSON change
+fn format_change(c: &JsonChange) -> String {
+    let path = if c.path.is_empty() { "(root)" } else { &c.path };
+    match (&c.before, &c.after) {
+        (Some(before), Some(after)) => {
+            // Numbers get a delta, the usual case for stats and budgets
+            let delta = match (before.as_f64(), after.as_f64()) {
+                (Some(x), Some(y)) => format!(" ({})", format_delta(y - x)),
+                _ => String::new(),
+            };
+            format!(
+                "- `~` `{}`: {} → {}{}\n",
+                path,
+                short_value(before),
+                short_value(after),
+                delta
+            )
+        }
+        (None, Some(after)) => format!("- `+` `{}`: {}\n", path, short_value(after)),
+        (Some(before), None) => format!("- `-` `{}`: {}\n", path, short_value(before)),
+        (None, None) => String::new(),
+    }
+}
+
+fn format_delta(delta: f64) -> String {
+    if delta.fract() == 0.0 {
+        signed(delta as i64)
+    } else if delta > 0.0 {
+        format!("+{:.4}", delta)
+    } else {
+        format!("{:.4}", delta)
+    }
+}
+
+/// Comparison of two sessions' aggregate stats
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionDiff {
+    pub a: String,
+    pub b: String,
+    pub changes: Vec<JsonChange>,
+}
+
+impl SessionDiff {
+    /// Compare stat summaries (any JSON object; numbers get deltas)
+    pub fn between(a: &str, summary_a: &Value, b: &str, summary_b: &Value) -> Self {
+        Self {
+            a: a.to_string(),
+            b: b.to_string(),
+            changes: json_diff(summary_a, summary_b),
+        }
+    }
+
+    /// Render as Markdown (TUI modal)
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "## ⇄ Session Diff\n\n**A:** {}  \n**B:** {}\n\n",
+            self.a, self.b
+        );
+        if self.changes.is_empty() {
+            md.push_str("_Identical stats._\n");
+        }
+        for c in &self.changes {
+            md.push_str(&format_change(c));
+        }
+        md
+    }
+}
+
+/// One line of a line diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineOp<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff of two texts in unified format (`@@ -1,3 +1,4 @@` hunks with
+/// `context` unchanged lines around each change, no file headers)
+///
+/// Returns an empty string when the texts have the same lines.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let ops = line_ops(&old, &new);
+
+    let changed: Vec<usize> = (0..ops.len())
+        .filter(|&i| !matches!(ops[i], LineOp::Same(_)))
+        .collect();
+    let Some(&first) = changed.first() else {
+        return String::new();
+    };
+
+    // Group changes whose context would touch into one hunk
+    let mut hunks = vec![(first, first)];
+    for &i in &changed[1..] {
+        let last = hunks.last_mut().expect("hunks start non-empty");
+        if i - last.1 <= 2 * context + 1 {
+            last.1 = i;
+        } else {
+            hunks.push((i, i));
+        }
+    }
+
+    let mut out = String::new();
+    for (first, last) in hunks {
+        let start = first.saturating_sub(context);
+        let end = (last + context + 1).min(ops.len());
+        let count = |range: &[LineOp], old_side: bool| {
+            range
+                .iter()
+                .filter(|op| match op {
+                    LineOp::Same(_) => true,
+                    LineOp::Removed(_) => old_side,
+                    LineOp::Added(_) => !old_side,
+                })
+                .count()
+        };
+        let (old_before, new_before) = (count(&ops[..start], true), count(&ops[..start], false));
+        let (old_len, new_len) = (
+            count(&ops[start..end], true),
+            count(&ops[start..end], false),
+        );
+        // An empty side points at the line before it, as in `diff -u`
+        let line_no = |before: usize, len: usize| if len == 0 { before } else { before + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            line_no(old_before, old_len),
+            old_len,
+            line_no(new_before, new_len),
+            new_len
+        ));
+        for op in &ops[start..end] {
+            let (marker, line) = match op {
+                LineOp::Same(line) => (' ', line),
+                LineOp::Removed(line) => ('-', line),
+                LineOp::Added(line) => ('+', line),
+            };
+            out.push(marker);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Lines of `old` and `new` as kept/removed/added, via a longest common
+/// subsequence of the part between the shared prefix and suffix
+fn line_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<LineOp<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut ops: Vec<LineOp> = old[..prefix].iter().map(|l| LineOp::Same(l)).collect();
+    if a.len() * b.len() > LCS_CELLS {
+        ops.extend(a.iter().map(|l| LineOp::Removed(l)));
+        ops.extend(b.iter().map(|l| LineOp::Added(l)));
+    } else {
+        // lcs[i][j] = LCS length of a[i..] and b[j..]
+        let width = b.len() + 1;
+        let mut lcs = vec![0u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                ops.push(LineOp::Same(a[i]));
+                i += 1;
+                j += 1;
+            } else if i < a.len()
+                && (j == b.len() || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+            {
+                ops.push(LineOp::Removed(a[i]));
+                i += 1;
+            } else {
+                ops.push(LineOp::Added(b[j]));
+                j += 1;
+            }
+        }
+    }
+    ops.extend(old[old.len() - suffix..].iter().map(|l| LineOp::Same(l)));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_diff_paths() {
+        let a = json!({"model": "sonnet", "thinking": {"budget_tokens": 1000}, "stop": ["a"]});
+        let b = json!({"model": "opus", "thinking": {"budget_tokens": 4000}, "stop": ["a", "b"], "temperature": 0.5});
+        let changes = json_diff(&a, &b);
+        let paths: Vec<(&str, ChangeKind)> =
+            changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+        assert_eq!(
+            paths,
+            [
+                ("model", ChangeKind::Changed),
+                ("stop[1]", ChangeKind::Added),
+                ("thinking.budget_tokens", ChangeKind::Changed),
+                ("temperature", ChangeKind::Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_request_diff_sections() {
+        let a = json!({
+            "model": "claude-sonnet-4",
+            "max_tokens": 8000,
+            "system": [{"type": "text", "text": "You are helpful.", "cache_control": {"type": "ephemeral"}}],
+            "tools": [{"name": "Read", "input_schema": {}}, {"name": "Bash", "input_schema": {}}],
+            "messages": [
+                {"role": "user", "content": [{"type": "text", "text": "hi", "cache_control": {"type": "ephemeral"}}]},
+                {"role": "assistant", "content": "hello"}
+            ]
+        });
+        let b = json!({
+            "model": "claude-sonnet-4",
+            "max_tokens": 16000,
+            "system": [{"type": "text", "text": "You are helpful."}, {"type": "text", "text": "Project rules:\nbe terse"}],
+            "tools": [{"name": "Read", "input_schema": {"type": "object"}}, {"name": "Grep", "input_schema": {}}],
+            "messages": [
+                {"role": "user", "content": [{"type": "text", "text": "hi"}]},
+                {"role": "assistant", "content": "hello"},
+                {"role": "user", "content": [{"type": "tool_result", "tool_use_id": "t1"}, {"type": "text", "text": "now run tests"}]}
+            ]
+        });
+
+        let diff = RequestDiff::between(&a, &b);
+        assert_eq!(diff.parameters.len(), 1);
+        assert_eq!(diff.parameters[0].path, "max_tokens");
+
+        assert_eq!(diff.system.len(), 1);
+        assert_eq!(diff.system[0].kind, ChangeKind::Added);
+        assert_eq!(diff.system[0].summary, "\"Project rules:\"");
+
+        let tools: Vec<(&str, ChangeKind)> = diff
+            .tools
+            .iter()
+            .map(|t| (t.name.as_str(), t.kind))
+            .collect();
+        assert_eq!(
+            tools,
+            [
+                ("Bash", ChangeKind::Removed),
+                ("Grep", ChangeKind::Added),
+                ("Read", ChangeKind::Changed)
+            ]
+        );
+        assert_eq!(diff.tools[2].changes[0].path, "input_schema.type");
+
+        // cache_control moved off the first message - still shared
+        assert_eq!(diff.messages_shared, 2);
+        assert!(diff.messages_removed.is_empty());
+        assert_eq!(diff.messages_added.len(), 1);
+        assert_eq!(
+            diff.messages_added[0].summary,
+            "tool_result, text · \"now run tests\""
+        );
+
+        let (before, after) = diff.total_tokens();
+        assert!(after > before);
+        assert!(RequestDiff::between(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn test_session_diff_markdown() {
+        let diff = SessionDiff::between(
+            "dev-1",
+            &json!({"cost_usd": 1.5, "tool_calls": 10}),
+            "dev-2",
+            &json!({"cost_usd": 4.5, "tool_calls": 10}),
+        );
+        assert_eq!(diff.changes.len(), 1);
+        assert!(diff
+            .to_markdown()
+            .contains("- `~` `cost_usd`: 1.5 → 4.5 (+3)"));
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(
+            unified_diff(old, new, 1),
+            "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -10,1 +10,2 @@\n j\n+k\n"
+        );
+        // Changes within 2 * context lines share a hunk
+        assert_eq!(
+            unified_diff("x\ny\nz", "X\ny\nZ", 1),
+            "@@ -1,3 +1,3 @@\n-x\n+X\n y\n-z\n+Z\n"
+        );
+        assert_eq!(unified_diff("", "new", 3), "@@ -0,0 +1,1 @@\n+new\n");
+        assert_eq!(unified_diff("same\n", "same", 3), "");
+    }
+}
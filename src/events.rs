@@ -135,6 +135,30 @@ pub enum ProxyEvent {
         modifications: Vec<String>,
     },
 
+    /// Response was rewritten in flight by response transformers
+    ResponseTransformed {
+        timestamp: DateTime<Utc>,
+        /// Name of the transformer
+        transformer: String,
+        /// Human-readable descriptions of modifications made
+        modifications: Vec<String>,
+    },
+
+    /// Policy engine evaluated a tool call or tool definition
+    PolicyDecision {
+        timestamp: DateTime<Utc>,
+        tool_name: String,
+        /// tool_use ID (None for request tool definitions)
+        tool_id: Option<String>,
+        /// "allow" or "deny"
+        decision: String,
+        /// Matching rule (None = policy default)
+        rule: Option<String>,
+        /// How a denial was enforced: "rewrite", "remind", "audit" ("none" for allows)
+        enforcement: String,
+        reason: String,
+    },
+
     /// Response was augmented (tokens injected)
     ResponseAugmented {
         timestamp: DateTime<Utc>,
@@ -143,6 +167,21 @@ pub enum ProxyEvent {
         /// Tokens injected
         tokens_injected: u32,
     },
+
+    /// config.toml was reloaded (or a reload was rejected)
+    ConfigReloaded {
+        timestamp: DateTime<Utc>,
+        /// What triggered it: "file", "sighup" or "api"
+        trigger: String,
+        success: bool,
+        /// Applied sections, or the validation error
+        message: String,
+        /// New theme, when the reload changed it (applied by the TUI)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        theme: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        use_theme_background: Option<bool>,
+    },
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -220,7 +259,10 @@ impl TrackedEvent {
             | ProxyEvent::UserPrompt { timestamp, .. }
             | ProxyEvent::AssistantResponse { timestamp, .. }
             | ProxyEvent::RequestTransformed { timestamp, .. }
-            | ProxyEvent::ResponseAugmented { timestamp, .. } => *timestamp,
+            | ProxyEvent::ResponseTransformed { timestamp, .. }
+            | ProxyEvent::PolicyDecision { timestamp, .. }
+            | ProxyEvent::ResponseAugmented { timestamp, .. }
+            | ProxyEvent::ConfigReloaded { timestamp, .. } => *timestamp,
         }
     }
 }
@@ -0,0 +1,701 @@
+//! Git-aware session correlation
+//!
+//! Answers "which commits did this Claude session contribute to?":
+//!
+//! 1. **Capture**: Claude Code's system prompt carries an `<env>` block with
+//!    the working directory (and a `gitStatus` section with the branch).
+//!    Lifestats records it per session in `session_context`.
+//! 2. **Link**: `aspy git-link` reads `git log` for a repository and matches
+//!    commits against sessions whose edited files (Edit/Write tool calls)
+//!    overlap the commit's files, within the session's active time range plus
+//!    a grace period for the user committing afterwards.
+//! 3. **Report**: each link carries the session cost accrued up to that commit
+//!    (since the previous linked commit), giving a cost-per-commit figure.
+//!
+//! Parsing and correlation are pure; only `link_repo()` touches git and SQLite.
+
+use crate::pipeline::file_activity::{extract_file_touches, FileOp};
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Schema for session context and commit links (shared with the lifestats migration)
+pub const GIT_LINK_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS session_context (
+        session_id TEXT PRIMARY KEY,
+        user_id TEXT,
+        cwd TEXT NOT NULL,
+        is_git_repo INTEGER,
+        git_branch TEXT,
+        platform TEXT,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_session_context_cwd ON session_context(cwd);
+
+    CREATE TABLE IF NOT EXISTS session_commits (
+        session_id TEXT NOT NULL,
+        repo_root TEXT NOT NULL,
+        commit_hash TEXT NOT NULL,
+        committed_at TEXT NOT NULL,
+        author TEXT,
+        subject TEXT,
+        files_changed INTEGER NOT NULL,
+        files_matched INTEGER NOT NULL,
+        cost_usd REAL NOT NULL DEFAULT 0,
+        linked_at TEXT NOT NULL,
+        PRIMARY KEY (session_id, commit_hash)
+    );
+    CREATE INDEX IF NOT EXISTS idx_session_commits_hash ON session_commits(commit_hash);
+    CREATE INDEX IF NOT EXISTS idx_session_commits_repo ON session_commits(repo_root);
+    CREATE INDEX IF NOT EXISTS idx_session_commits_time ON session_commits(committed_at);
+"#;
+
+/// How long after a session's last activity a commit can still be attributed to it
+pub const COMMIT_GRACE_MINUTES: i64 = 120;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Environment Capture
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Environment details parsed from Claude Code's system prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvInfo {
+    /// "Working directory:" line of the `<env>` block
+    pub cwd: String,
+    /// "Is directory a git repo:" line (Yes/No)
+    pub is_git_repo: Option<bool>,
+    /// "Current branch:" line of the gitStatus section
+    pub git_branch: Option<String>,
+    /// "Platform:" line of the `<env>` block
+    pub platform: Option<String>,
+}
+
+/// Extract environment details from a Messages API request body
+///
+/// The `system` field is either a plain string or an array of text blocks;
+/// the `<env>` block and the branch line may sit in different blocks.
+pub fn extract_env_info(body: &Value) -> Option<EnvInfo> {
+    let texts: Vec<&str> = match body.get("system")? {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect(),
+        _ => return None,
+    };
+
+    let mut env = texts.iter().find_map(|t| parse_env_block(t))?;
+    if env.git_branch.is_none() {
+        env.git_branch = texts.iter().find_map(|t| parse_git_branch(t));
+    }
+    Some(env)
+}
+
+/// Parse the `<env>...</env>` block of a system prompt
+pub fn parse_env_block(text: &str) -> Option<EnvInfo> {
+    let start = text.find("<env>")? + "<env>".len();
+    let end = text[start..]
+        .find("</env>")
+        .map_or(text.len(), |i| start + i);
+
+    let mut env = EnvInfo::default();
+    for line in text[start..end].lines().map(str::trim) {
+        if let Some(cwd) = line.strip_prefix("Working directory:") {
+            env.cwd = cwd.trim().to_string();
+        } else if let Some(is_repo) = line.strip_prefix("Is directory a git repo:") {
+            env.is_git_repo = Some(is_repo.trim().eq_ignore_ascii_case("yes"));
+        } else if let Some(platform) = line.strip_prefix("Platform:") {
+            env.platform = Some(platform.trim().to_string());
+        }
+    }
+
+    if env.cwd.is_empty() {
+        return None;
+    }
+    env.git_branch = parse_git_branch(text);
+    Some(env)
+}
+
+/// Parse the "Current branch:" line of Claude Code's gitStatus section
+fn parse_git_branch(text: &str) -> Option<String> {
+    text.lines()
+        .find_map(|line| line.trim().strip_prefix("Current branch:"))
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty())
+        .map(str::to_string)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Git Log
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// A commit read from `git log --name-only`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitCommit {
+    pub hash: String,
+    pub committed_at: DateTime<Utc>,
+    pub author: String,
+    pub subject: String,
+    /// Paths relative to the repository root
+    pub files: Vec<String>,
+}
+
+/// Record separator (0x1e) before each commit, unit separator (0x1f) between fields
+const LOG_FORMAT: &str = "--format=%x1e%H%x1f%cI%x1f%an%x1f%s";
+
+/// Parse `git log --name-only` output produced with `LOG_FORMAT`
+pub fn parse_git_log(output: &str) -> Vec<GitCommit> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let mut fields = lines.next()?.split('\x1f');
+
+            let hash = fields.next()?.trim();
+            if hash.is_empty() {
+                return None;
+            }
+            let committed_at = DateTime::parse_from_rfc3339(fields.next()?.trim())
+                .ok()?
+                .with_timezone(&Utc);
+            let author = fields.next().unwrap_or_default().to_string();
+            let subject = fields.next().unwrap_or_default().to_string();
+            let files = lines
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect();
+
+            Some(GitCommit {
+                hash: hash.to_string(),
+                committed_at,
+                author,
+                subject,
+                files,
+            })
+        })
+        .collect()
+}
+
+/// Resolve the top-level directory of the repository containing `path`
+pub fn repo_root(path: &Path) -> anyhow::Result<PathBuf> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .context("failed to run git")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} is not inside a git repository: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Read non-merge commits on all branches since `since`
+pub fn read_git_log(repo_root: &Path, since: DateTime<Utc>) -> anyhow::Result<Vec<GitCommit>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
+        .args(["log", "--all", "--no-merges", "--name-only", LOG_FORMAT])
+        .arg(format!("--since={}", since.to_rfc3339()))
+        .output()
+        .context("failed to run git log")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_git_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Correlation
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Activity of one session, as recorded in lifestats
+#[derive(Debug, Clone)]
+pub struct SessionWindow {
+    pub session_id: String,
+    pub user_id: Option<String>,
+    /// Working directory captured from the system prompt (if any)
+    pub cwd: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Paths written by Edit/Write tool calls, as Claude named them
+    pub edited_files: HashSet<String>,
+    /// (timestamp, cost) of each API call, for cost attribution
+    pub usage: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl SessionWindow {
+    /// Edited files expressed relative to `repo_root` (files outside are dropped)
+    fn files_in_repo(&self, repo_root: &Path) -> HashSet<String> {
+        self.edited_files
+            .iter()
+            .filter_map(|file| {
+                let path = Path::new(file);
+                let absolute = if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    Path::new(self.cwd.as_deref()?).join(path)
+                };
+                let relative = absolute.strip_prefix(repo_root).ok()?;
+                Some(relative.to_string_lossy().replace('\\', "/"))
+            })
+            .collect()
+    }
+}
+
+/// A session↔commit link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitLink {
+    pub session_id: String,
+    pub repo_root: String,
+    pub commit_hash: String,
+    /// RFC3339 commit time
+    pub committed_at: String,
+    pub author: String,
+    pub subject: String,
+    pub files_changed: u32,
+    /// Commit files the session edited
+    pub files_matched: u32,
+    /// Session cost accrued since the previous linked commit
+    pub cost_usd: f64,
+}
+
+/// Cost-per-commit across all sessions linked to a commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitCost {
+    pub commit_hash: String,
+    pub repo_root: String,
+    pub committed_at: String,
+    pub author: String,
+    pub subject: String,
+    pub sessions: u32,
+    pub files_changed: u32,
+    pub files_matched: u32,
+    pub cost_usd: f64,
+}
+
+/// Link sessions to the commits they contributed to
+///
+/// A commit is linked when it lands between the session's first activity and
+/// `grace` after its last, and touches at least one file the session edited.
+/// Each link is charged the session's API cost from the previous linked
+/// commit (or session start) up to the commit time.
+pub fn correlate(
+    sessions: &[SessionWindow],
+    commits: &[GitCommit],
+    repo_root: &Path,
+    grace: Duration,
+) -> Vec<CommitLink> {
+    let mut ordered: Vec<&GitCommit> = commits.iter().collect();
+    ordered.sort_by_key(|c| c.committed_at);
+
+    let mut links = Vec::new();
+    for session in sessions {
+        let files = session.files_in_repo(repo_root);
+        if files.is_empty() {
+            continue;
+        }
+
+        let mut previous: Option<DateTime<Utc>> = None;
+        for commit in &ordered {
+            if commit.committed_at < session.start || commit.committed_at > session.end + grace {
+                continue;
+            }
+            let matched = commit.files.iter().filter(|f| files.contains(*f)).count();
+            if matched == 0 {
+                continue;
+            }
+
+            let cost_usd = session
+                .usage
+                .iter()
+                .filter(|(ts, _)| previous.is_none_or(|p| *ts > p) && *ts <= commit.committed_at)
+                .map(|(_, cost)| cost)
+                .sum();
+            previous = Some(commit.committed_at);
+
+            links.push(CommitLink {
+                session_id: session.session_id.clone(),
+                repo_root: repo_root.to_string_lossy().to_string(),
+                commit_hash: commit.hash.clone(),
+                committed_at: commit.committed_at.to_rfc3339(),
+                author: commit.author.clone(),
+                subject: commit.subject.clone(),
+                files_changed: commit.files.len() as u32,
+                files_matched: matched as u32,
+                cost_usd,
+            });
+        }
+    }
+    links
+}
+
+/// Aggregate links into cost-per-commit rows (newest commit first)
+pub fn commit_costs(links: &[CommitLink]) -> Vec<CommitCost> {
+    let mut by_commit: HashMap<&str, CommitCost> = HashMap::new();
+    for link in links {
+        let entry = by_commit
+            .entry(link.commit_hash.as_str())
+            .or_insert_with(|| CommitCost {
+                commit_hash: link.commit_hash.clone(),
+                repo_root: link.repo_root.clone(),
+                committed_at: link.committed_at.clone(),
+                author: link.author.clone(),
+                subject: link.subject.clone(),
+                sessions: 0,
+                files_changed: link.files_changed,
+                files_matched: 0,
+                cost_usd: 0.0,
+            });
+        entry.sessions += 1;
+        entry.files_matched = entry.files_matched.max(link.files_matched);
+        entry.cost_usd += link.cost_usd;
+    }
+
+    let mut costs: Vec<CommitCost> = by_commit.into_values().collect();
+    costs.sort_by(|a, b| b.committed_at.cmp(&a.committed_at));
+    costs
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Linking Against Lifestats
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Result of a `link_repo()` run
+#[derive(Debug, Clone)]
+pub struct GitLinkSummary {
+    pub repo_root: PathBuf,
+    pub sessions_scanned: usize,
+    pub commits_scanned: usize,
+    pub links: Vec<CommitLink>,
+}
+
+/// Correlate recent sessions with commits in `repo` and store the links
+///
+/// Re-running replaces earlier links for the scanned sessions in this
+/// repository, so widening `days` or new commits are picked up cleanly.
+pub fn link_repo(
+    db_path: &Path,
+    repo: &Path,
+    days: u32,
+    user_id: Option<&str>,
+    dry_run: bool,
+) -> anyhow::Result<GitLinkSummary> {
+    let root = repo_root(repo)?;
+    let since = Utc::now() - Duration::days(days as i64);
+
+    let mut conn = Connection::open(db_path)?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    // Databases created before these tables existed get them here
+    conn.execute_batch(crate::pipeline::file_activity::FILE_ACTIVITY_SCHEMA)?;
+    conn.execute_batch(GIT_LINK_SCHEMA)?;
+
+    let sessions = load_sessions(&conn, user_id, since)?;
+    let commits = read_git_log(&root, since)?;
+    let links = correlate(
+        &sessions,
+        &commits,
+        &root,
+        Duration::minutes(COMMIT_GRACE_MINUTES),
+    );
+
+    if !dry_run {
+        store_links(&mut conn, &root, &sessions, &links)?;
+    }
+
+    Ok(GitLinkSummary {
+        repo_root: root,
+        sessions_scanned: sessions.len(),
+        commits_scanned: commits.len(),
+        links,
+    })
+}
+
+/// Build session windows from api_usage, tool_calls and file_activity
+fn load_sessions(
+    conn: &Connection,
+    user_id: Option<&str>,
+    since: DateTime<Utc>,
+) -> anyhow::Result<Vec<SessionWindow>> {
+    let since = since.to_rfc3339();
+    let parse_ts = |ts: &str| {
+        DateTime::parse_from_rfc3339(ts)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    };
+
+    let mut windows: HashMap<String, SessionWindow> = HashMap::new();
+
+    // API usage: activity range and cost
+    let mut stmt = conn.prepare(
+        "SELECT session_id, timestamp, COALESCE(cost_usd, 0) FROM api_usage
+         WHERE session_id IS NOT NULL AND timestamp >= ?1",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, f64>(2)?,
+        ))
+    })?;
+    for (session_id, ts, cost) in rows.filter_map(|r| r.ok()) {
+        if let Some(ts) = parse_ts(&ts) {
+            touch(&mut windows, session_id, ts).usage.push((ts, cost));
+        }
+    }
+
+    // Edit/Write calls (input_json is only present when tool I/O is stored)
+    let mut stmt = conn.prepare(
+        "SELECT session_id, timestamp, tool_name, input_json FROM tool_calls
+         WHERE session_id IS NOT NULL AND timestamp >= ?1
+           AND tool_name IN ('Edit', 'MultiEdit', 'Write', 'NotebookEdit')",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+    for (session_id, ts, tool_name, input_json) in rows.filter_map(|r| r.ok()) {
+        let Some(ts) = parse_ts(&ts) else { continue };
+        let window = touch(&mut windows, session_id, ts);
+        let input: Value = input_json
+            .and_then(|j| serde_json::from_str(&j).ok())
+            .unwrap_or(Value::Null);
+        for file in extract_file_touches(&tool_name, &input) {
+            window.edited_files.insert(file.path);
+        }
+    }
+
+    // File activity covers sessions recorded without tool I/O
+    let mut stmt = conn.prepare(
+        "SELECT session_id, timestamp, path FROM file_activity
+         WHERE session_id IS NOT NULL AND timestamp >= ?1 AND op IN (?2, ?3)",
+    )?;
+    let rows = stmt.query_map(
+        params![since, FileOp::Edit.as_str(), FileOp::Write.as_str()],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        },
+    )?;
+    for (session_id, ts, path) in rows.filter_map(|r| r.ok()) {
+        if let Some(ts) = parse_ts(&ts) {
+            touch(&mut windows, session_id, ts)
+                .edited_files
+                .insert(path);
+        }
+    }
+
+    // Attach user and captured working directory
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.user_id, c.cwd FROM sessions s
+         LEFT JOIN session_context c ON c.session_id = s.id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+    for (session_id, user, cwd) in rows.filter_map(|r| r.ok()) {
+        if let Some(window) = windows.get_mut(&session_id) {
+            window.user_id = user;
+            window.cwd = cwd;
+        }
+    }
+
+    let mut sessions: Vec<SessionWindow> = windows
+        .into_values()
+        .filter(|w| user_id.is_none() || w.user_id.as_deref() == user_id)
+        .collect();
+    sessions.sort_by_key(|w| w.start);
+    Ok(sessions)
+}
+
+/// Get (or start) a session's window, widening it to include `ts`
+fn touch(
+    windows: &mut HashMap<String, SessionWindow>,
+    session_id: String,
+    ts: DateTime<Utc>,
+) -> &mut SessionWindow {
+    let window = windows
+        .entry(session_id.clone())
+        .or_insert_with(|| SessionWindow {
+            session_id,
+            user_id: None,
+            cwd: None,
+            start: ts,
+            end: ts,
+            edited_files: HashSet::new(),
+            usage: Vec::new(),
+        });
+    window.start = window.start.min(ts);
+    window.end = window.end.max(ts);
+    window
+}
+
+/// Replace the scanned sessions' links for this repository
+fn store_links(
+    conn: &mut Connection,
+    repo_root: &Path,
+    sessions: &[SessionWindow],
+    links: &[CommitLink],
+) -> anyhow::Result<()> {
+    let repo_root = repo_root.to_string_lossy();
+    let linked_at = Utc::now().to_rfc3339();
+    let tx = conn.transaction()?;
+
+    for session in sessions {
+        tx.execute(
+            "DELETE FROM session_commits WHERE session_id = ?1 AND repo_root = ?2",
+            params![session.session_id, repo_root],
+        )?;
+    }
+    for link in links {
+        tx.execute(
+            "INSERT OR REPLACE INTO session_commits
+                (session_id, repo_root, commit_hash, committed_at, author, subject,
+                 files_changed, files_matched, cost_usd, linked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                link.session_id,
+                link.repo_root,
+                link.commit_hash,
+                link.committed_at,
+                link.author,
+                link.subject,
+                link.files_changed,
+                link.files_matched,
+                link.cost_usd,
+                linked_at
+            ],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_extract_env_info() {
+        let body = json!({
+            "system": [
+                {"type": "text", "text": "You are Claude Code."},
+                {"type": "text", "text": "<env>\nWorking directory: /home/dev/project\nIs directory a git repo: Yes\nPlatform: linux\n</env>"},
+                {"type": "text", "text": "gitStatus: snapshot\nCurrent branch: feature/x\n\nMain branch: main"}
+            ]
+        });
+
+        let env = extract_env_info(&body).unwrap();
+        assert_eq!(env.cwd, "/home/dev/project");
+        assert_eq!(env.is_git_repo, Some(true));
+        assert_eq!(env.platform.as_deref(), Some("linux"));
+        assert_eq!(env.git_branch.as_deref(), Some("feature/x"));
+
+        // No <env> block: nothing to capture
+        assert!(extract_env_info(&json!({"system": "You are helpful"})).is_none());
+    }
+
+    #[test]
+    fn test_parse_git_log() {
+        let output = "\x1eabc123\x1f2025-01-10T12:00:00+01:00\x1fDev\x1fFix parser\n\nsrc/parser.rs\nsrc/main.rs\n\
+                      \x1edef456\x1f2025-01-10T09:00:00+00:00\x1fDev\x1fDocs only\n\nREADME.md\n";
+
+        let commits = parse_git_log(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc123");
+        assert_eq!(commits[0].committed_at, ts("2025-01-10T11:00:00Z"));
+        assert_eq!(commits[0].subject, "Fix parser");
+        assert_eq!(commits[0].files, vec!["src/parser.rs", "src/main.rs"]);
+        assert_eq!(commits[1].files, vec!["README.md"]);
+    }
+
+    #[test]
+    fn test_correlate_links_and_costs() {
+        let session = SessionWindow {
+            session_id: "s1".into(),
+            user_id: None,
+            cwd: Some("/repo".into()),
+            start: ts("2025-01-10T10:00:00Z"),
+            end: ts("2025-01-10T12:00:00Z"),
+            edited_files: ["/repo/src/a.rs", "src/b.rs", "/elsewhere/c.rs"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            usage: vec![
+                (ts("2025-01-10T10:05:00Z"), 1.0),
+                (ts("2025-01-10T10:30:00Z"), 2.0),
+                (ts("2025-01-10T11:30:00Z"), 4.0),
+            ],
+        };
+        let commit = |hash: &str, at: &str, files: &[&str]| GitCommit {
+            hash: hash.into(),
+            committed_at: ts(at),
+            author: "Dev".into(),
+            subject: hash.into(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+        };
+        let commits = vec![
+            // Relative edit resolved against cwd
+            commit("second", "2025-01-10T13:00:00Z", &["src/b.rs"]),
+            commit("first", "2025-01-10T11:00:00Z", &["src/a.rs", "README.md"]),
+            // Overlapping time, unrelated files
+            commit("unrelated", "2025-01-10T11:10:00Z", &["docs/x.md"]),
+            // Outside the grace period
+            commit("late", "2025-01-10T15:00:00Z", &["src/a.rs"]),
+        ];
+
+        let links = correlate(
+            &[session],
+            &commits,
+            Path::new("/repo"),
+            Duration::minutes(COMMIT_GRACE_MINUTES),
+        );
+        let hashes: Vec<&str> = links.iter().map(|l| l.commit_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["first", "second"]);
+
+        // Usage up to the first commit, then the remainder
+        assert_eq!(links[0].cost_usd, 3.0);
+        assert_eq!(links[0].files_matched, 1);
+        assert_eq!(links[0].files_changed, 2);
+        assert_eq!(links[1].cost_usd, 4.0);
+
+        let costs = commit_costs(&links);
+        assert_eq!(costs[0].commit_hash, "second");
+        assert_eq!(costs[0].sessions, 1);
+    }
+}
@@ -0,0 +1,539 @@
+//! Session handoff documents
+//!
+//! A handoff doc is a structured summary of one session, built from lifestats,
+//! for whoever continues the work: the next session, or the post-compaction
+//! Claude. It collects:
+//!
+//! - **Goals**: the session's first user prompts
+//! - **Files touched**: from file activity, edits first
+//! - **Open TODOs**: the latest TodoWrite list, minus completed items
+//! - **Key decisions**: decision-like sentences from recent thinking blocks
+//! - **Last errors**: failed tool calls (user rejections excluded)
+//!
+//! Docs are written to `<dir>/<user_id>/` as Markdown plus a JSON sidecar on
+//! `/api/session/end` and on `ContextCompact`, served by
+//! `GET /api/session/handoff/:user_id`, and appended to compaction prompts by
+//! the compact-enhancer transformer.
+//!
+//! Building a doc from a `SessionDigest` is pure; only `HandoffService`
+//! touches SQLite and the filesystem.
+
+use crate::config::HandoffConfig;
+use crate::pipeline::lifestats_query::LifestatsQuery;
+use crate::pipeline::todos::{parse_todo_write, TodoStatus};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Earliest prompts treated as the session's goals
+pub const MAX_GOALS: usize = 3;
+/// Files listed in a handoff
+pub const MAX_FILES: usize = 15;
+/// Recent thinking blocks scanned for decisions
+pub const MAX_THINKING_BLOCKS: usize = 20;
+/// Decisions listed in a handoff
+pub const MAX_DECISIONS: usize = 8;
+/// Failed tool calls listed in a handoff
+pub const MAX_ERRORS: usize = 5;
+
+/// Characters kept per goal/decision/error line
+const MAX_LINE_CHARS: usize = 240;
+
+/// Phrases that mark a sentence in thinking as a decision
+const DECISION_MARKERS: &[&str] = &[
+    "decided",
+    "decision",
+    "i'll go with",
+    "going with",
+    "let's go with",
+    "instead of",
+    "rather than",
+    "the approach",
+    "better approach",
+    "we should use",
+    "i'll use",
+    "choose",
+    "chose",
+];
+
+// ============================================================================
+// Raw Material (from lifestats)
+// ============================================================================
+
+/// File activity for one path within a session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HandoffFile {
+    pub path: String,
+    pub reads: u64,
+    pub edits: u64,
+    pub writes: u64,
+}
+
+/// A failed tool call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HandoffError {
+    pub timestamp: String,
+    pub tool_name: String,
+    /// First line of the tool output (empty if tool I/O isn't stored)
+    pub message: String,
+}
+
+/// Everything lifestats knows about a session, before distillation
+#[derive(Debug, Clone, Default)]
+pub struct SessionDigest {
+    pub session_id: String,
+    pub user_id: Option<String>,
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
+    /// Earliest prompts first
+    pub prompts: Vec<String>,
+    /// Most-modified files first
+    pub files: Vec<HandoffFile>,
+    /// Input JSON of the latest TodoWrite call
+    pub latest_todos: Option<String>,
+    /// Thinking blocks, oldest first
+    pub thinking: Vec<String>,
+    /// Failed tool calls, most recent first
+    pub errors: Vec<HandoffError>,
+}
+
+// ============================================================================
+// Handoff Document
+// ============================================================================
+
+/// What produced a handoff doc
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandoffTrigger {
+    /// `/api/session/end` (SessionEnd hook)
+    SessionEnd,
+    /// `ContextCompact` detected after a compaction
+    Compact,
+    /// Built for injection into a compaction prompt
+    CompactionPrompt,
+}
+
+impl HandoffTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HandoffTrigger::SessionEnd => "session_end",
+            HandoffTrigger::Compact => "compact",
+            HandoffTrigger::CompactionPrompt => "compaction_prompt",
+        }
+    }
+}
+
+/// An open TodoWrite item
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HandoffTodo {
+    pub content: String,
+    /// "pending" or "in_progress"
+    pub status: String,
+}
+
+/// Structured handoff for one session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HandoffDoc {
+    pub session_id: String,
+    pub user_id: Option<String>,
+    pub generated_at: DateTime<Utc>,
+    pub trigger: HandoffTrigger,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    pub goals: Vec<String>,
+    pub files: Vec<HandoffFile>,
+    pub todos: Vec<HandoffTodo>,
+    pub decisions: Vec<String>,
+    pub errors: Vec<HandoffError>,
+}
+
+impl HandoffDoc {
+    /// Distill a session digest into a handoff doc
+    pub fn build(digest: SessionDigest, trigger: HandoffTrigger) -> Self {
+        Self {
+            goals: digest
+                .prompts
+                .iter()
+                .filter(|p| !p.trim().is_empty())
+                .take(MAX_GOALS)
+                .map(|p| truncate_line(p))
+                .collect(),
+            todos: digest
+                .latest_todos
+                .as_deref()
+                .map(open_todos)
+                .unwrap_or_default(),
+            decisions: extract_decisions(&digest.thinking),
+            files: digest.files.into_iter().take(MAX_FILES).collect(),
+            errors: digest.errors.into_iter().take(MAX_ERRORS).collect(),
+            session_id: digest.session_id,
+            user_id: digest.user_id,
+            generated_at: Utc::now(),
+            trigger,
+            cwd: digest.cwd,
+            git_branch: digest.git_branch,
+        }
+    }
+
+    /// Whether the doc carries anything worth handing off
+    pub fn is_empty(&self) -> bool {
+        self.goals.is_empty()
+            && self.files.is_empty()
+            && self.todos.is_empty()
+            && self.decisions.is_empty()
+            && self.errors.is_empty()
+    }
+
+    /// Render as Markdown (the form written to disk and injected into prompts)
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Session Handoff\n\n**Session:** `{}`  \n**Generated:** {} ({})\n",
+            self.session_id,
+            self.generated_at.to_rfc3339(),
+            self.trigger.as_str()
+        );
+        if let Some(cwd) = &self.cwd {
+            out.push_str(&format!("**Directory:** `{}`", cwd));
+            if let Some(branch) = &self.git_branch {
+                out.push_str(&format!(" (branch `{}`)", branch));
+            }
+            out.push('\n');
+        }
+
+        section(&mut out, "Goals", &self.goals, |g| format!("- {}", g));
+        section(&mut out, "Open TODOs", &self.todos, |t| {
+            let mark = if t.status == "in_progress" { "~" } else { " " };
+            format!("- [{}] {}", mark, t.content)
+        });
+        section(&mut out, "Files Touched", &self.files, |f| {
+            format!(
+                "- `{}` (read {}, edit {}, write {})",
+                f.path, f.reads, f.edits, f.writes
+            )
+        });
+        section(&mut out, "Key Decisions", &self.decisions, |d| {
+            format!("- {}", d)
+        });
+        section(&mut out, "Last Errors", &self.errors, |e| {
+            if e.message.is_empty() {
+                format!("- {} failed at {}", e.tool_name, e.timestamp)
+            } else {
+                format!("- {}: {}", e.tool_name, e.message)
+            }
+        });
+        out
+    }
+}
+
+/// Append a Markdown section (skipped when empty)
+fn section<T>(out: &mut String, title: &str, items: &[T], line: impl Fn(&T) -> String) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n## {}\n\n", title));
+    for item in items {
+        out.push_str(&line(item));
+        out.push('\n');
+    }
+}
+
+/// First line, capped at `MAX_LINE_CHARS`
+fn truncate_line(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or("").trim();
+    if line.chars().count() > MAX_LINE_CHARS {
+        let truncated: String = line.chars().take(MAX_LINE_CHARS).collect();
+        format!("{}…", truncated)
+    } else {
+        line.to_string()
+    }
+}
+
+/// First meaningful line of a stored tool output (`output_json`)
+///
+/// Outputs are stored as JSON: a string, or an array of content blocks.
+pub fn error_summary(output_json: &str) -> String {
+    let text = match serde_json::from_str::<Value>(output_json) {
+        Ok(Value::String(s)) => s,
+        Ok(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => output_json.to_string(),
+    };
+    text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(truncate_line)
+        .unwrap_or_default()
+}
+
+/// Parse a TodoWrite input and keep items that aren't completed
+pub fn open_todos(input_json: &str) -> Vec<HandoffTodo> {
+    serde_json::from_str::<Value>(input_json)
+        .ok()
+        .and_then(|input| parse_todo_write(&input))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|todo| todo.status != TodoStatus::Completed)
+        .map(|todo| HandoffTodo {
+            content: truncate_line(&todo.content),
+            status: todo.status.as_str().to_string(),
+        })
+        .collect()
+}
+
+/// Pick decision-like sentences from thinking, most recent first
+pub fn extract_decisions(thinking: &[String]) -> Vec<String> {
+    let mut decisions: Vec<String> = Vec::new();
+    for block in thinking.iter().rev() {
+        for sentence in block.split(['.', '\n']).map(str::trim) {
+            if sentence.len() < 20 {
+                continue;
+            }
+            let lower = sentence.to_lowercase();
+            if !DECISION_MARKERS.iter().any(|m| lower.contains(m)) {
+                continue;
+            }
+            let line = truncate_line(sentence);
+            if !decisions.contains(&line) {
+                decisions.push(line);
+            }
+            if decisions.len() >= MAX_DECISIONS {
+                return decisions;
+            }
+        }
+    }
+    decisions
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Keep user/session IDs safe as path components
+fn path_component(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Write a doc as `<dir>/<user>/<timestamp>-<session>.{md,json}`
+///
+/// Returns the Markdown path.
+pub fn write_doc(dir: &Path, doc: &HandoffDoc) -> anyhow::Result<PathBuf> {
+    let user_dir = dir.join(path_component(doc.user_id.as_deref().unwrap_or("unknown")));
+    std::fs::create_dir_all(&user_dir)
+        .with_context(|| format!("Failed to create {}", user_dir.display()))?;
+    let stem = format!(
+        "{}-{}",
+        doc.generated_at.format("%Y%m%dT%H%M%S"),
+        path_component(&doc.session_id)
+    );
+    let md_path = user_dir.join(format!("{}.md", stem));
+    std::fs::write(&md_path, doc.to_markdown())?;
+    std::fs::write(
+        user_dir.join(format!("{}.json", stem)),
+        serde_json::to_vec_pretty(doc)?,
+    )?;
+    Ok(md_path)
+}
+
+/// Most recent doc written for a user (by file name, which leads with the timestamp)
+pub fn latest_doc(dir: &Path, user_id: &str) -> anyhow::Result<Option<HandoffDoc>> {
+    let user_dir = dir.join(path_component(user_id));
+    let Ok(entries) = std::fs::read_dir(&user_dir) else {
+        return Ok(None);
+    };
+    let latest = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .max();
+    match latest {
+        Some(path) => {
+            let bytes = std::fs::read(&path)?;
+            Ok(Some(serde_json::from_slice(&bytes).with_context(|| {
+                format!("Invalid handoff doc {}", path.display())
+            })?))
+        }
+        None => Ok(None),
+    }
+}
+
+// ============================================================================
+// Service
+// ============================================================================
+
+/// Builds handoff docs from lifestats and persists them
+pub struct HandoffService {
+    query: Arc<LifestatsQuery>,
+    dir: PathBuf,
+    inject_on_compact: bool,
+}
+
+impl HandoffService {
+    pub fn new(query: Arc<LifestatsQuery>, config: &HandoffConfig) -> Self {
+        Self {
+            query,
+            dir: config.dir.clone(),
+            inject_on_compact: config.inject_on_compact,
+        }
+    }
+
+    pub fn inject_on_compact(&self) -> bool {
+        self.inject_on_compact
+    }
+
+    /// Directory docs are written to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Build a doc for a session (blocking: queries SQLite)
+    pub fn build(&self, session_id: &str, trigger: HandoffTrigger) -> anyhow::Result<HandoffDoc> {
+        let digest = self.query.get_session_digest(session_id)?;
+        Ok(HandoffDoc::build(digest, trigger))
+    }
+
+    /// Build and write a doc (blocking); `None` when the session has no history
+    pub fn generate(
+        &self,
+        session_id: &str,
+        user_id: Option<&str>,
+        trigger: HandoffTrigger,
+    ) -> anyhow::Result<Option<PathBuf>> {
+        let mut doc = self.build(session_id, trigger)?;
+        if doc.is_empty() {
+            return Ok(None);
+        }
+        if doc.user_id.is_none() {
+            doc.user_id = user_id.map(String::from);
+        }
+        write_doc(&self.dir, &doc).map(Some)
+    }
+
+    /// Generate in the background, logging the outcome
+    pub fn spawn_generate(
+        self: &Arc<Self>,
+        session_id: String,
+        user_id: Option<String>,
+        trigger: HandoffTrigger,
+    ) {
+        let service = Arc::clone(self);
+        tokio::task::spawn_blocking(move || {
+            match service.generate(&session_id, user_id.as_deref(), trigger) {
+                Ok(Some(path)) => tracing::info!(
+                    "Handoff doc written for session {} ({}): {}",
+                    session_id,
+                    trigger.as_str(),
+                    path.display()
+                ),
+                Ok(None) => {
+                    tracing::debug!("No lifestats history for session {}", session_id)
+                }
+                Err(e) => tracing::warn!("Handoff generation failed for {}: {}", session_id, e),
+            }
+        });
+    }
+
+    /// Latest doc written for a user
+    pub fn latest(&self, user_id: &str) -> anyhow::Result<Option<HandoffDoc>> {
+        latest_doc(&self.dir, user_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest() -> SessionDigest {
+        SessionDigest {
+            session_id: "abc/123".to_string(),
+            user_id: Some("dev-1".to_string()),
+            cwd: Some("/work/app".to_string()),
+            git_branch: Some("main".to_string()),
+            prompts: vec![
+                "Add retry logic to the uploader\nwith details".to_string(),
+                "  ".to_string(),
+                "Also cover timeouts".to_string(),
+            ],
+            files: vec![HandoffFile {
+                path: "src/upload.rs".to_string(),
+                reads: 2,
+                edits: 3,
+                writes: 0,
+            }],
+            latest_todos: Some(
+                r#"{"todos":[
+                    {"content":"Write retry loop","status":"completed","activeForm":"x"},
+                    {"content":"Add backoff tests","status":"in_progress","activeForm":"x"},
+                    {"content":"Update docs","status":"pending","activeForm":"x"}
+                ]}"#
+                .to_string(),
+            ),
+            thinking: vec![
+                "Looking at the code. I'll go with exponential backoff rather than fixed delays."
+                    .to_string(),
+                "Short. The approach is to wrap the client in a RetryPolicy struct.".to_string(),
+            ],
+            errors: vec![HandoffError {
+                timestamp: "2025-01-01T00:00:00Z".to_string(),
+                tool_name: "Bash".to_string(),
+                message: "cargo test: 1 failed".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_build_distills_digest() {
+        let doc = HandoffDoc::build(digest(), HandoffTrigger::SessionEnd);
+        assert_eq!(
+            doc.goals,
+            vec!["Add retry logic to the uploader", "Also cover timeouts"]
+        );
+        let todos: Vec<_> = doc.todos.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(todos, vec!["Add backoff tests", "Update docs"]);
+        // Most recent thinking first
+        assert_eq!(
+            doc.decisions,
+            vec![
+                "The approach is to wrap the client in a RetryPolicy struct",
+                "I'll go with exponential backoff rather than fixed delays"
+            ]
+        );
+
+        let md = doc.to_markdown();
+        assert!(md.contains("**Directory:** `/work/app` (branch `main`)"));
+        assert!(md.contains("- [~] Add backoff tests"));
+        assert!(md.contains("- `src/upload.rs` (read 2, edit 3, write 0)"));
+        assert!(md.contains("- Bash: cargo test: 1 failed"));
+    }
+
+    #[test]
+    fn test_write_and_read_latest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let mut first = HandoffDoc::build(digest(), HandoffTrigger::Compact);
+        first.generated_at = "2025-01-01T00:00:00Z".parse().unwrap();
+        let second = HandoffDoc::build(digest(), HandoffTrigger::SessionEnd);
+
+        let path = write_doc(dir, &first).unwrap();
+        assert!(path.ends_with("dev-1/20250101T000000-abc_123.md"));
+        write_doc(dir, &second).unwrap();
+
+        let latest = latest_doc(dir, "dev-1").unwrap().unwrap();
+        assert_eq!(latest.trigger, HandoffTrigger::SessionEnd);
+        assert!(latest_doc(dir, "nobody").unwrap().is_none());
+    }
+}
@@ -12,8 +12,12 @@
 
 mod cli;
 mod config;
+mod config_schema;
 mod demo;
+mod diff;
 mod events;
+mod git_link;
+mod handoff;
 mod logging;
 mod parser;
 mod pipeline;
@@ -22,7 +26,9 @@ mod proxy;
 mod startup;
 mod storage;
 mod theme;
+mod timeline;
 mod tokens;
+mod tool_analytics;
 mod tui;
 
 use anyhow::Result;
@@ -254,6 +260,12 @@ async fn main() -> Result<()> {
             None
         };
 
+    // Same validation as hot reload and `aspy config --check`; problems are logged, not fatal
+    match config_schema::check_config_file() {
+        Ok((_, diagnostics)) => config_schema::log_diagnostics(&diagnostics),
+        Err(e) => tracing::warn!("Config validation skipped: {:#}", e),
+    }
+
     // Generate session ID for this run
     let session_id = generate_session_id();
 
@@ -282,6 +294,11 @@ async fn main() -> Result<()> {
     let context_state: SharedContextState =
         Arc::new(Mutex::new(ContextState::new(config.context_limit)));
 
+    // Alert toasts/bells are delivered through the TUI (None when headless)
+    let alert_feed: Option<pipeline::alerts::AlertFeed> = config
+        .enable_tui
+        .then(|| Arc::new(Mutex::new(std::collections::VecDeque::new())));
+
     // Create shared statistics for HTTP API endpoints
     // TUI updates this as events arrive, API reads it for queries
     let shared_stats = Arc::new(Mutex::new(events::Stats::default()));
@@ -340,8 +357,10 @@ async fn main() -> Result<()> {
             use pipeline::{
                 embedding_indexer::EmbeddingIndexer,
                 embeddings::{self, AuthMethod, EmbeddingConfig, ProviderType},
+                file_activity::FileActivityProcessor,
                 lifestats::LifestatsProcessor,
                 lifestats_query::LifestatsQuery,
+                todos::TodoProcessor,
                 EventPipeline,
             };
 
@@ -352,6 +371,7 @@ async fn main() -> Result<()> {
                 db_path: config.lifestats.db_path.clone(),
                 store_thinking: config.lifestats.store_thinking,
                 store_tool_io: config.lifestats.store_tool_io,
+                capture_git_context: config.lifestats.capture_git_context,
                 max_thinking_size: config.lifestats.max_thinking_size,
                 retention_days: config.lifestats.retention_days,
                 channel_buffer: config.lifestats.channel_buffer,
@@ -363,8 +383,19 @@ async fn main() -> Result<()> {
 
             match LifestatsProcessor::new(lifestats_config) {
                 Ok(processor) => {
+                    let writer = processor.writer();
                     pipeline.register(processor);
 
+                    // File activity is written through the lifestats writer
+                    if config.lifestats.track_file_activity {
+                        pipeline.register(FileActivityProcessor::new(writer.clone()));
+                    }
+
+                    // So is the todo timeline
+                    if config.lifestats.track_todos {
+                        pipeline.register(TodoProcessor::new(writer));
+                    }
+
                     // Initialize OpenTelemetry exporter if configured (requires --features otel)
                     #[cfg(feature = "otel")]
                     if config.otel.is_configured() {
@@ -470,11 +501,7 @@ async fn main() -> Result<()> {
                                 None
                             };
 
-                            (
-                                Some(std::sync::Arc::new(pipeline)),
-                                Some(std::sync::Arc::new(query)),
-                                indexer,
-                            )
+                            (Some(pipeline), Some(std::sync::Arc::new(query)), indexer)
                         }
                         Err(e) => {
                             registry.fail("lifestats", e.to_string());
@@ -482,7 +509,7 @@ async fn main() -> Result<()> {
                                 "Failed to initialize lifestats query interface: {}",
                                 e
                             );
-                            (Some(std::sync::Arc::new(pipeline)), None, None)
+                            (Some(pipeline), None, None)
                         }
                     }
                 }
@@ -497,6 +524,29 @@ async fn main() -> Result<()> {
             (None, None, None)
         };
 
+        // Alert rules run with or without lifestats
+        let pipeline = if config.alerts.is_empty() {
+            pipeline
+        } else {
+            let mut pipeline = pipeline.unwrap_or_default();
+            match pipeline::alerts::AlertProcessor::new(
+                config.alerts.clone(),
+                config.context_limit,
+                alert_feed.clone(),
+            ) {
+                Ok(processor) => {
+                    pipeline.register(processor);
+                    registry.activate("alerts");
+                }
+                Err(e) => {
+                    registry.fail("alerts", e.to_string());
+                    tracing::error!("Failed to initialize alerts: {}", e);
+                }
+            }
+            Some(pipeline)
+        };
+        let pipeline = pipeline.map(std::sync::Arc::new);
+
         // Bundle channels and shared state for the proxy
         let channels = proxy::EventChannels {
             tui: event_tx_tui,
@@ -544,6 +594,7 @@ async fn main() -> Result<()> {
             streaming_thinking,
             shared_stats,
             shared_events,
+            alert_feed,
         )
         .await
         {
@@ -140,6 +140,13 @@ impl Parser {
         tracing::trace!("pending_calls now has {} entries", pending.len());
     }
 
+    /// Drop a pending tool call whose result will never arrive
+    ///
+    /// Used for calls the proxy blocked before the client saw them.
+    pub async fn forget_pending_tool(&self, id: &str) {
+        self.pending_calls.lock().await.remove(id);
+    }
+
     /// Parse an API request looking for tool results
     ///
     /// Tool results represent Claude Code's responses to previous tool calls.
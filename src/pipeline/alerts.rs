@@ -0,0 +1,890 @@
+//! Configurable alert rules with desktop, webhook and command sinks
+//!
+//! `[[alerts]]` rules match predicates over the event stream and notify
+//! through one or more sinks:
+//!
+//! ```toml
+//! [[alerts]]
+//! name = "Context filling up"
+//! type = "context_percent"
+//! above = 85
+//! sinks = ["toast", "bell"]
+//! cooldown_secs = 600
+//!
+//! [[alerts]]
+//! name = "API errors"
+//! type = "error_status"
+//! min_status = 500
+//! sinks = ["webhook"]
+//! webhook_url = "https://hooks.example.com/aspy"
+//! ```
+//!
+//! # Architecture
+//!
+//! ```text
+//! ProxyEvent
+//!     │
+//!     └──→ AlertProcessor (AlertEngine: evaluate rules, apply cooldowns)
+//!             ├──→ AlertFeed (toast, bell) ──→ TUI drains on tick
+//!             └──→ alert-dispatcher thread (command, webhook)
+//! ```
+//!
+//! Toasts and bells go through the TUI so nothing writes to the terminal
+//! mid-frame. Commands and webhooks run on a dedicated thread; the pipeline
+//! never waits on them.
+
+use super::{EventProcessor, ProcessContext, ProcessResult};
+use crate::events::ProxyEvent;
+use crate::tool_analytics::is_user_rejection;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+/// Alerts waiting for the TUI (toast/bell sinks)
+pub type AlertFeed = Arc<Mutex<VecDeque<AlertNotice>>>;
+
+/// Maximum undrained notices kept in the feed (oldest dropped first)
+const FEED_CAPACITY: usize = 32;
+
+/// Pending command/webhook deliveries before new ones are dropped
+const DISPATCH_BUFFER: usize = 64;
+
+/// Webhook request timeout
+const WEBHOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Time a command sink may run before it is killed
+const COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How long shutdown waits for the dispatcher to finish its current delivery
+const SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Predicate an alert rule matches on (from TOML, `type = "..."`)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// API response with status >= `min_status`
+    ErrorStatus {
+        #[serde(default = "default_min_status")]
+        min_status: u16,
+    },
+    /// Context window usage above `above` percent of `context_limit`
+    ContextPercent { above: f64 },
+    /// Spend over the last 60 minutes above `above_usd`
+    CostPerHour { above_usd: f64 },
+    /// `count` consecutive failed tool calls in a session (rejections excluded)
+    ToolFailureStreak { count: u32 },
+    /// Rate-limit remaining requests or tokens below a threshold
+    RateLimitRemaining {
+        #[serde(default)]
+        requests_below: Option<u32>,
+        #[serde(default)]
+        tokens_below: Option<u32>,
+    },
+}
+
+impl AlertCondition {
+    /// The `type` value used in config and alert payloads
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ErrorStatus { .. } => "error_status",
+            Self::ContextPercent { .. } => "context_percent",
+            Self::CostPerHour { .. } => "cost_per_hour",
+            Self::ToolFailureStreak { .. } => "tool_failure_streak",
+            Self::RateLimitRemaining { .. } => "rate_limit_remaining",
+        }
+    }
+}
+
+fn default_min_status() -> u16 {
+    400
+}
+
+/// Where a fired alert is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSink {
+    /// TUI toast notification
+    Toast,
+    /// Terminal bell
+    Bell,
+    /// Shell command, alert JSON on stdin
+    Command,
+    /// HTTP POST of the alert JSON
+    Webhook,
+}
+
+impl AlertSink {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Toast => "toast",
+            Self::Bell => "bell",
+            Self::Command => "command",
+            Self::Webhook => "webhook",
+        }
+    }
+}
+
+/// One `[[alerts]]` entry
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AlertRule {
+    /// Display name (shown in toasts and payloads)
+    pub name: String,
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+    #[serde(default = "default_sinks")]
+    pub sinks: Vec<AlertSink>,
+    /// Shell command for the `command` sink
+    #[serde(default)]
+    pub command: Option<String>,
+    /// URL for the `webhook` sink
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Minimum seconds between two firings of this rule (per session for
+    /// session-scoped conditions)
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_sinks() -> Vec<AlertSink> {
+    vec![AlertSink::Toast]
+}
+
+fn default_cooldown_secs() -> u64 {
+    300
+}
+
+impl AlertRule {
+    /// Check that every sink has what it needs
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.sinks.contains(&AlertSink::Command) && self.command.is_none() {
+            anyhow::bail!("alert '{}': command sink requires `command`", self.name);
+        }
+        if self.sinks.contains(&AlertSink::Webhook) && self.webhook_url.is_none() {
+            anyhow::bail!("alert '{}': webhook sink requires `webhook_url`", self.name);
+        }
+        if self.sinks.is_empty() {
+            anyhow::bail!("alert '{}': no sinks configured", self.name);
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Evaluation
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// An alert that passed its rule's cooldown (JSON payload for command/webhook)
+#[derive(Debug, Clone, Serialize)]
+pub struct FiredAlert {
+    pub rule: String,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub message: String,
+    /// Measured value that tripped the rule
+    pub value: f64,
+    pub threshold: f64,
+    pub timestamp: DateTime<Utc>,
+    pub session_id: Option<String>,
+    pub user_id: Option<String>,
+}
+
+/// A fired alert as delivered to the TUI
+#[derive(Debug, Clone)]
+pub struct AlertNotice {
+    pub alert: FiredAlert,
+    pub toast: bool,
+    pub bell: bool,
+}
+
+/// A rule match before cooldown: (value, threshold, message, session-scoped)
+type Match = (f64, f64, String, bool);
+
+/// Rule evaluation state (pure: no I/O, timestamps come from events)
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    context_limit: u64,
+    /// (timestamp, cost) of API calls within the last hour
+    recent_costs: VecDeque<(DateTime<Utc>, f64)>,
+    /// Consecutive failed tool calls per session
+    failure_streaks: HashMap<String, u32>,
+    /// Last firing per (rule index, scope)
+    last_fired: HashMap<(usize, String), DateTime<Utc>>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>, context_limit: u64) -> Self {
+        Self {
+            rules,
+            context_limit,
+            recent_costs: VecDeque::new(),
+            failure_streaks: HashMap::new(),
+            last_fired: HashMap::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Update state from an event and return (rule index, alert) for each firing
+    pub fn evaluate(
+        &mut self,
+        event: &ProxyEvent,
+        session_id: Option<&str>,
+        user_id: Option<&str>,
+    ) -> Vec<(usize, FiredAlert)> {
+        let session_key = session_id.unwrap_or_default().to_string();
+        self.update_state(event, &session_key);
+
+        let timestamp = event_timestamp(event);
+        let mut fired = Vec::new();
+
+        for index in 0..self.rules.len() {
+            let Some((value, threshold, message, session_scoped)) =
+                self.match_rule(&self.rules[index], event, &session_key)
+            else {
+                continue;
+            };
+
+            // Cooldown per rule, and per session for session-scoped conditions
+            let scope = if session_scoped {
+                session_key.clone()
+            } else {
+                String::new()
+            };
+            let cooldown = Duration::seconds(self.rules[index].cooldown_secs as i64);
+            if let Some(last) = self.last_fired.get(&(index, scope.clone())) {
+                if timestamp - *last < cooldown {
+                    continue;
+                }
+            }
+            self.last_fired.insert((index, scope), timestamp);
+
+            let rule = &self.rules[index];
+            fired.push((
+                index,
+                FiredAlert {
+                    rule: rule.name.clone(),
+                    kind: rule.condition.as_str(),
+                    message,
+                    value,
+                    threshold,
+                    timestamp,
+                    session_id: session_id.map(str::to_string),
+                    user_id: user_id.map(str::to_string),
+                },
+            ));
+        }
+
+        fired
+    }
+
+    /// Track the rolling cost window and failure streaks
+    fn update_state(&mut self, event: &ProxyEvent, session_key: &str) {
+        match event {
+            ProxyEvent::ApiUsage {
+                timestamp,
+                model,
+                input_tokens,
+                output_tokens,
+                cache_creation_tokens,
+                cache_read_tokens,
+            } => {
+                let cost = crate::pricing::calculate_cost(
+                    model,
+                    *input_tokens,
+                    *output_tokens,
+                    *cache_creation_tokens,
+                    *cache_read_tokens,
+                );
+                self.recent_costs.push_back((*timestamp, cost));
+                let cutoff = *timestamp - Duration::hours(1);
+                while self
+                    .recent_costs
+                    .front()
+                    .is_some_and(|(ts, _)| *ts < cutoff)
+                {
+                    self.recent_costs.pop_front();
+                }
+            }
+            ProxyEvent::ToolResult {
+                success, output, ..
+            } => {
+                let streak = self
+                    .failure_streaks
+                    .entry(session_key.to_string())
+                    .or_default();
+                if *success {
+                    *streak = 0;
+                } else if !is_user_rejection(&output.to_string()) {
+                    *streak += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn match_rule(&self, rule: &AlertRule, event: &ProxyEvent, session_key: &str) -> Option<Match> {
+        match (&rule.condition, event) {
+            (AlertCondition::ErrorStatus { min_status }, ProxyEvent::Response { status, .. })
+                if status >= min_status =>
+            {
+                Some((
+                    *status as f64,
+                    *min_status as f64,
+                    format!("API returned HTTP {}", status),
+                    false,
+                ))
+            }
+
+            (
+                AlertCondition::ContextPercent { above },
+                ProxyEvent::ApiUsage {
+                    input_tokens,
+                    cache_creation_tokens,
+                    cache_read_tokens,
+                    ..
+                },
+            ) if self.context_limit > 0 => {
+                let context = *input_tokens as u64
+                    + *cache_creation_tokens as u64
+                    + *cache_read_tokens as u64;
+                let percent = context as f64 / self.context_limit as f64 * 100.0;
+                (percent > *above).then(|| {
+                    (
+                        percent,
+                        *above,
+                        format!(
+                            "Context at {:.0}% ({}K / {}K tokens)",
+                            percent,
+                            context / 1000,
+                            self.context_limit / 1000
+                        ),
+                        true,
+                    )
+                })
+            }
+
+            (AlertCondition::CostPerHour { above_usd }, ProxyEvent::ApiUsage { .. }) => {
+                let spent: f64 = self.recent_costs.iter().map(|(_, cost)| cost).sum();
+                (spent > *above_usd).then(|| {
+                    (
+                        spent,
+                        *above_usd,
+                        format!("Spent ${:.2} in the last hour", spent),
+                        false,
+                    )
+                })
+            }
+
+            (
+                AlertCondition::ToolFailureStreak { count },
+                ProxyEvent::ToolResult {
+                    tool_name, success, ..
+                },
+            ) if !success => {
+                let streak = self.failure_streaks.get(session_key).copied().unwrap_or(0);
+                (streak >= *count).then(|| {
+                    (
+                        streak as f64,
+                        *count as f64,
+                        format!(
+                            "{} tool calls failed in a row (last: {})",
+                            streak, tool_name
+                        ),
+                        true,
+                    )
+                })
+            }
+
+            (
+                AlertCondition::RateLimitRemaining {
+                    requests_below,
+                    tokens_below,
+                },
+                ProxyEvent::RateLimitUpdate {
+                    requests_remaining,
+                    tokens_remaining,
+                    ..
+                },
+            ) => {
+                let below =
+                    |remaining: &Option<u32>, threshold: &Option<u32>| match (remaining, threshold)
+                    {
+                        (Some(r), Some(t)) if r < t => Some((*r, *t)),
+                        _ => None,
+                    };
+                if let Some((remaining, threshold)) = below(requests_remaining, requests_below) {
+                    Some((
+                        remaining as f64,
+                        threshold as f64,
+                        format!("Rate limit: {} requests remaining", remaining),
+                        false,
+                    ))
+                } else {
+                    below(tokens_remaining, tokens_below).map(|(remaining, threshold)| {
+                        (
+                            remaining as f64,
+                            threshold as f64,
+                            format!("Rate limit: {} tokens remaining", remaining),
+                            false,
+                        )
+                    })
+                }
+            }
+
+            _ => None,
+        }
+    }
+}
+
+/// Timestamp of any event (cooldowns run on event time, not wall time)
+fn event_timestamp(event: &ProxyEvent) -> DateTime<Utc> {
+    match event {
+        ProxyEvent::ToolCall { timestamp, .. }
+        | ProxyEvent::ToolResult { timestamp, .. }
+        | ProxyEvent::Request { timestamp, .. }
+        | ProxyEvent::Response { timestamp, .. }
+        | ProxyEvent::Error { timestamp, .. }
+        | ProxyEvent::HeadersCaptured { timestamp, .. }
+        | ProxyEvent::RateLimitUpdate { timestamp, .. }
+        | ProxyEvent::ApiUsage { timestamp, .. }
+        | ProxyEvent::Thinking { timestamp, .. }
+        | ProxyEvent::ContextCompact { timestamp, .. }
+        | ProxyEvent::ThinkingStarted { timestamp }
+        | ProxyEvent::UserPrompt { timestamp, .. }
+        | ProxyEvent::AssistantResponse { timestamp, .. }
+        | ProxyEvent::RequestTransformed { timestamp, .. }
+        | ProxyEvent::ResponseTransformed { timestamp, .. }
+        | ProxyEvent::PolicyDecision { timestamp, .. }
+        | ProxyEvent::ResponseAugmented { timestamp, .. }
+        | ProxyEvent::ConfigReloaded { timestamp, .. } => *timestamp,
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Processor
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Deliveries handled off the pipeline
+enum Dispatch {
+    Command { command: String, payload: String },
+    Webhook { url: String, payload: String },
+    Shutdown,
+}
+
+/// Event processor that evaluates `[[alerts]]` rules
+pub struct AlertProcessor {
+    engine: Mutex<AlertEngine>,
+    /// TUI feed for toast/bell (None when running headless)
+    feed: Option<AlertFeed>,
+    tx: SyncSender<Dispatch>,
+    dispatcher: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AlertProcessor {
+    /// Create the processor and start the dispatcher thread
+    ///
+    /// Fails if any rule is missing what its sinks need.
+    pub fn new(
+        rules: Vec<AlertRule>,
+        context_limit: u64,
+        feed: Option<AlertFeed>,
+    ) -> anyhow::Result<Self> {
+        for rule in &rules {
+            rule.validate()?;
+        }
+
+        let (tx, rx) = mpsc::sync_channel(DISPATCH_BUFFER);
+        let dispatcher = thread::Builder::new()
+            .name("alert-dispatcher".into())
+            .spawn(move || {
+                let client = reqwest::blocking::Client::builder()
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()
+                    .ok();
+
+                while let Ok(dispatch) = rx.recv() {
+                    match dispatch {
+                        Dispatch::Command { command, payload } => {
+                            if let Err(e) = run_command(&command, &payload, COMMAND_TIMEOUT) {
+                                tracing::warn!("Alert command failed: {}", e);
+                            }
+                        }
+                        Dispatch::Webhook { url, payload } => {
+                            let Some(client) = &client else { continue };
+                            match client
+                                .post(&url)
+                                .header("content-type", "application/json")
+                                .body(payload)
+                                .send()
+                            {
+                                Ok(resp) if !resp.status().is_success() => {
+                                    tracing::warn!("Alert webhook returned {}", resp.status())
+                                }
+                                Ok(_) => {}
+                                Err(e) => tracing::warn!("Alert webhook failed: {}", e),
+                            }
+                        }
+                        Dispatch::Shutdown => break,
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            engine: Mutex::new(AlertEngine::new(rules, context_limit)),
+            feed,
+            tx,
+            dispatcher: Mutex::new(Some(dispatcher)),
+        })
+    }
+
+    fn deliver(&self, rule: &AlertRule, alert: FiredAlert) {
+        tracing::warn!("Alert [{}]: {}", alert.rule, alert.message);
+
+        let toast = rule.sinks.contains(&AlertSink::Toast);
+        let bell = rule.sinks.contains(&AlertSink::Bell);
+        match &self.feed {
+            Some(feed) if toast || bell => {
+                if let Ok(mut feed) = feed.lock() {
+                    if feed.len() >= FEED_CAPACITY {
+                        feed.pop_front();
+                    }
+                    feed.push_back(AlertNotice {
+                        alert: alert.clone(),
+                        toast,
+                        bell,
+                    });
+                }
+            }
+            // Headless: the log line above stands in for the toast
+            None if bell => {
+                let mut stderr = std::io::stderr();
+                let _ = stderr.write_all(b"\x07");
+                let _ = stderr.flush();
+            }
+            _ => {}
+        }
+
+        let payload = match serde_json::to_string(&alert) {
+            Ok(payload) => payload,
+            Err(_) => return,
+        };
+        if let (true, Some(command)) = (rule.sinks.contains(&AlertSink::Command), &rule.command) {
+            self.dispatch(Dispatch::Command {
+                command: command.clone(),
+                payload: payload.clone(),
+            });
+        }
+        if let (true, Some(url)) = (rule.sinks.contains(&AlertSink::Webhook), &rule.webhook_url) {
+            self.dispatch(Dispatch::Webhook {
+                url: url.clone(),
+                payload,
+            });
+        }
+    }
+
+    fn dispatch(&self, dispatch: Dispatch) {
+        if let Err(mpsc::TrySendError::Full(_)) = self.tx.try_send(dispatch) {
+            tracing::warn!("Alert dispatcher busy: dropped delivery");
+        }
+    }
+}
+
+impl EventProcessor for AlertProcessor {
+    fn name(&self) -> &'static str {
+        "alerts"
+    }
+
+    fn process(&self, event: &ProxyEvent, ctx: &ProcessContext) -> ProcessResult {
+        let Ok(mut engine) = self.engine.lock() else {
+            return ProcessResult::Continue;
+        };
+
+        let fired = engine.evaluate(event, ctx.session_id.as_deref(), ctx.user_id.as_deref());
+        for (index, alert) in fired {
+            let rule = engine.rules()[index].clone();
+            self.deliver(&rule, alert);
+        }
+
+        ProcessResult::Continue
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        // Never block here: with a full queue the thread stops when the sender drops
+        let _ = self.tx.try_send(Dispatch::Shutdown);
+        let Some(handle) = self.dispatcher.lock().ok().and_then(|mut h| h.take()) else {
+            return Ok(());
+        };
+
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        while !handle.is_finished() {
+            if Instant::now() >= deadline {
+                // Detach: the thread exits once the sender is dropped
+                tracing::warn!(
+                    "Alert dispatcher did not finish within {:?}",
+                    SHUTDOWN_TIMEOUT
+                );
+                return Ok(());
+            }
+            thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let _ = handle.join();
+        Ok(())
+    }
+}
+
+/// Run a shell command with the alert JSON on stdin
+///
+/// The command is killed if it is still running after `timeout`.
+fn run_command(command: &str, payload: &str, timeout: std::time::Duration) -> anyhow::Result<()> {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", command]);
+        c
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut c = Command::new("sh");
+        c.args(["-c", command]);
+        c
+    };
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores stdin closes the pipe early; not an error
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("`{}` timed out after {:?}", command, timeout);
+        }
+        thread::sleep(std::time::Duration::from_millis(20));
+    };
+    if !status.success() {
+        anyhow::bail!("`{}` exited with {}", command, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(condition: AlertCondition, cooldown_secs: u64) -> AlertRule {
+        AlertRule {
+            name: "test".into(),
+            condition,
+            sinks: vec![AlertSink::Toast],
+            command: None,
+            webhook_url: None,
+            cooldown_secs,
+        }
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn usage(secs: i64, input_tokens: u32) -> ProxyEvent {
+        ProxyEvent::ApiUsage {
+            timestamp: at(secs),
+            model: "claude-sonnet-4-20250514".into(),
+            input_tokens,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+        }
+    }
+
+    fn tool_result(success: bool, output: &str) -> ProxyEvent {
+        ProxyEvent::ToolResult {
+            id: "t".into(),
+            timestamp: at(0),
+            tool_name: "Bash".into(),
+            output: json!(output),
+            duration: std::time::Duration::from_millis(10),
+            success,
+        }
+    }
+
+    #[test]
+    fn test_parse_rules_from_toml() {
+        #[derive(Deserialize)]
+        struct File {
+            alerts: Vec<AlertRule>,
+        }
+
+        let file: File = toml::from_str(
+            r#"
+            [[alerts]]
+            name = "ctx"
+            type = "context_percent"
+            above = 85
+            sinks = ["toast", "bell"]
+
+            [[alerts]]
+            name = "errors"
+            type = "error_status"
+            sinks = ["command"]
+            command = "notify-send aspy"
+            cooldown_secs = 0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.alerts[0].condition,
+            AlertCondition::ContextPercent { above: 85.0 }
+        );
+        assert_eq!(file.alerts[0].cooldown_secs, 300);
+        assert_eq!(
+            file.alerts[1].condition,
+            AlertCondition::ErrorStatus { min_status: 400 }
+        );
+        assert!(file.alerts[1].validate().is_ok());
+
+        let mut missing = file.alerts[1].clone();
+        missing.command = None;
+        assert!(missing.validate().is_err());
+    }
+
+    #[test]
+    fn test_context_percent_with_cooldown() {
+        let mut engine = AlertEngine::new(
+            vec![rule(AlertCondition::ContextPercent { above: 80.0 }, 60)],
+            100_000,
+        );
+
+        assert!(engine
+            .evaluate(&usage(0, 50_000), Some("s1"), None)
+            .is_empty());
+        assert_eq!(
+            engine.evaluate(&usage(10, 90_000), Some("s1"), None).len(),
+            1
+        );
+        // Still above threshold, but within cooldown
+        assert!(engine
+            .evaluate(&usage(20, 91_000), Some("s1"), None)
+            .is_empty());
+        // Other sessions have their own cooldown
+        assert_eq!(
+            engine.evaluate(&usage(20, 91_000), Some("s2"), None).len(),
+            1
+        );
+        // Cooldown elapsed
+        assert_eq!(
+            engine.evaluate(&usage(80, 92_000), Some("s1"), None).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_tool_failure_streak_ignores_rejections() {
+        let mut engine = AlertEngine::new(
+            vec![rule(AlertCondition::ToolFailureStreak { count: 3 }, 0)],
+            100_000,
+        );
+        let rejected = "The user doesn't want to take this action right now";
+
+        assert!(engine
+            .evaluate(&tool_result(false, "exit 1"), Some("s"), None)
+            .is_empty());
+        assert!(engine
+            .evaluate(&tool_result(false, rejected), Some("s"), None)
+            .is_empty());
+        assert!(engine
+            .evaluate(&tool_result(false, "exit 1"), Some("s"), None)
+            .is_empty());
+        let fired = engine.evaluate(&tool_result(false, "exit 2"), Some("s"), None);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1.value, 3.0);
+
+        // Success resets the streak
+        assert!(engine
+            .evaluate(&tool_result(true, "ok"), Some("s"), None)
+            .is_empty());
+        assert!(engine
+            .evaluate(&tool_result(false, "exit 1"), Some("s"), None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_error_status_and_rate_limit() {
+        let mut engine = AlertEngine::new(
+            vec![
+                rule(AlertCondition::ErrorStatus { min_status: 500 }, 0),
+                rule(
+                    AlertCondition::RateLimitRemaining {
+                        requests_below: Some(10),
+                        tokens_below: None,
+                    },
+                    0,
+                ),
+            ],
+            100_000,
+        );
+        let response = |status| ProxyEvent::Response {
+            request_id: "r".into(),
+            timestamp: at(0),
+            status,
+            body_size: 0,
+            ttfb: std::time::Duration::ZERO,
+            duration: std::time::Duration::ZERO,
+            body: None,
+        };
+
+        assert!(engine.evaluate(&response(429), None, None).is_empty());
+        let fired = engine.evaluate(&response(529), None, None);
+        assert_eq!(fired[0].0, 0);
+        assert_eq!(fired[0].1.kind, "error_status");
+
+        let limits = ProxyEvent::RateLimitUpdate {
+            timestamp: at(0),
+            requests_remaining: Some(3),
+            requests_limit: Some(50),
+            tokens_remaining: Some(1_000),
+            tokens_limit: None,
+            reset_time: None,
+        };
+        let fired = engine.evaluate(&limits, None, None);
+        assert_eq!(fired[0].0, 1);
+        assert_eq!(fired[0].1.message, "Rate limit: 3 requests remaining");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_run_command_killed_after_timeout() {
+        let start = Instant::now();
+        let timeout = std::time::Duration::from_millis(200);
+        let err = run_command("sleep 10", "{}", timeout).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+        run_command("cat > /dev/null", "{}", COMMAND_TIMEOUT).unwrap();
+    }
+}
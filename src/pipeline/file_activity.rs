@@ -0,0 +1,419 @@
+//! File activity tracking across tool calls
+//!
+//! Read/Edit/Write/Grep/Glob tool inputs name the files Claude touches. This
+//! module extracts `(path, operation)` pairs from `ProxyEvent::ToolCall`
+//! inputs and records them in the lifestats `file_activity` table, so
+//! questions like "which files were edited most this week" or "which sessions
+//! touched src/foo.rs" become simple queries.
+//!
+//! # Architecture
+//!
+//! ```text
+//! ProxyEvent::ToolCall
+//!         │
+//!         └──→ FileActivityProcessor (extract paths, non-blocking send)
+//!                 │
+//!                 └──→ lifestats-writer thread ──→ SQLite (file_activity)
+//! ```
+//!
+//! The table is created by the lifestats v5 migration; rows are inserted by
+//! the lifestats writer in the same batch transaction as the events.
+
+use super::lifestats::LifestatsWriter;
+use super::{EventProcessor, ProcessContext, ProcessResult};
+use crate::events::ProxyEvent;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Schema for the file_activity table (shared with the lifestats migration)
+pub const FILE_ACTIVITY_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS file_activity (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        call_id TEXT NOT NULL,
+        session_id TEXT,
+        timestamp TEXT NOT NULL,
+        tool_name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        op TEXT NOT NULL,                -- 'read', 'edit', 'write', 'search'
+        FOREIGN KEY (session_id) REFERENCES sessions(id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_file_activity_path ON file_activity(path);
+    CREATE INDEX IF NOT EXISTS idx_file_activity_session ON file_activity(session_id);
+    CREATE INDEX IF NOT EXISTS idx_file_activity_timestamp ON file_activity(timestamp);
+"#;
+
+/// What a tool call did to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileOp {
+    Read,
+    Edit,
+    Write,
+    /// Grep/Glob scoped to a file or directory
+    Search,
+}
+
+impl FileOp {
+    /// Parse operation name (returns None for unknown values)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "read" => Some(Self::Read),
+            "edit" => Some(Self::Edit),
+            "write" => Some(Self::Write),
+            "search" => Some(Self::Search),
+            _ => None,
+        }
+    }
+
+    /// Convert to string for storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Edit => "edit",
+            Self::Write => "write",
+            Self::Search => "search",
+        }
+    }
+}
+
+/// A file touched by a tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTouch {
+    pub path: String,
+    pub op: FileOp,
+}
+
+/// Extract touched paths from a tool call input
+///
+/// Returns an empty list for tools that don't name files (Bash commands are
+/// deliberately not parsed: guessing paths from shell syntax is unreliable).
+pub fn extract_file_touches(tool_name: &str, input: &serde_json::Value) -> Vec<FileTouch> {
+    let field = |name: &str| {
+        input
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+    };
+
+    let touch = match tool_name {
+        "Read" => field("file_path").map(|p| (p, FileOp::Read)),
+        "Edit" | "MultiEdit" => field("file_path").map(|p| (p, FileOp::Edit)),
+        "Write" => field("file_path").map(|p| (p, FileOp::Write)),
+        "NotebookEdit" => field("notebook_path").map(|p| (p, FileOp::Edit)),
+        "Grep" | "Glob" => field("path").map(|p| (p, FileOp::Search)),
+        _ => None,
+    };
+
+    touch
+        .map(|(path, op)| {
+            vec![FileTouch {
+                path: path.to_string(),
+                op,
+            }]
+        })
+        .unwrap_or_default()
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Processor
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// A row queued for the lifestats writer
+pub(super) struct ActivityRow {
+    call_id: String,
+    session_id: Option<String>,
+    timestamp: String,
+    tool_name: String,
+    touch: FileTouch,
+}
+
+/// Insert rows (called by the lifestats writer inside its batch transaction)
+pub(super) fn store_rows(conn: &Connection, rows: &[ActivityRow]) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO file_activity (call_id, session_id, timestamp, tool_name, path, op)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for row in rows {
+        stmt.execute(params![
+            row.call_id,
+            row.session_id,
+            row.timestamp,
+            row.tool_name,
+            row.touch.path,
+            row.touch.op.as_str()
+        ])?;
+    }
+    Ok(())
+}
+
+/// Records file touches from tool calls into the lifestats database
+pub struct FileActivityProcessor {
+    writer: LifestatsWriter,
+}
+
+impl FileActivityProcessor {
+    /// Create a processor storing rows through the lifestats writer
+    pub fn new(writer: LifestatsWriter) -> Self {
+        Self { writer }
+    }
+}
+
+impl EventProcessor for FileActivityProcessor {
+    fn name(&self) -> &'static str {
+        "file_activity"
+    }
+
+    fn process(&self, event: &ProxyEvent, ctx: &ProcessContext) -> ProcessResult {
+        let ProxyEvent::ToolCall {
+            id,
+            timestamp,
+            tool_name,
+            input,
+        } = event
+        else {
+            return ProcessResult::Continue;
+        };
+
+        let touches = extract_file_touches(tool_name, input);
+        if touches.is_empty() {
+            return ProcessResult::Continue;
+        }
+
+        let rows = touches
+            .into_iter()
+            .map(|touch| ActivityRow {
+                call_id: id.clone(),
+                session_id: ctx.session_id.as_deref().map(String::from),
+                timestamp: timestamp.to_rfc3339(),
+                tool_name: tool_name.clone(),
+                touch,
+            })
+            .collect();
+
+        self.writer.store_file_activity(rows);
+        ProcessResult::Continue
+    }
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Live Aggregation (TUI)
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Per-file counters
+#[derive(Debug, Clone, Default)]
+pub struct FileCounts {
+    pub reads: u32,
+    pub edits: u32,
+    pub writes: u32,
+    pub searches: u32,
+    pub sessions: HashSet<String>,
+}
+
+impl FileCounts {
+    pub fn total(&self) -> u32 {
+        self.reads + self.edits + self.writes + self.searches
+    }
+
+    fn record(&mut self, op: FileOp) {
+        match op {
+            FileOp::Read => self.reads += 1,
+            FileOp::Edit => self.edits += 1,
+            FileOp::Write => self.writes += 1,
+            FileOp::Search => self.searches += 1,
+        }
+    }
+}
+
+/// A file read again without being modified in between
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedundantReads {
+    pub session_id: Option<String>,
+    pub path: String,
+    pub reads: u32,
+    /// Reads that followed another read with no edit/write in between
+    pub redundant: u32,
+}
+
+/// In-memory file activity for the current TUI session
+#[derive(Debug, Default)]
+pub struct FileActivityLog {
+    files: HashMap<String, FileCounts>,
+    /// (session, path) -> (reads, redundant reads, read since last modification)
+    session_reads: HashMap<(Option<String>, String), (u32, u32, bool)>,
+}
+
+impl FileActivityLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a ToolCall event (other events are ignored)
+    pub fn record(&mut self, event: &ProxyEvent, session_id: Option<&str>) {
+        let ProxyEvent::ToolCall {
+            tool_name, input, ..
+        } = event
+        else {
+            return;
+        };
+
+        for touch in extract_file_touches(tool_name, input) {
+            let counts = self.files.entry(touch.path.clone()).or_default();
+            counts.record(touch.op);
+            if let Some(sid) = session_id {
+                counts.sessions.insert(sid.to_string());
+            }
+
+            let entry = self
+                .session_reads
+                .entry((session_id.map(String::from), touch.path))
+                .or_default();
+            match touch.op {
+                FileOp::Read => {
+                    entry.0 += 1;
+                    if entry.2 {
+                        entry.1 += 1;
+                    }
+                    entry.2 = true;
+                }
+                FileOp::Edit | FileOp::Write => entry.2 = false,
+                FileOp::Search => {}
+            }
+        }
+    }
+
+    /// Most-touched files, descending by total operations
+    pub fn top_files(&self, limit: usize) -> Vec<(&str, &FileCounts)> {
+        let mut files: Vec<_> = self
+            .files
+            .iter()
+            .map(|(path, counts)| (path.as_str(), counts))
+            .collect();
+        files.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then_with(|| a.0.cmp(b.0)));
+        files.truncate(limit);
+        files
+    }
+
+    /// Files re-read within a session without changes in between
+    pub fn redundant_reads(&self, limit: usize) -> Vec<RedundantReads> {
+        let mut reads: Vec<RedundantReads> = self
+            .session_reads
+            .iter()
+            .filter(|(_, (_, redundant, _))| *redundant > 0)
+            .map(
+                |((session_id, path), (reads, redundant, _))| RedundantReads {
+                    session_id: session_id.clone(),
+                    path: path.clone(),
+                    reads: *reads,
+                    redundant: *redundant,
+                },
+            )
+            .collect();
+        reads.sort_by(|a, b| {
+            b.redundant
+                .cmp(&a.redundant)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        reads.truncate(limit);
+        reads
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn tool_call(tool: &str, input: serde_json::Value) -> ProxyEvent {
+        ProxyEvent::ToolCall {
+            id: "t1".into(),
+            timestamp: Utc::now(),
+            tool_name: tool.into(),
+            input,
+        }
+    }
+
+    #[test]
+    fn test_extract_file_touches() {
+        let read = extract_file_touches("Read", &json!({"file_path": "/src/main.rs"}));
+        assert_eq!(
+            read,
+            vec![FileTouch {
+                path: "/src/main.rs".into(),
+                op: FileOp::Read
+            }]
+        );
+
+        let grep = extract_file_touches("Grep", &json!({"pattern": "fn", "path": "src/"}));
+        assert_eq!(grep[0].op, FileOp::Search);
+
+        // Glob without a path searches the cwd: nothing to attribute
+        assert!(extract_file_touches("Glob", &json!({"pattern": "**/*.rs"})).is_empty());
+        assert!(extract_file_touches("Bash", &json!({"command": "cat a.rs"})).is_empty());
+        assert!(extract_file_touches("Read", &json!({"file_path": "  "})).is_empty());
+    }
+
+    #[test]
+    fn test_file_op_roundtrip() {
+        for op in [FileOp::Read, FileOp::Edit, FileOp::Write, FileOp::Search] {
+            assert_eq!(FileOp::from_str(op.as_str()), Some(op));
+        }
+        assert_eq!(FileOp::from_str("delete"), None);
+    }
+
+    #[test]
+    fn test_store_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=OFF;").unwrap();
+        conn.execute_batch(FILE_ACTIVITY_SCHEMA).unwrap();
+        let rows: Vec<ActivityRow> = extract_file_touches("Edit", &json!({"file_path": "/a.rs"}))
+            .into_iter()
+            .map(|touch| ActivityRow {
+                call_id: "t1".into(),
+                session_id: Some("s1".into()),
+                timestamp: "2025-12-01T10:00:00Z".into(),
+                tool_name: "Edit".into(),
+                touch,
+            })
+            .collect();
+        store_rows(&conn, &rows).unwrap();
+
+        let (path, op): (String, String) = conn
+            .query_row("SELECT path, op FROM file_activity", [], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((path.as_str(), op.as_str()), ("/a.rs", "edit"));
+    }
+
+    #[test]
+    fn test_log_counts_redundant_reads() {
+        let mut log = FileActivityLog::new();
+        let read = tool_call("Read", json!({"file_path": "/a.rs"}));
+        let edit = tool_call("Edit", json!({"file_path": "/a.rs"}));
+
+        log.record(&read, Some("s1"));
+        log.record(&read, Some("s1")); // redundant
+        log.record(&edit, Some("s1"));
+        log.record(&read, Some("s1")); // legitimate re-read after edit
+        log.record(&read, Some("s2")); // different session
+
+        let top = log.top_files(10);
+        assert_eq!(top[0].0, "/a.rs");
+        assert_eq!(top[0].1.reads, 4);
+        assert_eq!(top[0].1.edits, 1);
+        assert_eq!(top[0].1.sessions.len(), 2);
+
+        let redundant = log.redundant_reads(10);
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(redundant[0].session_id.as_deref(), Some("s1"));
+        assert_eq!(redundant[0].reads, 3);
+        assert_eq!(redundant[0].redundant, 1);
+    }
+}
@@ -18,8 +18,12 @@
 //!                             └──→ SQLite (WAL mode)
 //! ```
 
+use super::file_activity::{self, ActivityRow};
+use super::todos::{PlanCache, TodoSnapshot};
 use super::{EventProcessor, ProcessContext, ProcessResult};
 use crate::events::ProxyEvent;
+use crate::git_link::extract_env_info;
+use crate::tool_analytics::is_user_rejection;
 use rusqlite::{params, Connection};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -40,20 +44,6 @@ fn truncate_utf8_safe(s: &str, max_bytes: usize) -> &str {
     &s[..end]
 }
 
-/// Known Claude Code rejection message patterns.
-/// These indicate the user rejected a tool call (not an actual error).
-const REJECTION_PATTERNS: &[&str] = &[
-    "The tool use was rejected (eg. if it was a file edit, the new_string was NOT written to the file)",
-    "The user doesn't want to take this action right now",
-];
-
-/// Check if a tool result output indicates a user rejection
-fn is_user_rejection(output: &str) -> bool {
-    REJECTION_PATTERNS
-        .iter()
-        .any(|pattern| output.contains(pattern))
-}
-
 /// Configuration for lifestats storage
 #[derive(Debug, Clone)]
 pub struct LifestatsConfig {
@@ -63,6 +53,8 @@ pub struct LifestatsConfig {
     pub store_thinking: bool,
     /// Whether to store full tool inputs/outputs
     pub store_tool_io: bool,
+    /// Whether to record the working directory and branch per session
+    pub capture_git_context: bool,
     /// Maximum thinking block size to store (bytes)
     pub max_thinking_size: usize,
     /// Retention period in days (0 = forever)
@@ -81,6 +73,7 @@ impl Default for LifestatsConfig {
             db_path: PathBuf::from("./data/lifestats.db"),
             store_thinking: true,
             store_tool_io: true,
+            capture_git_context: false,
             max_thinking_size: 100_000, // ~100KB per thinking block
             retention_days: 90,
             channel_buffer: 10_000, // Buffer before backpressure
@@ -118,11 +111,7 @@ impl LifestatsMetrics {
             avg_write_latency_us: {
                 let total = self.write_latency_us.load(Ordering::Relaxed);
                 let count = self.flush_count.load(Ordering::Relaxed);
-                if count > 0 {
-                    total / count
-                } else {
-                    0
-                }
+                total.checked_div(count).unwrap_or(0)
             },
         }
     }
@@ -141,9 +130,46 @@ pub struct MetricsSnapshot {
 /// Commands sent to the writer thread
 enum WriterCommand {
     Store(Box<ProxyEvent>, ProcessContext),
+    /// File touches extracted by `FileActivityProcessor`
+    FileActivity(Vec<ActivityRow>),
+    /// A TodoWrite plan parsed by `TodoProcessor`
+    Todos(TodoSnapshot),
     Shutdown,
 }
 
+/// Handle for companion processors that write through the lifestats writer
+///
+/// File activity and todo tracking share the writer thread (and its
+/// connection, schema and batching) instead of opening their own.
+#[derive(Clone)]
+pub struct LifestatsWriter {
+    tx: SyncSender<WriterCommand>,
+}
+
+impl LifestatsWriter {
+    /// Queue file activity rows (dropped with a warning under backpressure)
+    pub(super) fn store_file_activity(&self, rows: Vec<ActivityRow>) {
+        self.try_send(WriterCommand::FileActivity(rows), "file activity");
+    }
+
+    /// Queue a TodoWrite plan (dropped with a warning under backpressure)
+    pub(super) fn store_todos(&self, snapshot: TodoSnapshot) {
+        self.try_send(WriterCommand::Todos(snapshot), "TodoWrite plan");
+    }
+
+    fn try_send(&self, command: WriterCommand, what: &str) {
+        match self.tx.try_send(command) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(_)) => {
+                tracing::warn!("Lifestats backpressure: dropped {}", what);
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                tracing::error!("Lifestats writer thread disconnected");
+            }
+        }
+    }
+}
+
 /// Completion signal for graceful shutdown
 ///
 /// Uses a Condvar to block shutdown() until the writer thread has finished
@@ -243,6 +269,13 @@ impl LifestatsProcessor {
         })
     }
 
+    /// Handle for processors that store rows through this writer
+    pub fn writer(&self) -> LifestatsWriter {
+        LifestatsWriter {
+            tx: self.tx.clone(),
+        }
+    }
+
     /// Get current metrics snapshot
     #[allow(dead_code)] // Phase 2: Used by /api/lifestats/health endpoint
     pub fn metrics(&self) -> MetricsSnapshot {
@@ -266,6 +299,8 @@ impl LifestatsProcessor {
 
         // Batch buffer
         let mut batch: Vec<(ProxyEvent, ProcessContext)> = Vec::with_capacity(config.batch_size);
+        let mut activity: Vec<ActivityRow> = Vec::new();
+        let mut todo_plans = PlanCache::default();
         let mut last_flush = Instant::now();
 
         // Retention cleanup tracking (runs every 24 hours)
@@ -282,23 +317,32 @@ impl LifestatsProcessor {
                         .store(batch.len() as u64, Ordering::Relaxed);
 
                     // Flush if batch full
-                    if batch.len() >= config.batch_size {
-                        Self::flush_batch(&conn, &mut batch, &config, &metrics)?;
+                    if batch.len() + activity.len() >= config.batch_size {
+                        Self::flush_batch(&conn, &mut batch, &mut activity, &config, &metrics)?;
+                        last_flush = Instant::now();
+                    }
+                }
+                Ok(WriterCommand::FileActivity(rows)) => {
+                    activity.extend(rows);
+                    if batch.len() + activity.len() >= config.batch_size {
+                        Self::flush_batch(&conn, &mut batch, &mut activity, &config, &metrics)?;
                         last_flush = Instant::now();
                     }
                 }
+                Ok(WriterCommand::Todos(snapshot)) => {
+                    todo_plans.record(&conn, snapshot);
+                }
                 Ok(WriterCommand::Shutdown) => {
                     // Final flush before exit
-                    if !batch.is_empty() {
-                        Self::flush_batch(&conn, &mut batch, &config, &metrics)?;
-                    }
+                    Self::flush_batch(&conn, &mut batch, &mut activity, &config, &metrics)?;
                     tracing::debug!("Lifestats writer thread shutting down");
                     break;
                 }
                 Err(RecvTimeoutError::Timeout) => {
                     // Periodic flush even if batch not full
-                    if !batch.is_empty() && last_flush.elapsed() >= config.flush_interval {
-                        Self::flush_batch(&conn, &mut batch, &config, &metrics)?;
+                    let pending = !batch.is_empty() || !activity.is_empty();
+                    if pending && last_flush.elapsed() >= config.flush_interval {
+                        Self::flush_batch(&conn, &mut batch, &mut activity, &config, &metrics)?;
                         last_flush = Instant::now();
                     }
 
@@ -329,9 +373,7 @@ impl LifestatsProcessor {
                 }
                 Err(RecvTimeoutError::Disconnected) => {
                     // Channel closed, flush and exit
-                    if !batch.is_empty() {
-                        Self::flush_batch(&conn, &mut batch, &config, &metrics)?;
-                    }
+                    Self::flush_batch(&conn, &mut batch, &mut activity, &config, &metrics)?;
                     break;
                 }
             }
@@ -344,10 +386,11 @@ impl LifestatsProcessor {
     fn flush_batch(
         conn: &Connection,
         batch: &mut Vec<(ProxyEvent, ProcessContext)>,
+        activity: &mut Vec<ActivityRow>,
         config: &LifestatsConfig,
         metrics: &LifestatsMetrics,
     ) -> anyhow::Result<()> {
-        if batch.is_empty() {
+        if batch.is_empty() && activity.is_empty() {
             return Ok(());
         }
 
@@ -370,6 +413,15 @@ impl LifestatsProcessor {
             }
         }
 
+        if let Err(e) = file_activity::store_rows(conn, activity) {
+            tracing::warn!(
+                "Failed to store {} file activity rows: {}",
+                activity.len(),
+                e
+            );
+        }
+        activity.clear();
+
         conn.execute("COMMIT", [])?;
 
         // Update metrics
@@ -438,6 +490,18 @@ impl LifestatsProcessor {
         if current_version < 4 {
             Self::migrate_v3_to_v4(conn)?;
         }
+        if current_version < 5 {
+            Self::migrate_v4_to_v5(conn)?;
+        }
+        if current_version < 6 {
+            Self::migrate_v5_to_v6(conn)?;
+        }
+        if current_version < 7 {
+            Self::migrate_v6_to_v7(conn)?;
+        }
+        if current_version < 8 {
+            Self::migrate_v7_to_v8(conn)?;
+        }
 
         Ok(())
     }
@@ -728,6 +792,85 @@ impl LifestatsProcessor {
         Ok(())
     }
 
+    /// Migrate from v4 to v5: file activity tracking
+    ///
+    /// Adds the `file_activity` table populated by `FileActivityProcessor`
+    /// (one row per file touched by a Read/Edit/Write/Grep/Glob call).
+    fn migrate_v4_to_v5(conn: &Connection) -> anyhow::Result<()> {
+        conn.execute_batch(super::file_activity::FILE_ACTIVITY_SCHEMA)?;
+
+        conn.execute(
+            "UPDATE metadata SET value = '5' WHERE key = 'schema_version'",
+            [],
+        )?;
+
+        tracing::info!("Migrated lifestats database from v4 to v5 (added file_activity table)");
+        Ok(())
+    }
+
+    /// Migrate from v5 to v6: git-aware session correlation
+    ///
+    /// Adds `session_context` (working directory and branch captured from the
+    /// system prompt) and `session_commits` (links written by `aspy git-link`).
+    fn migrate_v5_to_v6(conn: &Connection) -> anyhow::Result<()> {
+        conn.execute_batch(crate::git_link::GIT_LINK_SCHEMA)?;
+
+        conn.execute(
+            "UPDATE metadata SET value = '6' WHERE key = 'schema_version'",
+            [],
+        )?;
+
+        tracing::info!(
+            "Migrated lifestats database from v5 to v6 (added session_context, session_commits)"
+        );
+        Ok(())
+    }
+
+    /// Migrate from v6 to v7: todo tracking
+    ///
+    /// Adds the `todo_events` table populated by `TodoProcessor` (one row per
+    /// todo transition observed between consecutive TodoWrite calls).
+    fn migrate_v6_to_v7(conn: &Connection) -> anyhow::Result<()> {
+        conn.execute_batch(super::todos::TODOS_SCHEMA)?;
+
+        conn.execute(
+            "UPDATE metadata SET value = '7' WHERE key = 'schema_version'",
+            [],
+        )?;
+
+        tracing::info!("Migrated lifestats database from v6 to v7 (added todo_events table)");
+        Ok(())
+    }
+
+    /// Migrate from v7 to v8: context compactions
+    ///
+    /// Adds the `compactions` table (one row per `ContextCompact` event) so the
+    /// TUI History view can count them per session.
+    fn migrate_v7_to_v8(conn: &Connection) -> anyhow::Result<()> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS compactions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT,
+                timestamp TEXT NOT NULL,
+                previous_context INTEGER NOT NULL,
+                new_context INTEGER NOT NULL,
+
+                FOREIGN KEY (session_id) REFERENCES sessions(id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_compactions_session ON compactions(session_id);
+            "#,
+        )?;
+
+        conn.execute(
+            "UPDATE metadata SET value = '8' WHERE key = 'schema_version'",
+            [],
+        )?;
+
+        tracing::info!("Migrated lifestats database from v7 to v8 (added compactions table)");
+        Ok(())
+    }
+
     /// Retention cleanup - deletes old data and syncs FTS indexes
     ///
     /// # FTS External Content Sync Contract
@@ -861,6 +1004,31 @@ impl LifestatsProcessor {
             params![cutoff_str],
         )? as u64;
 
+        deleted += conn.execute(
+            "DELETE FROM file_activity WHERE timestamp < ?1",
+            params![cutoff_str],
+        )? as u64;
+
+        deleted += conn.execute(
+            "DELETE FROM todo_events WHERE timestamp < ?1",
+            params![cutoff_str],
+        )? as u64;
+
+        deleted += conn.execute(
+            "DELETE FROM compactions WHERE timestamp < ?1",
+            params![cutoff_str],
+        )? as u64;
+
+        deleted += conn.execute(
+            "DELETE FROM session_commits WHERE committed_at < ?1",
+            params![cutoff_str],
+        )? as u64;
+
+        deleted += conn.execute(
+            "DELETE FROM session_context WHERE last_seen < ?1",
+            params![cutoff_str],
+        )? as u64;
+
         // 6. Clean up orphaned sessions (no recent activity)
         deleted += conn.execute(
             "DELETE FROM sessions WHERE started_at < ?1 AND ended_at IS NOT NULL",
@@ -1011,6 +1179,53 @@ impl LifestatsProcessor {
                 )?;
             }
 
+            ProxyEvent::Request {
+                timestamp,
+                body: Some(body),
+                ..
+            } if config.capture_git_context => {
+                // Working directory and branch from Claude Code's system prompt
+                if let (Some(sid), Some(env)) = (session_id, extract_env_info(body)) {
+                    let timestamp = timestamp.to_rfc3339();
+                    conn.execute(
+                        "INSERT INTO session_context (session_id, user_id, cwd, is_git_repo, git_branch, platform, first_seen, last_seen)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+                         ON CONFLICT(session_id) DO UPDATE SET
+                            cwd = excluded.cwd,
+                            is_git_repo = excluded.is_git_repo,
+                            git_branch = excluded.git_branch,
+                            platform = excluded.platform,
+                            last_seen = excluded.last_seen",
+                        params![
+                            sid,
+                            ctx.user_id.as_deref(),
+                            env.cwd,
+                            env.is_git_repo,
+                            env.git_branch,
+                            env.platform,
+                            timestamp
+                        ],
+                    )?;
+                }
+            }
+
+            ProxyEvent::ContextCompact {
+                timestamp,
+                previous_context,
+                new_context,
+            } => {
+                conn.execute(
+                    "INSERT INTO compactions (session_id, timestamp, previous_context, new_context)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        session_id,
+                        timestamp.to_rfc3339(),
+                        *previous_context as i64,
+                        *new_context as i64
+                    ],
+                )?;
+            }
+
             ProxyEvent::UserPrompt { timestamp, content } => {
                 conn.execute(
                     "INSERT INTO user_prompts (session_id, timestamp, content)
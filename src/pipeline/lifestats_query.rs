@@ -23,11 +23,42 @@
 //! multiple concurrent readers while the writer thread is active. The connection
 //! pool manages up to 4 read-only connections for query parallelism.
 
+use super::file_activity::FileOp;
+use super::todos::{self, TodoEvent, TodoItem};
+use crate::events::{ProxyEvent, TrackedEvent};
+use crate::git_link::CommitCost;
+use crate::handoff::{self, error_summary, HandoffError, HandoffFile, SessionDigest};
+use crate::tool_analytics::{self, ToolCallRecord, ToolInsights};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::time::Duration;
+
+/// Most recent tool calls scanned by `get_tool_insights`
+const MAX_INSIGHT_CALLS: usize = 5000;
+
+/// RFC3339 cutoff for "last N days" filters
+fn since_days(days: Option<u32>) -> Option<String> {
+    days.map(|d| (chrono::Utc::now() - chrono::Duration::days(d as i64)).to_rfc3339())
+}
+
+/// Parse a stored timestamp
+///
+/// Event rows use RFC3339; `sessions.started_at` comes from SQLite's
+/// `datetime('now')` (`YYYY-MM-DD HH:MM:SS`, UTC).
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|t| t.and_utc())
+        })
+}
 
 /// Search mode for FTS queries
 ///
@@ -241,6 +272,30 @@ pub struct ToolStats {
     pub errors: i64,
 }
 
+/// API usage for one day, model and client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyUsage {
+    /// UTC calendar day
+    pub day: NaiveDate,
+    pub model: String,
+    /// Session owner (`None` for usage outside a known session)
+    pub client: Option<String>,
+    pub tokens: i64, // = input + output + cache_read + cache_creation
+    pub cache_read_tokens: i64,
+    pub cost_usd: f64,
+    pub calls: i64,
+}
+
+/// Statistics breakdown by client (session owner)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientStats {
+    pub client: Option<String>,
+    pub sessions: i64,
+    pub tokens: i64,
+    pub cost_usd: f64,
+    pub calls: i64,
+}
+
 /// Query interface for lifestats database
 ///
 /// Uses connection pooling for efficient concurrent access.
@@ -1099,6 +1154,838 @@ impl LifestatsQuery {
         })
     }
 
+    // ═════════════════════════════════════════════════════════════════════════
+    // Usage History
+    // ═════════════════════════════════════════════════════════════════════════
+
+    /// API usage per UTC day, model and client (oldest first)
+    ///
+    /// Fine-grained enough to roll up into weeks or months, model mix over
+    /// time, or per-client totals without another query.
+    ///
+    /// # Arguments
+    /// * `days` - Only include usage from the last N days
+    pub fn get_daily_usage(&self, days: Option<u32>) -> anyhow::Result<Vec<DailyUsage>> {
+        let conn = self.conn()?;
+        let since = since_days(days);
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                substr(u.timestamp, 1, 10) AS day,
+                u.model,
+                s.user_id,
+                COALESCE(SUM(u.input_tokens + u.output_tokens + u.cache_read_tokens + u.cache_creation_tokens), 0),
+                COALESCE(SUM(u.cache_read_tokens), 0),
+                COALESCE(SUM(u.cost_usd), 0),
+                COUNT(*)
+            FROM api_usage u
+            LEFT JOIN sessions s ON u.session_id = s.id
+            WHERE (?1 IS NULL OR u.timestamp >= ?1)
+            GROUP BY day, u.model, s.user_id
+            ORDER BY day
+            "#,
+        )?;
+
+        let rows = stmt.query_map(params![since], |row| {
+            let day: String = row.get(0)?;
+            // Rows whose timestamp isn't RFC3339 are skipped rather than misdated
+            let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") else {
+                return Ok(None);
+            };
+            Ok(Some(DailyUsage {
+                day,
+                model: row.get(1)?,
+                client: row.get(2)?,
+                tokens: row.get(3)?,
+                cache_read_tokens: row.get(4)?,
+                cost_usd: row.get(5)?,
+                calls: row.get(6)?,
+            }))
+        })?;
+
+        let mut usage = Vec::new();
+        for row in rows {
+            usage.extend(row?);
+        }
+        Ok(usage)
+    }
+
+    /// Lifetime usage per client, most expensive first
+    pub fn get_client_stats(&self) -> anyhow::Result<Vec<ClientStats>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                s.user_id,
+                COUNT(DISTINCT u.session_id),
+                COALESCE(SUM(u.input_tokens + u.output_tokens + u.cache_read_tokens + u.cache_creation_tokens), 0),
+                COALESCE(SUM(u.cost_usd), 0) AS cost,
+                COUNT(*)
+            FROM api_usage u
+            LEFT JOIN sessions s ON u.session_id = s.id
+            GROUP BY s.user_id
+            ORDER BY cost DESC
+            "#,
+        )?;
+
+        let clients = stmt
+            .query_map([], |row| {
+                Ok(ClientStats {
+                    client: row.get(0)?,
+                    sessions: row.get(1)?,
+                    tokens: row.get(2)?,
+                    cost_usd: row.get(3)?,
+                    calls: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(clients)
+    }
+
+    // ═════════════════════════════════════════════════════════════════════════
+    // Tool Insights
+    // ═════════════════════════════════════════════════════════════════════════
+
+    /// Analyze recent tool calls for failure patterns, rejections and slow calls
+    ///
+    /// Loads up to `MAX_INSIGHT_CALLS` of the most recent tool calls (joined
+    /// with their results) and runs `tool_analytics::analyze` over them.
+    ///
+    /// # Arguments
+    /// * `user_id` - Only include sessions belonging to this user
+    /// * `days` - Only include calls from the last N days
+    /// * `limit` - Maximum entries per list in the report
+    pub fn get_tool_insights(
+        &self,
+        user_id: Option<&str>,
+        days: Option<u32>,
+        limit: usize,
+    ) -> anyhow::Result<ToolInsights> {
+        let conn = self.conn()?;
+
+        let since = since_days(days);
+
+        let sql = r#"
+            SELECT
+                tc.id,
+                tc.session_id,
+                tc.timestamp,
+                tc.tool_name,
+                tc.input_json,
+                tr.output_json,
+                tr.duration_ms,
+                tr.success,
+                COALESCE(tr.is_rejection, 0)
+            FROM tool_calls tc
+            LEFT JOIN tool_results tr ON tc.id = tr.call_id
+            LEFT JOIN sessions s ON tc.session_id = s.id
+            WHERE (?1 IS NULL OR s.user_id = ?1)
+              AND (?2 IS NULL OR tc.timestamp >= ?2)
+            ORDER BY tc.timestamp DESC
+            LIMIT ?3
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![user_id, since, MAX_INSIGHT_CALLS as i64], |row| {
+            let input_json: Option<String> = row.get(4)?;
+            let output_json: Option<String> = row.get(5)?;
+            let duration_ms: Option<i64> = row.get(6)?;
+            let success: Option<i64> = row.get(7)?;
+            let is_rejection: i64 = row.get(8)?;
+            Ok(ToolCallRecord {
+                call_id: row.get(0)?,
+                session_id: row.get(1)?,
+                timestamp: row.get(2)?,
+                tool_name: row.get(3)?,
+                input: input_json
+                    .and_then(|j| serde_json::from_str(&j).ok())
+                    .unwrap_or(serde_json::Value::Null),
+                output: output_json.map(|j| match serde_json::from_str(&j) {
+                    Ok(value) => tool_analytics::output_text(&value),
+                    Err(_) => j,
+                }),
+                duration_ms: duration_ms.map(|d| d.max(0) as u64),
+                success: success.map(|s| s != 0),
+                is_rejection: is_rejection != 0,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+        Ok(tool_analytics::analyze(&records, limit))
+    }
+
+    // ═════════════════════════════════════════════════════════════════════════
+    // File Activity
+    // ═════════════════════════════════════════════════════════════════════════
+
+    /// Most-touched files, with per-operation counts
+    ///
+    /// # Arguments
+    /// * `user_id` - Only include sessions belonging to this user
+    /// * `days` - Only include activity from the last N days
+    /// * `op` - Only count one operation (read, edit, write, search)
+    /// * `limit` - Maximum number of files
+    pub fn get_top_files(
+        &self,
+        user_id: Option<&str>,
+        days: Option<u32>,
+        op: Option<FileOp>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<FileStats>> {
+        let conn = self.conn()?;
+        let since = since_days(days);
+
+        let sql = r#"
+            SELECT
+                fa.path,
+                SUM(fa.op = 'read'),
+                SUM(fa.op = 'edit'),
+                SUM(fa.op = 'write'),
+                SUM(fa.op = 'search'),
+                COUNT(*) as total,
+                COUNT(DISTINCT fa.session_id),
+                MAX(fa.timestamp)
+            FROM file_activity fa
+            LEFT JOIN sessions s ON fa.session_id = s.id
+            WHERE (?1 IS NULL OR s.user_id = ?1)
+              AND (?2 IS NULL OR fa.timestamp >= ?2)
+              AND (?3 IS NULL OR fa.op = ?3)
+            GROUP BY fa.path
+            ORDER BY total DESC, MAX(fa.timestamp) DESC
+            LIMIT ?4
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(
+            params![user_id, since, op.map(|o| o.as_str()), limit as i64],
+            |row| {
+                Ok(FileStats {
+                    path: row.get(0)?,
+                    reads: row.get::<_, i64>(1)? as u64,
+                    edits: row.get::<_, i64>(2)? as u64,
+                    writes: row.get::<_, i64>(3)? as u64,
+                    searches: row.get::<_, i64>(4)? as u64,
+                    total: row.get::<_, i64>(5)? as u64,
+                    sessions: row.get::<_, i64>(6)? as u64,
+                    last_touched: row.get(7)?,
+                })
+            },
+        )?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// Sessions that touched a file
+    ///
+    /// `path` matches exactly or as a path suffix, so "src/foo.rs" finds
+    /// "/home/user/project/src/foo.rs".
+    pub fn get_file_sessions(
+        &self,
+        path: &str,
+        user_id: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<FileSessionActivity>> {
+        let conn = self.conn()?;
+        let suffix = format!("/{}", path.trim_start_matches('/'));
+
+        let sql = r#"
+            SELECT
+                fa.session_id,
+                s.user_id,
+                MIN(fa.timestamp),
+                MAX(fa.timestamp),
+                SUM(fa.op = 'read'),
+                SUM(fa.op = 'edit'),
+                SUM(fa.op = 'write'),
+                GROUP_CONCAT(DISTINCT fa.path)
+            FROM file_activity fa
+            LEFT JOIN sessions s ON fa.session_id = s.id
+            WHERE (fa.path = ?1 OR substr(fa.path, -length(?2)) = ?2)
+              AND (?3 IS NULL OR s.user_id = ?3)
+            GROUP BY fa.session_id
+            ORDER BY MAX(fa.timestamp) DESC
+            LIMIT ?4
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![path, suffix, user_id, limit as i64], |row| {
+            let paths: Option<String> = row.get(7)?;
+            Ok(FileSessionActivity {
+                session_id: row.get(0)?,
+                user_id: row.get(1)?,
+                first_touched: row.get(2)?,
+                last_touched: row.get(3)?,
+                reads: row.get::<_, i64>(4)? as u64,
+                edits: row.get::<_, i64>(5)? as u64,
+                writes: row.get::<_, i64>(6)? as u64,
+                paths: paths
+                    .map(|p| p.split(',').map(String::from).collect())
+                    .unwrap_or_default(),
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    /// Files read repeatedly within one session without changes in between
+    ///
+    /// A re-read after an Edit/Write is legitimate (the content changed); a
+    /// re-read of an unchanged file is counted as redundant context.
+    ///
+    /// # Arguments
+    /// * `user_id` - Only include sessions belonging to this user
+    /// * `session_id` - Only include one session
+    /// * `days` - Only include activity from the last N days
+    /// * `min_redundant` - Minimum redundant reads to report a file
+    /// * `limit` - Maximum number of results
+    pub fn get_repeated_reads(
+        &self,
+        user_id: Option<&str>,
+        session_id: Option<&str>,
+        days: Option<u32>,
+        min_redundant: u32,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RepeatedRead>> {
+        let conn = self.conn()?;
+        let since = since_days(days);
+
+        let sql = r#"
+            SELECT fa.session_id, fa.path, fa.op, fa.timestamp
+            FROM file_activity fa
+            LEFT JOIN sessions s ON fa.session_id = s.id
+            WHERE fa.session_id IS NOT NULL
+              AND fa.op IN ('read', 'edit', 'write')
+              AND (?1 IS NULL OR s.user_id = ?1)
+              AND (?2 IS NULL OR fa.session_id = ?2)
+              AND (?3 IS NULL OR fa.timestamp >= ?3)
+            ORDER BY fa.session_id, fa.path, fa.timestamp, fa.id
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![user_id, session_id, since], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut results: Vec<RepeatedRead> = Vec::new();
+        let mut read_since_change = false;
+        for row in rows {
+            let (session, path, op, timestamp) = row?;
+
+            let same_file = results
+                .last()
+                .is_some_and(|r| r.session_id == session && r.path == path);
+            if !same_file {
+                read_since_change = false;
+                results.push(RepeatedRead {
+                    session_id: session,
+                    path,
+                    reads: 0,
+                    redundant_reads: 0,
+                    modifications: 0,
+                    last_read: None,
+                });
+            }
+            let current = results.last_mut().expect("pushed above");
+
+            if op == FileOp::Read.as_str() {
+                current.reads += 1;
+                if read_since_change {
+                    current.redundant_reads += 1;
+                }
+                read_since_change = true;
+                current.last_read = Some(timestamp);
+            } else {
+                current.modifications += 1;
+                read_since_change = false;
+            }
+        }
+
+        results.retain(|r| r.redundant_reads >= u64::from(min_redundant.max(1)));
+        results.sort_by(|a, b| {
+            b.redundant_reads
+                .cmp(&a.redundant_reads)
+                .then_with(|| b.last_read.cmp(&a.last_read))
+        });
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    // ═════════════════════════════════════════════════════════════════════════
+    // Git Commits
+    // ═════════════════════════════════════════════════════════════════════════
+
+    /// Cost per commit, from links written by `aspy git-link`
+    ///
+    /// # Arguments
+    /// * `user_id` - Only include sessions belonging to this user
+    /// * `repo_root` - Only include commits in this repository
+    /// * `days` - Only include commits from the last N days
+    /// * `limit` - Maximum number of commits
+    pub fn get_commit_costs(
+        &self,
+        user_id: Option<&str>,
+        repo_root: Option<&str>,
+        days: Option<u32>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<CommitCost>> {
+        let conn = self.conn()?;
+        let since = since_days(days);
+
+        let sql = r#"
+            SELECT
+                sc.commit_hash,
+                sc.repo_root,
+                MAX(sc.committed_at),
+                MAX(sc.author),
+                MAX(sc.subject),
+                COUNT(DISTINCT sc.session_id),
+                MAX(sc.files_changed),
+                MAX(sc.files_matched),
+                SUM(sc.cost_usd)
+            FROM session_commits sc
+            LEFT JOIN sessions s ON sc.session_id = s.id
+            WHERE (?1 IS NULL OR s.user_id = ?1)
+              AND (?2 IS NULL OR sc.repo_root = ?2)
+              AND (?3 IS NULL OR sc.committed_at >= ?3)
+            GROUP BY sc.commit_hash, sc.repo_root
+            ORDER BY MAX(sc.committed_at) DESC
+            LIMIT ?4
+        "#;
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![user_id, repo_root, since, limit as i64], |row| {
+            Ok(CommitCost {
+                commit_hash: row.get(0)?,
+                repo_root: row.get(1)?,
+                committed_at: row.get(2)?,
+                author: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                subject: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                sessions: row.get::<_, i64>(5)? as u32,
+                files_changed: row.get::<_, i64>(6)? as u32,
+                files_matched: row.get::<_, i64>(7)? as u32,
+                cost_usd: row.get(8)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            results.push(row?);
+        }
+        Ok(results)
+    }
+
+    // ═════════════════════════════════════════════════════════════════════════
+    // Todo Plans
+    // ═════════════════════════════════════════════════════════════════════════
+
+    /// Current plan for a session, rebuilt from its todo timeline
+    pub fn get_todo_plan(&self, session_id: &str) -> anyhow::Result<Vec<TodoItem>> {
+        let conn = self.conn()?;
+        Ok(todos::load_plan(&conn, session_id)?)
+    }
+
+    /// Todo timeline for a session (oldest first)
+    pub fn get_todo_timeline(&self, session_id: &str) -> anyhow::Result<Vec<TodoEvent>> {
+        let conn = self.conn()?;
+        Ok(todos::load_timeline(&conn, session_id)?)
+    }
+
+    // ═════════════════════════════════════════════════════════════════════════
+    // Session Handoff
+    // ═════════════════════════════════════════════════════════════════════════
+
+    /// Raw material for a session handoff doc
+    ///
+    /// Collects the session's earliest prompts, file activity, latest
+    /// TodoWrite input, recent thinking and failed tool calls. Sections the
+    /// session has no rows for come back empty.
+    pub fn get_session_digest(&self, session_id: &str) -> anyhow::Result<SessionDigest> {
+        let conn = self.conn()?;
+
+        let context = conn
+            .query_row(
+                r#"
+                SELECT
+                    COALESCE(sc.user_id, (SELECT user_id FROM sessions WHERE id = ?1)),
+                    sc.cwd,
+                    sc.git_branch
+                FROM (SELECT ?1 AS id) q
+                LEFT JOIN session_context sc ON sc.session_id = q.id
+                "#,
+                params![session_id],
+                |row| {
+                    Ok((
+                        row.get::<_, Option<String>>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                },
+            )
+            .unwrap_or((None, None, None));
+
+        let mut stmt = conn.prepare(
+            "SELECT content FROM user_prompts WHERE session_id = ?1 ORDER BY timestamp, id LIMIT ?2",
+        )?;
+        let prompts = stmt
+            .query_map(
+                params![session_id, (handoff::MAX_GOALS * 2) as i64],
+                |row| row.get::<_, String>(0),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT path, SUM(op = 'read'), SUM(op = 'edit'), SUM(op = 'write')
+            FROM file_activity
+            WHERE session_id = ?1 AND op != 'search'
+            GROUP BY path
+            ORDER BY SUM(op IN ('edit', 'write')) DESC, COUNT(*) DESC, MAX(timestamp) DESC
+            LIMIT ?2
+            "#,
+        )?;
+        let files = stmt
+            .query_map(params![session_id, handoff::MAX_FILES as i64], |row| {
+                Ok(HandoffFile {
+                    path: row.get(0)?,
+                    reads: row.get::<_, i64>(1)? as u64,
+                    edits: row.get::<_, i64>(2)? as u64,
+                    writes: row.get::<_, i64>(3)? as u64,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let latest_todos = conn
+            .query_row(
+                r#"
+                SELECT input_json FROM tool_calls
+                WHERE session_id = ?1 AND tool_name = 'TodoWrite' AND input_json IS NOT NULL
+                ORDER BY timestamp DESC
+                LIMIT 1
+                "#,
+                params![session_id],
+                |row| row.get::<_, String>(0),
+            )
+            .ok();
+
+        let mut stmt = conn.prepare(
+            "SELECT content FROM thinking_blocks WHERE session_id = ?1 ORDER BY timestamp DESC, id DESC LIMIT ?2",
+        )?;
+        let mut thinking = stmt
+            .query_map(
+                params![session_id, handoff::MAX_THINKING_BLOCKS as i64],
+                |row| row.get::<_, String>(0),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        thinking.reverse();
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT tr.timestamp, tc.tool_name, tr.output_json
+            FROM tool_results tr
+            JOIN tool_calls tc ON tc.id = tr.call_id
+            WHERE tc.session_id = ?1 AND tr.success = 0 AND COALESCE(tr.is_rejection, 0) = 0
+            ORDER BY tr.timestamp DESC
+            LIMIT ?2
+            "#,
+        )?;
+        let errors = stmt
+            .query_map(params![session_id, handoff::MAX_ERRORS as i64], |row| {
+                let output: Option<String> = row.get(2)?;
+                Ok(HandoffError {
+                    timestamp: row.get(0)?,
+                    tool_name: row.get(1)?,
+                    message: output.as_deref().map(error_summary).unwrap_or_default(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SessionDigest {
+            session_id: session_id.to_string(),
+            user_id: context.0,
+            cwd: context.1,
+            git_branch: context.2,
+            prompts,
+            files,
+            latest_todos,
+            thinking,
+            errors,
+        })
+    }
+
+    // ═════════════════════════════════════════════════════════════════════════
+    // Session History
+    // ═════════════════════════════════════════════════════════════════════════
+
+    /// Most recent sessions with their activity totals (newest first)
+    ///
+    /// Totals are computed from the event tables - the aggregate columns on
+    /// `sessions` are not maintained by the writer.
+    pub fn list_sessions(&self, limit: usize) -> anyhow::Result<Vec<SessionSummary>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                s.id,
+                s.user_id,
+                s.started_at,
+                NULLIF(MAX(
+                    COALESCE((SELECT MAX(timestamp) FROM api_usage WHERE session_id = s.id), ''),
+                    COALESCE((SELECT MAX(timestamp) FROM tool_calls WHERE session_id = s.id), ''),
+                    COALESCE((SELECT MAX(timestamp) FROM user_prompts WHERE session_id = s.id), ''),
+                    COALESCE((SELECT MAX(timestamp) FROM assistant_responses WHERE session_id = s.id), '')
+                ), '') AS last_seen,
+                (SELECT COALESCE(SUM(cost_usd), 0) FROM api_usage WHERE session_id = s.id),
+                (SELECT COALESCE(SUM(input_tokens + output_tokens + cache_read_tokens + cache_creation_tokens), 0)
+                    FROM api_usage WHERE session_id = s.id),
+                (SELECT COUNT(*) FROM tool_calls WHERE session_id = s.id),
+                (SELECT COUNT(*) FROM user_prompts WHERE session_id = s.id),
+                (SELECT COUNT(*) FROM compactions WHERE session_id = s.id),
+                sc.cwd,
+                sc.git_branch
+            FROM sessions s
+            LEFT JOIN session_context sc ON sc.session_id = s.id
+            ORDER BY s.started_at DESC
+            LIMIT ?1
+            "#,
+        )?;
+
+        let sessions = stmt
+            .query_map(params![limit as i64], |row| {
+                let started_at: String = row.get(2)?;
+                let last_seen: Option<String> = row.get(3)?;
+                Ok(SessionSummary {
+                    id: row.get(0)?,
+                    user_id: row.get(1)?,
+                    started_at: parse_timestamp(&started_at),
+                    ended_at: last_seen.as_deref().and_then(parse_timestamp),
+                    cost_usd: row.get(4)?,
+                    tokens: row.get::<_, i64>(5)? as u64,
+                    tool_calls: row.get::<_, i64>(6)? as u64,
+                    prompts: row.get::<_, i64>(7)? as u64,
+                    compactions: row.get::<_, i64>(8)? as u64,
+                    cwd: row.get(9)?,
+                    git_branch: row.get(10)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// A session's stored events, rebuilt as tracked events (oldest first)
+    ///
+    /// Covers prompts, responses, thinking, tool calls and results, API usage
+    /// and compactions. Tool I/O is `null` when `store_tool_io` was off.
+    pub fn get_session_events(&self, session_id: &str) -> anyhow::Result<Vec<TrackedEvent>> {
+        let conn = self.conn()?;
+        let user_id: Option<String> = conn
+            .query_row(
+                "SELECT user_id FROM sessions WHERE id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .unwrap_or(None);
+
+        // (timestamp, event) - sorted at the end, rows that don't parse are skipped
+        let mut events: Vec<(String, ProxyEvent)> = Vec::new();
+        let json = |s: Option<String>| {
+            s.and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or(serde_json::Value::Null)
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, content FROM user_prompts WHERE session_id = ?1 ORDER BY id",
+        )?;
+        for row in stmt.query_map(params![session_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })? {
+            let (ts, content) = row?;
+            if let Some(timestamp) = parse_timestamp(&ts) {
+                events.push((ts, ProxyEvent::UserPrompt { timestamp, content }));
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, content FROM assistant_responses WHERE session_id = ?1 ORDER BY id",
+        )?;
+        for row in stmt.query_map(params![session_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })? {
+            let (ts, content) = row?;
+            if let Some(timestamp) = parse_timestamp(&ts) {
+                events.push((ts, ProxyEvent::AssistantResponse { timestamp, content }));
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, content, tokens FROM thinking_blocks WHERE session_id = ?1 ORDER BY id",
+        )?;
+        for row in stmt.query_map(params![session_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+            ))
+        })? {
+            let (ts, content, tokens) = row?;
+            if let Some(timestamp) = parse_timestamp(&ts) {
+                events.push((
+                    ts,
+                    ProxyEvent::Thinking {
+                        timestamp,
+                        content,
+                        token_estimate: tokens.unwrap_or(0) as u32,
+                    },
+                ));
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, tool_name, input_json FROM tool_calls WHERE session_id = ?1",
+        )?;
+        for row in stmt.query_map(params![session_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })? {
+            let (id, ts, tool_name, input) = row?;
+            if let Some(timestamp) = parse_timestamp(&ts) {
+                events.push((
+                    ts,
+                    ProxyEvent::ToolCall {
+                        id,
+                        timestamp,
+                        tool_name,
+                        input: json(input),
+                    },
+                ));
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT tr.call_id, tr.timestamp, tc.tool_name, tr.output_json, tr.duration_ms, tr.success
+            FROM tool_results tr
+            JOIN tool_calls tc ON tc.id = tr.call_id
+            WHERE tc.session_id = ?1
+            "#,
+        )?;
+        for row in stmt.query_map(params![session_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+            ))
+        })? {
+            let (id, ts, tool_name, output, duration_ms, success) = row?;
+            if let Some(timestamp) = parse_timestamp(&ts) {
+                events.push((
+                    ts,
+                    ProxyEvent::ToolResult {
+                        id,
+                        timestamp,
+                        tool_name,
+                        output: json(output),
+                        duration: Duration::from_millis(duration_ms.unwrap_or(0).max(0) as u64),
+                        success: success.unwrap_or(1) != 0,
+                    },
+                ));
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT timestamp, model, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens
+            FROM api_usage WHERE session_id = ?1 ORDER BY id
+            "#,
+        )?;
+        for row in stmt.query_map(params![session_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+            ))
+        })? {
+            let (ts, model, input, output, cache_creation, cache_read) = row?;
+            if let Some(timestamp) = parse_timestamp(&ts) {
+                events.push((
+                    ts,
+                    ProxyEvent::ApiUsage {
+                        timestamp,
+                        model,
+                        input_tokens: input.unwrap_or(0) as u32,
+                        output_tokens: output.unwrap_or(0) as u32,
+                        cache_creation_tokens: cache_creation.unwrap_or(0) as u32,
+                        cache_read_tokens: cache_read.unwrap_or(0) as u32,
+                    },
+                ));
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, previous_context, new_context FROM compactions WHERE session_id = ?1 ORDER BY id",
+        )?;
+        for row in stmt.query_map(params![session_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })? {
+            let (ts, previous_context, new_context) = row?;
+            if let Some(timestamp) = parse_timestamp(&ts) {
+                events.push((
+                    ts,
+                    ProxyEvent::ContextCompact {
+                        timestamp,
+                        previous_context: previous_context as u64,
+                        new_context: new_context as u64,
+                    },
+                ));
+            }
+        }
+
+        // Stable sort keeps table order for equal timestamps
+        events.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(events
+            .into_iter()
+            .map(|(_, event)| {
+                let mut tracked =
+                    TrackedEvent::new(event, user_id.clone(), Some(session_id.to_string()));
+                tracked.tracked_at = tracked.event_timestamp();
+                tracked
+            })
+            .collect())
+    }
+
     // ═════════════════════════════════════════════════════════════════════════
     // Semantic Search (Vector Similarity)
     // ═════════════════════════════════════════════════════════════════════════
@@ -1704,6 +2591,75 @@ impl LifestatsQuery {
     }
 }
 
+/// A past session with its activity totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub id: String,
+    /// Client ID (or API key hash)
+    pub user_id: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Latest prompt, response, tool call or API call
+    pub ended_at: Option<DateTime<Utc>>,
+    pub cost_usd: f64,
+    pub tokens: u64,
+    pub tool_calls: u64,
+    pub prompts: u64,
+    pub compactions: u64,
+    pub cwd: Option<String>,
+    pub git_branch: Option<String>,
+}
+
+impl SessionSummary {
+    /// Time from first seen to last activity
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        match (self.started_at, self.ended_at) {
+            (Some(start), Some(end)) if end >= start => Some(end - start),
+            _ => None,
+        }
+    }
+}
+
+/// Activity summary for one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStats {
+    pub path: String,
+    pub reads: u64,
+    pub edits: u64,
+    pub writes: u64,
+    pub searches: u64,
+    pub total: u64,
+    /// Distinct sessions that touched the file
+    pub sessions: u64,
+    pub last_touched: String,
+}
+
+/// A session's activity on a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSessionActivity {
+    pub session_id: Option<String>,
+    pub user_id: Option<String>,
+    pub first_touched: String,
+    pub last_touched: String,
+    pub reads: u64,
+    pub edits: u64,
+    pub writes: u64,
+    /// Matching paths as recorded (several when the query was a suffix)
+    pub paths: Vec<String>,
+}
+
+/// A file read repeatedly in one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedRead {
+    pub session_id: String,
+    pub path: String,
+    pub reads: u64,
+    /// Reads of an unchanged file (content already in context)
+    pub redundant_reads: u64,
+    /// Edits and writes in between
+    pub modifications: u64,
+    pub last_read: Option<String>,
+}
+
 /// Embedding statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingStats {
@@ -1720,3 +2676,155 @@ pub struct EmbeddingStats {
     pub total_documents: u64,
     pub progress_pct: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::lifestats::{LifestatsConfig, LifestatsProcessor};
+    use crate::pipeline::{EventProcessor, ProcessContext};
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn at(secs: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, secs).unwrap()
+    }
+
+    /// Store events through the real writer, then open a query pool on the file
+    fn store(dir: &Path, events: Vec<(&str, ProxyEvent)>) -> LifestatsQuery {
+        let db_path = dir.join("lifestats.db");
+        let processor = LifestatsProcessor::new(LifestatsConfig {
+            db_path: db_path.clone(),
+            ..LifestatsConfig::default()
+        })
+        .unwrap();
+        for (session, event) in &events {
+            processor.process(
+                event,
+                &ProcessContext::new(Some(session), Some("dev-1"), false),
+            );
+        }
+        processor.shutdown().unwrap();
+        LifestatsQuery::new(&db_path).unwrap()
+    }
+
+    fn prompt(secs: u32) -> ProxyEvent {
+        ProxyEvent::UserPrompt {
+            timestamp: at(secs),
+            content: format!("prompt at {}", secs),
+        }
+    }
+
+    #[test]
+    fn test_list_sessions_order_and_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let query = store(
+            dir.path(),
+            vec![
+                ("s1", prompt(1)),
+                ("s2", prompt(2)),
+                ("s2", prompt(5)),
+                ("s3", prompt(3)),
+                (
+                    "s2",
+                    ProxyEvent::ApiUsage {
+                        timestamp: at(4),
+                        model: "claude-sonnet-4".into(),
+                        input_tokens: 100,
+                        output_tokens: 20,
+                        cache_creation_tokens: 0,
+                        cache_read_tokens: 5,
+                    },
+                ),
+            ],
+        );
+
+        // The writer stamps started_at with the wall clock; pin it for ordering
+        let conn = query.conn().unwrap();
+        for (id, started) in [("s1", "09:00:00"), ("s2", "09:30:00"), ("s3", "09:15:00")] {
+            conn.execute(
+                "UPDATE sessions SET started_at = ?2 WHERE id = ?1",
+                params![id, format!("2025-01-01 {}", started)],
+            )
+            .unwrap();
+        }
+
+        let sessions = query.list_sessions(2).unwrap();
+        let ids: Vec<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["s2", "s3"]);
+
+        let s2 = &sessions[0];
+        assert_eq!(s2.user_id.as_deref(), Some("dev-1"));
+        assert_eq!(s2.prompts, 2);
+        assert_eq!(s2.tokens, 125);
+        assert_eq!(s2.ended_at, Some(at(5)));
+        assert_eq!(query.list_sessions(10).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_session_events_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = json!({"command": "cargo test"});
+        let query = store(
+            dir.path(),
+            vec![
+                // Stored out of order: results come back sorted by timestamp
+                (
+                    "s1",
+                    ProxyEvent::AssistantResponse {
+                        timestamp: at(4),
+                        content: "All green".into(),
+                    },
+                ),
+                ("s1", prompt(1)),
+                (
+                    "s1",
+                    ProxyEvent::ToolCall {
+                        id: "toolu_1".into(),
+                        timestamp: at(2),
+                        tool_name: "Bash".into(),
+                        input: input.clone(),
+                    },
+                ),
+                (
+                    "s1",
+                    ProxyEvent::ToolResult {
+                        id: "toolu_1".into(),
+                        timestamp: at(3),
+                        tool_name: "Bash".into(),
+                        output: json!("ok"),
+                        duration: Duration::from_millis(1500),
+                        success: true,
+                    },
+                ),
+                ("other", prompt(2)),
+            ],
+        );
+
+        let events = query.get_session_events("s1").unwrap();
+        assert_eq!(events.len(), 4);
+        assert!(events.iter().all(|e| e.user_id.as_deref() == Some("dev-1")
+            && e.session_id.as_deref() == Some("s1")
+            && e.tracked_at == e.event_timestamp()));
+
+        assert!(matches!(
+            &events[0].event,
+            ProxyEvent::UserPrompt { content, .. } if content == "prompt at 1"
+        ));
+        assert!(matches!(
+            &events[1].event,
+            ProxyEvent::ToolCall { id, tool_name, input: stored, .. }
+                if id == "toolu_1" && tool_name == "Bash" && *stored == input
+        ));
+        assert!(matches!(
+            &events[2].event,
+            ProxyEvent::ToolResult { output, duration, success: true, .. }
+                if *output == json!("ok") && *duration == Duration::from_millis(1500)
+        ));
+        assert!(matches!(
+            &events[3].event,
+            ProxyEvent::AssistantResponse { timestamp, .. } if *timestamp == at(4)
+        ));
+
+        assert!(query.get_session_events("missing").unwrap().is_empty());
+    }
+}
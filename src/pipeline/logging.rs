@@ -39,7 +39,10 @@ impl EventProcessor for LoggingProcessor {
             ProxyEvent::UserPrompt { .. } => "UserPrompt",
             ProxyEvent::AssistantResponse { .. } => "AssistantResponse",
             ProxyEvent::RequestTransformed { .. } => "RequestTransformed",
+            ProxyEvent::ResponseTransformed { .. } => "ResponseTransformed",
+            ProxyEvent::PolicyDecision { .. } => "PolicyDecision",
             ProxyEvent::ResponseAugmented { .. } => "ResponseAugmented",
+            ProxyEvent::ConfigReloaded { .. } => "ConfigReloaded",
         };
 
         // Log event type with context
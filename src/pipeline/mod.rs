@@ -21,11 +21,14 @@ use crate::events::ProxyEvent;
 use std::borrow::Cow;
 use std::sync::Arc;
 
+pub mod alerts;
 pub mod embedding_indexer;
 pub mod embeddings;
+pub mod file_activity;
 pub mod lifestats;
 pub mod lifestats_query;
 pub mod logging;
+pub mod todos;
 
 #[cfg(feature = "otel")]
 pub mod otel;
@@ -0,0 +1,604 @@
+//! Todo and plan tracking from TodoWrite tool calls
+//!
+//! Claude Code's `TodoWrite` tool sends the agent's whole task list on every
+//! call. This module diffs consecutive lists per session into a timeline of
+//! transitions (created, started, completed, abandoned, reopened) and records
+//! it in the lifestats `todo_events` table, so the current plan of any session
+//! can be rebuilt and progress compared across concurrent Claude instances.
+//!
+//! # Architecture
+//!
+//! ```text
+//! ProxyEvent::ToolCall (TodoWrite)
+//!         │
+//!         └──→ TodoProcessor (parse list, non-blocking send)
+//!                 │
+//!                 └──→ lifestats-writer thread (diff against last plan) ──→ SQLite (todo_events)
+//! ```
+//!
+//! The lifestats writer owns the per-session "last plan" cache (`PlanCache`)
+//! and rebuilds it from the table on a miss, so a restart doesn't replay every
+//! item as `created`.
+
+use super::lifestats::LifestatsWriter;
+use super::{EventProcessor, ProcessContext, ProcessResult};
+use crate::events::ProxyEvent;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Schema for the todo_events table (shared with the lifestats migration)
+pub const TODOS_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS todo_events (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        call_id TEXT NOT NULL,
+        session_id TEXT,
+        timestamp TEXT NOT NULL,
+        content TEXT NOT NULL,
+        active_form TEXT,
+        status TEXT NOT NULL,            -- 'pending', 'in_progress', 'completed'
+        transition TEXT NOT NULL,        -- 'created', 'started', 'completed', 'abandoned', 'reopened'
+        FOREIGN KEY (session_id) REFERENCES sessions(id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_todo_events_session ON todo_events(session_id);
+    CREATE INDEX IF NOT EXISTS idx_todo_events_timestamp ON todo_events(timestamp);
+"#;
+
+/// Sessions whose last plan is kept in `PlanCache`
+const MAX_CACHED_SESSIONS: usize = 256;
+
+/// Status of a single todo item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatus {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+impl TodoStatus {
+    /// Parse status name (returns None for unknown values)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(Self::Pending),
+            "in_progress" => Some(Self::InProgress),
+            "completed" => Some(Self::Completed),
+            _ => None,
+        }
+    }
+
+    /// Convert to string for storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::InProgress => "in_progress",
+            Self::Completed => "completed",
+        }
+    }
+}
+
+/// A todo item as sent by TodoWrite
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TodoItem {
+    pub content: String,
+    pub status: TodoStatus,
+    /// Present-tense label Claude Code shows while the item is in progress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_form: Option<String>,
+}
+
+/// Parse a TodoWrite input into its items
+///
+/// Returns None when the input has no `todos` array. Items without content
+/// are skipped; unknown statuses are treated as pending.
+pub fn parse_todo_write(input: &Value) -> Option<Vec<TodoItem>> {
+    let todos = input.get("todos")?.as_array()?;
+    Some(
+        todos
+            .iter()
+            .filter_map(|todo| {
+                let content = todo.get("content")?.as_str()?.trim();
+                if content.is_empty() {
+                    return None;
+                }
+                let status = todo
+                    .get("status")
+                    .and_then(|s| s.as_str())
+                    .and_then(TodoStatus::from_str)
+                    .unwrap_or(TodoStatus::Pending);
+                let active_form = todo
+                    .get("activeForm")
+                    .and_then(|s| s.as_str())
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from);
+                Some(TodoItem {
+                    content: content.to_string(),
+                    status,
+                    active_form,
+                })
+            })
+            .collect(),
+    )
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Timeline
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// What happened to an item between two TodoWrite calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TodoTransition {
+    /// First seen in the list
+    Created,
+    /// Moved to in_progress
+    Started,
+    /// Moved to completed
+    Completed,
+    /// Removed from the list without being completed
+    Abandoned,
+    /// Moved from completed back to pending/in_progress
+    Reopened,
+}
+
+impl TodoTransition {
+    /// Parse transition name (returns None for unknown values)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "created" => Some(Self::Created),
+            "started" => Some(Self::Started),
+            "completed" => Some(Self::Completed),
+            "abandoned" => Some(Self::Abandoned),
+            "reopened" => Some(Self::Reopened),
+            _ => None,
+        }
+    }
+
+    /// Convert to string for storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Started => "started",
+            Self::Completed => "completed",
+            Self::Abandoned => "abandoned",
+            Self::Reopened => "reopened",
+        }
+    }
+}
+
+/// One timeline entry: an item and the transition it went through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TodoChange {
+    pub item: TodoItem,
+    pub transition: TodoTransition,
+}
+
+/// Diff two consecutive plans into transitions, in list order
+///
+/// Items are matched by content. An item created already in progress or
+/// completed yields `created` followed by `started`/`completed`. Completed
+/// items dropped from the list are not abandoned — Claude Code clears them.
+pub fn diff_plans(prev: &[TodoItem], next: &[TodoItem]) -> Vec<TodoChange> {
+    let mut changes = Vec::new();
+    let change = |item: &TodoItem, transition| TodoChange {
+        item: item.clone(),
+        transition,
+    };
+
+    for item in next {
+        match prev.iter().find(|p| p.content == item.content) {
+            None => {
+                changes.push(change(item, TodoTransition::Created));
+                match item.status {
+                    TodoStatus::Pending => {}
+                    TodoStatus::InProgress => changes.push(change(item, TodoTransition::Started)),
+                    TodoStatus::Completed => changes.push(change(item, TodoTransition::Completed)),
+                }
+            }
+            Some(old) if old.status != item.status => {
+                let transition = match (old.status, item.status) {
+                    (TodoStatus::Completed, _) => TodoTransition::Reopened,
+                    (_, TodoStatus::Completed) => TodoTransition::Completed,
+                    (_, TodoStatus::InProgress) => TodoTransition::Started,
+                    // Put back to pending: record the status change as a reopen
+                    _ => TodoTransition::Reopened,
+                };
+                changes.push(change(item, transition));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for old in prev {
+        if old.status != TodoStatus::Completed && !next.iter().any(|n| n.content == old.content) {
+            changes.push(change(old, TodoTransition::Abandoned));
+        }
+    }
+
+    changes
+}
+
+/// Rebuild the current plan by replaying a session's timeline (oldest first)
+///
+/// Items keep the order they were first created in; abandoned items drop out.
+pub fn replay_plan(changes: &[TodoChange]) -> Vec<TodoItem> {
+    let mut plan: Vec<TodoItem> = Vec::new();
+    for change in changes {
+        let existing = plan.iter().position(|i| i.content == change.item.content);
+        match (change.transition, existing) {
+            (TodoTransition::Abandoned, Some(idx)) => {
+                plan.remove(idx);
+            }
+            (TodoTransition::Abandoned, None) => {}
+            (_, Some(idx)) => plan[idx] = change.item.clone(),
+            (_, None) => plan.push(change.item.clone()),
+        }
+    }
+    plan
+}
+
+/// Status counts for a plan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TodoCounts {
+    pub pending: usize,
+    pub in_progress: usize,
+    pub completed: usize,
+}
+
+impl TodoCounts {
+    pub fn of(items: &[TodoItem]) -> Self {
+        let mut counts = Self::default();
+        for item in items {
+            match item.status {
+                TodoStatus::Pending => counts.pending += 1,
+                TodoStatus::InProgress => counts.in_progress += 1,
+                TodoStatus::Completed => counts.completed += 1,
+            }
+        }
+        counts
+    }
+
+    pub fn total(&self) -> usize {
+        self.pending + self.in_progress + self.completed
+    }
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Processor
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// A TodoWrite call queued for the lifestats writer
+pub(super) struct TodoSnapshot {
+    call_id: String,
+    session_id: Option<String>,
+    timestamp: String,
+    items: Vec<TodoItem>,
+}
+
+/// Last plan per session, owned by the lifestats writer thread
+///
+/// Rebuilt from the table on a miss, so a restart doesn't replay every item
+/// as `created`.
+#[derive(Default)]
+pub(super) struct PlanCache {
+    /// Key: session id, "" when unknown
+    plans: HashMap<String, Vec<TodoItem>>,
+}
+
+impl PlanCache {
+    /// Diff a snapshot against the session's last plan and store the transitions
+    ///
+    /// TodoWrite calls are rare (a few per turn), so each is written immediately.
+    pub(super) fn record(&mut self, conn: &Connection, snapshot: TodoSnapshot) {
+        let key = snapshot.session_id.clone().unwrap_or_default();
+        if !self.plans.contains_key(&key) {
+            if self.plans.len() >= MAX_CACHED_SESSIONS {
+                self.plans.clear();
+            }
+            let previous = match snapshot.session_id.as_deref() {
+                Some(sid) => load_plan(conn, sid).unwrap_or_else(|e| {
+                    tracing::warn!("Failed to load todo plan for {}: {}", sid, e);
+                    Vec::new()
+                }),
+                None => Vec::new(),
+            };
+            self.plans.insert(key.clone(), previous);
+        }
+
+        let previous = self.plans.get(&key).map(Vec::as_slice).unwrap_or_default();
+        let changes = diff_plans(previous, &snapshot.items);
+        if let Err(e) = store_changes(conn, &snapshot, &changes) {
+            tracing::warn!("Failed to store {} todo transitions: {}", changes.len(), e);
+        }
+        self.plans.insert(key, snapshot.items);
+    }
+}
+
+fn store_changes(
+    conn: &Connection,
+    snapshot: &TodoSnapshot,
+    changes: &[TodoChange],
+) -> rusqlite::Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO todo_events
+             (call_id, session_id, timestamp, content, active_form, status, transition)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        for change in changes {
+            stmt.execute(params![
+                snapshot.call_id,
+                snapshot.session_id,
+                snapshot.timestamp,
+                change.item.content,
+                change.item.active_form,
+                change.item.status.as_str(),
+                change.transition.as_str()
+            ])?;
+        }
+    }
+    tx.commit()
+}
+
+/// Records TodoWrite plans as a per-session timeline in the lifestats database
+pub struct TodoProcessor {
+    writer: LifestatsWriter,
+}
+
+impl TodoProcessor {
+    /// Create a processor storing plans through the lifestats writer
+    pub fn new(writer: LifestatsWriter) -> Self {
+        Self { writer }
+    }
+}
+
+/// Load a session's timeline from the database (oldest first)
+///
+/// Shared by the writer (cache miss) and `LifestatsQuery`.
+pub fn load_timeline(conn: &Connection, session_id: &str) -> rusqlite::Result<Vec<TodoEvent>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT timestamp, call_id, content, active_form, status, transition
+         FROM todo_events WHERE session_id = ?1 ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![session_id], |row| {
+        let status: String = row.get(4)?;
+        let transition: String = row.get(5)?;
+        Ok(TodoEvent {
+            timestamp: row.get(0)?,
+            call_id: row.get(1)?,
+            change: TodoChange {
+                item: TodoItem {
+                    content: row.get(2)?,
+                    status: TodoStatus::from_str(&status).unwrap_or(TodoStatus::Pending),
+                    active_form: row.get(3)?,
+                },
+                transition: TodoTransition::from_str(&transition)
+                    .unwrap_or(TodoTransition::Created),
+            },
+        })
+    })?;
+    rows.collect()
+}
+
+/// Rebuild a session's current plan from the database
+pub fn load_plan(conn: &Connection, session_id: &str) -> rusqlite::Result<Vec<TodoItem>> {
+    let changes: Vec<TodoChange> = load_timeline(conn, session_id)?
+        .into_iter()
+        .map(|e| e.change)
+        .collect();
+    Ok(replay_plan(&changes))
+}
+
+/// A stored timeline entry
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoEvent {
+    pub timestamp: String,
+    pub call_id: String,
+    #[serde(flatten)]
+    pub change: TodoChange,
+}
+
+impl EventProcessor for TodoProcessor {
+    fn name(&self) -> &'static str {
+        "todos"
+    }
+
+    fn process(&self, event: &ProxyEvent, ctx: &ProcessContext) -> ProcessResult {
+        let ProxyEvent::ToolCall {
+            id,
+            timestamp,
+            tool_name,
+            input,
+        } = event
+        else {
+            return ProcessResult::Continue;
+        };
+
+        if tool_name != "TodoWrite" {
+            return ProcessResult::Continue;
+        }
+        let Some(items) = parse_todo_write(input) else {
+            return ProcessResult::Continue;
+        };
+
+        let snapshot = TodoSnapshot {
+            call_id: id.clone(),
+            session_id: ctx.session_id.as_deref().map(String::from),
+            timestamp: timestamp.to_rfc3339(),
+            items,
+        };
+
+        self.writer.store_todos(snapshot);
+        ProcessResult::Continue
+    }
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Live Plans (TUI)
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Latest plan per TUI session (keyed like the session selector: by user id)
+#[derive(Debug, Default)]
+pub struct TodoBoard {
+    plans: HashMap<String, Vec<TodoItem>>,
+}
+
+impl TodoBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a ToolCall event (non-TodoWrite events are ignored)
+    pub fn record(&mut self, event: &ProxyEvent, user_id: Option<&str>) {
+        let ProxyEvent::ToolCall {
+            tool_name, input, ..
+        } = event
+        else {
+            return;
+        };
+        if tool_name != "TodoWrite" {
+            return;
+        }
+        if let Some(items) = parse_todo_write(input) {
+            self.plans
+                .insert(user_id.unwrap_or_default().to_string(), items);
+        }
+    }
+
+    /// Current plan for a session (None if it never called TodoWrite)
+    pub fn plan(&self, user_id: Option<&str>) -> Option<&[TodoItem]> {
+        self.plans
+            .get(user_id.unwrap_or_default())
+            .map(Vec::as_slice)
+            .filter(|items| !items.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn item(content: &str, status: TodoStatus) -> TodoItem {
+        TodoItem {
+            content: content.into(),
+            status,
+            active_form: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_todo_write() {
+        let input = json!({"todos": [
+            {"content": "Write parser", "status": "in_progress", "activeForm": "Writing parser"},
+            {"content": "  ", "status": "pending"},
+            {"content": "Add tests", "status": "bogus"}
+        ]});
+        let items = parse_todo_write(&input).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].status, TodoStatus::InProgress);
+        assert_eq!(items[0].active_form.as_deref(), Some("Writing parser"));
+        assert_eq!(items[1].status, TodoStatus::Pending);
+        assert!(parse_todo_write(&json!({"command": "ls"})).is_none());
+    }
+
+    #[test]
+    fn test_diff_plans_transitions() {
+        let first = vec![
+            item("Write parser", TodoStatus::InProgress),
+            item("Add tests", TodoStatus::Pending),
+            item("Update docs", TodoStatus::Pending),
+        ];
+        let changes = diff_plans(&[], &first);
+        let transitions: Vec<_> = changes.iter().map(|c| c.transition).collect();
+        assert_eq!(
+            transitions,
+            vec![
+                TodoTransition::Created,
+                TodoTransition::Started,
+                TodoTransition::Created,
+                TodoTransition::Created
+            ]
+        );
+
+        // Parser done, tests started, docs dropped, benchmark added
+        let second = vec![
+            item("Write parser", TodoStatus::Completed),
+            item("Add tests", TodoStatus::InProgress),
+            item("Run benchmark", TodoStatus::Pending),
+        ];
+        let changes = diff_plans(&first, &second);
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.item.content.as_str(), c.transition))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Write parser", TodoTransition::Completed),
+                ("Add tests", TodoTransition::Started),
+                ("Run benchmark", TodoTransition::Created),
+                ("Update docs", TodoTransition::Abandoned),
+            ]
+        );
+
+        // Completed items cleared from the list are not abandoned
+        let third = vec![item("Add tests", TodoStatus::InProgress)];
+        let changes = diff_plans(&second, &third);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].item.content, "Run benchmark");
+        assert_eq!(changes[0].transition, TodoTransition::Abandoned);
+    }
+
+    #[test]
+    fn test_replay_plan_matches_latest_list() {
+        let first = vec![
+            item("Write parser", TodoStatus::InProgress),
+            item("Update docs", TodoStatus::Pending),
+        ];
+        let second = vec![
+            item("Write parser", TodoStatus::Completed),
+            item("Add tests", TodoStatus::Pending),
+        ];
+        let mut timeline = diff_plans(&[], &first);
+        timeline.extend(diff_plans(&first, &second));
+
+        let plan = replay_plan(&timeline);
+        assert_eq!(plan, second);
+        assert_eq!(
+            TodoCounts::of(&plan),
+            TodoCounts {
+                pending: 1,
+                in_progress: 0,
+                completed: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_timeline_roundtrip_through_sqlite() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=OFF;").unwrap();
+        conn.execute_batch(TODOS_SCHEMA).unwrap();
+        let plan = vec![item("Write parser", TodoStatus::InProgress)];
+        let snapshot = TodoSnapshot {
+            call_id: "toolu_1".into(),
+            session_id: Some("s1".into()),
+            timestamp: "2025-12-01T10:00:00Z".into(),
+            items: plan.clone(),
+        };
+        PlanCache::default().record(&conn, snapshot);
+
+        let timeline = load_timeline(&conn, "s1").unwrap();
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[1].change.transition, TodoTransition::Started);
+        assert_eq!(load_plan(&conn, "s1").unwrap(), plan);
+        assert!(load_plan(&conn, "other").unwrap().is_empty());
+    }
+}
@@ -78,7 +78,7 @@ impl EventBuffer {
 }
 
 /// Get the type name of an event (matches JSON "type" field)
-fn event_type_name(event: &ProxyEvent) -> &'static str {
+pub(crate) fn event_type_name(event: &ProxyEvent) -> &'static str {
     match event {
         ProxyEvent::ToolCall { .. } => "ToolCall",
         ProxyEvent::ToolResult { .. } => "ToolResult",
@@ -94,7 +94,10 @@ fn event_type_name(event: &ProxyEvent) -> &'static str {
         ProxyEvent::UserPrompt { .. } => "UserPrompt",
         ProxyEvent::AssistantResponse { .. } => "AssistantResponse",
         ProxyEvent::RequestTransformed { .. } => "RequestTransformed",
+        ProxyEvent::ResponseTransformed { .. } => "ResponseTransformed",
+        ProxyEvent::PolicyDecision { .. } => "PolicyDecision",
         ProxyEvent::ResponseAugmented { .. } => "ResponseAugmented",
+        ProxyEvent::ConfigReloaded { .. } => "ConfigReloaded",
     }
 }
 
@@ -399,6 +402,8 @@ pub struct ContextBreakdown {
     pub input: u64,
     /// Cached tokens read from prompt cache
     pub cached: u64,
+    /// Estimated tokens of image/document attachments (part of input + cached)
+    pub attachments: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -474,12 +479,222 @@ pub async fn get_context(
         breakdown: ContextBreakdown {
             input: ctx.input_tokens(),
             cached: ctx.last_cached,
+            attachments: ctx.attachment_tokens,
         },
     };
 
     Ok(Json(response))
 }
 
+// ============================================================================
+// Diff Endpoint
+// ============================================================================
+
+/// Query parameters for GET /api/diff
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    /// Request id of the "before" side (default: the request preceding `b`)
+    pub a: Option<String>,
+    /// Request id of the "after" side (default: the latest request)
+    pub b: Option<String>,
+    /// Take requests from this user's session instead of the global buffer
+    pub user: Option<String>,
+    /// Compare two sessions' stats instead (api_key_hash of each)
+    pub session_a: Option<String>,
+    pub session_b: Option<String>,
+}
+
+/// Response for GET /api/diff
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiffResponse {
+    Request {
+        a: String,
+        b: String,
+        #[serde(flatten)]
+        diff: crate::diff::RequestDiff,
+    },
+    Session(crate::diff::SessionDiff),
+}
+
+/// GET /api/diff - Structural diff of two requests or two sessions
+///
+/// Query params:
+///   - a, b: Request ids to compare (both optional - defaults to the latest
+///     request vs the one before it)
+///   - user: Look requests up in this user's session (api_key_hash)
+///   - session_a, session_b: Compare two sessions' aggregate stats instead
+pub async fn get_diff(
+    State(state): State<crate::proxy::ProxyState>,
+    Query(params): Query<DiffQuery>,
+) -> Result<Json<DiffResponse>, ApiError> {
+    if params.session_a.is_some() || params.session_b.is_some() {
+        let (Some(a), Some(b)) = (&params.session_a, &params.session_b) else {
+            return Err(ApiError::BadRequest(
+                "Session diff needs both ?session_a= and ?session_b=".to_string(),
+            ));
+        };
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock sessions: {}", e)))?;
+        let summary = |user: &str| {
+            sessions
+                .get_user_session(&UserId::new(user))
+                .map(|s| stats_summary(&s.stats))
+                .ok_or_else(|| ApiError::NotFound(format!("No active session for user: {}", user)))
+        };
+        let diff = crate::diff::SessionDiff::between(a, &summary(a)?, b, &summary(b)?);
+        return Ok(Json(DiffResponse::Session(diff)));
+    }
+
+    // Only the two chosen bodies are cloned under the lock; the diff runs after
+    let (a, b) = (params.a.as_deref(), params.b.as_deref());
+    let [(id_a, body_a), (id_b, body_b)] = if let Some(ref user_hash) = params.user {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock sessions: {}", e)))?;
+        let session = sessions
+            .get_user_session(&UserId::new(user_hash))
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("No active session for user: {}", user_hash))
+            })?;
+        request_pair(&mut session.events.iter(), a, b)?
+    } else {
+        let events_buffer = state
+            .events
+            .lock()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock events: {}", e)))?;
+        request_pair(&mut events_buffer.events.iter(), a, b)?
+    };
+
+    Ok(Json(DiffResponse::Request {
+        diff: crate::diff::RequestDiff::between(&body_a, &body_b),
+        a: id_a,
+        b: id_b,
+    }))
+}
+
+/// The two requests to diff: ids `a` and `b`, defaulting to the last two
+fn request_pair(
+    events: &mut dyn Iterator<Item = &ProxyEvent>,
+    a: Option<&str>,
+    b: Option<&str>,
+) -> Result<[(String, serde_json::Value); 2], ApiError> {
+    // Requests with bodies, oldest first
+    let requests: Vec<(&str, &serde_json::Value)> = events
+        .filter_map(|e| match e {
+            ProxyEvent::Request {
+                id,
+                body: Some(body),
+                ..
+            } => Some((id.as_str(), body)),
+            _ => None,
+        })
+        .collect();
+
+    let position = |id: &str| {
+        requests
+            .iter()
+            .position(|(rid, _)| *rid == id)
+            .ok_or_else(|| ApiError::NotFound(format!("No request body for id: {}", id)))
+    };
+    let b = match b {
+        Some(id) => position(id)?,
+        None => requests
+            .len()
+            .checked_sub(1)
+            .ok_or_else(|| ApiError::NotFound("No requests captured yet".to_string()))?,
+    };
+    let a = match a {
+        Some(id) => position(id)?,
+        None => b.checked_sub(1).ok_or_else(|| {
+            ApiError::NotFound("No earlier request to compare against".to_string())
+        })?,
+    };
+
+    let owned = |i: usize| (requests[i].0.to_string(), requests[i].1.clone());
+    Ok([owned(a), owned(b)])
+}
+
+// ============================================================================
+// Timeline Endpoint
+// ============================================================================
+
+/// Query parameters for GET /api/timeline
+#[derive(Debug, Deserialize)]
+pub struct TimelineQuery {
+    /// Take events from this user's session instead of the global buffer
+    pub user: Option<String>,
+    /// `chrome` (trace-event JSON, the default) or `spans`
+    pub format: Option<String>,
+}
+
+/// GET /api/timeline - Where a session's wall-clock time went
+///
+/// Query params:
+///   - user: Build from this user's session (api_key_hash); without it the
+///     global buffer is used, which mixes sessions
+///   - format: `chrome` (default) returns Chrome trace-event JSON for
+///     chrome://tracing or Perfetto; `spans` returns the waterfall spans
+pub async fn get_timeline(
+    State(state): State<crate::proxy::ProxyState>,
+    Query(params): Query<TimelineQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let timeline = if let Some(ref user_hash) = params.user {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock sessions: {}", e)))?;
+        let session = sessions
+            .get_user_session(&UserId::new(user_hash))
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("No active session for user: {}", user_hash))
+            })?;
+        crate::timeline::Timeline::from_events(session.events.iter())
+    } else {
+        let events_buffer = state
+            .events
+            .lock()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock events: {}", e)))?;
+        crate::timeline::Timeline::from_events(events_buffer.events.iter())
+    };
+
+    match params.format.as_deref().unwrap_or("chrome") {
+        "chrome" => Ok(Json(timeline.to_chrome_trace())),
+        "spans" => serde_json::to_value(&timeline)
+            .map(Json)
+            .map_err(|e| ApiError::Internal(format!("Failed to serialize timeline: {}", e))),
+        other => Err(ApiError::BadRequest(format!(
+            "Unknown format: {} (expected chrome or spans)",
+            other
+        ))),
+    }
+}
+
+/// Aggregate stats compared by session diffs
+fn stats_summary(stats: &Stats) -> serde_json::Value {
+    serde_json::json!({
+        "requests": stats.total_requests,
+        "failed_requests": stats.failed_requests,
+        "tool_calls": stats.total_tool_calls,
+        "failed_tool_calls": stats.failed_tool_calls,
+        "turns": stats.turn_count,
+        "compactions": stats.compact_count,
+        "thinking_blocks": stats.thinking_blocks,
+        "tokens": {
+            "input": stats.total_input_tokens,
+            "output": stats.total_output_tokens,
+            "cache_creation": stats.total_cache_creation_tokens,
+            "cache_read": stats.total_cache_read_tokens,
+        },
+        "cost_usd": stats.total_cost(),
+        "models": stats.model_calls,
+        "tools": stats.tool_calls_by_name,
+    })
+}
+
 // ============================================================================
 // Session Management Endpoints
 // ============================================================================
@@ -535,6 +750,9 @@ pub struct SessionListItem {
     pub status: String,
     /// Event count in this session
     pub event_count: usize,
+    /// Project root whose `.aspy.toml` applies to this session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
     /// Session-specific stats summary
     pub stats: SessionStatsSummary,
 }
@@ -625,6 +843,16 @@ pub async fn session_end(
     };
 
     sessions.end_session(&session_key, reason);
+    drop(sessions);
+
+    // Hand off to the next session (written in the background)
+    if let Some(handoff) = &state.live.load().handoff {
+        handoff.spawn_generate(
+            session_key.to_string(),
+            Some(request.user_id.clone()),
+            crate::handoff::HandoffTrigger::SessionEnd,
+        );
+    }
 
     tracing::info!(
         session_id = %request.session_id,
@@ -643,6 +871,39 @@ pub async fn session_end(
     }))
 }
 
+/// Response for GET /api/session/handoff/:user_id
+#[derive(Debug, Serialize)]
+pub struct HandoffResponse {
+    #[serde(flatten)]
+    pub doc: crate::handoff::HandoffDoc,
+    /// The doc rendered as Markdown (ready to paste or inject)
+    pub markdown: String,
+}
+
+/// GET /api/session/handoff/:user_id - Latest handoff doc for a user
+///
+/// Called by the next session (e.g. a SessionStart hook) to resume where the
+/// previous one stopped. Docs are written on session end and compaction.
+pub async fn get_session_handoff(
+    State(state): State<crate::proxy::ProxyState>,
+    Path(user_id): Path<String>,
+) -> Result<Json<HandoffResponse>, ApiError> {
+    let handoff = state.live.load().handoff.clone().ok_or_else(|| {
+        ApiError::NotFound("Session handoff not enabled (set [handoff] enabled = true)".to_string())
+    })?;
+
+    let doc = tokio::task::spawn_blocking(move || handoff.latest(&user_id))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Task join error: {}", e)))?
+        .map_err(|e| ApiError::Internal(format!("Failed to read handoff: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound("No handoff doc for this user".to_string()))?;
+
+    Ok(Json(HandoffResponse {
+        markdown: doc.to_markdown(),
+        doc,
+    }))
+}
+
 /// GET /api/sessions - List all active sessions
 ///
 /// Returns information about all tracked sessions, including their status
@@ -672,6 +933,7 @@ pub async fn get_sessions(
                 started: s.started,
                 status: status.to_string(),
                 event_count: s.events.len(),
+                project: s.project.as_ref().map(|p| p.display().to_string()),
                 stats: SessionStatsSummary {
                     requests: s.stats.total_requests,
                     tool_calls: s.stats.total_tool_calls,
@@ -689,6 +951,121 @@ pub async fn get_sessions(
     }))
 }
 
+// ═════════════════════════════════════════════════════════════════════════════
+// Todo Endpoints
+// ═════════════════════════════════════════════════════════════════════════════
+
+use crate::pipeline::todos::{TodoCounts, TodoEvent, TodoItem};
+
+/// Current plan of one session
+#[derive(Debug, Serialize)]
+pub struct SessionPlan {
+    pub session_id: String,
+    pub user_id: String,
+    pub status: String,
+    pub counts: TodoCounts,
+    pub items: Vec<TodoItem>,
+}
+
+/// Response for GET /api/todos
+#[derive(Debug, Serialize)]
+pub struct TodosResponse {
+    pub sessions: Vec<SessionPlan>,
+}
+
+/// GET /api/todos - Current plan of every live session
+///
+/// Lists sessions that are active or idle and have called TodoWrite, so a
+/// dashboard can show progress across concurrent Claude instances.
+pub async fn get_todos(
+    State(state): State<crate::proxy::ProxyState>,
+) -> Result<Json<TodosResponse>, ApiError> {
+    let query_interface = state
+        .lifestats_query
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Lifestats query interface not available".to_string()))?;
+
+    // Snapshot live sessions, then release the lock before querying
+    let live: Vec<(String, String, &'static str)> = {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock sessions: {}", e)))?;
+        sessions
+            .all_sessions()
+            .filter_map(|s| {
+                let status = match &s.status {
+                    crate::proxy::sessions::SessionStatus::Active => "active",
+                    crate::proxy::sessions::SessionStatus::Idle { .. } => "idle",
+                    crate::proxy::sessions::SessionStatus::Ended { .. } => return None,
+                };
+                Some((s.key.to_string(), s.user_id.to_string(), status))
+            })
+            .collect()
+    };
+
+    let mut plans = Vec::new();
+    for (session_id, user_id, status) in live {
+        let items = query_interface
+            .get_todo_plan(&session_id)
+            .map_err(|e| ApiError::Internal(format!("Failed to get todo plan: {}", e)))?;
+        if items.is_empty() {
+            continue;
+        }
+        plans.push(SessionPlan {
+            session_id,
+            user_id,
+            status: status.to_string(),
+            counts: TodoCounts::of(&items),
+            items,
+        });
+    }
+
+    Ok(Json(TodosResponse { sessions: plans }))
+}
+
+/// Response for GET /api/todos/:session_id
+#[derive(Debug, Serialize)]
+pub struct SessionTodosResponse {
+    pub session_id: String,
+    pub counts: TodoCounts,
+    pub items: Vec<TodoItem>,
+    pub timeline: Vec<TodoEvent>,
+}
+
+/// GET /api/todos/:session_id - Plan and todo timeline of one session
+///
+/// Works for ended sessions too (anything still in lifestats).
+pub async fn get_session_todos(
+    State(state): State<crate::proxy::ProxyState>,
+    Path(session_id): Path<String>,
+) -> Result<Json<SessionTodosResponse>, ApiError> {
+    let query_interface = state
+        .lifestats_query
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Lifestats query interface not available".to_string()))?;
+
+    let timeline = query_interface
+        .get_todo_timeline(&session_id)
+        .map_err(|e| ApiError::Internal(format!("Failed to get todo timeline: {}", e)))?;
+    if timeline.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "No todos recorded for session {}",
+            session_id
+        )));
+    }
+
+    let changes: Vec<_> = timeline.iter().map(|e| e.change.clone()).collect();
+    let items = crate::pipeline::todos::replay_plan(&changes);
+
+    Ok(Json(SessionTodosResponse {
+        session_id,
+        counts: TodoCounts::of(&items),
+        items,
+        timeline,
+    }))
+}
+
 /// API error responses
 /// Converted to HTTP status codes via IntoResponse
 #[derive(Debug)]
@@ -712,6 +1089,51 @@ impl IntoResponse for ApiError {
     }
 }
 
+// ============================================================================
+// Token Calibration Endpoint
+// ============================================================================
+
+/// GET /api/tokens/calibration - Drift of local token estimates vs actual usage
+///
+/// Each counter (heuristic, bpe, and api when count_tokens is enabled) reports
+/// its recent drift and a correction factor.
+pub async fn get_token_calibration(
+    State(state): State<crate::proxy::ProxyState>,
+) -> Result<Json<crate::tokens::CalibrationReport>, ApiError> {
+    let calibration = state.token_calibration.as_ref().ok_or_else(|| {
+        ApiError::NotFound(
+            "Token calibration is disabled. Set [tokens] calibration = true in config.".to_string(),
+        )
+    })?;
+
+    let calibration = calibration
+        .lock()
+        .map_err(|e| ApiError::Internal(format!("Failed to lock calibration: {}", e)))?;
+
+    Ok(Json(calibration.report()))
+}
+
+// ============================================================================
+// Config Reload Endpoint
+// ============================================================================
+
+/// POST /api/config/reload - Re-read config.toml and swap reloadable sections
+///
+/// Returns 200 with the applied sections, or 422 with the validation error
+/// (the running config is kept).
+pub async fn reload_config(
+    State(state): State<crate::proxy::ProxyState>,
+) -> (StatusCode, Json<crate::proxy::reload::ReloadReport>) {
+    let report =
+        crate::proxy::reload::reload(&state, crate::proxy::reload::ReloadTrigger::Api).await;
+    let status = if report.success {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    (status, Json(report))
+}
+
 // ============================================================================
 // Log Search Endpoint
 // ============================================================================
@@ -1081,9 +1503,13 @@ fn truncate_around_match(text: &str, keyword: &str, max_len: usize) -> String {
 // Lifestats Endpoints
 // ═════════════════════════════════════════════════════════════════════════════
 
+use crate::git_link::CommitCost;
+use crate::pipeline::file_activity::FileOp;
 use crate::pipeline::lifestats_query::{
-    ContextMatch, LifetimeStats, PromptMatch, ResponseMatch, SearchMode, ThinkingMatch,
+    ContextMatch, FileSessionActivity, FileStats, LifetimeStats, PromptMatch, RepeatedRead,
+    ResponseMatch, SearchMode, ThinkingMatch,
 };
+use crate::tool_analytics::ToolInsights;
 
 /// Response for lifestats health endpoint
 #[derive(Debug, Serialize)]
@@ -1331,6 +1757,226 @@ pub async fn lifestats_stats(
     Ok(Json(stats))
 }
 
+/// Query parameters for tool insights endpoint
+#[derive(Debug, Deserialize)]
+pub struct ToolInsightsQuery {
+    /// Only include sessions for this user
+    pub user: Option<String>,
+    /// Only include calls from the last N days
+    pub days: Option<u32>,
+    /// Maximum entries per list (default: 10, max: 100)
+    #[serde(default = "default_context_limit")]
+    pub limit: usize,
+}
+
+/// GET /api/lifestats/tools/insights - Tool failure patterns and slow calls
+///
+/// Returns repeated failures, Edit mismatches, non-zero Bash exits,
+/// rejections by tool and the slowest individual calls.
+///
+/// Query params:
+///   - user: Filter to one user's sessions (optional)
+///   - days: Only include the last N days (optional)
+///   - limit: Max entries per list (default: 10, max: 100)
+pub async fn lifestats_tool_insights(
+    State(state): State<super::ProxyState>,
+    Query(params): Query<ToolInsightsQuery>,
+) -> Result<Json<ToolInsights>, ApiError> {
+    let query_interface = state
+        .lifestats_query
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Lifestats query interface not available".to_string()))?;
+
+    let limit = params.limit.min(100);
+    let insights = query_interface
+        .get_tool_insights(params.user.as_deref(), params.days, limit)
+        .map_err(|e| ApiError::Internal(format!("Failed to get tool insights: {}", e)))?;
+
+    Ok(Json(insights))
+}
+
+/// Query parameters for top files endpoint
+#[derive(Debug, Deserialize)]
+pub struct TopFilesQuery {
+    /// Only include sessions for this user
+    pub user: Option<String>,
+    /// Only include activity from the last N days
+    pub days: Option<u32>,
+    /// Only count one operation: read, edit, write, search
+    pub op: Option<String>,
+    /// Maximum results (default: 10, max: 100)
+    #[serde(default = "default_context_limit")]
+    pub limit: usize,
+}
+
+/// GET /api/lifestats/files/top - Most-touched files
+///
+/// Query params:
+///   - user: Filter to one user's sessions (optional)
+///   - days: Only include the last N days (optional)
+///   - op: read|edit|write|search (optional, default: all)
+///   - limit: Max results (default: 10, max: 100)
+pub async fn lifestats_top_files(
+    State(state): State<super::ProxyState>,
+    Query(params): Query<TopFilesQuery>,
+) -> Result<Json<Vec<FileStats>>, ApiError> {
+    let query_interface = state
+        .lifestats_query
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Lifestats query interface not available".to_string()))?;
+
+    let op = match params.op.as_deref() {
+        Some(op) => Some(FileOp::from_str(op).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Unknown op '{}' (expected read, edit, write or search)",
+                op
+            ))
+        })?),
+        None => None,
+    };
+
+    let limit = params.limit.min(100);
+    let files = query_interface
+        .get_top_files(params.user.as_deref(), params.days, op, limit)
+        .map_err(|e| ApiError::Internal(format!("Failed to get file activity: {}", e)))?;
+
+    Ok(Json(files))
+}
+
+/// Query parameters for file sessions endpoint
+#[derive(Debug, Deserialize)]
+pub struct FileSessionsQuery {
+    /// File path (exact or suffix, e.g. "src/main.rs")
+    pub path: String,
+    /// Only include sessions for this user
+    pub user: Option<String>,
+    /// Maximum results (default: 10, max: 100)
+    #[serde(default = "default_context_limit")]
+    pub limit: usize,
+}
+
+/// GET /api/lifestats/files/sessions - Sessions that touched a file
+///
+/// Query params:
+///   - path: File path, matched exactly or as a suffix (required)
+///   - user: Filter to one user's sessions (optional)
+///   - limit: Max results (default: 10, max: 100)
+pub async fn lifestats_file_sessions(
+    State(state): State<super::ProxyState>,
+    Query(params): Query<FileSessionsQuery>,
+) -> Result<Json<Vec<FileSessionActivity>>, ApiError> {
+    let query_interface = state
+        .lifestats_query
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Lifestats query interface not available".to_string()))?;
+
+    if params.path.trim().is_empty() {
+        return Err(ApiError::BadRequest("path must not be empty".to_string()));
+    }
+
+    let limit = params.limit.min(100);
+    let sessions = query_interface
+        .get_file_sessions(params.path.trim(), params.user.as_deref(), limit)
+        .map_err(|e| ApiError::Internal(format!("Failed to get file sessions: {}", e)))?;
+
+    Ok(Json(sessions))
+}
+
+/// Query parameters for repeated reads endpoint
+#[derive(Debug, Deserialize)]
+pub struct RepeatedReadsQuery {
+    /// Only include sessions for this user
+    pub user: Option<String>,
+    /// Only include one session
+    pub session: Option<String>,
+    /// Only include activity from the last N days
+    pub days: Option<u32>,
+    /// Minimum redundant reads to report a file (default: 1)
+    #[serde(default = "default_min_redundant")]
+    pub min_redundant: u32,
+    /// Maximum results (default: 10, max: 100)
+    #[serde(default = "default_context_limit")]
+    pub limit: usize,
+}
+
+fn default_min_redundant() -> u32 {
+    1
+}
+
+/// GET /api/lifestats/files/repeated-reads - Files re-read without changes
+///
+/// Query params:
+///   - user: Filter to one user's sessions (optional)
+///   - session: Filter to one session (optional)
+///   - days: Only include the last N days (optional)
+///   - min_redundant: Minimum redundant reads (default: 1)
+///   - limit: Max results (default: 10, max: 100)
+pub async fn lifestats_repeated_reads(
+    State(state): State<super::ProxyState>,
+    Query(params): Query<RepeatedReadsQuery>,
+) -> Result<Json<Vec<RepeatedRead>>, ApiError> {
+    let query_interface = state
+        .lifestats_query
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Lifestats query interface not available".to_string()))?;
+
+    let limit = params.limit.min(100);
+    let reads = query_interface
+        .get_repeated_reads(
+            params.user.as_deref(),
+            params.session.as_deref(),
+            params.days,
+            params.min_redundant,
+            limit,
+        )
+        .map_err(|e| ApiError::Internal(format!("Failed to get repeated reads: {}", e)))?;
+
+    Ok(Json(reads))
+}
+
+/// Query parameters for git commit costs endpoint
+#[derive(Debug, Deserialize)]
+pub struct CommitCostsQuery {
+    /// Only include sessions for this user
+    pub user: Option<String>,
+    /// Only include commits in this repository (top-level path)
+    pub repo: Option<String>,
+    /// Only include commits from the last N days
+    pub days: Option<u32>,
+    /// Maximum results (default: 10, max: 100)
+    #[serde(default = "default_context_limit")]
+    pub limit: usize,
+}
+
+/// GET /api/lifestats/git/commits - Cost per commit (links from `aspy git-link`)
+///
+/// Query params:
+///   - user: Filter to one user's sessions (optional)
+///   - repo: Filter to one repository root (optional)
+///   - days: Only include the last N days (optional)
+///   - limit: Max results (default: 10, max: 100)
+pub async fn lifestats_commit_costs(
+    State(state): State<super::ProxyState>,
+    Query(params): Query<CommitCostsQuery>,
+) -> Result<Json<Vec<CommitCost>>, ApiError> {
+    let query_interface = state
+        .lifestats_query
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Lifestats query interface not available".to_string()))?;
+
+    let limit = params.limit.min(100);
+    let commits = query_interface
+        .get_commit_costs(
+            params.user.as_deref(),
+            params.repo.as_deref(),
+            params.days,
+            limit,
+        )
+        .map_err(|e| ApiError::Internal(format!("Failed to get commit costs: {}", e)))?;
+
+    Ok(Json(commits))
+}
+
 // ═════════════════════════════════════════════════════════════════════════════
 // User-Scoped Lifestats Endpoints (Cross-Session Context Recovery)
 // ═════════════════════════════════════════════════════════════════════════════
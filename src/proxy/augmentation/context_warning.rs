@@ -168,6 +168,10 @@ impl Augmenter for ContextWarningAugmenter {
         );
 
         let sse_bytes = self.generate_sse_block(ctx.next_block_index, &annotation);
-        Some(AugmentedContent::from_text(sse_bytes, &annotation))
+        Some(AugmentedContent::from_text(
+            sse_bytes,
+            &annotation,
+            ctx.token_counter,
+        ))
     }
 }
@@ -4,6 +4,11 @@
 // Augmenters can inject content into SSE streams (e.g., context warnings,
 // annotations, debug info) without touching core proxy logic.
 //
+// Each injection carries both forms of the same content block: SSE events for
+// streaming responses and a JSON block that `augment_message()` appends to
+// buffered (`stream: false`) responses. Both are Anthropic format; the proxy
+// translates them for OpenAI clients like any other response content.
+//
 // # Architecture
 //
 // ```
@@ -13,7 +18,7 @@
 // Each augmenter implements the `Augmenter` trait:
 // - `name()`: Human-readable identifier for logging
 // - `should_apply()`: Determines if augmenter should run for this response
-// - `generate_sse()`: Produces SSE bytes to inject
+// - `generate()`: Produces the content to inject (SSE bytes + JSON block)
 //
 // # Adding New Augmenters
 //
@@ -25,7 +30,9 @@ mod context_warning;
 
 pub use context_warning::ContextWarningAugmenter;
 
+use crate::tokens::TokenCounter;
 use crate::SharedContextState;
+use serde_json::{json, Value};
 
 // ============================================================================
 // Augmentation Result
@@ -34,25 +41,31 @@ use crate::SharedContextState;
 /// Result of an augmentation including the SSE bytes and token estimate
 #[derive(Debug)]
 pub struct AugmentedContent {
-    /// The SSE bytes to inject
+    /// The SSE bytes to inject (streaming responses)
     pub sse_bytes: Vec<u8>,
+    /// The same content as a JSON content block (buffered responses)
+    pub content_block: Value,
     /// Estimated tokens in the injected content
     pub tokens_injected: u32,
 }
 
 impl AugmentedContent {
     /// Create new augmented content with token estimate
-    pub fn new(sse_bytes: Vec<u8>, tokens_injected: u32) -> Self {
+    pub fn new(sse_bytes: Vec<u8>, content_block: Value, tokens_injected: u32) -> Self {
         Self {
             sse_bytes,
+            content_block,
             tokens_injected,
         }
     }
 
-    /// Create augmented content, auto-estimating tokens from text content
-    pub fn from_text(sse_bytes: Vec<u8>, text_content: &str) -> Self {
-        let tokens = crate::tokens::estimate_tokens(text_content);
-        Self::new(sse_bytes, tokens)
+    /// Create augmented content for a text block, estimating tokens from the text
+    pub fn from_text(sse_bytes: Vec<u8>, text_content: &str, counter: &dyn TokenCounter) -> Self {
+        Self::new(
+            sse_bytes,
+            json!({"type": "text", "text": text_content}),
+            counter.count(text_content),
+        )
     }
 }
 
@@ -73,6 +86,9 @@ pub struct AugmentationContext<'a> {
 
     /// Shared context state (token counts, warning thresholds)
     pub context_state: &'a SharedContextState,
+
+    /// Counter for sizing injected content (from `[tokens] counter`)
+    pub token_counter: &'a dyn TokenCounter,
 }
 
 /// Parsed stop reason for cleaner pattern matching
@@ -105,6 +121,16 @@ impl StopReason {
             None
         }
     }
+
+    /// Parse the `stop_reason` field of a buffered message
+    pub fn from_message(message: &Value) -> Option<Self> {
+        Some(match message.get("stop_reason")?.as_str()? {
+            "end_turn" => StopReason::EndTurn,
+            "tool_use" => StopReason::ToolUse,
+            "max_tokens" => StopReason::MaxTokens,
+            _ => StopReason::Other,
+        })
+    }
 }
 
 // ============================================================================
@@ -132,7 +158,7 @@ impl StopReason {
 ///     fn generate(&self, ctx: &AugmentationContext) -> Option<AugmentedContent> {
 ///         let text = "injected content";
 ///         let sse = b"event: ...\ndata: ...\n\n".to_vec();
-///         Some(AugmentedContent::from_text(sse, text))
+///         Some(AugmentedContent::from_text(sse, text, ctx.token_counter))
 ///     }
 /// }
 /// ```
@@ -150,7 +176,8 @@ pub trait Augmenter: Send + Sync {
     ///
     /// Only called if `should_apply()` returned `true`.
     /// Return `None` if no injection is needed (e.g., threshold not met).
-    /// Return `Some(AugmentedContent)` with valid SSE bytes and token estimate.
+    /// Return `Some(AugmentedContent)` with valid SSE bytes, the matching
+    /// content block, and a token estimate.
     fn generate(&self, ctx: &AugmentationContext) -> Option<AugmentedContent>;
 }
 
@@ -228,6 +255,34 @@ impl AugmentationPipeline {
         None
     }
 
+    /// Augment a buffered (non-streaming) Anthropic message in place
+    ///
+    /// Builds the context from the message itself (model, stop_reason, block
+    /// count) and appends the injected content block. Returns the injection
+    /// so the caller can report tokens.
+    pub fn augment_message(
+        &self,
+        message: &mut Value,
+        context_state: &SharedContextState,
+        token_counter: &dyn TokenCounter,
+    ) -> Option<AugmentedContent> {
+        let stop_reason = StopReason::from_message(message)?;
+        let content = message.get("content")?.as_array()?;
+        let ctx = AugmentationContext {
+            model: message.get("model").and_then(|m| m.as_str()).unwrap_or(""),
+            stop_reason,
+            next_block_index: content.len() as u32,
+            context_state,
+            token_counter,
+        };
+        let augmented = self.process(&ctx)?;
+        message
+            .get_mut("content")
+            .and_then(|c| c.as_array_mut())?
+            .push(augmented.content_block.clone());
+        Some(augmented)
+    }
+
     /// Get names of registered augmenters (for logging/debug)
     pub fn augmenter_names(&self) -> Vec<&'static str> {
         self.augmenters.iter().map(|a| a.name()).collect()
@@ -242,3 +297,68 @@ impl Default for AugmentationPipeline {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::CounterKind;
+    use crate::ContextState;
+    use std::sync::{Arc, Mutex};
+
+    fn state_at(percent: u64) -> SharedContextState {
+        let mut state = ContextState::new(100_000);
+        state.update(percent * 1_000, 0, 0);
+        Arc::new(Mutex::new(state))
+    }
+
+    fn pipeline() -> AugmentationPipeline {
+        let mut pipeline = AugmentationPipeline::new();
+        pipeline.register(ContextWarningAugmenter::new());
+        pipeline
+    }
+
+    #[test]
+    fn test_augment_message_appends_warning_block() {
+        let mut message = json!({
+            "model": "claude-sonnet-4",
+            "content": [{"type": "text", "text": "Done."}],
+            "stop_reason": "end_turn"
+        });
+        let augmented = pipeline()
+            .augment_message(
+                &mut message,
+                &state_at(82),
+                CounterKind::default().counter(),
+            )
+            .expect("warning above threshold");
+
+        let content = message["content"].as_array().unwrap();
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[1], augmented.content_block);
+        assert!(content[1]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Context at 82.0%"));
+        assert!(augmented.tokens_injected > 0);
+    }
+
+    #[test]
+    fn test_augment_message_skips_tool_use_and_below_threshold() {
+        let mut tool_use = json!({
+            "model": "claude-sonnet-4",
+            "content": [{"type": "tool_use", "id": "t", "name": "Read", "input": {}}],
+            "stop_reason": "tool_use"
+        });
+        let counter = CounterKind::default().counter();
+        assert!(pipeline()
+            .augment_message(&mut tool_use, &state_at(90), counter)
+            .is_none());
+        assert_eq!(tool_use["content"].as_array().unwrap().len(), 1);
+
+        let mut quiet =
+            json!({"model": "claude-sonnet-4", "content": [], "stop_reason": "end_turn"});
+        assert!(pipeline()
+            .augment_message(&mut quiet, &state_at(10), counter)
+            .is_none());
+    }
+}
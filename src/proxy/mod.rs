@@ -10,6 +10,10 @@
 
 pub mod api;
 pub mod augmentation;
+pub mod policy;
+pub mod projects;
+pub mod reload;
+pub mod response_transformation;
 pub mod sessions;
 pub mod sse;
 pub mod transformation;
@@ -22,6 +26,7 @@ use crate::events::{generate_id, ProxyEvent, TrackedEvent};
 use crate::parser::models::CapturedHeaders;
 use crate::parser::Parser;
 use crate::pipeline::{EventPipeline, ProcessContext};
+use crate::storage::attachments::AttachmentStore;
 use crate::{SharedContextState, StreamingThinking};
 use anyhow::{Context, Result};
 use augmentation::{AugmentationContext, AugmentationPipeline, StopReason};
@@ -90,6 +95,27 @@ fn extract_user_prompt(body: &serde_json::Value) -> Option<String> {
     }
 }
 
+/// Swap base64 image/document blocks in a logged body for store references
+///
+/// Decoding and hashing megabyte payloads is blocking work, so it runs off
+/// the async runtime. On a join error the body is lost; a placeholder saying
+/// so is logged instead of the base64.
+async fn extract_attachments(
+    store: &Arc<AttachmentStore>,
+    mut body: serde_json::Value,
+) -> serde_json::Value {
+    let store = store.clone();
+    tokio::task::spawn_blocking(move || {
+        store.extract(&mut body);
+        body
+    })
+    .await
+    .unwrap_or_else(|e| {
+        tracing::warn!("Attachment extraction failed: {}", e);
+        serde_json::Value::from("[body not logged: attachment extraction failed]")
+    })
+}
+
 /// Shared state for the proxy server
 #[derive(Clone)]
 pub struct ProxyState {
@@ -107,8 +133,6 @@ pub struct ProxyState {
     streaming_thinking: StreamingThinking,
     /// Shared context state for augmentation
     context_state: SharedContextState,
-    /// Augmentation pipeline for response modification
-    augmentation: Arc<AugmentationPipeline>,
     /// Shared statistics for API endpoints
     stats: api::SharedStats,
     /// Shared events buffer for API endpoints
@@ -117,20 +141,26 @@ pub struct ProxyState {
     pub sessions: api::SharedSessions,
     /// Log directory for session log search
     pub log_dir: std::path::PathBuf,
-    /// Client and provider configuration for multi-user routing
-    clients: ClientsConfig,
     /// Event processing pipeline (optional, for lifestats storage and other processors)
     pipeline: Option<Arc<EventPipeline>>,
     /// Query interface for lifestats database (optional, requires lifestats enabled)
     pub lifestats_query: Option<Arc<crate::pipeline::lifestats_query::LifestatsQuery>>,
-    /// Translation pipeline for OpenAI ↔ Anthropic format conversion
-    translation: Arc<TranslationPipeline>,
-    /// Transformation pipeline for request modification (system-reminder editing, etc.)
-    transformation: Arc<transformation::TransformationPipeline>,
-    /// Transformers config (for enabled flag and future per-transformer settings)
-    transformers_config: crate::config::Transformers,
+    /// Content-addressed store for image/document blocks (optional, [attachments])
+    attachments: Option<Arc<AttachmentStore>>,
+    /// Hot-reloadable config: transformers, augmentation, translation, clients, handoff
+    live: Arc<reload::Swappable<reload::LiveConfig>>,
+    /// Response transformation pipeline (redaction, tool guard, etc.)
+    response_transformation: Arc<response_transformation::ResponseTransformationPipeline>,
+    /// Tool-use policy engine (optional, requires [policy] enabled)
+    policy: Option<Arc<policy::PolicyEngine>>,
     /// Handle to the embedding indexer (optional, requires embeddings enabled)
     pub embedding_indexer: Option<crate::pipeline::embedding_indexer::IndexerHandle>,
+    /// Token counting config (counter selection for transformers/augmenters)
+    tokens_config: crate::config::TokensConfig,
+    /// Estimate-vs-actual drift tracking (optional, requires tokens.calibration)
+    pub token_calibration: Option<crate::tokens::SharedCalibration>,
+    /// Upstream count_tokens client (optional, requires tokens.count_tokens_api)
+    count_tokens: Option<Arc<crate::tokens::CountTokensClient>>,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -186,6 +216,10 @@ struct ResponseContext {
     user_id: Option<String>,
     /// Translation context for response translation (if format differs)
     translation_ctx: translation::TranslationContext,
+    /// Augmenters for this request (project overlay or global)
+    augmentation: Arc<AugmentationPipeline>,
+    /// Translation pipeline snapshot taken when the request arrived
+    translation: Arc<TranslationPipeline>,
 }
 
 /// Start the proxy server
@@ -208,8 +242,15 @@ pub async fn start_proxy(
         .build()
         .context("Failed to create HTTP client")?;
 
-    // Create augmentation pipeline from config (opt-in augmenters)
-    let augmentation = Arc::new(AugmentationPipeline::from_config(&config.augmentation));
+    // Reloadable pipelines (augmentation, translation, transformation, clients)
+    let live = reload::LiveConfig::from_config(
+        &config,
+        Config::load_file_value(),
+        shared.lifestats_query.as_ref(),
+    )?;
+
+    // Augmentation pipeline (opt-in augmenters)
+    let augmentation = &live.augmentation;
     if augmentation.is_empty() {
         tracing::debug!("Augmentation pipeline: no augmenters enabled");
     } else {
@@ -219,18 +260,16 @@ pub async fn start_proxy(
         );
     }
 
-    // Create translation pipeline from config (opt-in feature)
-    let translation = Arc::new(TranslationPipeline::from_config(&config.translation));
+    // Translation pipeline (opt-in feature)
+    let translation = &live.translation;
     if translation.is_enabled() {
         tracing::info!("Translation pipeline enabled (OpenAI ↔ Anthropic)");
     } else {
         tracing::debug!("Translation pipeline: disabled");
     }
 
-    // Create transformation pipeline from config (opt-in feature)
-    let transformation = Arc::new(transformation::TransformationPipeline::from_config(
-        &config.transformers,
-    ));
+    // Transformation pipeline (opt-in feature)
+    let transformation = &live.transformation;
     if !transformation.is_empty() {
         tracing::info!(
             "Transformation pipeline enabled with: {:?}",
@@ -240,6 +279,79 @@ pub async fn start_proxy(
         tracing::debug!("Transformation pipeline: no transformers enabled");
     }
 
+    if let Some(handoff) = &live.handoff {
+        tracing::info!(
+            "Session handoff enabled: writing to {}",
+            handoff.dir().display()
+        );
+    }
+
+    // Attachment store: a failure here only costs log size, so don't abort startup
+    let attachments = if config.attachments.enabled {
+        match AttachmentStore::new(config.attachments.dir.clone()) {
+            Ok(store) => Some(Arc::new(store)),
+            Err(e) => {
+                tracing::warn!("Attachment capture disabled: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Create policy engine from config (opt-in feature)
+    let policy = if config.policy.enabled {
+        let engine = policy::PolicyEngine::from_config(&config.policy)
+            .context("Invalid [policy] configuration")?;
+        tracing::info!(
+            "Policy engine enabled: {} rule(s), mode {}",
+            engine.rule_count(),
+            config.policy.mode.as_str()
+        );
+        Some(Arc::new(engine))
+    } else {
+        None
+    };
+
+    // Create response transformation pipeline from config (opt-in feature)
+    // Policy enforcement runs last so it sees calls as other transformers left them
+    let mut response_transformation =
+        response_transformation::ResponseTransformationPipeline::from_config(
+            &config.response_transformers,
+        );
+    if let Some(engine) = policy.as_ref().filter(|e| e.rewrites()) {
+        response_transformation.register(policy::PolicyEnforcer::new(engine.clone()));
+    }
+    let response_transformation = Arc::new(response_transformation);
+    if !response_transformation.is_empty() {
+        tracing::info!(
+            "Response transformation pipeline enabled with: {:?}",
+            response_transformation.transformer_names()
+        );
+    } else {
+        tracing::debug!("Response transformation pipeline: no transformers enabled");
+    }
+
+    // Token calibration and upstream count_tokens (both off the hot path)
+    let token_calibration = config
+        .tokens
+        .calibration
+        .then(|| Arc::new(std::sync::Mutex::new(crate::tokens::Calibration::new())));
+    let count_tokens = config.tokens.count_tokens_api.then(|| {
+        Arc::new(crate::tokens::CountTokensClient::new(
+            client.clone(),
+            &api_url,
+            config.tokens.counter,
+            config.tokens.cache_size,
+        ))
+    });
+    tracing::debug!(
+        counter = config.tokens.counter.as_str(),
+        calibration = token_calibration.is_some(),
+        count_tokens_api = count_tokens.is_some(),
+        "Token counting configured"
+    );
+
     // Log client routing config if present
     if config.clients.is_configured() {
         tracing::info!(
@@ -265,18 +377,20 @@ pub async fn start_proxy(
         api_url,
         streaming_thinking: shared.streaming_thinking,
         context_state: shared.context,
-        augmentation,
         stats: shared.stats,
         events: shared.events,
         sessions: shared.sessions,
         log_dir: config.log_dir.clone(),
-        clients: config.clients.clone(),
         pipeline: shared.pipeline,
         lifestats_query: shared.lifestats_query,
+        attachments,
         embedding_indexer: shared.embedding_indexer,
-        translation,
-        transformation,
-        transformers_config: config.transformers.clone(),
+        live: Arc::new(reload::Swappable::new(live)),
+        response_transformation,
+        policy,
+        tokens_config: config.tokens.clone(),
+        token_calibration,
+        count_tokens,
     };
 
     // Build the router - API endpoints + proxy handler
@@ -285,13 +399,32 @@ pub async fn start_proxy(
         .route("/api/stats", axum::routing::get(api::get_stats))
         .route("/api/events", axum::routing::get(api::get_events))
         .route("/api/context", axum::routing::get(api::get_context))
+        .route("/api/diff", axum::routing::get(api::get_diff))
+        .route("/api/timeline", axum::routing::get(api::get_timeline))
+        .route(
+            "/api/tokens/calibration",
+            axum::routing::get(api::get_token_calibration),
+        )
         // Session management endpoints
         .route("/api/sessions", axum::routing::get(api::get_sessions))
+        .route("/api/todos", axum::routing::get(api::get_todos))
+        .route(
+            "/api/todos/:session_id",
+            axum::routing::get(api::get_session_todos),
+        )
         .route(
             "/api/session/start",
             axum::routing::post(api::session_start),
         )
         .route("/api/session/end", axum::routing::post(api::session_end))
+        .route(
+            "/api/session/handoff/:user_id",
+            axum::routing::get(api::get_session_handoff),
+        )
+        .route(
+            "/api/config/reload",
+            axum::routing::post(api::reload_config),
+        )
         // Log search endpoint
         .route("/api/search", axum::routing::post(api::search_logs))
         // Lifestats endpoints
@@ -323,6 +456,26 @@ pub async fn start_proxy(
             "/api/lifestats/stats",
             axum::routing::get(api::lifestats_stats),
         )
+        .route(
+            "/api/lifestats/tools/insights",
+            axum::routing::get(api::lifestats_tool_insights),
+        )
+        .route(
+            "/api/lifestats/files/top",
+            axum::routing::get(api::lifestats_top_files),
+        )
+        .route(
+            "/api/lifestats/files/sessions",
+            axum::routing::get(api::lifestats_file_sessions),
+        )
+        .route(
+            "/api/lifestats/files/repeated-reads",
+            axum::routing::get(api::lifestats_repeated_reads),
+        )
+        .route(
+            "/api/lifestats/git/commits",
+            axum::routing::get(api::lifestats_commit_costs),
+        )
         // User-scoped lifestats endpoints
         .route(
             "/api/lifestats/search/user/:user_id/thinking",
@@ -363,7 +516,10 @@ pub async fn start_proxy(
         )
         // Proxy handler (catch-all)
         .route("/*path", any(proxy_handler))
-        .with_state(state);
+        .with_state(state.clone());
+
+    // Hot reload: config.toml watcher + SIGHUP
+    reload::spawn_watchers(state);
 
     tracing::info!("Starting proxy on {}", bind_addr);
 
@@ -417,6 +573,17 @@ impl ProxyState {
             event
         };
 
+        // Write a handoff doc once a compaction is detected (covers the pre-compact session)
+        if let (Some(handoff), Some(sid), ProxyEvent::ContextCompact { .. }) =
+            (&self.live.load().handoff, &session_id, &final_event)
+        {
+            handoff.spawn_generate(
+                sid.clone(),
+                user_id.map(String::from),
+                crate::handoff::HandoffTrigger::Compact,
+            );
+        }
+
         // Wrap in TrackedEvent with user/session context
         let tracked = TrackedEvent::new(
             final_event.clone(),
@@ -435,6 +602,158 @@ impl ProxyState {
             }
         }
     }
+
+    /// Record token estimates for an outgoing request (for calibration)
+    ///
+    /// Runs in a background task: local counters walk the whole body and the
+    /// count_tokens fetch is a network round-trip, neither belongs on the
+    /// request path. `fetch_exact` is false when the request is routed to a
+    /// provider other than the default API (count_tokens would hit the wrong host).
+    fn spawn_token_estimates(
+        &self,
+        request_id: &str,
+        body: serde_json::Value,
+        headers: &axum::http::HeaderMap,
+        fetch_exact: bool,
+    ) {
+        let calibration = self.token_calibration.clone();
+        let count_tokens = self.count_tokens.clone().filter(|_| fetch_exact);
+        if calibration.is_none() && count_tokens.is_none() {
+            return;
+        }
+
+        let request_id = request_id.to_string();
+        let headers = headers.clone();
+        tokio::spawn(async move {
+            if let Some(calibration) = &calibration {
+                let estimates: Vec<_> = [
+                    crate::tokens::CounterKind::Heuristic,
+                    crate::tokens::CounterKind::Bpe,
+                ]
+                .iter()
+                .map(|kind| {
+                    let counter = kind.counter();
+                    (counter.name(), counter.count_request(&body))
+                })
+                .collect();
+                if let Ok(mut cal) = calibration.lock() {
+                    for (name, tokens) in estimates {
+                        cal.record_estimate(&request_id, name, tokens);
+                    }
+                }
+            }
+
+            if let Some(client) = count_tokens {
+                use crate::tokens::TokenCounter;
+                match client.fetch(&body, &headers).await {
+                    Ok(tokens) => {
+                        if let Some(calibration) = &calibration {
+                            if let Ok(mut cal) = calibration.lock() {
+                                cal.record_estimate(&request_id, client.name(), tokens);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::debug!("count_tokens fetch failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Resolve pending token estimates when ApiUsage arrives for a request
+    fn resolve_token_estimates(&self, request_id: &str, event: &ProxyEvent) {
+        let Some(calibration) = &self.token_calibration else {
+            return;
+        };
+        if let ProxyEvent::ApiUsage {
+            input_tokens,
+            cache_creation_tokens,
+            cache_read_tokens,
+            ..
+        } = event
+        {
+            let actual = input_tokens + cache_creation_tokens + cache_read_tokens;
+            if let Ok(mut cal) = calibration.lock() {
+                cal.resolve(request_id, actual);
+            }
+        }
+    }
+
+    /// Emit the events parsed from a completed response
+    ///
+    /// Keeps the context state and token calibration current and audits tool
+    /// calls against policy. Calls in `blocked_tools` were replaced before the
+    /// client saw them: their policy decision is still recorded, but the
+    /// `ToolCall` itself is dropped so file activity, todos and tool stats only
+    /// count calls the client received.
+    async fn emit_parsed_events(
+        &self,
+        events: Vec<ProxyEvent>,
+        request_id: &str,
+        user_id: Option<&str>,
+        blocked_tools: &[String],
+    ) {
+        for event in events {
+            // Update context state when we see ApiUsage (skip Haiku utility calls)
+            if let ProxyEvent::ApiUsage {
+                input_tokens,
+                cache_creation_tokens,
+                cache_read_tokens,
+                model,
+                ..
+            } = &event
+            {
+                if !model.to_lowercase().contains("haiku") {
+                    if let Ok(mut ctx) = self.context_state.lock() {
+                        ctx.update(
+                            *input_tokens as u64,
+                            *cache_creation_tokens as u64,
+                            *cache_read_tokens as u64,
+                        );
+                    }
+                }
+            }
+            // Reset warnings on context compact
+            if let ProxyEvent::ContextCompact { .. } = &event {
+                if let Ok(mut ctx) = self.context_state.lock() {
+                    ctx.reset_warnings();
+                }
+            }
+            self.resolve_token_estimates(request_id, &event);
+            let decision = self.audit_policy(&event, user_id);
+            match blocked_call(&event, blocked_tools) {
+                // No result will ever come back for it
+                Some(id) => self.parser.forget_pending_tool(id).await,
+                None => self.send_event(event, user_id).await,
+            }
+            if let Some(decision) = decision {
+                self.send_event(decision, user_id).await;
+            }
+        }
+    }
+
+    /// Evaluate a parsed tool call against policy (returns the audit event)
+    fn audit_policy(&self, event: &ProxyEvent, user_id: Option<&str>) -> Option<ProxyEvent> {
+        let policy = self.policy.as_ref()?;
+        let ProxyEvent::ToolCall {
+            id,
+            tool_name,
+            input,
+            ..
+        } = event
+        else {
+            return None;
+        };
+        let verdict = policy.audit_tool_call(id, tool_name, input, user_id);
+        Some(verdict.into_event(tool_name, Some(id)))
+    }
+}
+
+/// ID of a parsed tool call that the response transformers blocked
+fn blocked_call<'a>(event: &'a ProxyEvent, blocked_tools: &[String]) -> Option<&'a str> {
+    match event {
+        ProxyEvent::ToolCall { id, .. } if blocked_tools.contains(id) => Some(id),
+        _ => None,
+    }
 }
 
 /// Result of extracting client routing from a path
@@ -531,8 +850,11 @@ async fn proxy_handler(
     let uri = req.uri().clone();
     let headers = req.headers().clone();
 
+    // One config snapshot for the whole request, even if a reload lands mid-flight
+    let live = state.live.load();
+
     // Extract client routing from path (before we consume the request)
-    let routing = extract_client_routing(uri.path(), &state.clients, &state.api_url);
+    let routing = extract_client_routing(uri.path(), &live.clients, &state.api_url);
 
     // Use client_id for user identification if available, otherwise fall back to API key hash
     let user_id = routing
@@ -565,6 +887,43 @@ async fn proxy_handler(
     let is_likely_messages =
         routing.api_path.contains("/messages") || routing.api_path.contains("/chat/completions");
 
+    // Project overlay (.aspy.toml) picks this request's transformers and augmenters
+    // (filesystem and TOML work goes to the blocking pool)
+    let project_dir = if is_likely_messages && method == "POST" {
+        projects::ProjectOverlays::project_dir(&headers, &body_bytes)
+    } else {
+        None
+    };
+    let project = match project_dir {
+        Some(dir) => {
+            let live = live.clone();
+            tokio::task::spawn_blocking(move || live.projects.resolve(&dir))
+                .await
+                .ok()
+                .flatten()
+        }
+        None => None,
+    };
+    if let (Some(project), Some(uid)) = (&project, &user_id) {
+        if let Ok(mut sessions) = state.sessions.lock() {
+            if let Some(session) = sessions.get_user_session_mut(&sessions::UserId::new(uid)) {
+                session.project = Some(project.root.clone());
+            }
+        }
+    }
+    let (transformers_enabled, transformation, augmentation) = match &project {
+        Some(p) => (
+            p.transformers_enabled,
+            p.transformation.clone(),
+            p.augmentation.clone(),
+        ),
+        None => (
+            live.transformers_config.enabled,
+            live.transformation.clone(),
+            live.augmentation.clone(),
+        ),
+    };
+
     // ─────────────────────────────────────────────────────────────────────────
     // REQUEST TRANSFORMATION (runs BEFORE translation, on known Anthropic format)
     // ─────────────────────────────────────────────────────────────────────────
@@ -573,16 +932,50 @@ async fn proxy_handler(
     let (body_bytes, body_was_transformed, transform_tokens, transform_modifications) =
         if is_likely_messages
             && method == "POST"
-            && state.transformers_config.enabled
-            && !state.transformation.is_empty()
+            && transformers_enabled
+            && !transformation.is_empty()
         {
             if let Ok(body_json) = serde_json::from_slice::<serde_json::Value>(&body_bytes) {
                 let model = body_json.get("model").and_then(|m| m.as_str());
+
+                // Handoff doc for compaction prompts (built before the sync pipeline)
+                let handoff_markdown = match (&live.handoff, &user_id) {
+                    (Some(service), Some(uid))
+                        if service.inject_on_compact()
+                            && transformation::CompactEnhancer::new()
+                                .is_compaction_body(&body_json) =>
+                    {
+                        let session_id = state.sessions.lock().ok().and_then(|sessions| {
+                            sessions.get_session_id(&sessions::UserId::new(uid))
+                        });
+                        match session_id {
+                            Some(sid) => {
+                                let service = service.clone();
+                                tokio::task::spawn_blocking(move || {
+                                    service.build(
+                                        &sid,
+                                        crate::handoff::HandoffTrigger::CompactionPrompt,
+                                    )
+                                })
+                                .await
+                                .ok()
+                                .and_then(|doc| doc.ok())
+                                .filter(|doc| !doc.is_empty())
+                                .map(|doc| doc.to_markdown())
+                            }
+                            None => None,
+                        }
+                    }
+                    _ => None,
+                };
+
                 let mut ctx = transformation::TransformContext::new(
                     user_id.as_deref(),
                     &routing.api_path,
                     model,
                 );
+                ctx.token_counter = state.tokens_config.counter;
+                ctx.handoff = handoff_markdown.as_deref();
 
                 // Extract tool_result_count and compute session turn_number
                 if let Some(messages) = body_json.get("messages").and_then(|m| m.as_array()) {
@@ -645,11 +1038,47 @@ async fn proxy_handler(
                     ctx.client_id.unwrap_or("unknown")
                 );
 
+                // Context enricher retrieval: lifestats search off the async runtime
+                let semantic_context = match (&live.context_retriever, &user_id) {
+                    (Some(retriever), Some(uid)) => {
+                        match transformation::ContextEnricher::prompt_text(&body_json)
+                            .filter(|prompt| retriever.wants_context(&ctx, prompt))
+                        {
+                            Some(prompt) => {
+                                let current_session =
+                                    state.sessions.lock().ok().and_then(|sessions| {
+                                        sessions.get_session_id(&sessions::UserId::new(uid))
+                                    });
+                                let retriever = retriever.clone();
+                                let uid = uid.clone();
+                                match tokio::task::spawn_blocking(move || {
+                                    retriever.retrieve(&uid, &prompt, current_session.as_deref())
+                                })
+                                .await
+                                {
+                                    Ok(Ok(context)) => context,
+                                    Ok(Err(e)) => {
+                                        tracing::warn!("Context enricher retrieval failed: {}", e);
+                                        None
+                                    }
+                                    Err(e) => {
+                                        tracing::warn!("Context enricher task failed: {}", e);
+                                        None
+                                    }
+                                }
+                            }
+                            None => None,
+                        }
+                    }
+                    _ => None,
+                };
+                ctx.semantic_context = semantic_context.as_ref();
+
                 tracing::debug!(
-                    transformers = ?state.transformation.transformer_names(),
+                    transformers = ?transformation.transformer_names(),
                     "Running transformation pipeline on request"
                 );
-                match state.transformation.transform(&body_json, &ctx) {
+                match transformation.transform(&body_json, &ctx) {
                     transformation::TransformResult::Modified {
                         body: new_body,
                         tokens,
@@ -702,12 +1131,47 @@ async fn proxy_handler(
             (body_bytes.to_vec(), false, None, Vec::new())
         };
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // POLICY (tool definitions + pending reminders, Anthropic format)
+    // ─────────────────────────────────────────────────────────────────────────
+    let mut policy_events = Vec::new();
+    let (body_bytes, body_was_transformed) = match &state.policy {
+        Some(policy) if is_likely_messages && method == "POST" => {
+            match serde_json::from_slice::<serde_json::Value>(&body_bytes) {
+                Ok(mut body_json) => {
+                    let denials = policy.apply_to_definitions(&mut body_json);
+                    let removed = denials
+                        .iter()
+                        .filter(|(_, v)| v.enforcement == policy::EnforcementMode::Rewrite)
+                        .count();
+                    let reminded = policy.inject_reminders(&mut body_json, user_id.as_deref());
+                    if reminded > 0 {
+                        tracing::info!("Policy: injected {} reminder(s)", reminded);
+                    }
+                    policy_events.extend(
+                        denials
+                            .into_iter()
+                            .map(|(name, verdict)| verdict.into_event(&name, None)),
+                    );
+                    if removed > 0 || reminded > 0 {
+                        let bytes = serde_json::to_vec(&body_json).unwrap_or(body_bytes);
+                        (bytes, true)
+                    } else {
+                        (body_bytes, body_was_transformed)
+                    }
+                }
+                Err(_) => (body_bytes, body_was_transformed),
+            }
+        }
+        _ => (body_bytes, body_was_transformed),
+    };
+
     // Determine target API format based on provider config
     // If provider expects OpenAI format, translate Anthropic → OpenAI
     let target_format = routing
         .client_id
         .as_ref()
-        .and_then(|cid| state.clients.get_client_api_format(cid))
+        .and_then(|cid| live.clients.get_client_api_format(cid))
         .map(|fmt| match fmt {
             crate::config::ApiFormat::Anthropic => translation::ApiFormat::Anthropic,
             crate::config::ApiFormat::Openai => translation::ApiFormat::OpenAI,
@@ -715,7 +1179,7 @@ async fn proxy_handler(
         .unwrap_or(translation::ApiFormat::Anthropic);
 
     // Apply translation if enabled, targeting the provider's expected format
-    let (translated_body, translation_ctx, translated_path) = state
+    let (translated_body, translation_ctx, translated_path) = live
         .translation
         .translate_request_for_target(&routing.api_path, &headers, &body_bytes, target_format)
         .map_err(|e| ProxyError::BodyRead(format!("Translation failed: {}", e)))?;
@@ -770,6 +1234,26 @@ async fn proxy_handler(
         }
     }
 
+    // Record token estimates for calibration (Anthropic-format bodies only;
+    // translated requests are sized differently upstream)
+    if is_messages_endpoint && method == "POST" && !translation_ctx.needs_response_translation() {
+        if let Some(ref body) = request_body {
+            state.spawn_token_estimates(
+                &request_id,
+                body.clone(),
+                &headers,
+                routing.base_url == state.api_url,
+            );
+        }
+    }
+
+    // Move base64 attachments into the store so logs and lifestats keep references.
+    // Runs after token estimation, which must see the real blocks.
+    let request_body = match (request_body, &state.attachments) {
+        (Some(body), Some(store)) => Some(extract_attachments(store, body).await),
+        (body, _) => body,
+    };
+
     // Emit request event (use original path for logging, not stripped path)
     state
         .send_event(
@@ -801,11 +1285,22 @@ async fn proxy_handler(
             .await;
     }
 
+    // Emit policy decisions for request tool definitions
+    for event in policy_events {
+        state.send_event(event, user_id.as_deref()).await;
+    }
+
     // Parse request for tool results if this is a messages endpoint
     if is_messages_endpoint && method == "POST" {
         match state.parser.parse_request(&body_bytes).await {
             Ok(events) => {
-                for event in events {
+                for mut event in events {
+                    // Tool results can carry images too (e.g. Read on a screenshot)
+                    if let (ProxyEvent::ToolResult { output, .. }, Some(store)) =
+                        (&mut event, &state.attachments)
+                    {
+                        *output = extract_attachments(store, std::mem::take(output)).await;
+                    }
                     state.send_event(event, user_id.as_deref()).await;
                 }
             }
@@ -846,14 +1341,18 @@ async fn proxy_handler(
     let auth_config = routing
         .client_id
         .as_ref()
-        .and_then(|cid| state.clients.get_effective_auth(cid));
+        .and_then(|cid| live.clients.get_effective_auth(cid));
 
     // Copy relevant headers with auth transformation
     for (key, value) in headers.iter() {
         let key_str = key.as_str();
 
-        // Skip connection control headers
-        if key_str == "host" || key_str == "connection" || key_str == "transfer-encoding" {
+        // Skip connection control headers and Aspy's own project header
+        if key_str == "host"
+            || key_str == "connection"
+            || key_str == "transfer-encoding"
+            || key_str == projects::PROJECT_HEADER
+        {
             continue;
         }
 
@@ -1002,6 +1501,8 @@ async fn proxy_handler(
         state,
         user_id,
         translation_ctx,
+        augmentation,
+        translation: live.translation.clone(),
     };
 
     // Decide: streaming (SSE) or buffered (JSON) response handling
@@ -1027,6 +1528,8 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
         state,
         user_id,
         translation_ctx,
+        augmentation,
+        translation,
     } = ctx;
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -1055,10 +1558,11 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
     let request_id_clone = request_id.clone();
     let streaming_thinking = state.streaming_thinking.clone();
     let context_state = state.context_state.clone();
-    let augmentation = state.augmentation.clone();
     let _sessions = state.sessions.clone();
     let user_id_clone = user_id.clone();
-    let translation_pipeline = state.translation.clone();
+    let translation_pipeline = translation;
+    let token_counter = state.tokens_config.counter;
+    let response_transformation = state.response_transformation.clone();
     let mut translation_ctx = translation_ctx;
 
     // Spawn task to stream response while accumulating
@@ -1075,6 +1579,14 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
         // Track model for injection filtering (skip Haiku utility calls)
         let mut response_model = String::new();
 
+        // Response transformers rewrite the stream in flight (None = raw passthrough)
+        let mut rewriter = if is_messages_endpoint {
+            response_transformation
+                .stream_rewriter(&response_transformation::ResponseTransformContext)
+        } else {
+            None
+        };
+
         // Get translator reference if translation is needed (OpenAI ↔ Anthropic)
         let translator = if needs_translation {
             translation_pipeline
@@ -1144,67 +1656,72 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
                                 line_buffer = line_buffer[newline_pos + 1..].to_string();
                             }
                         }
+                    }
 
-                        // Check if this chunk contains message_delta - inject before forwarding
-                        // Only inject on end_turn responses (not tool_use)
-                        if injected_tokens.is_none() {
-                            match std::str::from_utf8(&chunk) {
-                                Err(e) => {
-                                    tracing::warn!(
-                                        "SSE injection skipped: UTF-8 decode failed: {}",
-                                        e
-                                    );
-                                }
-                                Ok(chunk_str) => {
-                                    // Check for message_delta to trigger augmentation
-                                    if chunk_str.contains("message_delta") {
-                                        if let Some(stop_reason) = StopReason::from_chunk(chunk_str)
-                                        {
-                                            // Build augmentation context
-                                            let aug_ctx = AugmentationContext {
-                                                model: &response_model,
-                                                stop_reason,
-                                                next_block_index: max_block_index,
-                                                context_state: &context_state,
-                                            };
+                    // Rewrite in flight (transformers see raw Anthropic SSE; translation runs after)
+                    let outgoing = match rewriter.as_mut() {
+                        Some(r) => Bytes::from(r.push(&chunk)),
+                        None => chunk,
+                    };
 
-                                            // Run augmentation pipeline
-                                            if let Some(augmented) = augmentation.process(&aug_ctx)
-                                            {
-                                                // Translate injection if needed (augmentation produces Anthropic SSE)
-                                                let injection_to_send = if let Some(t) = &translator
-                                                {
-                                                    match t.translate_chunk(
-                                                        &augmented.sse_bytes,
-                                                        &mut translation_ctx,
-                                                    ) {
-                                                        Ok(translated)
-                                                            if !translated.is_empty() =>
-                                                        {
-                                                            Bytes::from(translated)
-                                                        }
-                                                        Ok(_) => {
-                                                            Bytes::from(augmented.sse_bytes.clone())
-                                                        } // Empty = shouldn't happen for complete injection
-                                                        Err(e) => {
-                                                            tracing::warn!("Augmentation translation failed: {}", e);
-                                                            Bytes::from(augmented.sse_bytes.clone())
-                                                            // Fallback to raw
-                                                        }
+                    // Check if this chunk contains message_delta - inject before forwarding
+                    // Only inject on end_turn responses (not tool_use)
+                    let mut injection: Option<Bytes> = None;
+                    if is_messages_endpoint && injected_tokens.is_none() {
+                        match std::str::from_utf8(&outgoing) {
+                            Err(e) => {
+                                tracing::warn!("SSE injection skipped: UTF-8 decode failed: {}", e);
+                            }
+                            Ok(chunk_str) => {
+                                // Check for message_delta to trigger augmentation
+                                if chunk_str.contains("message_delta") {
+                                    if let Some(stop_reason) = StopReason::from_chunk(chunk_str) {
+                                        // Build augmentation context
+                                        let aug_ctx = AugmentationContext {
+                                            model: &response_model,
+                                            stop_reason,
+                                            next_block_index: rewriter
+                                                .as_ref()
+                                                .map_or(max_block_index, |r| r.next_block_index()),
+                                            context_state: &context_state,
+                                            token_counter: token_counter.counter(),
+                                        };
+
+                                        // Run augmentation pipeline
+                                        if let Some(augmented) = augmentation.process(&aug_ctx) {
+                                            // Translate injection if needed (augmentation produces Anthropic SSE)
+                                            let injection_to_send = if let Some(t) = &translator {
+                                                match t.translate_chunk(
+                                                    &augmented.sse_bytes,
+                                                    &mut translation_ctx,
+                                                ) {
+                                                    Ok(translated) if !translated.is_empty() => {
+                                                        Bytes::from(translated)
                                                     }
-                                                } else {
-                                                    Bytes::from(augmented.sse_bytes.clone())
-                                                };
-                                                let _ = tx.send(Ok(injection_to_send)).await;
-                                                injected_tokens = Some(augmented.tokens_injected);
-
-                                                // Track token injection for stats
-                                                tracing::debug!(
-                                                    tokens_injected = augmented.tokens_injected,
-                                                    "Augmentation injected ~{} tokens",
-                                                    augmented.tokens_injected
-                                                );
-                                            }
+                                                    Ok(_) => {
+                                                        Bytes::from(augmented.sse_bytes.clone())
+                                                    } // Empty = shouldn't happen for complete injection
+                                                    Err(e) => {
+                                                        tracing::warn!(
+                                                            "Augmentation translation failed: {}",
+                                                            e
+                                                        );
+                                                        Bytes::from(augmented.sse_bytes.clone())
+                                                        // Fallback to raw
+                                                    }
+                                                }
+                                            } else {
+                                                Bytes::from(augmented.sse_bytes.clone())
+                                            };
+                                            injection = Some(injection_to_send);
+                                            injected_tokens = Some(augmented.tokens_injected);
+
+                                            // Track token injection for stats
+                                            tracing::debug!(
+                                                tokens_injected = augmented.tokens_injected,
+                                                "Augmentation injected ~{} tokens",
+                                                augmented.tokens_injected
+                                            );
                                         }
                                     }
                                 }
@@ -1212,23 +1729,30 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
                         }
                     }
 
-                    // Forward chunk to client (translate if needed)
-                    let chunk_to_send = if let Some(t) = &translator {
-                        match t.translate_chunk(&chunk, &mut translation_ctx) {
-                            Ok(translated) if !translated.is_empty() => {
-                                Some(Bytes::from(translated))
-                            }
-                            Ok(_) => None, // Partial line buffered, nothing to send yet
-                            Err(e) => {
-                                tracing::warn!("Chunk translation failed (skipping): {}", e);
-                                None // Log and skip per error handling strategy
-                            }
+                    // Inject right before message_delta. The rewriter emits whole events, so the
+                    // split is exact; raw chunks keep the inject-before-chunk behavior.
+                    let (head, tail) = match (&injection, &rewriter) {
+                        (Some(_), Some(_)) => {
+                            let pos = outgoing
+                                .windows(b"event: message_delta".len())
+                                .position(|w| w == b"event: message_delta")
+                                .unwrap_or(0);
+                            (outgoing.slice(..pos), outgoing.slice(pos..))
                         }
-                    } else {
-                        Some(chunk)
+                        _ => (Bytes::new(), outgoing),
                     };
 
-                    if let Some(bytes_to_send) = chunk_to_send {
+                    if let Some(bytes_to_send) =
+                        translate_for_client(translator, head, &mut translation_ctx)
+                    {
+                        let _ = tx.send(Ok(bytes_to_send)).await;
+                    }
+                    if let Some(injection_to_send) = injection {
+                        let _ = tx.send(Ok(injection_to_send)).await;
+                    }
+                    if let Some(bytes_to_send) =
+                        translate_for_client(translator, tail, &mut translation_ctx)
+                    {
                         if tx.send(Ok(bytes_to_send)).await.is_err() {
                             // Client disconnected, but continue accumulating for logging
                             tracing::debug!("Client disconnected during streaming");
@@ -1261,6 +1785,20 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
             }
         }
 
+        // Flush anything the rewriter is still holding (only non-empty on truncated streams)
+        let mut response_modifications = Vec::new();
+        let mut blocked_tools = Vec::new();
+        if let Some(r) = rewriter.as_mut() {
+            let rest = Bytes::from(r.finish());
+            if let Some(bytes_to_send) =
+                translate_for_client(translator, rest, &mut translation_ctx)
+            {
+                let _ = tx.send(Ok(bytes_to_send)).await;
+            }
+            response_modifications = r.take_modifications();
+            blocked_tools = r.blocked_tools().to_vec();
+        }
+
         // Send stream terminator if translation is active (e.g., "data: [DONE]" for OpenAI)
         if let Some(t) = &translator {
             if let Some(terminator) = t.finalize(&translation_ctx) {
@@ -1313,37 +1851,27 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
                 .parse_response(&accumulated, user_id_clone.as_deref())
                 .await
             {
-                for event in events {
-                    // Update context state when we see ApiUsage (skip Haiku utility calls)
-                    if let ProxyEvent::ApiUsage {
-                        input_tokens,
-                        cache_creation_tokens,
-                        cache_read_tokens,
-                        model,
-                        ..
-                    } = &event
-                    {
-                        if !model.to_lowercase().contains("haiku") {
-                            if let Ok(mut ctx) = context_state.lock() {
-                                ctx.update(
-                                    *input_tokens as u64,
-                                    *cache_creation_tokens as u64,
-                                    *cache_read_tokens as u64,
-                                );
-                            }
-                        }
-                    }
-                    // Reset warnings on context compact
-                    if let ProxyEvent::ContextCompact { .. } = &event {
-                        if let Ok(mut ctx) = context_state.lock() {
-                            ctx.reset_warnings();
-                        }
-                    }
-                    send_event(event).await;
-                }
+                state
+                    .emit_parsed_events(
+                        events,
+                        &request_id_clone,
+                        user_id_clone.as_deref(),
+                        &blocked_tools,
+                    )
+                    .await;
             }
         }
 
+        // Emit transformation event if any response transformer changed the stream
+        if !response_modifications.is_empty() {
+            send_event(ProxyEvent::ResponseTransformed {
+                timestamp: Utc::now(),
+                transformer: "response-transformation-pipeline".to_string(),
+                modifications: response_modifications,
+            })
+            .await;
+        }
+
         // Emit augmentation event if tokens were injected
         if let Some(tokens) = injected_tokens {
             send_event(ProxyEvent::ResponseAugmented {
@@ -1381,6 +1909,65 @@ async fn handle_streaming_response(ctx: ResponseContext) -> Result<Response<Body
         .map_err(|e| ProxyError::ResponseBuild(e.to_string()))
 }
 
+/// Translate an outgoing stream chunk for the client, if translation is active
+///
+/// Returns `None` when there is nothing to send (empty chunk, partial line
+/// buffered by the translator, or a translation error that was logged).
+fn translate_for_client(
+    translator: Option<&dyn translation::ResponseTranslator>,
+    chunk: Bytes,
+    ctx: &mut translation::TranslationContext,
+) -> Option<Bytes> {
+    if chunk.is_empty() {
+        return None;
+    }
+    let Some(t) = translator else {
+        return Some(chunk);
+    };
+    match t.translate_chunk(&chunk, ctx) {
+        Ok(translated) if !translated.is_empty() => Some(Bytes::from(translated)),
+        Ok(_) => None, // Partial line buffered, nothing to send yet
+        Err(e) => {
+            tracing::warn!("Chunk translation failed (skipping): {}", e);
+            None // Log and skip per error handling strategy
+        }
+    }
+}
+
+/// Run augmenters on a buffered Anthropic-format response body
+///
+/// Returns the (possibly) augmented body and the injected token estimate.
+fn augment_buffered(
+    state: &ProxyState,
+    augmentation: &AugmentationPipeline,
+    body: Bytes,
+) -> (Bytes, Option<u32>) {
+    if augmentation.is_empty() {
+        return (body, None);
+    }
+    let Ok(mut json) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return (body, None);
+    };
+    let Some(augmented) = augmentation.augment_message(
+        &mut json,
+        &state.context_state,
+        state.tokens_config.counter.counter(),
+    ) else {
+        return (body, None);
+    };
+    match serde_json::to_vec(&json) {
+        Ok(bytes) => {
+            tracing::debug!(
+                tokens_injected = augmented.tokens_injected,
+                "Augmentation injected ~{} tokens (buffered)",
+                augmented.tokens_injected
+            );
+            (Bytes::from(bytes), Some(augmented.tokens_injected))
+        }
+        Err(_) => (body, None),
+    }
+}
+
 /// Handle non-streaming responses (JSON) - buffer and forward
 async fn handle_buffered_response(ctx: ResponseContext) -> Result<Response<Body>, ProxyError> {
     let ResponseContext {
@@ -1394,6 +1981,8 @@ async fn handle_buffered_response(ctx: ResponseContext) -> Result<Response<Body>
         state,
         user_id,
         translation_ctx,
+        augmentation,
+        translation,
     } = ctx;
     // Read full response body
     let response_body = response
@@ -1448,11 +2037,52 @@ async fn handle_buffered_response(ctx: ResponseContext) -> Result<Response<Body>
         )
         .await;
 
+    // Apply response transformers on the Anthropic-format body (before translation)
+    // The original is kept for parsing so recorded events (and policy audit) match upstream
+    let original_body = response_body.clone();
+    let mut response_modifications = Vec::new();
+    let mut blocked_tools = Vec::new();
+    let response_body =
+        if is_messages_endpoint && status.is_success() && !state.response_transformation.is_empty()
+        {
+            match serde_json::from_slice::<serde_json::Value>(&response_body) {
+                Ok(mut json) => {
+                    let edits = state.response_transformation.transform_message(
+                        &mut json,
+                        &response_transformation::ResponseTransformContext,
+                    );
+                    response_modifications = edits.modifications;
+                    blocked_tools = edits.blocked_tools;
+                    if response_modifications.is_empty() {
+                        response_body
+                    } else {
+                        serde_json::to_vec(&json)
+                            .map(Bytes::from)
+                            .unwrap_or(response_body)
+                    }
+                }
+                Err(_) => response_body,
+            }
+        } else {
+            response_body
+        };
+    let body_rewritten = !response_modifications.is_empty();
+
+    // Augment while the body is still Anthropic format (OpenAI backends are
+    // augmented after translation below, if the client speaks Anthropic)
+    let augment = is_messages_endpoint && status.is_success();
+    let (response_body, mut injected_tokens) =
+        if augment && translation_ctx.backend_format == translation::ApiFormat::Anthropic {
+            augment_buffered(&state, &augmentation, response_body)
+        } else {
+            (response_body, None)
+        };
+
     // Apply response translation FIRST (so parser sees Anthropic format)
     let final_response_body = if translation_ctx.needs_response_translation() && status.is_success()
     {
         // Get response translator (backend_format → client_format)
-        if let Some(translator) = state.translation.get_response_translator(
+        if let Some(translator) = translation.get_response_translator(
             translation_ctx.backend_format,
             translation_ctx.client_format,
         ) {
@@ -1479,44 +2109,64 @@ async fn handle_buffered_response(ctx: ResponseContext) -> Result<Response<Body>
         response_body
     };
 
+    let final_response_body = if augment
+        && translation_ctx.needs_response_translation()
+        && translation_ctx.backend_format != translation::ApiFormat::Anthropic
+        && translation_ctx.client_format == translation::ApiFormat::Anthropic
+    {
+        let (body, tokens) = augment_buffered(&state, &augmentation, final_response_body);
+        injected_tokens = tokens;
+        body
+    } else {
+        final_response_body
+    };
+
     // Parse response for tool calls, assistant content, usage (uses translated body for correct format)
     if is_messages_endpoint && status.is_success() {
+        let parse_body = if translation_ctx.needs_response_translation() {
+            &final_response_body
+        } else {
+            &original_body
+        };
         if let Ok(events) = state
             .parser
-            .parse_response(&final_response_body, user_id.as_deref())
+            .parse_response(parse_body, user_id.as_deref())
             .await
         {
-            for event in events {
-                // Update context state when we see ApiUsage (skip Haiku utility calls)
-                if let ProxyEvent::ApiUsage {
-                    input_tokens,
-                    cache_creation_tokens,
-                    cache_read_tokens,
-                    model,
-                    ..
-                } = &event
-                {
-                    if !model.to_lowercase().contains("haiku") {
-                        if let Ok(mut ctx) = state.context_state.lock() {
-                            ctx.update(
-                                *input_tokens as u64,
-                                *cache_creation_tokens as u64,
-                                *cache_read_tokens as u64,
-                            );
-                        }
-                    }
-                }
-                // Reset warnings on context compact
-                if let ProxyEvent::ContextCompact { .. } = &event {
-                    if let Ok(mut ctx) = state.context_state.lock() {
-                        ctx.reset_warnings();
-                    }
-                }
-                state.send_event(event, user_id.as_deref()).await;
-            }
+            state
+                .emit_parsed_events(events, &request_id, user_id.as_deref(), &blocked_tools)
+                .await;
         }
     }
 
+    // Emit transformation event if any response transformer changed the body
+    if body_rewritten {
+        state
+            .send_event(
+                ProxyEvent::ResponseTransformed {
+                    timestamp: Utc::now(),
+                    transformer: "response-transformation-pipeline".to_string(),
+                    modifications: response_modifications,
+                },
+                user_id.as_deref(),
+            )
+            .await;
+    }
+
+    // Emit augmentation event if tokens were injected
+    if let Some(tokens) = injected_tokens {
+        state
+            .send_event(
+                ProxyEvent::ResponseAugmented {
+                    timestamp: Utc::now(),
+                    augmenter: "context-warning".to_string(),
+                    tokens_injected: tokens,
+                },
+                user_id.as_deref(),
+            )
+            .await;
+    }
+
     // Build response to return to client
     let mut builder = Response::builder().status(status.as_u16());
 
@@ -1524,8 +2174,12 @@ async fn handle_buffered_response(ctx: ResponseContext) -> Result<Response<Body>
         if key == "transfer-encoding" || key == "connection" {
             continue;
         }
-        // Update content-length if translation occurred
-        if key == "content-length" && translation_ctx.needs_response_translation() {
+        // Update content-length if translation, transformation or augmentation occurred
+        if key == "content-length"
+            && (translation_ctx.needs_response_translation()
+                || body_rewritten
+                || injected_tokens.is_some())
+        {
             continue; // Will be set automatically from body
         }
         builder = builder.header(key, value);
@@ -1696,6 +2350,7 @@ impl IntoResponse for ProxyError {
 mod tests {
     use super::*;
     use crate::config::{ClientConfig, ProviderConfig};
+    use serde_json::json;
     use std::collections::HashMap;
 
     fn make_test_clients() -> ClientsConfig {
@@ -1816,4 +2471,52 @@ mod tests {
         assert_eq!(routing.client_id, Some("dev-1".to_string()));
         assert_eq!(routing.api_path, "/v1/messages/count_tokens");
     }
+
+    #[tokio::test]
+    async fn test_denied_tool_produces_no_tool_call_event() {
+        let policy: policy::PolicyConfig = toml::from_str(
+            r#"
+            enabled = true
+            mode = "rewrite"
+
+            [[rules]]
+            name = "no-rm-rf"
+            effect = "deny"
+            tools = ["Bash"]
+            commands = ["rm\\s+-rf"]
+            "#,
+        )
+        .unwrap();
+        let engine = Arc::new(policy::PolicyEngine::from_config(&policy).unwrap());
+        let mut pipeline = response_transformation::ResponseTransformationPipeline::new();
+        pipeline.register(policy::PolicyEnforcer::new(engine.clone()));
+
+        let upstream = include_str!("response_transformation/test_data/tool_use_stream.sse");
+        let mut rewriter = pipeline
+            .stream_rewriter(&response_transformation::ResponseTransformContext)
+            .unwrap();
+        rewriter.push(upstream.as_bytes());
+        rewriter.finish();
+        let blocked = rewriter.blocked_tools().to_vec();
+        assert_eq!(blocked, vec!["toolu_01A09q90qw90lq917835lq9"]);
+
+        // Events are parsed from the upstream bytes, as in the proxy
+        let events = Parser::new()
+            .parse_response(upstream.as_bytes(), None)
+            .await
+            .unwrap();
+        let recorded: Vec<&str> = events
+            .iter()
+            .filter(|e| blocked_call(e, &blocked).is_none())
+            .filter_map(|e| match e {
+                ProxyEvent::ToolCall { tool_name, .. } => Some(tool_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(recorded, vec!["Read"]);
+
+        // The denial itself is still audited
+        let verdict = engine.audit_tool_call(&blocked[0], "Bash", &json!({}), None);
+        assert!(verdict.is_deny());
+    }
 }
@@ -0,0 +1,781 @@
+//! Policy engine for tool-use guardrails
+//!
+//! Organization-wide rules on what Claude may do, enforced at the proxy rather
+//! than in per-machine client settings. Rules are evaluated against every
+//! `tool_use` block in responses and against the tool definitions in each
+//! request.
+//!
+//! # Rules
+//!
+//! Rules are checked in order and the first match wins (firewall-style). A
+//! rule matches when the tool name matches one of `tools` AND, for each
+//! matcher it specifies, the tool input matches at least one pattern:
+//!
+//! - `paths`    - globs against `file_path` / `notebook_path` / `path`
+//! - `commands` - regexes against Bash `command`
+//! - `domains`  - hosts of WebFetch `url` (`*.example.com` matches subdomains)
+//!
+//! # Enforcement
+//!
+//! - `rewrite` - denied calls are replaced in the response with an explanation
+//!   (via the response transformation pipeline); denied tool definitions are
+//!   removed from requests
+//! - `remind`  - calls go through, and a `<system-reminder>` describing the
+//!   violation is injected into that client's next request
+//! - `audit`   - record only
+//!
+//! Every decision is emitted as a `ProxyEvent::PolicyDecision`.
+//!
+//! # Configuration
+//!
+//! ```toml
+//! [policy]
+//! enabled = true
+//! mode = "rewrite"   # rewrite, remind, audit
+//! default = "allow"  # effect when no rule matches
+//!
+//! [[policy.rules]]
+//! name = "no-recursive-delete"
+//! effect = "deny"
+//! tools = ["Bash"]
+//! commands = ["rm\\s+-rf\\s+/"]
+//!
+//! [[policy.rules]]
+//! name = "secrets"
+//! effect = "deny"
+//! tools = ["Read", "Edit", "Write"]
+//! paths = ["**/.env", "**/*.pem"]
+//! mode = "remind"    # per-rule override
+//! ```
+
+use super::response_transformation::{ResponseTransformer, ToolUse, ToolUseAction};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Input fields that hold a file path, in lookup order
+const PATH_FIELDS: &[&str] = &["file_path", "notebook_path", "path"];
+
+/// Upper bound on enforced verdicts awaiting audit (guards against leaks if a
+/// response is never parsed)
+const MAX_PENDING_VERDICTS: usize = 1024;
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Allow or deny
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyEffect {
+    #[default]
+    Allow,
+    Deny,
+}
+
+impl PolicyEffect {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyEffect::Allow => "allow",
+            PolicyEffect::Deny => "deny",
+        }
+    }
+}
+
+/// How a denial is enforced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnforcementMode {
+    /// Replace denied calls with an explanation; strip denied tool definitions
+    #[default]
+    Rewrite,
+    /// Let calls through and remind Claude on the next request
+    Remind,
+    /// Record only
+    Audit,
+}
+
+impl EnforcementMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnforcementMode::Rewrite => "rewrite",
+            EnforcementMode::Remind => "remind",
+            EnforcementMode::Audit => "audit",
+        }
+    }
+}
+
+fn default_tools() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+/// A single policy rule (from TOML)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PolicyRule {
+    /// Rule name (shown in decisions and explanations)
+    pub name: String,
+    pub effect: PolicyEffect,
+    /// Tool names this rule applies to (`*` wildcards allowed)
+    #[serde(default = "default_tools")]
+    pub tools: Vec<String>,
+    /// Path globs for file tools
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    /// Command regexes for Bash
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    /// Host patterns for WebFetch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+    /// Explanation shown to Claude on denial
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Per-rule enforcement override
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<EnforcementMode>,
+}
+
+/// Policy settings (`[policy]` section)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct PolicyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Default enforcement for denials
+    #[serde(default)]
+    pub mode: EnforcementMode,
+    /// Effect when no rule matches
+    #[serde(default)]
+    pub default: PolicyEffect,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+// ============================================================================
+// Verdicts
+// ============================================================================
+
+/// Outcome of evaluating one tool call or tool definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub effect: PolicyEffect,
+    /// Matching rule name (`None` = default effect)
+    pub rule: Option<String>,
+    /// Enforcement applied (meaningful for denials)
+    pub enforcement: EnforcementMode,
+    /// Human-readable reason
+    pub reason: String,
+}
+
+impl Verdict {
+    pub fn is_deny(&self) -> bool {
+        self.effect == PolicyEffect::Deny
+    }
+
+    /// Convert into an audit event
+    pub fn into_event(self, tool_name: &str, tool_id: Option<&str>) -> crate::events::ProxyEvent {
+        let enforcement = if self.is_deny() {
+            self.enforcement.as_str()
+        } else {
+            "none"
+        };
+        crate::events::ProxyEvent::PolicyDecision {
+            timestamp: chrono::Utc::now(),
+            tool_name: tool_name.to_string(),
+            tool_id: tool_id.map(String::from),
+            decision: self.effect.as_str().to_string(),
+            rule: self.rule,
+            enforcement: enforcement.to_string(),
+            reason: self.reason,
+        }
+    }
+}
+
+// ============================================================================
+// Compiled Rules
+// ============================================================================
+
+struct CompiledRule {
+    name: String,
+    effect: PolicyEffect,
+    tools: Vec<Regex>,
+    paths: Vec<Regex>,
+    commands: Vec<Regex>,
+    domains: Vec<String>,
+    message: Option<String>,
+    mode: Option<EnforcementMode>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &PolicyRule) -> anyhow::Result<Self> {
+        let home = dirs::home_dir().map(|h| h.to_string_lossy().replace('\\', "/"));
+        let expand = |p: &String| match (&home, p.strip_prefix("~/")) {
+            (Some(home), Some(rest)) => format!("{}/{}", home, rest),
+            _ => p.clone(),
+        };
+        Ok(Self {
+            name: rule.name.clone(),
+            effect: rule.effect,
+            tools: rule
+                .tools
+                .iter()
+                .map(|t| glob_to_regex(t, false))
+                .collect::<Result<_, _>>()?,
+            paths: rule
+                .paths
+                .iter()
+                .map(|p| glob_to_regex(&expand(p), true))
+                .collect::<Result<_, _>>()?,
+            commands: rule
+                .commands
+                .iter()
+                .map(|c| Regex::new(c))
+                .collect::<Result<_, _>>()?,
+            domains: rule.domains.iter().map(|d| d.to_lowercase()).collect(),
+            message: rule.message.clone(),
+            mode: rule.mode,
+        })
+    }
+
+    fn matches_tool(&self, name: &str) -> bool {
+        self.tools.iter().any(|t| t.is_match(name))
+    }
+
+    /// Rule only constrains the tool name
+    fn is_name_only(&self) -> bool {
+        self.paths.is_empty() && self.commands.is_empty() && self.domains.is_empty()
+    }
+
+    /// Check input matchers; returns the matched value for the explanation
+    fn matches_input(&self, input: &Value) -> Option<String> {
+        let mut matched = None;
+        if !self.paths.is_empty() {
+            let path = PATH_FIELDS
+                .iter()
+                .find_map(|f| input.get(*f).and_then(|v| v.as_str()))?;
+            let normalized = path.replace('\\', "/");
+            if !self.paths.iter().any(|p| p.is_match(&normalized)) {
+                return None;
+            }
+            matched = Some(path.to_string());
+        }
+        if !self.commands.is_empty() {
+            let command = input.get("command").and_then(|v| v.as_str())?;
+            if !self.commands.iter().any(|c| c.is_match(command)) {
+                return None;
+            }
+            matched = Some(command.to_string());
+        }
+        if !self.domains.is_empty() {
+            let url = input.get("url").and_then(|v| v.as_str())?;
+            let host = url_host(url)?;
+            if !self.domains.iter().any(|d| domain_matches(d, &host)) {
+                return None;
+            }
+            matched = Some(host);
+        }
+        Some(matched.unwrap_or_default())
+    }
+}
+
+/// Convert a glob to an anchored regex
+///
+/// `**` crosses directories; `*` and `?` do not when `path` is true.
+fn glob_to_regex(glob: &str, path: bool) -> Result<Regex, regex::Error> {
+    let single = if path { "[^/]*" } else { ".*" };
+    let one = if path { "[^/]" } else { "." };
+    let mut re = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // "**/" also matches zero directories
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str(single),
+            '?' => re.push_str(one),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re)
+}
+
+/// Extract the lowercase host from a URL (no scheme required)
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+/// `example.com` matches exactly; `*.example.com` matches any subdomain
+fn domain_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+        None => pattern == host,
+    }
+}
+
+// ============================================================================
+// Policy Engine
+// ============================================================================
+
+/// Evaluates tool use against policy rules and tracks enforcement state
+pub struct PolicyEngine {
+    rules: Vec<CompiledRule>,
+    mode: EnforcementMode,
+    default: PolicyEffect,
+    /// Verdicts enforced in flight, keyed by tool_use ID, awaiting audit
+    enforced: Mutex<PendingVerdicts>,
+    /// Reminders queued for each user's next request
+    reminders: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl PolicyEngine {
+    /// Compile rules from config (fails on an invalid glob or regex)
+    pub fn from_config(config: &PolicyConfig) -> anyhow::Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|r| {
+                CompiledRule::compile(r)
+                    .map_err(|e| anyhow::anyhow!("policy rule '{}': {}", r.name, e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            rules,
+            mode: config.mode,
+            default: config.default,
+            enforced: Mutex::new(PendingVerdicts::default()),
+            reminders: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether any denial is enforced by rewriting responses
+    pub fn rewrites(&self) -> bool {
+        self.mode == EnforcementMode::Rewrite
+            || self
+                .rules
+                .iter()
+                .any(|r| r.mode == Some(EnforcementMode::Rewrite))
+    }
+
+    fn verdict(&self, rule: &CompiledRule, tool: &str, matched: &str) -> Verdict {
+        let reason = match (&rule.message, rule.effect) {
+            (Some(message), _) => message.clone(),
+            (None, PolicyEffect::Deny) if matched.is_empty() => {
+                format!("{} is not permitted by policy rule '{}'", tool, rule.name)
+            }
+            (None, PolicyEffect::Deny) => format!(
+                "{} on `{}` is not permitted by policy rule '{}'",
+                tool, matched, rule.name
+            ),
+            (None, PolicyEffect::Allow) => format!("allowed by policy rule '{}'", rule.name),
+        };
+        Verdict {
+            effect: rule.effect,
+            rule: Some(rule.name.clone()),
+            enforcement: rule.mode.unwrap_or(self.mode),
+            reason,
+        }
+    }
+
+    fn default_verdict(&self, tool: &str) -> Verdict {
+        Verdict {
+            effect: self.default,
+            rule: None,
+            enforcement: self.mode,
+            reason: match self.default {
+                PolicyEffect::Allow => "no rule matched (default allow)".to_string(),
+                PolicyEffect::Deny => {
+                    format!("{} is not permitted by policy (default deny)", tool)
+                }
+            },
+        }
+    }
+
+    /// Evaluate a tool call
+    pub fn evaluate(&self, tool: &str, input: &Value) -> Verdict {
+        for rule in self.rules.iter().filter(|r| r.matches_tool(tool)) {
+            if let Some(matched) = rule.matches_input(input) {
+                return self.verdict(rule, tool, &matched);
+            }
+        }
+        self.default_verdict(tool)
+    }
+
+    /// Evaluate a tool definition (no input available)
+    ///
+    /// A definition is decided by the first name-only rule for the tool. If an
+    /// input-dependent rule for the tool comes first, individual calls may
+    /// still be allowed, so the definition is allowed.
+    pub fn evaluate_definition(&self, tool: &str) -> Verdict {
+        match self.rules.iter().find(|r| r.matches_tool(tool)) {
+            Some(rule) if rule.is_name_only() => self.verdict(rule, tool, ""),
+            Some(rule) => Verdict {
+                effect: PolicyEffect::Allow,
+                rule: Some(rule.name.clone()),
+                enforcement: rule.mode.unwrap_or(self.mode),
+                reason: format!("calls are checked by policy rule '{}'", rule.name),
+            },
+            None => self.default_verdict(tool),
+        }
+    }
+
+    /// Apply policy to a request's tool definitions
+    ///
+    /// Denied definitions are removed when enforcement is `rewrite`; `remind`
+    /// denials queue nothing (the tools are visible anyway) but still count.
+    /// Returns `(tool_name, verdict)` for each denial.
+    pub fn apply_to_definitions(&self, body: &mut Value) -> Vec<(String, Verdict)> {
+        let Some(tools) = body.get_mut("tools").and_then(|t| t.as_array_mut()) else {
+            return Vec::new();
+        };
+        let mut denials = Vec::new();
+        tools.retain(|tool| {
+            let Some(name) = tool.get("name").and_then(|n| n.as_str()) else {
+                return true;
+            };
+            let verdict = self.evaluate_definition(name);
+            if !verdict.is_deny() {
+                return true;
+            }
+            let keep = verdict.enforcement != EnforcementMode::Rewrite;
+            denials.push((name.to_string(), verdict));
+            keep
+        });
+        denials
+    }
+
+    /// Record the verdict for a completed tool call (from the parsed response)
+    ///
+    /// Uses the verdict enforced in flight if there was one, so the audit trail
+    /// matches what the client saw. `remind` denials queue a reminder for the
+    /// user's next request.
+    pub fn audit_tool_call(
+        &self,
+        tool_id: &str,
+        tool_name: &str,
+        input: &Value,
+        user_id: Option<&str>,
+    ) -> Verdict {
+        let enforced = self
+            .enforced
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(tool_id));
+        let verdict = enforced.unwrap_or_else(|| self.evaluate(tool_name, input));
+
+        if verdict.is_deny() && verdict.enforcement == EnforcementMode::Remind {
+            let key = user_id.unwrap_or("unknown").to_string();
+            if let Ok(mut reminders) = self.reminders.lock() {
+                reminders.entry(key).or_default().push(format!(
+                    "Policy violation: your previous {} call ({}) was not permitted: {}. \
+                     Do not repeat this action; ask the user how to proceed instead.",
+                    tool_name, tool_id, verdict.reason
+                ));
+            }
+        }
+        verdict
+    }
+
+    /// Inject queued reminders for this user into the request's last user message
+    ///
+    /// Returns the number of reminders injected.
+    pub fn inject_reminders(&self, body: &mut Value, user_id: Option<&str>) -> usize {
+        let key = user_id.unwrap_or("unknown");
+        let pending = match self.reminders.lock() {
+            Ok(mut map) => map.remove(key).unwrap_or_default(),
+            Err(_) => return 0,
+        };
+        if pending.is_empty() {
+            return 0;
+        }
+
+        let Some(message) = body
+            .get_mut("messages")
+            .and_then(|m| m.as_array_mut())
+            .and_then(|msgs| {
+                msgs.iter_mut()
+                    .rev()
+                    .find(|m| m.get("role").and_then(|r| r.as_str()) == Some("user"))
+            })
+        else {
+            // No user message to attach to - keep them for the next request
+            if let Ok(mut map) = self.reminders.lock() {
+                map.entry(key.to_string()).or_default().extend(pending);
+            }
+            return 0;
+        };
+
+        let block = json!({
+            "type": "text",
+            "text": format!("<system-reminder>\n{}\n</system-reminder>", pending.join("\n\n"))
+        });
+        match message.get_mut("content") {
+            Some(Value::Array(blocks)) => blocks.push(block),
+            Some(content) => {
+                let text = content.as_str().unwrap_or_default().to_string();
+                *content = json!([{"type": "text", "text": text}, block]);
+            }
+            None => message["content"] = json!([block]),
+        }
+        pending.len()
+    }
+
+    fn remember(&self, tool_id: &str, verdict: &Verdict) {
+        if let Ok(mut pending) = self.enforced.lock() {
+            pending.insert(tool_id, verdict.clone());
+        }
+    }
+}
+
+/// Enforced verdicts awaiting audit, oldest evicted first
+#[derive(Debug, Default)]
+struct PendingVerdicts {
+    verdicts: HashMap<String, Verdict>,
+    /// Insertion order, for eviction
+    order: VecDeque<String>,
+}
+
+impl PendingVerdicts {
+    fn insert(&mut self, tool_id: &str, verdict: Verdict) {
+        if self.verdicts.insert(tool_id.to_string(), verdict).is_some() {
+            return;
+        }
+        self.order.push_back(tool_id.to_string());
+        if self.order.len() > MAX_PENDING_VERDICTS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.verdicts.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&mut self, tool_id: &str) -> Option<Verdict> {
+        self.verdicts.remove(tool_id)
+    }
+}
+
+// ============================================================================
+// Response Enforcement
+// ============================================================================
+
+/// Response transformer that enforces `rewrite` denials in flight
+pub struct PolicyEnforcer {
+    engine: Arc<PolicyEngine>,
+}
+
+impl PolicyEnforcer {
+    pub fn new(engine: Arc<PolicyEngine>) -> Self {
+        Self { engine }
+    }
+}
+
+impl ResponseTransformer for PolicyEnforcer {
+    fn name(&self) -> &'static str {
+        "policy"
+    }
+
+    fn inspects_tool_use(&self) -> bool {
+        true
+    }
+
+    fn transform_tool_use(&self, tool: &ToolUse) -> ToolUseAction {
+        let verdict = self.engine.evaluate(tool.name, tool.input);
+        self.engine.remember(tool.id, &verdict);
+        if verdict.is_deny() && verdict.enforcement == EnforcementMode::Rewrite {
+            ToolUseAction::Block {
+                message: format!(
+                    "⛔ Blocked by organization policy: {}. The {} call was not executed.",
+                    verdict.reason, tool.name
+                ),
+            }
+        } else {
+            ToolUseAction::Keep
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(toml_str: &str) -> PolicyEngine {
+        let config: PolicyConfig = toml::from_str(toml_str).unwrap();
+        PolicyEngine::from_config(&config).unwrap()
+    }
+
+    const POLICY: &str = r#"
+        enabled = true
+        mode = "rewrite"
+
+        [[rules]]
+        name = "no-recursive-delete"
+        effect = "deny"
+        tools = ["Bash"]
+        commands = ["rm\\s+-rf\\s+/"]
+
+        [[rules]]
+        name = "secrets"
+        effect = "deny"
+        tools = ["Read", "Edit", "Write"]
+        paths = ["**/.env", "**/*.pem"]
+        mode = "remind"
+
+        [[rules]]
+        name = "docs-only"
+        effect = "allow"
+        tools = ["WebFetch"]
+        domains = ["docs.rs", "*.rust-lang.org"]
+
+        [[rules]]
+        name = "no-web"
+        effect = "deny"
+        tools = ["WebFetch", "WebSearch"]
+
+        [[rules]]
+        name = "no-mcp-shell"
+        effect = "deny"
+        tools = ["mcp__shell__*"]
+    "#;
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let e = engine(POLICY);
+
+        let v = e.evaluate("Bash", &json!({"command": "rm -rf /var"}));
+        assert!(v.is_deny());
+        assert_eq!(v.rule.as_deref(), Some("no-recursive-delete"));
+        assert!(!e
+            .evaluate("Bash", &json!({"command": "rm -rf ./build"}))
+            .is_deny());
+
+        let v = e.evaluate("Read", &json!({"file_path": "/repo/config/.env"}));
+        assert_eq!(v.enforcement, EnforcementMode::Remind);
+        assert!(v.is_deny());
+        assert!(!e
+            .evaluate("Read", &json!({"file_path": "/repo/src/env.rs"}))
+            .is_deny());
+
+        let docs = json!({"url": "https://doc.rust-lang.org/std/"});
+        assert_eq!(
+            e.evaluate("WebFetch", &docs).rule.as_deref(),
+            Some("docs-only")
+        );
+        let other = json!({"url": "https://evil.example.com/x"});
+        assert_eq!(
+            e.evaluate("WebFetch", &other).rule.as_deref(),
+            Some("no-web")
+        );
+    }
+
+    #[test]
+    fn test_definitions_respect_input_rules() {
+        let e = engine(POLICY);
+        let mut body = json!({
+            "tools": [
+                {"name": "Bash"}, {"name": "WebFetch"}, {"name": "WebSearch"},
+                {"name": "mcp__shell__exec"}, {"name": "Read"}
+            ]
+        });
+        let denials = e.apply_to_definitions(&mut body);
+        let names: Vec<_> = denials.iter().map(|(n, _)| n.as_str()).collect();
+        // WebFetch has an input-dependent allow rule first, so it stays
+        assert_eq!(names, vec!["WebSearch", "mcp__shell__exec"]);
+        let remaining: Vec<_> = body["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(remaining, vec!["Bash", "WebFetch", "Read"]);
+    }
+
+    #[test]
+    fn test_remind_queues_reminder_for_next_request() {
+        let e = engine(POLICY);
+        let input = json!({"file_path": "/home/me/app/.env"});
+        let v = e.audit_tool_call("toolu_1", "Read", &input, Some("dev-1"));
+        assert!(v.is_deny());
+
+        // Other users are unaffected
+        let mut other = json!({"messages": [{"role": "user", "content": "hi"}]});
+        assert_eq!(e.inject_reminders(&mut other, Some("dev-2")), 0);
+
+        let mut body = json!({"messages": [{"role": "user", "content": "continue"}]});
+        assert_eq!(e.inject_reminders(&mut body, Some("dev-1")), 1);
+        let content = body["messages"][0]["content"].as_array().unwrap();
+        assert_eq!(content[0]["text"], "continue");
+        assert!(content[1]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("<system-reminder>\nPolicy violation"));
+        // Drained
+        assert_eq!(e.inject_reminders(&mut body, Some("dev-1")), 0);
+    }
+
+    #[test]
+    fn test_enforcer_verdict_is_reused_for_audit() {
+        let e = Arc::new(engine(POLICY));
+        let enforcer = PolicyEnforcer::new(e.clone());
+        let input = json!({"command": "rm -rf /"});
+        let action = enforcer.transform_tool_use(&ToolUse {
+            id: "toolu_9",
+            name: "Bash",
+            input: &input,
+        });
+        assert!(matches!(action, ToolUseAction::Block { .. }));
+
+        let v = e.audit_tool_call("toolu_9", "Bash", &input, None);
+        assert_eq!(v.enforcement, EnforcementMode::Rewrite);
+        assert!(e.enforced.lock().unwrap().verdicts.is_empty());
+    }
+
+    #[test]
+    fn test_pending_verdicts_evict_oldest_first() {
+        let e = engine(POLICY);
+        let verdict = e.evaluate("Bash", &json!({"command": "rm -rf /"}));
+        let mut pending = PendingVerdicts::default();
+        for i in 0..=MAX_PENDING_VERDICTS {
+            pending.insert(&format!("toolu_{}", i), verdict.clone());
+        }
+
+        assert_eq!(pending.verdicts.len(), MAX_PENDING_VERDICTS);
+        assert!(pending.remove("toolu_0").is_none());
+        assert!(pending.remove("toolu_1").is_some());
+        assert!(pending
+            .remove(&format!("toolu_{}", MAX_PENDING_VERDICTS))
+            .is_some());
+    }
+
+    #[test]
+    fn test_glob_and_host_helpers() {
+        let g = glob_to_regex("**/*.pem", true).unwrap();
+        assert!(g.is_match("key.pem"));
+        assert!(g.is_match("/etc/ssl/key.pem"));
+        assert!(!g.is_match("/etc/ssl/key.pem.bak"));
+        assert!(!glob_to_regex("src/*.rs", true)
+            .unwrap()
+            .is_match("src/a/b.rs"));
+
+        assert_eq!(
+            url_host("https://user@Docs.RS:443/x?y").as_deref(),
+            Some("docs.rs")
+        );
+        assert!(domain_matches("*.rust-lang.org", "doc.rust-lang.org"));
+        assert!(!domain_matches("*.rust-lang.org", "rust-lang.org"));
+    }
+}
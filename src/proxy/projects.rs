@@ -0,0 +1,328 @@
+//! Per-project configuration overlays
+//!
+//! Different repositories want different transformers, tag-editor rules and
+//! context-warning thresholds. Each request is mapped to a project root -
+//! from the `x-aspy-project` header, or the working directory Claude Code
+//! puts in its system prompt - and the nearest `.aspy.toml` at or above it is
+//! merged over the global config (see [`Config::for_project`]).
+//!
+//! Only per-request decisions come from the overlay: the transformation
+//! pipeline and augmentation. Process-wide settings (bind address, lifestats,
+//! logging, routing) always come from the global config.
+//!
+//! Profiles are cached per root and rebuilt when `.aspy.toml` changes; the
+//! directory-to-root lookup is cached per directory for [`ROOT_RECHECK`]. A
+//! broken overlay is logged once per change and the request falls back to
+//! the global pipelines. Resolving touches the filesystem, so the proxy runs
+//! it on the blocking pool.
+
+use super::augmentation::AugmentationPipeline;
+use super::transformation::TransformationPipeline;
+use crate::config::{Config, PROJECT_CONFIG_FILE};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Header a client can set to name its project directory explicitly
+pub const PROJECT_HEADER: &str = "x-aspy-project";
+
+/// How long a directory's project root lookup is reused before walking again
+///
+/// Bounds how late a newly created `.aspy.toml` is noticed; edits to an
+/// existing one are picked up on the next request via its mtime.
+pub const ROOT_RECHECK: Duration = Duration::from_secs(30);
+
+/// Pipelines built from a project's merged config
+pub struct ProjectProfile {
+    /// Directory containing the `.aspy.toml`
+    pub root: PathBuf,
+    /// Master switch from the merged `[transformers]` section
+    pub transformers_enabled: bool,
+    pub transformation: Arc<TransformationPipeline>,
+    pub augmentation: Arc<AugmentationPipeline>,
+}
+
+/// Cached result of loading one overlay
+struct CachedProfile {
+    /// `.aspy.toml` modification time the entry was built from
+    modified: Option<SystemTime>,
+    /// None if the overlay failed to load
+    profile: Option<Arc<ProjectProfile>>,
+}
+
+/// Cached project root lookup for one directory
+struct CachedRoot {
+    /// None if no `.aspy.toml` was found at or above the directory
+    root: Option<PathBuf>,
+    checked: Instant,
+}
+
+/// Resolves requests to project profiles
+pub struct ProjectOverlays {
+    /// Raw global config file, merged under every overlay
+    global: Option<toml::Value>,
+    roots: Mutex<HashMap<PathBuf, CachedRoot>>,
+    profiles: Mutex<HashMap<PathBuf, CachedProfile>>,
+}
+
+impl ProjectOverlays {
+    pub fn new(global: Option<toml::Value>) -> Self {
+        Self {
+            global,
+            roots: Mutex::new(HashMap::new()),
+            profiles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Directory a request belongs to: explicit header, else the system prompt's cwd
+    ///
+    /// Only the top-level `system` value is deserialized; the rest of the
+    /// body is skipped without parsing.
+    pub fn project_dir(headers: &axum::http::HeaderMap, body: &[u8]) -> Option<PathBuf> {
+        if let Some(dir) = headers
+            .get(PROJECT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            return Some(PathBuf::from(dir));
+        }
+
+        let system: serde_json::Value =
+            serde_json::from_slice(top_level_value(body, "system")?).ok()?;
+        let system = serde_json::json!({ "system": system });
+        crate::git_link::extract_env_info(&system)
+            .map(|env| PathBuf::from(env.cwd))
+            .filter(|cwd| cwd.is_absolute())
+    }
+
+    /// Profile for a project directory, or None when it has no overlay
+    ///
+    /// Stats `.aspy.toml` (and walks up from `dir` when the root lookup is
+    /// stale), so call it off the async runtime.
+    pub fn resolve(&self, dir: &Path) -> Option<Arc<ProjectProfile>> {
+        let root = self.project_root(dir)?;
+        self.profile(&root)
+    }
+
+    /// Nearest directory at or above `dir` with a `.aspy.toml` (cached)
+    fn project_root(&self, dir: &Path) -> Option<PathBuf> {
+        if let Some(cached) = self.roots.lock().ok()?.get(dir) {
+            if cached.checked.elapsed() < ROOT_RECHECK {
+                return cached.root.clone();
+            }
+        }
+
+        let root = Config::find_project_root(dir);
+        self.roots.lock().ok()?.insert(
+            dir.to_path_buf(),
+            CachedRoot {
+                root: root.clone(),
+                checked: Instant::now(),
+            },
+        );
+        root
+    }
+
+    /// Profile for a project root, (re)loading its overlay if it changed
+    pub fn profile(&self, root: &Path) -> Option<Arc<ProjectProfile>> {
+        let modified = std::fs::metadata(root.join(PROJECT_CONFIG_FILE))
+            .and_then(|m| m.modified())
+            .ok();
+
+        if let Some(cached) = self.profiles.lock().ok()?.get(root) {
+            if cached.modified == modified {
+                return cached.profile.clone();
+            }
+        }
+
+        // Built outside the lock: other projects' requests don't wait on this parse
+        let profile = match Config::for_project(self.global.as_ref(), root) {
+            Ok(config) => {
+                tracing::info!("Loaded project config: {}", root.display());
+                Some(Arc::new(ProjectProfile {
+                    root: root.to_path_buf(),
+                    transformers_enabled: config.transformers.enabled,
+                    transformation: Arc::new(TransformationPipeline::from_config(
+                        &config.transformers,
+                    )),
+                    augmentation: Arc::new(AugmentationPipeline::from_config(&config.augmentation)),
+                }))
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring project config, using global config: {:#}", e);
+                None
+            }
+        };
+
+        self.profiles.lock().ok()?.insert(
+            root.to_path_buf(),
+            CachedProfile {
+                modified,
+                profile: profile.clone(),
+            },
+        );
+        profile
+    }
+}
+
+/// Raw bytes of a top-level member's value, found without parsing the body
+///
+/// Other members (notably the long `messages` array) are skipped by matching
+/// quotes and brackets, so only the requested value gets deserialized.
+fn top_level_value<'a>(body: &'a [u8], key: &str) -> Option<&'a [u8]> {
+    let mut pos = skip_whitespace(body, 0);
+    if body.get(pos) != Some(&b'{') {
+        return None;
+    }
+    pos += 1;
+
+    loop {
+        pos = skip_whitespace(body, pos);
+        if body.get(pos) != Some(&b'"') {
+            return None;
+        }
+        let key_end = skip_string(body, pos)?;
+        let name = &body[pos + 1..key_end - 1];
+
+        pos = skip_whitespace(body, key_end);
+        if body.get(pos) != Some(&b':') {
+            return None;
+        }
+        pos = skip_whitespace(body, pos + 1);
+        let value_end = skip_value(body, pos)?;
+        if name == key.as_bytes() {
+            return Some(&body[pos..value_end]);
+        }
+
+        pos = skip_whitespace(body, value_end);
+        if body.get(pos) != Some(&b',') {
+            return None;
+        }
+        pos += 1;
+    }
+}
+
+fn skip_whitespace(body: &[u8], mut pos: usize) -> usize {
+    while body.get(pos).is_some_and(u8::is_ascii_whitespace) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Index just past the string whose opening quote is at `start`
+fn skip_string(body: &[u8], start: usize) -> Option<usize> {
+    let mut pos = start + 1;
+    while let Some(&byte) = body.get(pos) {
+        match byte {
+            b'\\' => pos += 2,
+            b'"' => return Some(pos + 1),
+            _ => pos += 1,
+        }
+    }
+    None
+}
+
+/// Index just past the value starting at `start`
+fn skip_value(body: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut pos = start;
+    while let Some(&byte) = body.get(pos) {
+        match byte {
+            b'"' => {
+                pos = skip_string(body, pos)?;
+                if depth == 0 {
+                    return Some(pos);
+                }
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return Some(pos),
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            b',' if depth == 0 => return Some(pos),
+            _ => {}
+        }
+        pos += 1;
+    }
+    (depth == 0).then_some(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderMap;
+
+    fn temp_project(overlay: &str) -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::create_dir_all(dir.join("src/nested")).unwrap();
+        std::fs::write(dir.join(PROJECT_CONFIG_FILE), overlay).unwrap();
+        (tmp, dir)
+    }
+
+    #[test]
+    fn test_resolve_from_system_prompt_cwd() {
+        let (_tmp, root) = temp_project(
+            "[augmentation]\ncontext_warning = false\n\n[transformers]\nenabled = true\n",
+        );
+        let global: toml::Value = toml::from_str(
+            "[transformers]\nenabled = false\n[augmentation]\ncontext_warning = true\n",
+        )
+        .unwrap();
+        let overlays = ProjectOverlays::new(Some(global));
+
+        let cwd = root.join("src/nested");
+        let body = serde_json::json!({
+            "system": [{"type": "text", "text": format!("<env>\nWorking directory: {}\n</env>", cwd.display())}],
+            "messages": []
+        });
+        let dir = ProjectOverlays::project_dir(&HeaderMap::new(), body.to_string().as_bytes());
+        assert_eq!(dir.as_deref(), Some(cwd.as_path()));
+        let profile = overlays.resolve(&cwd).expect("overlay should apply");
+
+        assert_eq!(profile.root, root);
+        assert!(profile.transformers_enabled);
+        assert!(profile.augmentation.is_empty());
+
+        // Cached until the file changes
+        let again = overlays.profile(&root).unwrap();
+        assert!(Arc::ptr_eq(&profile, &again));
+    }
+
+    #[test]
+    fn test_header_wins_and_broken_overlay_falls_back() {
+        let (_tmp, root) = temp_project("[transformers\n");
+        let mut headers = HeaderMap::new();
+        headers.insert(PROJECT_HEADER, root.display().to_string().parse().unwrap());
+
+        assert_eq!(
+            ProjectOverlays::project_dir(&headers, b"{}"),
+            Some(root.clone())
+        );
+        assert!(ProjectOverlays::new(None).resolve(&root).is_none());
+    }
+
+    #[test]
+    fn test_top_level_value_skips_other_members() {
+        let body = br#"{"messages": [{"role": "user", "content": "{\"system\": [1, \"]\"]}"}],
+            "stream": true, "system": [{"type": "text", "text": "hi"}], "max_tokens": 10}"#;
+        assert_eq!(
+            top_level_value(body, "system"),
+            Some(&br#"[{"type": "text", "text": "hi"}]"#[..])
+        );
+        assert_eq!(top_level_value(body, "stream"), Some(&b"true"[..]));
+        assert_eq!(top_level_value(body, "max_tokens"), Some(&b"10"[..]));
+        assert_eq!(top_level_value(body, "tools"), None);
+        assert_eq!(top_level_value(b"[1, 2]", "system"), None);
+        assert_eq!(
+            top_level_value(br#"{"system": "unterminated"#, "system"),
+            None
+        );
+    }
+}
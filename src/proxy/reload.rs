@@ -0,0 +1,417 @@
+//! Hot reload of config.toml
+//!
+//! Transformers (including the context enricher's retrieval settings),
+//! augmentation, translation, client routing and session handoff can change
+//! while Claude Code sessions keep running through the proxy. Three triggers
+//! re-read the file:
+//!
+//! - a watcher polling config.toml's modification time
+//! - `SIGHUP` (Unix)
+//! - `POST /api/config/reload`
+//!
+//! The file is parsed and validated (the same checks as `aspy config --check`)
+//! before anything is touched; a config with errors, or one that fails to
+//! load, leaves the running one in place and the report lists what is wrong.
+//! Reloads run one at a time, whichever trigger fired. A successful reload
+//! replaces one [`LiveConfig`] behind a [`Swappable`] handle. Requests load
+//! the handle once and keep that snapshot, so a request never mixes
+//! transformers from one config with clients from another.
+//!
+//! Settings bound at startup (bind address, lifestats, logging, OTel, policy,
+//! response transformers) still need a restart; a reload that changes them
+//! says so. Every reload is reported as a `ConfigReloaded` event, which the
+//! TUI shows as a toast (and uses to apply a changed theme).
+
+use super::augmentation::AugmentationPipeline;
+use super::projects::ProjectOverlays;
+use super::transformation::{ContextRetriever, TransformationPipeline};
+use super::translation::TranslationPipeline;
+use super::ProxyState;
+use crate::config::{ClientsConfig, Config, Transformers};
+use crate::config_schema::{self, Severity};
+use crate::events::ProxyEvent;
+use crate::handoff::HandoffService;
+use crate::pipeline::lifestats_query::LifestatsQuery;
+use anyhow::Context;
+use chrono::Utc;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// How often the watcher checks config.toml
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Editors save in several steps; wait for the file to settle
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Top-level config keys applied without a restart
+///
+/// `theme` and `use_theme_background` are applied by the TUI.
+const RELOADABLE_KEYS: &[&str] = &[
+    "transformers",
+    "augmentation",
+    "translation",
+    "clients",
+    "providers",
+    "handoff",
+    "theme",
+    "use_theme_background",
+];
+
+/// A value that can be replaced atomically while readers hold the old one
+///
+/// Readers get an `Arc` snapshot; `store` swaps the pointer under a brief
+/// write lock, so in-flight requests finish on the config they started with.
+pub struct Swappable<T> {
+    inner: RwLock<Arc<T>>,
+}
+
+impl<T> Swappable<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: RwLock::new(Arc::new(value)),
+        }
+    }
+
+    /// Current snapshot
+    pub fn load(&self) -> Arc<T> {
+        self.inner
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Replace the value for all future `load`s
+    pub fn store(&self, value: T) {
+        *self
+            .inner
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(value);
+    }
+}
+
+/// The reloadable part of the proxy's configuration
+pub struct LiveConfig {
+    /// Transformers config (for the enabled flag)
+    pub transformers_config: Transformers,
+    pub transformation: Arc<TransformationPipeline>,
+    pub augmentation: Arc<AugmentationPipeline>,
+    pub translation: Arc<TranslationPipeline>,
+    pub clients: ClientsConfig,
+    /// Project overlays merge over the global file, so they reload with it
+    pub projects: ProjectOverlays,
+    /// Session handoff generator (requires handoff + lifestats enabled)
+    pub handoff: Option<Arc<HandoffService>>,
+    /// Lifestats retrieval for the context enricher (requires lifestats)
+    pub context_retriever: Option<Arc<ContextRetriever>>,
+    /// Raw file this was built from (for change detection)
+    raw: Option<toml::Value>,
+}
+
+impl LiveConfig {
+    /// Build the reloadable state; fails on an invalid context enricher config
+    ///
+    /// `lifestats` is bound at startup and shared by every reload.
+    pub fn from_config(
+        config: &Config,
+        raw: Option<toml::Value>,
+        lifestats: Option<&Arc<LifestatsQuery>>,
+    ) -> anyhow::Result<Self> {
+        // Session handoff docs (opt-in, built from lifestats)
+        let handoff = match (lifestats, config.handoff.enabled) {
+            (Some(query), true) => Some(Arc::new(HandoffService::new(
+                query.clone(),
+                &config.handoff,
+            ))),
+            (None, true) => {
+                tracing::warn!("Session handoff requires lifestats; handoff docs disabled");
+                None
+            }
+            _ => None,
+        };
+
+        // Context enricher retrieval (runs before the sync transformer, needs lifestats)
+        let enricher = config
+            .transformers
+            .context_enricher
+            .as_ref()
+            .filter(|c| c.enabled && config.transformers.enabled);
+        let context_retriever = match (lifestats, enricher) {
+            (Some(query), Some(enricher_config)) => Some(Arc::new(
+                ContextRetriever::new(query.clone(), enricher_config, &config.embeddings)
+                    .context("Invalid [transformers.context-enricher] configuration")?,
+            )),
+            (None, Some(_)) => {
+                tracing::warn!("Context enricher requires lifestats; no context will be injected");
+                None
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            transformers_config: config.transformers.clone(),
+            transformation: Arc::new(TransformationPipeline::from_config(&config.transformers)),
+            augmentation: Arc::new(AugmentationPipeline::from_config(&config.augmentation)),
+            translation: Arc::new(TranslationPipeline::from_config(&config.translation)),
+            clients: config.clients.clone(),
+            projects: ProjectOverlays::new(raw.clone()),
+            handoff,
+            context_retriever,
+            raw,
+        })
+    }
+}
+
+/// What asked for a reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadTrigger {
+    FileChanged,
+    Signal,
+    Api,
+}
+
+impl ReloadTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FileChanged => "file",
+            Self::Signal => "sighup",
+            Self::Api => "api",
+        }
+    }
+}
+
+/// Outcome of a reload (also the `POST /api/config/reload` response)
+#[derive(Debug, Serialize)]
+pub struct ReloadReport {
+    pub success: bool,
+    pub trigger: &'static str,
+    /// Changed top-level sections that were applied
+    pub applied: Vec<String>,
+    /// Changed top-level sections that need a restart to take effect
+    pub needs_restart: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Validation problems in the file, e.g. `line 12: error: …`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+}
+
+impl ReloadReport {
+    fn summary(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        let mut summary = if self.applied.is_empty() {
+            "no reloadable changes".to_string()
+        } else {
+            format!("applied {}", self.applied.join(", "))
+        };
+        if !self.needs_restart.is_empty() {
+            summary.push_str(&format!(
+                "; restart needed for {}",
+                self.needs_restart.join(", ")
+            ));
+        }
+        summary
+    }
+}
+
+/// Top-level keys whose values differ between two config files
+fn changed_keys(old: Option<&toml::Value>, new: Option<&toml::Value>) -> Vec<String> {
+    let empty = toml::map::Map::new();
+    let old = old.and_then(|v| v.as_table()).unwrap_or(&empty);
+    let new = new.and_then(|v| v.as_table()).unwrap_or(&empty);
+
+    let mut keys: Vec<String> = old
+        .keys()
+        .chain(new.keys())
+        .filter(|k| old.get(*k) != new.get(*k))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Serializes reloads from the file watcher, SIGHUP and the API
+static RELOAD_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Re-read config.toml and swap in the reloadable parts
+pub async fn reload(state: &ProxyState, trigger: ReloadTrigger) -> ReloadReport {
+    let _guard = RELOAD_LOCK.lock().await;
+
+    // Parsing, validation and building (embedding provider, regexes) stay off the runtime
+    let lifestats = state.lifestats_query.clone();
+    let (loaded, diagnostics) = tokio::task::spawn_blocking(move || {
+        let (source, diagnostics) = match config_schema::check_config_file() {
+            Ok(checked) => checked,
+            Err(e) => return (Err(e), Vec::new()),
+        };
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        let loaded = if errors > 0 {
+            Err(anyhow::anyhow!(
+                "config.toml has {} error{}",
+                errors,
+                if errors == 1 { "" } else { "s" }
+            ))
+        } else {
+            Config::from_source(source.as_deref()).and_then(|(config, raw)| {
+                let live = LiveConfig::from_config(&config, raw, lifestats.as_ref())?;
+                Ok((config, live))
+            })
+        };
+        config_schema::log_diagnostics(&diagnostics);
+        let diagnostics: Vec<String> = diagnostics.iter().map(|d| d.located()).collect();
+        (loaded, diagnostics)
+    })
+    .await
+    .unwrap_or_else(|e| {
+        (
+            Err(anyhow::anyhow!("Reload task failed: {}", e)),
+            Vec::new(),
+        )
+    });
+
+    let (report, theme) = match loaded {
+        Ok((config, live)) => {
+            let previous = state.live.load();
+            let (applied, needs_restart): (Vec<String>, Vec<String>) =
+                changed_keys(previous.raw.as_ref(), live.raw.as_ref())
+                    .into_iter()
+                    .partition(|k| RELOADABLE_KEYS.contains(&k.as_str()));
+
+            let theme_changed = applied
+                .iter()
+                .any(|k| k == "theme" || k == "use_theme_background");
+            let theme = theme_changed.then(|| (config.theme.clone(), config.use_theme_background));
+
+            state.live.store(live);
+
+            let report = ReloadReport {
+                success: true,
+                trigger: trigger.as_str(),
+                applied,
+                needs_restart,
+                error: None,
+                diagnostics,
+            };
+            tracing::info!(
+                "Config reloaded ({}): {}",
+                trigger.as_str(),
+                report.summary()
+            );
+            (report, theme)
+        }
+        Err(e) => {
+            let report = ReloadReport {
+                success: false,
+                trigger: trigger.as_str(),
+                applied: Vec::new(),
+                needs_restart: Vec::new(),
+                error: Some(format!("{:#}", e)),
+                diagnostics,
+            };
+            tracing::warn!(
+                "Config reload failed ({}), keeping current config: {}",
+                trigger.as_str(),
+                report.summary()
+            );
+            (report, None)
+        }
+    };
+
+    state
+        .send_event(
+            ProxyEvent::ConfigReloaded {
+                timestamp: Utc::now(),
+                trigger: trigger.as_str().to_string(),
+                success: report.success,
+                message: report.summary(),
+                theme: theme.as_ref().map(|(name, _)| name.clone()),
+                use_theme_background: theme.map(|(_, background)| background),
+            },
+            None,
+        )
+        .await;
+
+    report
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Start the file watcher and SIGHUP handler
+pub fn spawn_watchers(state: ProxyState) {
+    if let Some(path) = Config::config_path() {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let mut last = modified(&path);
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                let current = modified(&path);
+                // A missing file (mid-save, or deleted) keeps the running config
+                if current.is_none() || current == last {
+                    continue;
+                }
+                tokio::time::sleep(SETTLE_DELAY).await;
+                last = modified(&path);
+                reload(&state, ReloadTrigger::FileChanged).await;
+            }
+        });
+    }
+
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                tracing::warn!("SIGHUP reload unavailable: {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            reload(&state, ReloadTrigger::Signal).await;
+        }
+    });
+    #[cfg(not(unix))]
+    drop(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_keys() {
+        let old: toml::Value = toml::from_str(
+            "theme = \"A\"\n[transformers]\nenabled = true\n[lifestats]\nenabled = true\n",
+        )
+        .unwrap();
+        let new: toml::Value =
+            toml::from_str("theme = \"B\"\n[transformers]\nenabled = true\n[clients.dev]\nname = \"Dev\"\nprovider = \"p\"\n")
+                .unwrap();
+
+        assert_eq!(
+            changed_keys(Some(&old), Some(&new)),
+            vec!["clients", "lifestats", "theme"]
+        );
+        assert!(changed_keys(Some(&old), Some(&old)).is_empty());
+        assert_eq!(changed_keys(None, Some(&new)).len(), 3);
+    }
+
+    #[test]
+    fn test_swappable_keeps_old_snapshot() {
+        let handle = Swappable::new(1);
+        let before = handle.load();
+        handle.store(2);
+        assert_eq!(*before, 1);
+        assert_eq!(*handle.load(), 2);
+    }
+}
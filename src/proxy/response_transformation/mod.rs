@@ -0,0 +1,577 @@
+//! Response transformation pipeline for in-flight response rewriting
+//!
+//! Augmenters can only append a block once `stop_reason` is known. Response
+//! transformers instead rewrite the response as it passes through the proxy:
+//! redacting text, rewriting or blocking `tool_use` inputs, stripping
+//! thinking blocks, or annotating tool calls.
+//!
+//! # Architecture
+//!
+//! ```text
+//! SSE chunks    → StreamRewriter ─┐
+//!                                 ├→ [Transformer₁, Transformer₂, ...] → client
+//! Buffered JSON → transform_message ┘
+//! ```
+//!
+//! Both paths share the same hooks, so a transformer behaves identically for
+//! streaming and non-streaming responses. The stream rewriter owns all the
+//! SSE bookkeeping (block index remapping, `input_json_delta` buffering,
+//! `stop_reason` fix-ups); transformers only see text and complete tool calls.
+//!
+//! # Fail-Safe Guarantee
+//!
+//! Events the rewriter does not understand are forwarded verbatim. Anything
+//! that fails to parse passes through unchanged.
+
+mod redactor;
+mod stream;
+mod thinking_stripper;
+mod tool_annotator;
+mod tool_guard;
+
+pub use redactor::{Redactor, RedactorConfig};
+pub use stream::StreamRewriter;
+pub use thinking_stripper::{ThinkingStripper, ThinkingStripperConfig};
+pub use tool_annotator::{ToolAnnotator, ToolAnnotatorConfig};
+#[cfg(test)]
+pub(crate) use tool_guard::{GuardAction, GuardRuleConfig};
+pub use tool_guard::{ToolGuard, ToolGuardConfig};
+
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+// ============================================================================
+// Transformer Inputs and Actions
+// ============================================================================
+
+/// Context provided to response transformers for decision-making
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResponseTransformContext;
+
+/// What `transform_message` changed in a buffered response
+#[derive(Debug, Default)]
+pub struct MessageEdits {
+    /// Descriptions of the modifications made; empty means unchanged
+    pub modifications: Vec<String>,
+    /// IDs of tool calls replaced by a blocked notice (never seen by the client)
+    pub blocked_tools: Vec<String>,
+}
+
+/// A complete tool call, assembled from `content_block_start` plus all of its
+/// `input_json_delta` fragments (or read directly from a buffered response)
+#[derive(Debug, Clone)]
+pub struct ToolUse<'a> {
+    /// Tool use ID (e.g., "toolu_01...")
+    pub id: &'a str,
+    /// Tool name (e.g., "Bash")
+    pub name: &'a str,
+    /// Parsed tool input
+    pub input: &'a Value,
+}
+
+/// What should happen to a tool call
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolUseAction {
+    /// Forward the tool call unchanged
+    Keep,
+    /// Forward the tool call with this input instead
+    Rewrite {
+        input: Value,
+        /// Human-readable description of the rewrite
+        reason: String,
+    },
+    /// Replace the tool call with a text block containing this message
+    Block { message: String },
+    /// Forward the tool call, preceded by a text block containing this note
+    Annotate { note: String },
+}
+
+// ============================================================================
+// Response Transformer Trait
+// ============================================================================
+
+/// Trait for response transformers
+///
+/// Every hook has a pass-through default, so a transformer only implements the
+/// parts of the response it cares about. The `rewrites_text` and
+/// `inspects_tool_use` flags let the stream rewriter skip buffering work when
+/// no registered transformer needs it.
+///
+/// # Sync Design
+///
+/// Hooks run inline on the streaming path and must be cheap. Like
+/// `RequestTransformer`, do any slow work elsewhere and pass results in.
+pub trait ResponseTransformer: Send + Sync {
+    /// Human-readable name for logging and debugging
+    fn name(&self) -> &'static str;
+
+    /// Check if this transformer should run for this response
+    fn should_apply(&self, _ctx: &ResponseTransformContext) -> bool {
+        true
+    }
+
+    /// Remove content blocks of this type entirely (e.g., "thinking")
+    fn drops_block(&self, _block_type: &str) -> bool {
+        false
+    }
+
+    /// Whether `transform_text` may return `Some`
+    fn rewrites_text(&self) -> bool {
+        false
+    }
+
+    /// Rewrite assistant text
+    ///
+    /// On the streaming path this is called per flushed text segment. The
+    /// rewriter holds back the trailing partial word of each delta, so
+    /// patterns without whitespace are never split across calls.
+    fn transform_text(&self, _text: &str) -> Option<String> {
+        None
+    }
+
+    /// Whether `transform_tool_use` may return anything other than `Keep`
+    fn inspects_tool_use(&self) -> bool {
+        false
+    }
+
+    /// Decide what happens to a complete tool call
+    fn transform_tool_use(&self, _tool: &ToolUse) -> ToolUseAction {
+        ToolUseAction::Keep
+    }
+}
+
+// ============================================================================
+// Response Transformation Pipeline
+// ============================================================================
+
+/// Pipeline that runs responses through registered transformers
+pub struct ResponseTransformationPipeline {
+    transformers: Vec<Box<dyn ResponseTransformer>>,
+}
+
+impl ResponseTransformationPipeline {
+    /// Create an empty pipeline (passthrough)
+    pub fn new() -> Self {
+        Self {
+            transformers: Vec::new(),
+        }
+    }
+
+    /// Create pipeline from config
+    ///
+    /// Respects the `enabled` master switch. Transformers are opt-in, and one
+    /// with an invalid config is skipped with a warning rather than failing
+    /// startup.
+    pub fn from_config(config: &crate::config::ResponseTransformers) -> Self {
+        let mut pipeline = Self::new();
+        if !config.enabled {
+            return pipeline;
+        }
+
+        if let Some(ref guard_config) = config.tool_guard {
+            if guard_config.enabled {
+                match ToolGuard::from_config(guard_config) {
+                    Ok(guard) => {
+                        tracing::info!(
+                            "Registered tool-guard response transformer ({} rules)",
+                            guard.rule_count()
+                        );
+                        pipeline.register(guard);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to create tool-guard: {}. Transformer disabled.", e);
+                    }
+                }
+            }
+        }
+
+        if let Some(ref redactor_config) = config.redactor {
+            if redactor_config.enabled {
+                match Redactor::from_config(redactor_config) {
+                    Ok(redactor) => {
+                        tracing::info!(
+                            "Registered redactor response transformer ({} patterns)",
+                            redactor.pattern_count()
+                        );
+                        pipeline.register(redactor);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to create redactor: {}. Transformer disabled.", e);
+                    }
+                }
+            }
+        }
+
+        if let Some(ref stripper_config) = config.thinking_stripper {
+            if stripper_config.enabled {
+                pipeline.register(ThinkingStripper::new());
+                tracing::info!("Registered thinking-stripper response transformer");
+            }
+        }
+
+        if let Some(ref annotator_config) = config.tool_annotator {
+            if annotator_config.enabled {
+                pipeline.register(ToolAnnotator::from_config(annotator_config));
+                tracing::info!("Registered tool-annotator response transformer");
+            }
+        }
+
+        pipeline
+    }
+
+    /// Register a transformer
+    ///
+    /// Transformers are called in registration order.
+    pub fn register(&mut self, transformer: impl ResponseTransformer + 'static) {
+        self.transformers.push(Box::new(transformer));
+    }
+
+    /// Check if pipeline has any transformers
+    pub fn is_empty(&self) -> bool {
+        self.transformers.is_empty()
+    }
+
+    /// Get names of registered transformers (for logging/debug)
+    pub fn transformer_names(&self) -> Vec<&'static str> {
+        self.transformers.iter().map(|t| t.name()).collect()
+    }
+
+    /// Transformers that apply to this response
+    fn active(&self, ctx: &ResponseTransformContext) -> Vec<&dyn ResponseTransformer> {
+        self.transformers
+            .iter()
+            .filter(|t| t.should_apply(ctx))
+            .map(|t| t.as_ref())
+            .collect()
+    }
+
+    /// Create a stream rewriter for one SSE response
+    ///
+    /// Returns `None` when no transformer applies, so the caller can keep
+    /// forwarding raw chunks.
+    pub fn stream_rewriter(&self, ctx: &ResponseTransformContext) -> Option<StreamRewriter<'_>> {
+        let active = self.active(ctx);
+        (!active.is_empty()).then(|| StreamRewriter::new(active))
+    }
+
+    /// Transform a buffered (non-streaming) Anthropic message in place
+    ///
+    /// Bodies without a `content` array (errors, other formats) are left alone.
+    pub fn transform_message(
+        &self,
+        body: &mut Value,
+        ctx: &ResponseTransformContext,
+    ) -> MessageEdits {
+        let active = self.active(ctx);
+        let mut log = ModificationLog::default();
+        let Some(content) = body.get_mut("content").and_then(|c| c.as_array_mut()) else {
+            return MessageEdits::default();
+        };
+        if active.is_empty() {
+            return MessageEdits::default();
+        }
+
+        let mut tools = ToolTally::default();
+        let mut output = Vec::with_capacity(content.len());
+        for block in content.drain(..) {
+            let block_type = block.get("type").and_then(|t| t.as_str()).unwrap_or("");
+            if let Some(t) = active.iter().find(|t| t.drops_block(block_type)) {
+                log.record(t.name(), format!("removed {} block", block_type));
+                continue;
+            }
+            match block_type {
+                "text" => {
+                    let mut block = block;
+                    if let Some(text) = block.get("text").and_then(|t| t.as_str()) {
+                        if let Some(new_text) = apply_text(&active, text, &mut log) {
+                            block["text"] = json!(new_text);
+                        }
+                    }
+                    output.push(block);
+                }
+                "tool_use" => {
+                    let id = block.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                    let name = block.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                    let input = block.get("input").cloned().unwrap_or_else(|| json!({}));
+                    let outcome = apply_tool_use(&active, id, name, input, &mut log);
+                    tools.count(id, &outcome);
+                    if let Some(note) = outcome.note {
+                        output.push(json!({"type": "text", "text": note}));
+                    }
+                    match outcome.input {
+                        Some(input) => {
+                            let mut block = block;
+                            block["input"] = input;
+                            output.push(block);
+                        }
+                        None => output.push(json!({"type": "text", "text": outcome.message})),
+                    }
+                }
+                _ => output.push(block),
+            }
+        }
+        *content = output;
+
+        if tools.all_blocked()
+            && body.get("stop_reason").and_then(|s| s.as_str()) == Some("tool_use")
+        {
+            body["stop_reason"] = json!("end_turn");
+            log.record("pipeline", "stop_reason tool_use → end_turn".to_string());
+        }
+
+        MessageEdits {
+            modifications: log.into_vec(),
+            blocked_tools: tools.blocked,
+        }
+    }
+}
+
+impl Default for ResponseTransformationPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// Shared Helpers (used by both streaming and buffered paths)
+// ============================================================================
+
+/// Run text through every text-rewriting transformer
+fn apply_text(
+    active: &[&dyn ResponseTransformer],
+    text: &str,
+    log: &mut ModificationLog,
+) -> Option<String> {
+    let mut current: Option<String> = None;
+    for t in active.iter().filter(|t| t.rewrites_text()) {
+        let input = current.as_deref().unwrap_or(text);
+        if let Some(rewritten) = t.transform_text(input) {
+            if rewritten != input {
+                log.record(t.name(), "rewrote text".to_string());
+                current = Some(rewritten);
+            }
+        }
+    }
+    current
+}
+
+/// Result of running a tool call through the pipeline
+#[derive(Debug)]
+struct ToolOutcome {
+    /// Final input, or `None` if the call was blocked
+    input: Option<Value>,
+    /// Text shown in place of a blocked call
+    message: String,
+    /// Text block to insert before the call (annotations)
+    note: Option<String>,
+    /// Whether the input differs from what the model produced
+    rewritten: bool,
+}
+
+/// Run a tool call through every tool-inspecting transformer
+///
+/// Rewrites compose in registration order; the first `Block` wins and stops
+/// evaluation. Annotations are collected and joined.
+fn apply_tool_use(
+    active: &[&dyn ResponseTransformer],
+    id: &str,
+    name: &str,
+    input: Value,
+    log: &mut ModificationLog,
+) -> ToolOutcome {
+    let mut input = input;
+    let mut notes: Vec<String> = Vec::new();
+    let mut rewritten = false;
+
+    for t in active.iter().filter(|t| t.inspects_tool_use()) {
+        let tool = ToolUse {
+            id,
+            name,
+            input: &input,
+        };
+        match t.transform_tool_use(&tool) {
+            ToolUseAction::Keep => {}
+            ToolUseAction::Rewrite {
+                input: new_input,
+                reason,
+            } => {
+                log.record(t.name(), format!("rewrote {} input: {}", name, reason));
+                input = new_input;
+                rewritten = true;
+            }
+            ToolUseAction::Block { message } => {
+                log.record(t.name(), format!("blocked {} call", name));
+                return ToolOutcome {
+                    input: None,
+                    message,
+                    note: None,
+                    rewritten,
+                };
+            }
+            ToolUseAction::Annotate { note } => {
+                log.record(t.name(), format!("annotated {} call", name));
+                notes.push(note);
+            }
+        }
+    }
+
+    ToolOutcome {
+        input: Some(input),
+        message: String::new(),
+        note: (!notes.is_empty()).then(|| notes.join("\n")),
+        rewritten,
+    }
+}
+
+/// Counts kept vs blocked tool calls to decide whether `stop_reason` must change
+///
+/// A response that ends in `tool_use` with no tool calls left would leave the
+/// client waiting for results it can never produce.
+#[derive(Debug, Default, Clone)]
+struct ToolTally {
+    kept: usize,
+    /// IDs of the blocked calls
+    blocked: Vec<String>,
+}
+
+impl ToolTally {
+    fn count(&mut self, id: &str, outcome: &ToolOutcome) {
+        if outcome.input.is_some() {
+            self.kept += 1;
+        } else {
+            self.blocked.push(id.to_string());
+        }
+    }
+
+    fn all_blocked(&self) -> bool {
+        !self.blocked.is_empty() && self.kept == 0
+    }
+}
+
+/// Deduplicated, counted modification descriptions
+///
+/// Streaming text is rewritten per segment, so the same modification can
+/// happen many times per response. Entries are reported once with a count.
+#[derive(Debug, Default)]
+struct ModificationLog {
+    entries: BTreeMap<String, usize>,
+}
+
+impl ModificationLog {
+    fn record(&mut self, transformer: &str, what: String) {
+        *self
+            .entries
+            .entry(format!("{}: {}", transformer, what))
+            .or_default() += 1;
+    }
+
+    fn into_vec(self) -> Vec<String> {
+        self.entries
+            .into_iter()
+            .map(|(entry, count)| {
+                if count > 1 {
+                    format!("{} (×{})", entry, count)
+                } else {
+                    entry
+                }
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard_rm_rf() -> ToolGuard {
+        ToolGuard::from_config(&ToolGuardConfig {
+            enabled: true,
+            rules: vec![GuardRuleConfig {
+                tool: "Bash".to_string(),
+                field: "command".to_string(),
+                pattern: r"rm\s+-rf".to_string(),
+                action: GuardAction::Block,
+                replacement: None,
+                message: None,
+            }],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_empty_pipeline_has_no_rewriter() {
+        let pipeline = ResponseTransformationPipeline::new();
+        assert!(pipeline
+            .stream_rewriter(&ResponseTransformContext)
+            .is_none());
+    }
+
+    #[test]
+    fn test_buffered_block_converts_tool_use_and_fixes_stop_reason() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(guard_rm_rf());
+        pipeline.register(ThinkingStripper::new());
+
+        let mut body = json!({
+            "type": "message",
+            "content": [
+                {"type": "thinking", "thinking": "hmm", "signature": "sig"},
+                {"type": "text", "text": "Cleaning up."},
+                {"type": "tool_use", "id": "toolu_1", "name": "Bash",
+                 "input": {"command": "rm -rf /tmp/build"}}
+            ],
+            "stop_reason": "tool_use"
+        });
+
+        let edits = pipeline.transform_message(&mut body, &ResponseTransformContext);
+        let mods = edits.modifications;
+        let content = body["content"].as_array().unwrap();
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[0]["text"], "Cleaning up.");
+        assert_eq!(content[1]["type"], "text");
+        assert!(content[1]["text"]
+            .as_str()
+            .unwrap()
+            .contains("rm -rf /tmp/build"));
+        assert_eq!(body["stop_reason"], "end_turn");
+        assert!(mods
+            .iter()
+            .any(|m| m.starts_with("tool-guard: blocked Bash")));
+        assert!(mods.iter().any(|m| m.starts_with("thinking-stripper")));
+        assert_eq!(edits.blocked_tools, vec!["toolu_1"]);
+    }
+
+    #[test]
+    fn test_buffered_keeps_stop_reason_when_a_tool_survives() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(guard_rm_rf());
+
+        let mut body = json!({
+            "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "Bash", "input": {"command": "rm -rf x"}},
+                {"type": "tool_use", "id": "toolu_2", "name": "Bash", "input": {"command": "ls"}}
+            ],
+            "stop_reason": "tool_use"
+        });
+
+        pipeline.transform_message(&mut body, &ResponseTransformContext);
+        assert_eq!(body["stop_reason"], "tool_use");
+        assert_eq!(body["content"][1]["id"], "toolu_2");
+    }
+
+    #[test]
+    fn test_buffered_error_body_is_untouched() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(ThinkingStripper::new());
+
+        let mut body = json!({"type": "error", "error": {"type": "overloaded_error"}});
+        let before = body.clone();
+        let edits = pipeline.transform_message(&mut body, &ResponseTransformContext);
+        assert!(edits.modifications.is_empty());
+        assert_eq!(body, before);
+    }
+}
@@ -0,0 +1,97 @@
+//! Redactor - masks sensitive text in assistant responses
+//!
+//! Applies regex patterns to text blocks (streamed deltas and buffered
+//! responses alike). Tool inputs are not touched; use the tool guard to
+//! rewrite those.
+//!
+//! # Configuration
+//!
+//! ```toml
+//! [response_transformers.redactor]
+//! enabled = true
+//! patterns = ["sk-ant-[A-Za-z0-9_-]+", "AKIA[0-9A-Z]{16}"]
+//! replacement = "[REDACTED]"
+//! ```
+
+use super::ResponseTransformer;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+fn default_replacement() -> String {
+    "[REDACTED]".to_string()
+}
+
+/// Configuration for the Redactor transformer
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RedactorConfig {
+    /// Whether the transformer is enabled
+    #[serde(default)]
+    pub enabled: bool,
+    /// Regex patterns to mask
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Replacement text (supports $1, $2 capture groups)
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+}
+
+impl Default for RedactorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            patterns: Vec::new(),
+            replacement: default_replacement(),
+        }
+    }
+}
+
+/// Transformer that masks regex matches in assistant text
+pub struct Redactor {
+    patterns: Vec<Regex>,
+    replacement: String,
+}
+
+impl Redactor {
+    /// Create from configuration (fails on an invalid pattern)
+    pub fn from_config(config: &RedactorConfig) -> anyhow::Result<Self> {
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            patterns,
+            replacement: config.replacement.clone(),
+        })
+    }
+
+    /// Number of compiled patterns
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+}
+
+impl ResponseTransformer for Redactor {
+    fn name(&self) -> &'static str {
+        "redactor"
+    }
+
+    fn rewrites_text(&self) -> bool {
+        !self.patterns.is_empty()
+    }
+
+    fn transform_text(&self, text: &str) -> Option<String> {
+        let mut current: Option<String> = None;
+        for pattern in &self.patterns {
+            let input = current.as_deref().unwrap_or(text);
+            if pattern.is_match(input) {
+                current = Some(
+                    pattern
+                        .replace_all(input, self.replacement.as_str())
+                        .into_owned(),
+                );
+            }
+        }
+        current
+    }
+}
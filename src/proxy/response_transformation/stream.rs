@@ -0,0 +1,639 @@
+//! SSE stream rewriter
+//!
+//! Sits between the upstream byte stream and the client. Chunks are split into
+//! complete SSE events; each event is either forwarded verbatim or re-emitted
+//! after the transformers have had their say.
+//!
+//! # Bookkeeping
+//!
+//! - **Block indices**: dropped blocks free their slot and inserted blocks
+//!   (annotations, blocked-tool notices) take one, so every forwarded block is
+//!   renumbered to keep the client's indices dense and ordered.
+//! - **Tool inputs**: `input_json_delta` fragments are buffered until
+//!   `content_block_stop` so transformers see the complete input. Unchanged
+//!   calls are replayed fragment-for-fragment; rewritten calls are sent as a
+//!   single delta.
+//! - **Text**: the trailing partial word of each text delta is held back until
+//!   the next delta (or block stop), so a redaction pattern without whitespace
+//!   is never split across chunk boundaries. Patterns that contain whitespace
+//!   can still straddle a flush and slip through.
+//! - **stop_reason**: when every tool call was blocked, `tool_use` becomes
+//!   `end_turn` so the client does not wait for results that will never come.
+
+use super::{apply_text, apply_tool_use, ModificationLog, ResponseTransformer, ToolTally};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Per-block state, keyed by upstream block index
+enum Block {
+    /// Forwarded as-is apart from index renumbering
+    Forward { index: u32 },
+    /// Text block whose deltas are rewritten (with held-back tail)
+    Text { index: u32, pending: String },
+    /// Removed from the stream entirely
+    Dropped,
+    /// Tool call buffered until complete
+    Tool {
+        start: RawEvent,
+        deltas: Vec<RawEvent>,
+        json: String,
+    },
+}
+
+/// A buffered event, kept with its original bytes for verbatim replay
+struct RawEvent {
+    data: Value,
+    bytes: Vec<u8>,
+    upstream: u64,
+}
+
+impl RawEvent {
+    fn new(data: Value, original: &[u8], upstream: u64) -> Self {
+        Self {
+            data,
+            bytes: original.to_vec(),
+            upstream,
+        }
+    }
+
+    fn replay(self, out: &mut Vec<u8>, index: u32) {
+        forward(out, self.data, self.upstream, index, &self.bytes);
+    }
+}
+
+/// Rewrites one SSE response through the active transformers
+///
+/// Create via `ResponseTransformationPipeline::stream_rewriter`. Feed raw
+/// upstream chunks to `push` and forward what it returns; call `finish` once
+/// the upstream stream ends.
+pub struct StreamRewriter<'a> {
+    active: Vec<&'a dyn ResponseTransformer>,
+    rewrites_text: bool,
+    inspects_tools: bool,
+    /// Bytes of an incomplete event carried over between chunks
+    buffer: Vec<u8>,
+    blocks: HashMap<u64, Block>,
+    /// Next block index the client will see
+    next_index: u32,
+    tools: ToolTally,
+    log: ModificationLog,
+}
+
+impl<'a> StreamRewriter<'a> {
+    pub(super) fn new(active: Vec<&'a dyn ResponseTransformer>) -> Self {
+        let rewrites_text = active.iter().any(|t| t.rewrites_text());
+        let inspects_tools = active.iter().any(|t| t.inspects_tool_use());
+        Self {
+            active,
+            rewrites_text,
+            inspects_tools,
+            buffer: Vec::new(),
+            blocks: HashMap::new(),
+            next_index: 0,
+            tools: ToolTally::default(),
+            log: ModificationLog::default(),
+        }
+    }
+
+    /// Feed an upstream chunk, returning the bytes to forward (may be empty)
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        self.buffer.extend_from_slice(chunk);
+        let mut out = Vec::with_capacity(chunk.len());
+
+        while let Some((end, sep_len)) = find_event_end(&self.buffer) {
+            let event: Vec<u8> = self.buffer.drain(..end + sep_len).collect();
+            match std::str::from_utf8(&event[..end]) {
+                Ok(raw) => self.process_event(raw, &event, &mut out),
+                Err(_) => out.extend_from_slice(&event),
+            }
+        }
+
+        out
+    }
+
+    /// Flush anything still buffered at end of stream
+    ///
+    /// A well-formed stream leaves nothing behind. On a truncated stream the
+    /// partial event and any buffered tool call are forwarded unchanged.
+    pub fn finish(&mut self) -> Vec<u8> {
+        let mut out = std::mem::take(&mut self.buffer);
+        let mut open: Vec<(u64, Block)> = self.blocks.drain().collect();
+        open.sort_by_key(|(idx, _)| *idx);
+        for (_, block) in open {
+            match block {
+                Block::Text { index, pending } => self.emit_text_delta(&mut out, index, &pending),
+                Block::Tool { start, deltas, .. } => {
+                    let index = self.alloc_index();
+                    start.replay(&mut out, index);
+                    for delta in deltas {
+                        delta.replay(&mut out, index);
+                    }
+                }
+                Block::Forward { .. } | Block::Dropped => {}
+            }
+        }
+        out
+    }
+
+    /// Index the next inserted block should use (for augmentation)
+    pub fn next_block_index(&self) -> u32 {
+        self.next_index
+    }
+
+    /// Descriptions of the modifications made so far
+    pub fn take_modifications(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.log).into_vec()
+    }
+
+    /// IDs of tool calls replaced by a blocked notice (never seen by the client)
+    pub fn blocked_tools(&self) -> &[String] {
+        &self.tools.blocked
+    }
+
+    fn alloc_index(&mut self) -> u32 {
+        let index = self.next_index;
+        self.next_index += 1;
+        index
+    }
+
+    /// Handle one complete event (`raw` excludes the trailing blank line)
+    fn process_event(&mut self, raw: &str, original: &[u8], out: &mut Vec<u8>) {
+        let Some(data) = parse_event_data(raw) else {
+            out.extend_from_slice(original);
+            return;
+        };
+        let event_type = data.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        let upstream_index = data.get("index").and_then(|i| i.as_u64());
+
+        match (event_type, upstream_index) {
+            ("content_block_start", Some(idx)) => self.on_block_start(idx, data, original, out),
+            ("content_block_delta", Some(idx)) => self.on_block_delta(idx, data, original, out),
+            ("content_block_stop", Some(idx)) => self.on_block_stop(idx, data, original, out),
+            ("message_delta", _) => self.on_message_delta(data, original, out),
+            _ => out.extend_from_slice(original),
+        }
+    }
+
+    fn on_block_start(&mut self, idx: u64, data: Value, original: &[u8], out: &mut Vec<u8>) {
+        let block_type = data
+            .get("content_block")
+            .and_then(|b| b.get("type"))
+            .and_then(|t| t.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        if let Some(t) = self.active.iter().find(|t| t.drops_block(&block_type)) {
+            self.log
+                .record(t.name(), format!("removed {} block", block_type));
+            self.blocks.insert(idx, Block::Dropped);
+            return;
+        }
+
+        if block_type == "tool_use" && self.inspects_tools {
+            self.blocks.insert(
+                idx,
+                Block::Tool {
+                    start: RawEvent::new(data, original, idx),
+                    deltas: Vec::new(),
+                    json: String::new(),
+                },
+            );
+            return;
+        }
+
+        let index = self.alloc_index();
+        forward(out, data, idx, index, original);
+        let block = if block_type == "text" && self.rewrites_text {
+            Block::Text {
+                index,
+                pending: String::new(),
+            }
+        } else {
+            Block::Forward { index }
+        };
+        self.blocks.insert(idx, block);
+    }
+
+    fn on_block_delta(&mut self, idx: u64, data: Value, original: &[u8], out: &mut Vec<u8>) {
+        let delta_type = data
+            .get("delta")
+            .and_then(|d| d.get("type"))
+            .and_then(|t| t.as_str())
+            .unwrap_or("");
+
+        match self.blocks.get_mut(&idx) {
+            Some(Block::Forward { index }) => forward(out, data, idx, *index, original),
+            Some(Block::Text { index, pending }) if delta_type == "text_delta" => {
+                let index = *index;
+                let text = data["delta"]["text"].as_str().unwrap_or("");
+                pending.push_str(text);
+                let split = flush_point(pending);
+                let ready: String = pending.drain(..split).collect();
+                self.emit_text_delta(out, index, &ready);
+            }
+            Some(Block::Text { index, .. }) => forward(out, data, idx, *index, original),
+            Some(Block::Dropped) => {}
+            Some(Block::Tool { deltas, json, .. }) => {
+                if let Some(partial) = data
+                    .get("delta")
+                    .and_then(|d| d.get("partial_json"))
+                    .and_then(|p| p.as_str())
+                {
+                    json.push_str(partial);
+                }
+                deltas.push(RawEvent::new(data, original, idx));
+            }
+            None => out.extend_from_slice(original),
+        }
+    }
+
+    fn on_block_stop(&mut self, idx: u64, data: Value, original: &[u8], out: &mut Vec<u8>) {
+        match self.blocks.remove(&idx) {
+            Some(Block::Forward { index }) => forward(out, data, idx, index, original),
+            Some(Block::Text { index, pending }) => {
+                self.emit_text_delta(out, index, &pending);
+                forward(out, data, idx, index, original);
+            }
+            Some(Block::Dropped) => {}
+            Some(Block::Tool {
+                start,
+                deltas,
+                json,
+            }) => {
+                let stop = RawEvent::new(data, original, idx);
+                self.finish_tool(out, start, deltas, &json, stop);
+            }
+            None => out.extend_from_slice(original),
+        }
+    }
+
+    fn on_message_delta(&mut self, mut data: Value, original: &[u8], out: &mut Vec<u8>) {
+        let is_tool_use = data
+            .get("delta")
+            .and_then(|d| d.get("stop_reason"))
+            .and_then(|s| s.as_str())
+            == Some("tool_use");
+
+        if is_tool_use && self.tools.all_blocked() {
+            data["delta"]["stop_reason"] = json!("end_turn");
+            self.log
+                .record("pipeline", "stop_reason tool_use → end_turn".to_string());
+            emit(out, &data);
+        } else {
+            out.extend_from_slice(original);
+        }
+    }
+
+    /// Run a complete tool call through the transformers and emit the result
+    fn finish_tool(
+        &mut self,
+        out: &mut Vec<u8>,
+        start: RawEvent,
+        deltas: Vec<RawEvent>,
+        json: &str,
+        stop: RawEvent,
+    ) {
+        let block = &start.data["content_block"];
+        let id = block
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let name = block
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let parsed = if json.trim().is_empty() {
+            Ok(json!({}))
+        } else {
+            serde_json::from_str::<Value>(json)
+        };
+
+        let Ok(input) = parsed else {
+            // Can't inspect what we can't parse - forward untouched
+            tracing::debug!(tool = %name, "Tool input is not valid JSON, forwarding as-is");
+            self.tools.kept += 1;
+            let index = self.alloc_index();
+            start.replay(out, index);
+            for delta in deltas {
+                delta.replay(out, index);
+            }
+            stop.replay(out, index);
+            return;
+        };
+
+        let outcome = apply_tool_use(&self.active, &id, &name, input, &mut self.log);
+        self.tools.count(&id, &outcome);
+
+        if let Some(note) = &outcome.note {
+            let index = self.alloc_index();
+            emit_text_block(out, index, note);
+        }
+
+        let index = self.alloc_index();
+        match outcome.input {
+            Some(_) if !outcome.rewritten => {
+                start.replay(out, index);
+                for delta in deltas {
+                    delta.replay(out, index);
+                }
+                stop.replay(out, index);
+            }
+            Some(input) => {
+                emit(out, &with_index(start.data, index));
+                emit(
+                    out,
+                    &json!({
+                        "type": "content_block_delta",
+                        "index": index,
+                        "delta": {"type": "input_json_delta", "partial_json": input.to_string()}
+                    }),
+                );
+                emit(out, &with_index(stop.data, index));
+            }
+            None => emit_text_block(out, index, &outcome.message),
+        }
+    }
+
+    fn emit_text_delta(&mut self, out: &mut Vec<u8>, index: u32, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let text =
+            apply_text(&self.active, text, &mut self.log).unwrap_or_else(|| text.to_string());
+        emit(
+            out,
+            &json!({
+                "type": "content_block_delta",
+                "index": index,
+                "delta": {"type": "text_delta", "text": text}
+            }),
+        );
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Find the end of the first complete event: (position, separator length)
+fn find_event_end(buf: &[u8]) -> Option<(usize, usize)> {
+    let lf = buf.windows(2).position(|w| w == b"\n\n").map(|p| (p, 2));
+    let crlf = buf
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|p| (p, 4));
+    match (lf, crlf) {
+        (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Parse the JSON payload of an SSE event (joins multi-line `data:` fields)
+fn parse_event_data(raw: &str) -> Option<Value> {
+    let data: Vec<&str> = raw
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|d| d.trim())
+        .collect();
+    if data.is_empty() {
+        return None;
+    }
+    serde_json::from_str(&data.join("\n")).ok()
+}
+
+/// Byte position up to which held-back text can be flushed
+///
+/// Everything through the last whitespace character is safe to emit; the
+/// trailing partial word waits for the next delta.
+fn flush_point(pending: &str) -> usize {
+    pending
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(0)
+}
+
+fn with_index(mut data: Value, index: u32) -> Value {
+    data["index"] = json!(index);
+    data
+}
+
+/// Forward an event, re-serializing only if its index changed
+fn forward(out: &mut Vec<u8>, data: Value, upstream: u64, index: u32, original: &[u8]) {
+    if upstream == index as u64 {
+        out.extend_from_slice(original);
+    } else {
+        emit(out, &with_index(data, index));
+    }
+}
+
+/// Write an event in Anthropic SSE framing
+fn emit(out: &mut Vec<u8>, data: &Value) {
+    let event_type = data
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("message");
+    out.extend_from_slice(format!("event: {}\ndata: {}\n\n", event_type, data).as_bytes());
+}
+
+/// Write a complete text block (start, one delta, stop)
+fn emit_text_block(out: &mut Vec<u8>, index: u32, text: &str) {
+    emit(
+        out,
+        &json!({
+            "type": "content_block_start",
+            "index": index,
+            "content_block": {"type": "text", "text": ""}
+        }),
+    );
+    emit(
+        out,
+        &json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": {"type": "text_delta", "text": text}
+        }),
+    );
+    emit(out, &json!({"type": "content_block_stop", "index": index}));
+}
+
+// ============================================================================
+// Tests (driven by recorded SSE fixtures in test_data/)
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::proxy::sse;
+
+    const TOOL_USE_STREAM: &str = include_str!("test_data/tool_use_stream.sse");
+    const THINKING_STREAM: &str = include_str!("test_data/thinking_stream.sse");
+
+    fn guard(action: GuardAction, replacement: Option<&str>) -> ToolGuard {
+        ToolGuard::from_config(&ToolGuardConfig {
+            enabled: true,
+            rules: vec![GuardRuleConfig {
+                tool: "Bash".to_string(),
+                field: "command".to_string(),
+                pattern: r"rm\s+-rf".to_string(),
+                action,
+                replacement: replacement.map(String::from),
+                message: None,
+            }],
+        })
+        .unwrap()
+    }
+
+    /// Run a fixture through the rewriter in small, awkwardly sized chunks
+    fn rewrite(pipeline: &ResponseTransformationPipeline, fixture: &str) -> (String, Vec<String>) {
+        let mut rewriter = pipeline
+            .stream_rewriter(&ResponseTransformContext)
+            .expect("pipeline should be active");
+        let mut out = Vec::new();
+        for chunk in fixture.as_bytes().chunks(37) {
+            out.extend(rewriter.push(chunk));
+        }
+        out.extend(rewriter.finish());
+        (
+            String::from_utf8(out).unwrap(),
+            rewriter.take_modifications(),
+        )
+    }
+
+    /// Block indices seen in content_block_start events, in order
+    fn start_indices(stream: &str) -> Vec<u32> {
+        stream
+            .lines()
+            .filter_map(sse::extract_content_block_index)
+            .collect()
+    }
+
+    #[test]
+    fn test_block_converts_tool_use_to_text() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(guard(GuardAction::Block, None));
+
+        let (out, mods) = rewrite(&pipeline, TOOL_USE_STREAM);
+        let assembled = sse::assemble_to_json(&out).unwrap();
+        let content = assembled["content"].as_array().unwrap();
+
+        // text, blocked Bash (now text), surviving Read tool
+        assert_eq!(content.len(), 3);
+        assert_eq!(content[1]["type"], "text");
+        assert!(content[1]["text"]
+            .as_str()
+            .unwrap()
+            .contains("rm -rf ./build"));
+        assert_eq!(content[2]["name"], "Read");
+        assert_eq!(start_indices(&out), vec![0, 1, 2]);
+        // A tool call survived, so stop_reason stays tool_use
+        assert_eq!(assembled["stop_reason"], "tool_use");
+        assert!(mods.iter().any(|m| m == "tool-guard: blocked Bash call"));
+    }
+
+    #[test]
+    fn test_rewrite_replaces_input_json() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(guard(GuardAction::Rewrite, Some("rm -ri")));
+
+        let (out, _) = rewrite(&pipeline, TOOL_USE_STREAM);
+
+        let inputs: Vec<Value> = out
+            .split("\n\n")
+            .filter_map(super::parse_event_data)
+            .filter(|d| d["delta"]["type"] == "input_json_delta")
+            .filter(|d| d["index"] == 1)
+            .map(|d| serde_json::from_str(d["delta"]["partial_json"].as_str().unwrap()).unwrap())
+            .collect();
+        assert_eq!(inputs.len(), 1, "rewritten input is sent as one delta");
+        assert_eq!(inputs[0]["command"], "rm -ri ./build");
+        assert_eq!(start_indices(&out), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_unchanged_tool_use_is_replayed_verbatim() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(ToolGuard::from_config(&ToolGuardConfig::default()).unwrap());
+        pipeline.register(ThinkingStripper::new());
+
+        let (out, mods) = rewrite(&pipeline, TOOL_USE_STREAM);
+        assert_eq!(out, TOOL_USE_STREAM);
+        assert!(mods.is_empty());
+    }
+
+    #[test]
+    fn test_strip_thinking_renumbers_blocks() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(ThinkingStripper::new());
+
+        let (out, mods) = rewrite(&pipeline, THINKING_STREAM);
+        assert!(!out.contains("thinking_delta"));
+        assert!(!out.contains("signature_delta"));
+        assert_eq!(start_indices(&out), vec![0]);
+        let assembled = sse::assemble_to_json(&out).unwrap();
+        assert_eq!(
+            assembled["content"][0]["text"],
+            "Use sk-ant-api03Xk9fQ2 for the demo."
+        );
+        assert_eq!(mods, vec!["thinking-stripper: removed thinking block"]);
+    }
+
+    #[test]
+    fn test_redaction_spans_delta_boundaries() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(
+            Redactor::from_config(&RedactorConfig {
+                enabled: true,
+                patterns: vec![r"sk-ant-[A-Za-z0-9]+".to_string()],
+                replacement: "[REDACTED]".to_string(),
+            })
+            .unwrap(),
+        );
+
+        let (out, _) = rewrite(&pipeline, THINKING_STREAM);
+        let assembled = sse::assemble_to_json(&out).unwrap();
+        // The key in the fixture is split across two text deltas
+        assert_eq!(
+            assembled["content"][1]["text"],
+            "Use [REDACTED] for the demo."
+        );
+        assert!(!out.contains("sk-ant"));
+    }
+
+    #[test]
+    fn test_annotation_inserts_text_block_before_tool() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(ToolAnnotator::from_config(&ToolAnnotatorConfig {
+            enabled: true,
+            tools: vec!["Read".to_string()],
+            template: "reading {summary}".to_string(),
+        }));
+
+        let (out, _) = rewrite(&pipeline, TOOL_USE_STREAM);
+        let assembled = sse::assemble_to_json(&out).unwrap();
+        let content = assembled["content"].as_array().unwrap();
+        assert_eq!(content.len(), 4);
+        assert_eq!(content[2]["text"], "reading src/main.rs");
+        assert_eq!(content[3]["name"], "Read");
+        assert_eq!(start_indices(&out), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_all_tools_blocked_rewrites_stop_reason() {
+        let mut pipeline = ResponseTransformationPipeline::new();
+        pipeline.register(guard(GuardAction::Block, None));
+
+        let stream = TOOL_USE_STREAM.replace(r#""name":"Read""#, r#""name":"Bash""#);
+        let stream = stream.replace(
+            r#"{\"file_path\": \"src/main.rs\"}"#,
+            r#"{\"command\": \"rm -rf /\"}"#,
+        );
+        let (out, mods) = rewrite(&pipeline, &stream);
+        let assembled = sse::assemble_to_json(&out).unwrap();
+        assert_eq!(assembled["stop_reason"], "end_turn");
+        assert!(mods.iter().any(|m| m.contains("end_turn")));
+    }
+}
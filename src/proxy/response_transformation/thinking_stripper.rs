@@ -0,0 +1,53 @@
+//! Thinking stripper - removes thinking blocks before they reach the client
+//!
+//! Useful when a client should never see (or store) extended thinking. Aspy
+//! still records the original response, so the thinking panel and lifestats
+//! keep working.
+//!
+//! Note: with extended thinking and tool use, the API expects the thinking
+//! block that preceded a tool call to be sent back on the next request.
+//! Stripping it can cause that request to be rejected, so prefer enabling this
+//! for sessions without tool use.
+//!
+//! # Configuration
+//!
+//! ```toml
+//! [response_transformers.thinking-stripper]
+//! enabled = true
+//! ```
+
+use super::ResponseTransformer;
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the ThinkingStripper transformer
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ThinkingStripperConfig {
+    /// Whether the transformer is enabled
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Transformer that drops `thinking` and `redacted_thinking` blocks
+pub struct ThinkingStripper;
+
+impl ThinkingStripper {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ThinkingStripper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResponseTransformer for ThinkingStripper {
+    fn name(&self) -> &'static str {
+        "thinking-stripper"
+    }
+
+    fn drops_block(&self, block_type: &str) -> bool {
+        matches!(block_type, "thinking" | "redacted_thinking")
+    }
+}
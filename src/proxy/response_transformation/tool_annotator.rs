@@ -0,0 +1,114 @@
+//! Tool annotator - adds a visible note before selected tool calls
+//!
+//! Inserts a short text block ahead of each matching `tool_use`, e.g. to make
+//! policy-relevant calls stand out in the client transcript. The tool call
+//! itself is forwarded unchanged.
+//!
+//! # Configuration
+//!
+//! ```toml
+//! [response_transformers.tool-annotator]
+//! enabled = true
+//! tools = ["Bash", "Write"]          # empty = all tools
+//! template = "▸ {tool}: {summary}"   # {tool}, {id}, {summary}
+//! ```
+
+use super::{ResponseTransformer, ToolUse, ToolUseAction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Input fields tried (in order) when summarizing a tool call
+const SUMMARY_FIELDS: &[&str] = &[
+    "command",
+    "file_path",
+    "path",
+    "pattern",
+    "url",
+    "query",
+    "description",
+];
+
+/// Maximum characters of input shown in `{summary}`
+const SUMMARY_MAX_CHARS: usize = 80;
+
+fn default_template() -> String {
+    "▸ {tool}: {summary}".to_string()
+}
+
+/// Configuration for the ToolAnnotator transformer
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ToolAnnotatorConfig {
+    /// Whether the transformer is enabled
+    #[serde(default)]
+    pub enabled: bool,
+    /// Tool names to annotate (empty = all tools)
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Annotation template
+    #[serde(default = "default_template")]
+    pub template: String,
+}
+
+impl Default for ToolAnnotatorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tools: Vec::new(),
+            template: default_template(),
+        }
+    }
+}
+
+/// Transformer that inserts a text note before matching tool calls
+pub struct ToolAnnotator {
+    tools: Vec<String>,
+    template: String,
+}
+
+impl ToolAnnotator {
+    pub fn from_config(config: &ToolAnnotatorConfig) -> Self {
+        Self {
+            tools: config.tools.clone(),
+            template: config.template.clone(),
+        }
+    }
+}
+
+/// One-line summary of a tool input (first well-known field, else compact JSON)
+fn summarize(input: &Value) -> String {
+    let text = SUMMARY_FIELDS
+        .iter()
+        .find_map(|f| input.get(*f).and_then(|v| v.as_str()))
+        .map(String::from)
+        .unwrap_or_else(|| input.to_string());
+    let line = text.lines().next().unwrap_or("");
+    if line.chars().count() > SUMMARY_MAX_CHARS {
+        let truncated: String = line.chars().take(SUMMARY_MAX_CHARS).collect();
+        format!("{}…", truncated)
+    } else {
+        line.to_string()
+    }
+}
+
+impl ResponseTransformer for ToolAnnotator {
+    fn name(&self) -> &'static str {
+        "tool-annotator"
+    }
+
+    fn inspects_tool_use(&self) -> bool {
+        true
+    }
+
+    fn transform_tool_use(&self, tool: &ToolUse) -> ToolUseAction {
+        if !self.tools.is_empty() && !self.tools.iter().any(|t| t == tool.name) {
+            return ToolUseAction::Keep;
+        }
+        ToolUseAction::Annotate {
+            note: self
+                .template
+                .replace("{tool}", tool.name)
+                .replace("{id}", tool.id)
+                .replace("{summary}", &summarize(tool.input)),
+        }
+    }
+}
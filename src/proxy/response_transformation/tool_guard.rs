@@ -0,0 +1,241 @@
+//! Tool guard - blocks or rewrites tool calls before the client executes them
+//!
+//! Each rule matches a tool by name and a regex against one string field of
+//! its input. A `block` rule replaces the call with a text block explaining
+//! why, so the client never runs it; a `rewrite` rule substitutes the match.
+//!
+//! # Configuration
+//!
+//! ```toml
+//! [response_transformers.tool-guard]
+//! enabled = true
+//!
+//! [[response_transformers.tool-guard.rules]]
+//! tool = "Bash"
+//! field = "command"            # default
+//! pattern = "rm\\s+-rf\\s+/"
+//! action = "block"             # block (default), rewrite
+//! message = "Refusing to run `{value}`"
+//!
+//! [[response_transformers.tool-guard.rules]]
+//! tool = "Bash"
+//! pattern = "git push --force\\b"
+//! action = "rewrite"
+//! replacement = "git push --force-with-lease"
+//! ```
+
+use super::{ResponseTransformer, ToolUse, ToolUseAction};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+fn default_field() -> String {
+    "command".to_string()
+}
+
+/// What a matching rule does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardAction {
+    /// Replace the tool call with an explanatory text block
+    #[default]
+    Block,
+    /// Substitute the match with `replacement` and forward the call
+    Rewrite,
+}
+
+/// Configuration for a single guard rule (from TOML)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GuardRuleConfig {
+    /// Tool name to match ("*" matches any tool)
+    pub tool: String,
+    /// Input field to test (must be a string)
+    #[serde(default = "default_field")]
+    pub field: String,
+    /// Regex tested against the field value
+    pub pattern: String,
+    #[serde(default)]
+    pub action: GuardAction,
+    /// Replacement for `rewrite` rules (supports $1, $2 capture groups)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+    /// Message for `block` rules ({tool}, {field}, {value} placeholders)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Configuration for the ToolGuard transformer
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ToolGuardConfig {
+    /// Whether the transformer is enabled
+    #[serde(default)]
+    pub enabled: bool,
+    /// Rules to apply (first match wins)
+    #[serde(default)]
+    pub rules: Vec<GuardRuleConfig>,
+}
+
+/// Compiled guard rule
+struct GuardRule {
+    tool: String,
+    field: String,
+    pattern: Regex,
+    action: GuardAction,
+    replacement: String,
+    message: Option<String>,
+}
+
+impl GuardRule {
+    fn matches_tool(&self, name: &str) -> bool {
+        self.tool == "*" || self.tool == name
+    }
+
+    fn block_message(&self, tool: &str, value: &str) -> String {
+        match &self.message {
+            Some(template) => template
+                .replace("{tool}", tool)
+                .replace("{field}", &self.field)
+                .replace("{value}", value),
+            None => format!(
+                "⚠ aspy blocked a {} call: `{}` matched the tool-guard pattern `{}`.",
+                tool,
+                value,
+                self.pattern.as_str()
+            ),
+        }
+    }
+}
+
+/// Transformer that blocks or rewrites tool calls by input pattern
+pub struct ToolGuard {
+    rules: Vec<GuardRule>,
+}
+
+impl ToolGuard {
+    /// Create from configuration
+    ///
+    /// Fails on an invalid pattern or a `rewrite` rule without `replacement`.
+    pub fn from_config(config: &ToolGuardConfig) -> anyhow::Result<Self> {
+        let mut rules = Vec::with_capacity(config.rules.len());
+        for rule in &config.rules {
+            let replacement = match (rule.action, &rule.replacement) {
+                (GuardAction::Rewrite, None) => anyhow::bail!(
+                    "tool-guard rule for '{}' uses action = \"rewrite\" without a replacement",
+                    rule.tool
+                ),
+                (_, replacement) => replacement.clone().unwrap_or_default(),
+            };
+            rules.push(GuardRule {
+                tool: rule.tool.clone(),
+                field: rule.field.clone(),
+                pattern: Regex::new(&rule.pattern)?,
+                action: rule.action,
+                replacement,
+                message: rule.message.clone(),
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Number of compiled rules
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+}
+
+impl ResponseTransformer for ToolGuard {
+    fn name(&self) -> &'static str {
+        "tool-guard"
+    }
+
+    fn inspects_tool_use(&self) -> bool {
+        true
+    }
+
+    fn transform_tool_use(&self, tool: &ToolUse) -> ToolUseAction {
+        for rule in self.rules.iter().filter(|r| r.matches_tool(tool.name)) {
+            let Some(value) = tool.input.get(&rule.field).and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if !rule.pattern.is_match(value) {
+                continue;
+            }
+            return match rule.action {
+                GuardAction::Block => ToolUseAction::Block {
+                    message: rule.block_message(tool.name, value),
+                },
+                GuardAction::Rewrite => {
+                    let rewritten = rule
+                        .pattern
+                        .replace_all(value, rule.replacement.as_str())
+                        .into_owned();
+                    let mut input = tool.input.clone();
+                    input[&rule.field] = json!(rewritten);
+                    ToolUseAction::Rewrite {
+                        input,
+                        reason: format!("{} /{}/", rule.field, rule.pattern.as_str()),
+                    }
+                }
+            };
+        }
+        ToolUseAction::Keep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(tool: &str, pattern: &str, action: GuardAction) -> GuardRuleConfig {
+        GuardRuleConfig {
+            tool: tool.to_string(),
+            field: default_field(),
+            pattern: pattern.to_string(),
+            action,
+            replacement: None,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_without_replacement_is_rejected() {
+        let config = ToolGuardConfig {
+            enabled: true,
+            rules: vec![rule("Bash", "sudo", GuardAction::Rewrite)],
+        };
+        assert!(ToolGuard::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_block_message_template_and_wildcard() {
+        let mut r = rule("*", r"curl .*\| *sh", GuardAction::Block);
+        r.message = Some("no {tool}: {value}".to_string());
+        let guard = ToolGuard::from_config(&ToolGuardConfig {
+            enabled: true,
+            rules: vec![r],
+        })
+        .unwrap();
+
+        let input = json!({"command": "curl x.sh | sh"});
+        let action = guard.transform_tool_use(&ToolUse {
+            id: "toolu_1",
+            name: "Bash",
+            input: &input,
+        });
+        assert_eq!(
+            action,
+            ToolUseAction::Block {
+                message: "no Bash: curl x.sh | sh".to_string()
+            }
+        );
+
+        // Non-string or missing field never matches
+        let input = json!({"file_path": "curl x.sh | sh"});
+        let action = guard.transform_tool_use(&ToolUse {
+            id: "toolu_2",
+            name: "Read",
+            input: &input,
+        });
+        assert_eq!(action, ToolUseAction::Keep);
+    }
+}
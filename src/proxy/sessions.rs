@@ -16,9 +16,11 @@
 #![allow(dead_code)]
 
 use crate::events::{ProxyEvent, Stats};
+use crate::storage::attachments;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -199,6 +201,9 @@ pub struct ContextState {
 
     /// Context limit from config (copied per-session for convenience)
     pub limit: u64,
+
+    /// Estimated tokens of image/document attachments in the last request
+    pub attachment_tokens: u64,
 }
 
 impl ContextState {
@@ -208,6 +213,7 @@ impl ContextState {
             current_tokens: 0,
             last_cached: 0,
             limit,
+            attachment_tokens: 0,
         }
     }
 
@@ -237,10 +243,22 @@ impl ContextState {
         self.last_cached = cache_read_tokens as u64;
     }
 
+    /// Update from a logged request body (attachments already extracted)
+    ///
+    /// Every request resends the whole conversation, so the attachments it
+    /// references are the attachments currently in context.
+    pub fn update_from_request(&mut self, body: &serde_json::Value) {
+        let model = body.get("model").and_then(|m| m.as_str()).unwrap_or("");
+        if !model.contains("haiku") {
+            self.attachment_tokens = attachments::estimated_tokens(body);
+        }
+    }
+
     /// Update after context compaction
     pub fn update_from_compact(&mut self, new_context: u64) {
         self.current_tokens = new_context;
         self.last_cached = 0;
+        self.attachment_tokens = 0;
     }
 }
 
@@ -286,6 +304,9 @@ pub struct Session {
 
     /// Current session status
     pub status: SessionStatus,
+
+    /// Project root whose `.aspy.toml` overlay applies (if any)
+    pub project: Option<PathBuf>,
 }
 
 impl Session {
@@ -314,6 +335,7 @@ impl Session {
             context: ContextState::with_limit(context_limit),
             events: VecDeque::with_capacity(MAX_SESSION_EVENTS),
             status: SessionStatus::Active,
+            project: None,
         }
     }
 
@@ -333,19 +355,22 @@ impl Session {
                 cache_read_tokens,
                 model,
                 ..
-            } => {
+            } if !model.contains("haiku") => {
                 // Don't update context for Haiku (summarization, not main conversation)
-                if !model.contains("haiku") {
-                    self.context.update_from_api_usage(
-                        *input_tokens,
-                        *cache_creation_tokens,
-                        *cache_read_tokens,
-                    );
-                }
+                self.context.update_from_api_usage(
+                    *input_tokens,
+                    *cache_creation_tokens,
+                    *cache_read_tokens,
+                );
             }
             ProxyEvent::ContextCompact { new_context, .. } => {
                 self.context.update_from_compact(*new_context);
             }
+            ProxyEvent::Request {
+                body: Some(body), ..
+            } => {
+                self.context.update_from_request(body);
+            }
             _ => {}
         }
 
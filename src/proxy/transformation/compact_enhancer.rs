@@ -15,12 +15,10 @@
 //!
 //! # Injection
 //!
-//! Appends `## Aspy Session Context` section to the end of the compaction prompt,
-//! providing:
-//! - Compact count (previous compressions this session)
-//! - Context token usage
-//! - Turn number
-//! - Top tool usage (future)
+//! Appends `## Aspy Continuity Enhancement` section to the end of the compaction
+//! prompt, asking the summarizer to preserve work tracks and decisions. When
+//! `[handoff]` is enabled, the session's handoff doc (goals, files, open TODOs,
+//! decisions, errors from lifestats) is appended as `<aspy-handoff>`.
 
 use super::{RequestTransformer, TransformContext, TransformResult};
 use serde::Deserialize;
@@ -114,12 +112,29 @@ impl CompactEnhancer {
         }
     }
 
+    /// Check whether a request body's last user message is a compaction prompt
+    ///
+    /// Lets the proxy prepare async context (the handoff document) before the
+    /// synchronous transformation pipeline runs.
+    pub fn is_compaction_body(&self, body: &Value) -> bool {
+        body["messages"]
+            .as_array()
+            .and_then(|messages| {
+                messages
+                    .iter()
+                    .rev()
+                    .find(|m| m["role"].as_str() == Some("user"))
+            })
+            .and_then(Self::extract_user_content)
+            .is_some_and(|content| self.detector.is_compaction_request(&content))
+    }
+
     /// Build the context injection string
     ///
     /// Generates instructions for the compacting LLM to preserve continuity.
     /// Focuses on capturing work tracks and mental flow, not stats that reset anyway.
-    fn build_injection(&self, _ctx: &TransformContext) -> String {
-        r#"
+    fn build_injection(&self, ctx: &TransformContext) -> String {
+        let mut injection = r#"
 
 ## Aspy Continuity Enhancement
 
@@ -129,7 +144,17 @@ impl CompactEnhancer {
 - **Current Mental Model:** The user's goals and approach being taken
 
 **Post-compaction recovery:** The continuing Claude has `aspy_lifestats_context_hybrid` to search the full pre-compaction conversation. Include 3-5 searchable keywords (feature names, concepts, file paths) that would help locate detailed context."#
-            .to_string()
+            .to_string();
+
+        if let Some(handoff) = ctx.handoff {
+            injection.push_str(
+                "\n\n**Session record:** Aspy's record of this session follows. \
+                 Carry its open TODOs and decisions into the summary.\n\n<aspy-handoff>\n",
+            );
+            injection.push_str(handoff.trim_end());
+            injection.push_str("\n</aspy-handoff>");
+        }
+        injection
     }
 
     /// Extract text content from a user message (handles both string and array formats)
@@ -238,18 +263,23 @@ impl RequestTransformer for CompactEnhancer {
             if let Some(user_msg) = messages.get_mut(last_user_idx) {
                 if Self::append_to_message(user_msg, &injection) {
                     // Estimate tokens for the injection
-                    let tokens_added = crate::tokens::estimate_tokens(&injection);
+                    let tokens_added = ctx.token_counter.counter().count(&injection);
 
                     tracing::info!(
                         tokens_injected = tokens_added,
                         "Compaction detected - injected Aspy continuity context"
                     );
 
+                    let mut modifications =
+                        vec!["Compaction detected, injected <aspy-continuity/>".to_string()];
+                    if ctx.handoff.is_some() {
+                        modifications.push("Injected <aspy-handoff/>".to_string());
+                    }
                     return TransformResult::modified_with_info(
                         new_body,
                         0, // We don't track what was there before (injection only)
                         tokens_added,
-                        vec!["Compaction detected, injected <aspy-continuity/>".to_string()],
+                        modifications,
                     );
                 }
             }
@@ -418,6 +448,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_transform_injects_handoff_document() {
+        let enhancer = CompactEnhancer::new();
+        let body = build_test_body_with_content(COMPACTION_PROMPT);
+        let mut ctx = TransformContext::new(None, "/v1/messages", None);
+        ctx.handoff = Some("# Session Handoff\n\n## Goals\n\n- Fix the parser");
+
+        match enhancer.transform(&body, &ctx) {
+            TransformResult::Modified { body: new_body, .. } => {
+                let content = extract_last_user_content(&new_body);
+                assert!(content.contains("<aspy-handoff>"));
+                assert!(content.contains("- Fix the parser"));
+            }
+            other => panic!("Expected Modified, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_transform_ignores_normal_request() {
         let enhancer = CompactEnhancer::new();
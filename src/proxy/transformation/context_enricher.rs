@@ -0,0 +1,559 @@
+//! ContextEnricher - Injects relevant past context from lifestats (RAG)
+//!
+//! When a session starts (or a prompt matches a configured trigger), the proxy
+//! searches the user's lifestats history for the prompt using hybrid search
+//! (FTS5 + embeddings when available) and this transformer injects the top
+//! matches as an `<aspy-context>` block at the start of the user's message.
+//!
+//! # Async Preparation
+//!
+//! Retrieval touches SQLite and possibly a remote embedding provider, so it
+//! can't run inside the synchronous pipeline. The proxy handler asks the
+//! [`ContextRetriever`] whether the request qualifies, runs the search on a
+//! blocking thread, and passes the result in via `TransformContext::semantic_context`.
+//! The transformer itself only formats and injects within the token budget.
+
+use super::{RequestTransformer, TransformContext, TransformResult};
+use crate::pipeline::embeddings::{
+    create_provider, AuthMethod, EmbeddingConfig, EmbeddingProvider, ProviderType,
+};
+use crate::pipeline::lifestats_query::{ContextMatch, LifestatsQuery, MatchType, SearchMode};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Characters kept per match before budgeting
+const MAX_MATCH_CHARS: usize = 600;
+
+/// Prompt keywords used to build the FTS query
+const MAX_QUERY_TERMS: usize = 8;
+
+/// Prompt characters sent to the embedding provider
+const MAX_EMBED_CHARS: usize = 2000;
+
+/// Common words that make poor search terms
+const STOPWORDS: &[&str] = &[
+    "about", "after", "also", "been", "before", "being", "could", "does", "doing", "from", "have",
+    "here", "into", "just", "like", "make", "more", "need", "only", "please", "should", "some",
+    "than", "that", "their", "them", "then", "there", "these", "they", "this", "want", "were",
+    "what", "when", "where", "which", "while", "will", "with", "would", "your",
+];
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+fn default_top_k() -> usize {
+    5
+}
+
+fn default_max_tokens() -> u32 {
+    1500
+}
+
+fn default_first_turn() -> bool {
+    true
+}
+
+fn default_min_prompt_chars() -> usize {
+    20
+}
+
+/// Configuration for the ContextEnricher transformer
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContextEnricherConfig {
+    /// Whether the context enricher is enabled
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum number of matches to retrieve
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+    /// Token budget for the injected `<aspy-context>` block
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// Retrieve on the first user prompt of a session
+    #[serde(default = "default_first_turn")]
+    pub first_turn: bool,
+    /// Also retrieve every N user prompts (0 = never)
+    #[serde(default)]
+    pub every_turns: u64,
+    /// Regexes; a prompt matching any of them triggers retrieval
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Prompts shorter than this are never enriched
+    #[serde(default = "default_min_prompt_chars")]
+    pub min_prompt_chars: usize,
+}
+
+impl Default for ContextEnricherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            top_k: default_top_k(),
+            max_tokens: default_max_tokens(),
+            first_turn: default_first_turn(),
+            every_turns: 0,
+            patterns: Vec::new(),
+            min_prompt_chars: default_min_prompt_chars(),
+        }
+    }
+}
+
+// ============================================================================
+// Semantic Context
+// ============================================================================
+
+/// Retrieved context for one request, passed to the transformer via `TransformContext`
+#[derive(Debug, Clone)]
+pub struct SemanticContext {
+    /// Search type used: "hybrid" or "fts_only"
+    pub search_type: &'static str,
+    /// Matches in rank order (best first)
+    pub matches: Vec<ContextMatch>,
+}
+
+// ============================================================================
+// Context Retriever (async prep)
+// ============================================================================
+
+/// Decides when to retrieve and runs the lifestats search
+///
+/// Owned by `ProxyState`; `retrieve` is blocking and must run via `spawn_blocking`.
+pub struct ContextRetriever {
+    query: Arc<LifestatsQuery>,
+    provider: Option<Box<dyn EmbeddingProvider>>,
+    top_k: usize,
+    first_turn: bool,
+    every_turns: u64,
+    patterns: Vec<Regex>,
+    min_prompt_chars: usize,
+}
+
+impl ContextRetriever {
+    /// Create a retriever; an embedding provider is created when embeddings are enabled
+    pub fn new(
+        query: Arc<LifestatsQuery>,
+        config: &ContextEnricherConfig,
+        embeddings: &crate::config::EmbeddingsConfig,
+    ) -> anyhow::Result<Self> {
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", p, e)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let provider = if embeddings.is_enabled() {
+            let provider_type = match embeddings.provider.as_str() {
+                "local" => ProviderType::Local,
+                "remote" => ProviderType::Remote,
+                _ => ProviderType::None,
+            };
+            let auth_method = match embeddings.auth_method.as_str() {
+                "api-key" => AuthMethod::ApiKey,
+                _ => AuthMethod::Bearer,
+            };
+            let embed_config = EmbeddingConfig {
+                provider: provider_type,
+                model: embeddings.model.clone(),
+                api_key: embeddings.api_key.clone(),
+                api_base: embeddings.api_base.clone(),
+                api_version: embeddings.api_version.clone(),
+                auth_method,
+                dimensions: None,
+                batch_size: 1,   // Only need one embedding
+                timeout_secs: 5, // Sits on the request path
+            };
+            Some(create_provider(&embed_config)).filter(|p| p.is_ready())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            query,
+            provider,
+            top_k: config.top_k.max(1),
+            first_turn: config.first_turn,
+            every_turns: config.every_turns,
+            patterns,
+            min_prompt_chars: config.min_prompt_chars,
+        })
+    }
+
+    /// Check whether a request should be enriched
+    ///
+    /// Only fresh user prompts qualify (never tool-result continuations).
+    pub fn wants_context(&self, ctx: &TransformContext, prompt: &str) -> bool {
+        if ctx.tool_result_count.unwrap_or(0) > 0 || prompt.len() < self.min_prompt_chars {
+            return false;
+        }
+        let turn = ctx.turn_number.unwrap_or(0);
+        (self.first_turn && turn == 1)
+            || (self.every_turns > 0 && turn > 1 && turn.is_multiple_of(self.every_turns))
+            || self.patterns.iter().any(|re| re.is_match(prompt))
+    }
+
+    /// Search the user's history for the prompt (blocking)
+    ///
+    /// Matches from `current_session` are dropped — they are already in the conversation.
+    pub fn retrieve(
+        &self,
+        user_id: &str,
+        prompt: &str,
+        current_session: Option<&str>,
+    ) -> anyhow::Result<Option<SemanticContext>> {
+        let fts_query = match build_fts_query(prompt) {
+            Some(q) => q,
+            None => return Ok(None),
+        };
+
+        let embed_text: String = prompt.chars().take(MAX_EMBED_CHARS).collect();
+        let embedding = self
+            .provider
+            .as_ref()
+            .and_then(|p| match p.embed(&embed_text) {
+                Ok(result) => Some(result.embedding),
+                Err(e) => {
+                    tracing::debug!("Context enricher: query embedding failed: {}", e);
+                    None
+                }
+            });
+
+        // Over-fetch so filtering out the current session still leaves top_k
+        let limit = self.top_k * 2;
+        let (search_type, results) = match embedding {
+            Some(ref e) => (
+                "hybrid",
+                self.query.recover_context_hybrid_user(
+                    user_id,
+                    &fts_query,
+                    Some(e),
+                    limit,
+                    SearchMode::Natural,
+                )?,
+            ),
+            None => (
+                "fts_only",
+                self.query
+                    .recover_user_context(user_id, &fts_query, limit, SearchMode::Natural)?,
+            ),
+        };
+
+        let matches: Vec<ContextMatch> = results
+            .into_iter()
+            .filter(|m| current_session.is_none() || m.session_id.as_deref() != current_session)
+            .filter(|m| !m.content.trim().is_empty() && m.content.trim() != prompt.trim())
+            .take(self.top_k)
+            .collect();
+
+        if matches.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(SemanticContext {
+                search_type,
+                matches,
+            }))
+        }
+    }
+}
+
+/// Build an FTS5 query from a prompt: distinctive words joined with OR
+///
+/// Natural-mode FTS treats spaces as AND, which is far too strict for a
+/// whole prompt, so we pick a handful of longer non-stopword terms instead.
+fn build_fts_query(prompt: &str) -> Option<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in prompt.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        let word = word.to_lowercase();
+        if word.chars().count() < 4
+            || word.chars().all(|c| c.is_ascii_digit())
+            || STOPWORDS.contains(&word.as_str())
+            || terms.contains(&word)
+        {
+            continue;
+        }
+        terms.push(word);
+        if terms.len() == MAX_QUERY_TERMS {
+            break;
+        }
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" OR "))
+    }
+}
+
+// ============================================================================
+// Context Enricher Transformer
+// ============================================================================
+
+/// Request transformer that injects retrieved context as `<aspy-context>`
+pub struct ContextEnricher {
+    max_tokens: u32,
+}
+
+impl ContextEnricher {
+    /// Create a new ContextEnricher from config
+    pub fn new(config: &ContextEnricherConfig) -> Self {
+        Self {
+            max_tokens: config.max_tokens,
+        }
+    }
+
+    /// Text of the last user message (string or text blocks), for retrieval
+    pub fn prompt_text(body: &Value) -> Option<String> {
+        let last_user = body["messages"]
+            .as_array()?
+            .iter()
+            .rev()
+            .find(|m| m["role"].as_str() == Some("user"))?;
+
+        match &last_user["content"] {
+            Value::String(s) => Some(s.clone()),
+            Value::Array(arr) => {
+                // Skip system reminders injected by the client
+                let texts: Vec<&str> = arr
+                    .iter()
+                    .filter(|b| b["type"].as_str() == Some("text"))
+                    .filter_map(|b| b["text"].as_str())
+                    .filter(|t| !t.trim_start().starts_with("<system-reminder>"))
+                    .collect();
+                if texts.is_empty() {
+                    None
+                } else {
+                    Some(texts.join("\n"))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Render matches into an `<aspy-context>` block within the token budget
+    ///
+    /// Returns the block and the number of matches that fit.
+    fn build_block(&self, context: &SemanticContext, ctx: &TransformContext) -> (String, usize) {
+        let counter = ctx.token_counter.counter();
+        let header = "<aspy-context>\nRelevant excerpts from this user's past sessions \
+                      (retrieved by Aspy). Use them if they help; they may be outdated.\n";
+        let footer = "</aspy-context>\n";
+
+        let mut block = header.to_string();
+        let mut used = counter.count(header) + counter.count(footer);
+        let mut included = 0;
+
+        for m in &context.matches {
+            let label = match m.match_type {
+                MatchType::Thinking => "thinking",
+                MatchType::UserPrompt => "prompt",
+                MatchType::AssistantResponse => "response",
+            };
+            let date = m.timestamp.get(..10).unwrap_or(&m.timestamp);
+            let content = m.content.trim();
+            let content = if content.chars().count() > MAX_MATCH_CHARS {
+                let cut: String = content.chars().take(MAX_MATCH_CHARS).collect();
+                format!("{}…", cut.trim_end())
+            } else {
+                content.to_string()
+            };
+
+            let entry = format!("\n[{} · {}]\n{}\n", label, date, content);
+            let tokens = counter.count(&entry);
+            if used + tokens > self.max_tokens {
+                break;
+            }
+            used += tokens;
+            block.push_str(&entry);
+            included += 1;
+        }
+
+        block.push_str(footer);
+        (block, included)
+    }
+}
+
+impl RequestTransformer for ContextEnricher {
+    fn name(&self) -> &'static str {
+        "context-enricher"
+    }
+
+    fn should_apply(&self, ctx: &TransformContext) -> bool {
+        ctx.semantic_context.is_some() && ctx.path.ends_with("/messages")
+    }
+
+    fn transform(&self, body: &Value, ctx: &TransformContext) -> TransformResult {
+        let context = match ctx.semantic_context {
+            Some(c) if !c.matches.is_empty() => c,
+            _ => return TransformResult::Unchanged,
+        };
+
+        let (block, included) = self.build_block(context, ctx);
+        if included == 0 {
+            return TransformResult::Unchanged;
+        }
+
+        let mut new_body = body.clone();
+        let last_user = new_body["messages"].as_array_mut().and_then(|messages| {
+            messages
+                .iter_mut()
+                .rev()
+                .find(|m| m["role"].as_str() == Some("user"))
+        });
+        let last_user = match last_user {
+            Some(m) => m,
+            None => return TransformResult::Unchanged,
+        };
+
+        // Prepend as its own text block so the user's text stays intact
+        let context_block = serde_json::json!({"type": "text", "text": block});
+        match &mut last_user["content"] {
+            Value::String(s) => {
+                let text = std::mem::take(s);
+                last_user["content"] =
+                    serde_json::json!([context_block, {"type": "text", "text": text}]);
+            }
+            Value::Array(arr) => arr.insert(0, context_block),
+            _ => return TransformResult::Unchanged,
+        }
+
+        let tokens_added = ctx.token_counter.counter().count(&block);
+        TransformResult::modified_with_info(
+            new_body,
+            0,
+            tokens_added,
+            vec![format!(
+                "Injected <aspy-context/> ({} of {} matches, {})",
+                included,
+                context.matches.len(),
+                context.search_type
+            )],
+        )
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_match(match_type: MatchType, content: &str) -> ContextMatch {
+        ContextMatch {
+            match_type,
+            session_id: Some("dev-1/old-session".to_string()),
+            timestamp: "2025-11-02T10:00:00Z".to_string(),
+            content: content.to_string(),
+            rank: 1.0,
+        }
+    }
+
+    fn test_context(matches: Vec<ContextMatch>) -> SemanticContext {
+        SemanticContext {
+            search_type: "fts_only",
+            matches,
+        }
+    }
+
+    #[test]
+    fn test_build_fts_query_picks_distinctive_terms() {
+        let query =
+            build_fts_query("Can you fix the websocket reconnect logic? The websocket drops.")
+                .expect("should build a query");
+        assert_eq!(query, "websocket OR reconnect OR logic OR drops");
+        assert!(build_fts_query("do it now").is_none());
+    }
+
+    #[test]
+    fn test_transform_prepends_context_block() {
+        let enricher = ContextEnricher::new(&ContextEnricherConfig::default());
+        let context = test_context(vec![
+            test_match(
+                MatchType::UserPrompt,
+                "Switch the reconnect to exponential backoff",
+            ),
+            test_match(MatchType::Thinking, "Backoff should cap at 30 seconds"),
+        ]);
+        let mut ctx = TransformContext::new(None, "/v1/messages", None);
+        ctx.semantic_context = Some(&context);
+        let body = serde_json::json!({
+            "messages": [{"role": "user", "content": "Fix the websocket reconnect logic"}]
+        });
+
+        match enricher.transform(&body, &ctx) {
+            TransformResult::Modified {
+                body: new_body,
+                modifications,
+                ..
+            } => {
+                let content = new_body["messages"][0]["content"].as_array().unwrap();
+                assert_eq!(content.len(), 2);
+                let injected = content[0]["text"].as_str().unwrap();
+                assert!(injected.starts_with("<aspy-context>"));
+                assert!(injected.contains("[prompt · 2025-11-02]"));
+                assert!(injected.contains("cap at 30 seconds"));
+                assert_eq!(content[1]["text"], "Fix the websocket reconnect logic");
+                assert!(modifications[0].contains("2 of 2 matches"));
+            }
+            other => panic!("Expected Modified, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_transform_respects_token_budget() {
+        let config = ContextEnricherConfig {
+            max_tokens: 120,
+            ..Default::default()
+        };
+        let enricher = ContextEnricher::new(&config);
+        let long = "backoff ".repeat(200);
+        let context = test_context(vec![
+            test_match(MatchType::UserPrompt, "Use exponential backoff"),
+            test_match(MatchType::AssistantResponse, &long),
+        ]);
+        let mut ctx = TransformContext::new(None, "/v1/messages", None);
+        ctx.semantic_context = Some(&context);
+        let body = serde_json::json!({
+            "messages": [{"role": "user", "content": [{"type": "text", "text": "Retry logic?"}]}]
+        });
+
+        match enricher.transform(&body, &ctx) {
+            TransformResult::Modified { modifications, .. } => {
+                assert!(modifications[0].contains("1 of 2 matches"));
+            }
+            other => panic!("Expected Modified, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_transform_skips_without_context() {
+        let enricher = ContextEnricher::new(&ContextEnricherConfig::default());
+        let ctx = TransformContext::new(None, "/v1/messages", None);
+        assert!(!enricher.should_apply(&ctx));
+
+        let empty = test_context(Vec::new());
+        let mut ctx = TransformContext::new(None, "/v1/messages", None);
+        ctx.semantic_context = Some(&empty);
+        let body = serde_json::json!({"messages": [{"role": "user", "content": "hello"}]});
+        assert!(matches!(
+            enricher.transform(&body, &ctx),
+            TransformResult::Unchanged
+        ));
+    }
+
+    #[test]
+    fn test_prompt_text_skips_system_reminders() {
+        let body = serde_json::json!({
+            "messages": [{"role": "user", "content": [
+                {"type": "text", "text": "<system-reminder>Todo list is empty</system-reminder>"},
+                {"type": "text", "text": "Where did we leave the parser?"}
+            ]}]
+        });
+        assert_eq!(
+            ContextEnricher::prompt_text(&body).as_deref(),
+            Some("Where did we leave the parser?")
+        );
+    }
+}
@@ -24,10 +24,14 @@
 //! Worst case: the original unmodified request goes through.
 
 mod compact_enhancer;
+mod context_enricher;
 mod tag_editor;
 
 // Re-exports for config parsing and transformer implementations
 pub use compact_enhancer::{CompactEnhancer, CompactEnhancerConfig};
+pub use context_enricher::{
+    ContextEnricher, ContextEnricherConfig, ContextRetriever, SemanticContext,
+};
 #[allow(unused_imports)]
 pub use tag_editor::{
     InjectPosition, PositionConfig, RuleConfig, TagEditor, TagEditorConfig, TagRule, WhenCondition,
@@ -147,12 +151,12 @@ impl TransformResult {
 /// Context provided to transformers for decision-making
 ///
 /// Contains information available at request handling time.
-/// Future fields (like `semantic_context`) will be populated by
-/// async prep work in the proxy handler before the sync pipeline runs.
+/// Some fields (`handoff`, `semantic_context`) are populated by async prep
+/// work in the proxy handler before the sync pipeline runs.
 ///
 /// Fields are read by transformer implementations via pattern matching or direct access.
 /// Even if not currently used by TagEditor, they are part of the public API
-/// for future transformers (ContextEnricher, ModelRouter, etc.).
+/// for future transformers (ModelRouter, etc.).
 #[derive(Debug, Clone, Default)]
 pub struct TransformContext<'a> {
     /// Client ID from routing (e.g., "dev-1")
@@ -184,8 +188,19 @@ pub struct TransformContext<'a> {
     /// Number of tool_result blocks in current user message
     /// Used by: has_tool_results condition
     pub tool_result_count: Option<usize>,
-    // Future: semantic_context for RAG injection
-    // pub semantic_context: Option<&'a SemanticContext>,
+
+    /// Counter used for `TransformTokens` estimates (from `[tokens] counter`)
+    pub token_counter: crate::tokens::CounterKind,
+
+    /// Handoff doc (Markdown) for the current session, prepared asynchronously
+    /// by the proxy handler when the request is a compaction prompt
+    /// Used by: CompactEnhancer
+    pub handoff: Option<&'a str>,
+
+    /// Past context retrieved from lifestats for this prompt, prepared
+    /// asynchronously by the proxy handler
+    /// Used by: ContextEnricher
+    pub semantic_context: Option<&'a SemanticContext>,
 }
 
 impl<'a> TransformContext<'a> {
@@ -199,6 +214,9 @@ impl<'a> TransformContext<'a> {
             context_limit: None,
             turn_number: None,
             tool_result_count: None,
+            token_counter: crate::tokens::CounterKind::default(),
+            handoff: None,
+            semantic_context: None,
         }
     }
 
@@ -305,6 +323,18 @@ impl TransformationPipeline {
             }
         }
 
+        // Context enricher (opt-in; retrieval happens in the proxy handler)
+        if let Some(ref enricher_config) = config.context_enricher {
+            if enricher_config.enabled {
+                pipeline.register(ContextEnricher::new(enricher_config));
+                tracing::info!(
+                    "Registered context-enricher transformer (top_k={}, budget={} tokens)",
+                    enricher_config.top_k,
+                    enricher_config.max_tokens
+                );
+            }
+        }
+
         // Compact enhancer (opt-in)
         if let Some(ref compact_config) = config.compact_enhancer {
             if compact_config.enabled {
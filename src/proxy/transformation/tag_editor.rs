@@ -967,8 +967,9 @@ impl RequestTransformer for TagEditor {
         );
 
         // Estimate token difference
-        let tokens_before = crate::tokens::estimate_json_tokens(body);
-        let tokens_after = crate::tokens::estimate_json_tokens(&new_body);
+        let counter = ctx.token_counter.counter();
+        let tokens_before = counter.count_json(body);
+        let tokens_after = counter.count_json(&new_body);
 
         // Deduplicate modifications (same message may appear multiple times)
         let mut unique_modifications: Vec<String> = Vec::new();
@@ -0,0 +1,548 @@
+//! Content-addressed attachment store
+//!
+//! Requests carrying `image` or `document` blocks embed the file as base64,
+//! which bloats every log line and lifestats row by megabytes - and Claude
+//! Code resends the same screenshot on every turn. The store decodes each
+//! block once, writes it to `<dir>/<sha[..2]>/<sha>.<ext>` and swaps the
+//! block's `source` for a small reference:
+//!
+//! ```json
+//! {"type": "image", "source": {"type": "aspy_attachment", "sha256": "…",
+//!   "media_type": "image/png", "size_bytes": 48213, "width": 1280,
+//!   "height": 800, "estimated_tokens": 1365, "path": "data/attachments/…"}}
+//! ```
+//!
+//! Only the logged copy of a request is rewritten; the body forwarded
+//! upstream is untouched. References carry their token estimate so the
+//! context breakdown can count attachments without touching the files.
+//!
+//! Every turn resends the whole history, so recent references are cached by a
+//! fast hash of their base64 text: a payload seen before is swapped without
+//! being decoded or SHA-256 hashed again.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// `source.type` marking a block whose payload lives in the store
+pub const REFERENCE_SOURCE_TYPE: &str = "aspy_attachment";
+
+/// Longest image edge the API keeps before downscaling
+const MAX_IMAGE_EDGE: f64 = 1568.0;
+
+/// Pixel budget the API downscales to (~1600 tokens)
+const MAX_IMAGE_PIXELS: f64 = 1_200_000.0;
+
+/// Pixels per token for images (Anthropic vision docs: tokens ≈ w*h/750)
+const PIXELS_PER_TOKEN: f64 = 750.0;
+
+/// Fallback when an image's dimensions can't be read (a full-size image)
+const UNKNOWN_IMAGE_TOKENS: u64 = 1_600;
+
+/// PDF pages cost their text plus a rendered page image
+const TOKENS_PER_DOCUMENT_PAGE: u64 = 2_000;
+
+/// Recently extracted payloads remembered to skip re-decoding
+const RECENT_CAPACITY: usize = 256;
+
+/// Kind of attachment block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentKind {
+    Image,
+    Document,
+}
+
+impl AttachmentKind {
+    fn from_block_type(block_type: &str) -> Option<Self> {
+        match block_type {
+            "image" => Some(Self::Image),
+            "document" => Some(Self::Document),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Image => "image",
+            Self::Document => "document",
+        }
+    }
+}
+
+/// Reference left in place of an extracted attachment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentRef {
+    /// Hex SHA-256 of the decoded bytes (the store key)
+    pub sha256: String,
+    pub media_type: String,
+    pub size_bytes: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Page count (PDF documents only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<u32>,
+    /// Estimated input tokens the attachment costs each time it is sent
+    pub estimated_tokens: u64,
+    /// Location of the stored file
+    pub path: PathBuf,
+}
+
+impl AttachmentRef {
+    /// The `source` object written into the block
+    fn to_source(&self) -> Value {
+        let mut source = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = source.as_object_mut() {
+            obj.insert("type".into(), Value::from(REFERENCE_SOURCE_TYPE));
+        }
+        source
+    }
+
+    /// Human-readable one-liner: `image/png 1280×800, 47.1 KB, ~1365 tokens`
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.media_type.clone()];
+        if let (Some(w), Some(h)) = (self.width, self.height) {
+            parts[0] = format!("{} {}×{}", self.media_type, w, h);
+        }
+        if let Some(pages) = self.pages {
+            parts.push(format!("{} pages", pages));
+        }
+        parts.push(format_size(self.size_bytes));
+        parts.push(format!("~{} tokens", self.estimated_tokens));
+        parts.join(", ")
+    }
+}
+
+/// Cache key for a base64 payload (much cheaper than decoding and SHA-256)
+fn payload_key(kind: AttachmentKind, media_type: &str, data: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    kind.hash(&mut hasher);
+    media_type.hash(&mut hasher);
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A reference found in a rewritten body, with the kind of block it replaced
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundAttachment {
+    pub kind: AttachmentKind,
+    pub reference: AttachmentRef,
+}
+
+/// Content-addressed store on disk
+pub struct AttachmentStore {
+    dir: PathBuf,
+    /// References for recently seen payloads, keyed by `payload_key`
+    recent: Mutex<RecentRefs>,
+}
+
+/// Bounded payload-key → reference cache, oldest evicted first
+#[derive(Default)]
+struct RecentRefs {
+    refs: HashMap<u64, AttachmentRef>,
+    order: VecDeque<u64>,
+}
+
+impl RecentRefs {
+    fn insert(&mut self, key: u64, reference: AttachmentRef) {
+        if self.refs.insert(key, reference).is_some() {
+            return;
+        }
+        self.order.push_back(key);
+        if self.order.len() > RECENT_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.refs.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl AttachmentStore {
+    /// Open (and create) the store directory
+    pub fn new(dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&dir).context("Failed to create attachment directory")?;
+        Ok(Self {
+            dir,
+            recent: Mutex::new(RecentRefs::default()),
+        })
+    }
+
+    /// Store decoded bytes, returning the reference (dedup by SHA-256)
+    pub fn store(
+        &self,
+        kind: AttachmentKind,
+        media_type: &str,
+        bytes: &[u8],
+    ) -> Result<AttachmentRef> {
+        let sha256 = format!("{:x}", Sha256::digest(bytes));
+        let path =
+            self.dir
+                .join(&sha256[..2])
+                .join(format!("{}.{}", sha256, extension_for(media_type)));
+
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).context("Failed to create attachment shard")?;
+            }
+            // Write-then-rename so a concurrent reader never sees a partial file
+            let tmp = path.with_extension("partial");
+            fs::write(&tmp, bytes).context("Failed to write attachment")?;
+            fs::rename(&tmp, &path).context("Failed to finalize attachment")?;
+        }
+
+        let (width, height, pages, estimated_tokens) = match kind {
+            AttachmentKind::Image => {
+                let dims = image_dimensions(bytes);
+                let tokens = dims
+                    .map(|(w, h)| image_tokens(w, h))
+                    .unwrap_or(UNKNOWN_IMAGE_TOKENS);
+                (dims.map(|d| d.0), dims.map(|d| d.1), None, tokens)
+            }
+            AttachmentKind::Document => {
+                let pages = (media_type == "application/pdf").then(|| pdf_pages(bytes));
+                let tokens = pages.unwrap_or(1).max(1) as u64 * TOKENS_PER_DOCUMENT_PAGE;
+                (None, None, pages, tokens)
+            }
+        };
+
+        Ok(AttachmentRef {
+            sha256,
+            media_type: media_type.to_string(),
+            size_bytes: bytes.len() as u64,
+            width,
+            height,
+            pages,
+            estimated_tokens,
+            path,
+        })
+    }
+
+    /// Extract every base64 image/document block in `value` into the store
+    ///
+    /// Walks the whole tree, so blocks nested in `tool_result` content are
+    /// found too. Blocks that fail to decode or write are left as they were.
+    pub fn extract(&self, value: &mut Value) -> Vec<AttachmentRef> {
+        let mut refs = Vec::new();
+        self.extract_into(value, &mut refs);
+        refs
+    }
+
+    fn extract_into(&self, value: &mut Value, refs: &mut Vec<AttachmentRef>) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.extract_into(item, refs);
+                }
+            }
+            Value::Object(obj) => {
+                let kind = obj
+                    .get("type")
+                    .and_then(|t| t.as_str())
+                    .and_then(AttachmentKind::from_block_type);
+                if let Some(kind) = kind {
+                    if let Some(reference) = self.extract_block(kind, obj.get("source")) {
+                        obj.insert("source".into(), reference.to_source());
+                        refs.push(reference);
+                        return;
+                    }
+                }
+                for child in obj.values_mut() {
+                    self.extract_into(child, refs);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn extract_block(&self, kind: AttachmentKind, source: Option<&Value>) -> Option<AttachmentRef> {
+        let source = source?;
+        if source.get("type").and_then(|t| t.as_str()) != Some("base64") {
+            return None;
+        }
+        let media_type = source.get("media_type").and_then(|m| m.as_str())?;
+        let data = source.get("data").and_then(|d| d.as_str())?;
+
+        let key = payload_key(kind, media_type, data);
+        if let Some(reference) = self
+            .recent
+            .lock()
+            .ok()
+            .and_then(|recent| recent.refs.get(&key).cloned())
+        {
+            return Some(reference);
+        }
+
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .ok()?;
+        match self.store(kind, media_type, &bytes) {
+            Ok(reference) => {
+                if let Ok(mut recent) = self.recent.lock() {
+                    recent.insert(key, reference.clone());
+                }
+                Some(reference)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to store {} attachment: {}", kind.as_str(), e);
+                None
+            }
+        }
+    }
+}
+
+/// Collect the attachment references in an already-rewritten body
+pub fn references(value: &Value) -> Vec<FoundAttachment> {
+    let mut found = Vec::new();
+    collect_references(value, &mut found);
+    found
+}
+
+fn collect_references(value: &Value, found: &mut Vec<FoundAttachment>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| collect_references(v, found)),
+        Value::Object(obj) => {
+            let kind = obj
+                .get("type")
+                .and_then(|t| t.as_str())
+                .and_then(AttachmentKind::from_block_type);
+            let source = obj
+                .get("source")
+                .filter(|s| s.get("type").and_then(|t| t.as_str()) == Some(REFERENCE_SOURCE_TYPE));
+            if let (Some(kind), Some(source)) = (kind, source) {
+                if let Ok(reference) = serde_json::from_value(source.clone()) {
+                    found.push(FoundAttachment { kind, reference });
+                }
+                return;
+            }
+            obj.values().for_each(|v| collect_references(v, found));
+        }
+        _ => {}
+    }
+}
+
+/// Estimated tokens of all attachments referenced by a rewritten request body
+pub fn estimated_tokens(body: &Value) -> u64 {
+    references(body)
+        .iter()
+        .map(|a| a.reference.estimated_tokens)
+        .sum()
+}
+
+/// Open a stored attachment with the platform's default viewer
+pub fn open(path: &Path) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut cmd = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut cmd = std::process::Command::new("xdg-open");
+
+    cmd.arg(path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .context("Failed to launch viewer")?;
+    Ok(())
+}
+
+fn extension_for(media_type: &str) -> &'static str {
+    match media_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "application/pdf" => "pdf",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+/// Human-readable byte count (`512 B`, `1.5 KB`, `2.0 MB`)
+pub(crate) fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{} B", b),
+    }
+}
+
+/// Token cost of an image after the API's downscaling
+fn image_tokens(width: u32, height: u32) -> u64 {
+    let (mut w, mut h) = (width as f64, height as f64);
+    let edge_scale = (MAX_IMAGE_EDGE / w.max(h)).min(1.0);
+    w *= edge_scale;
+    h *= edge_scale;
+    let pixel_scale = (MAX_IMAGE_PIXELS / (w * h)).sqrt().min(1.0);
+    ((w * pixel_scale) * (h * pixel_scale) / PIXELS_PER_TOKEN).ceil() as u64
+}
+
+/// Read width and height from PNG, GIF, JPEG or WebP headers
+fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be32 = |at: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+    };
+    let le16 = |at: usize| -> Option<u32> {
+        Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32)
+    };
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be32(16)?, be32(20)?));
+    }
+    if bytes.starts_with(b"GIF8") {
+        return Some((le16(6)?, le16(8)?));
+    }
+    if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        let le24 = |at: usize| -> Option<u32> {
+            let b = bytes.get(at..at + 3)?;
+            Some(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16)
+        };
+        return match bytes.get(12..16)? {
+            b"VP8X" => Some((le24(24)? + 1, le24(27)? + 1)),
+            b"VP8 " => Some((le16(26)? & 0x3fff, le16(28)? & 0x3fff)),
+            b"VP8L" => {
+                let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+                Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+            }
+            _ => None,
+        };
+    }
+    if bytes.starts_with(&[0xff, 0xd8]) {
+        // Walk JPEG segments to the first start-of-frame marker
+        let mut at = 2;
+        while at + 9 < bytes.len() {
+            if bytes[at] != 0xff {
+                return None;
+            }
+            let marker = bytes[at + 1];
+            let len = u16::from_be_bytes([bytes[at + 2], bytes[at + 3]]) as usize;
+            let is_sof = matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc);
+            if is_sof {
+                let h = u16::from_be_bytes([bytes[at + 5], bytes[at + 6]]) as u32;
+                let w = u16::from_be_bytes([bytes[at + 7], bytes[at + 8]]) as u32;
+                return Some((w, h));
+            }
+            at += 2 + len;
+        }
+    }
+    None
+}
+
+/// Count `/Type /Page` objects (not `/Pages`) in a PDF
+fn pdf_pages(bytes: &[u8]) -> u32 {
+    let needle = b"/Type";
+    let mut count = 0;
+    let mut at = 0;
+    while let Some(pos) = bytes[at..].windows(needle.len()).position(|w| w == needle) {
+        let rest = &bytes[at + pos + needle.len()..];
+        let rest = &rest[rest.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+        if rest.starts_with(b"/Page") && !rest.starts_with(b"/Pages") {
+            count += 1;
+        }
+        at += pos + needle.len();
+    }
+    count.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Minimal PNG header (signature + IHDR) for the given size
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    fn temp_store() -> (AttachmentStore, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        (AttachmentStore::new(dir.path().to_path_buf()).unwrap(), dir)
+    }
+
+    #[test]
+    fn test_extract_replaces_blocks_and_dedups() {
+        let (store, dir) = temp_store();
+        let data = base64::engine::general_purpose::STANDARD.encode(png(1280, 800));
+        let image = json!({"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": data}});
+        let mut body = json!({
+            "messages": [
+                {"role": "user", "content": [image.clone(), {"type": "text", "text": "what is this?"}]},
+                {"role": "user", "content": [{"type": "tool_result", "tool_use_id": "t1", "content": [image]}]}
+            ]
+        });
+
+        let refs = store.extract(&mut body);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].sha256, refs[1].sha256);
+        assert_eq!((refs[0].width, refs[0].height), (Some(1280), Some(800)));
+        assert!(refs[0].path.exists());
+        assert_eq!(
+            fs::read_dir(dir.path().join(&refs[0].sha256[..2]))
+                .unwrap()
+                .count(),
+            1
+        );
+
+        // Base64 is gone from the body, references are found again
+        assert!(!body.to_string().contains(&data));
+        let found = references(&body);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].kind, AttachmentKind::Image);
+        assert_eq!(found[0].reference, refs[0]);
+        assert_eq!(estimated_tokens(&body), refs[0].estimated_tokens * 2);
+
+        // Already-rewritten bodies are left alone
+        assert!(store.extract(&mut body).is_empty());
+    }
+
+    #[test]
+    fn test_repeated_payload_is_served_from_cache() {
+        let (store, _dir) = temp_store();
+        let data = base64::engine::general_purpose::STANDARD.encode(png(64, 64));
+        let body = json!([{"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": data}}]);
+
+        let first = store.extract(&mut body.clone());
+        // A cache hit skips the write, so the deleted file stays gone
+        fs::remove_file(&first[0].path).unwrap();
+        let second = store.extract(&mut body.clone());
+        assert_eq!(first, second);
+        assert!(!second[0].path.exists());
+    }
+
+    #[test]
+    fn test_image_tokens_follow_downscaling() {
+        assert_eq!(image_tokens(200, 200), 54);
+        // 1092×1092 is the API's largest square that isn't resized
+        assert_eq!(image_tokens(1092, 1092), 1590);
+        // Oversized images are capped near 1600 tokens
+        assert_eq!(image_tokens(4000, 3000), 1600);
+    }
+
+    #[test]
+    fn test_dimensions_and_pages() {
+        assert_eq!(image_dimensions(&png(10, 20)), Some((10, 20)));
+        assert_eq!(image_dimensions(b"GIF89a\x0a\x00\x14\x00"), Some((10, 20)));
+        assert_eq!(image_dimensions(b"not an image"), None);
+
+        let pdf = b"%PDF-1.4 /Type /Pages /Count 2 /Type /Page /Type/Page";
+        assert_eq!(pdf_pages(pdf), 2);
+    }
+}
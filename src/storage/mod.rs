@@ -8,6 +8,8 @@
 // Each session gets its own log file: aspy-YYYYMMDD-HHMMSS-XXXX.jsonl
 // Example: jq '.tool_name' logs/aspy-20251127-143022-a7b3.jsonl
 
+pub mod attachments;
+
 use crate::events::TrackedEvent;
 use anyhow::{Context, Result};
 use std::fs::{self, OpenOptions};
@@ -48,4 +48,13 @@ logs = "#8fad5c"
 [code]
 inline = "#e8b87a"
 block = "#9ca8b4"
+keyword = "#c9a66b"
+type = "#5da9a1"
+function = "#6b98b8"
+string = "#8fad5c"
+number = "#d4a54a"
+comment = "#8a8279"
+diff_added = "#8fad5c"
+diff_removed = "#c75f4a"
+diff_hunk = "#a88fad"
 "##;
@@ -48,4 +48,13 @@ logs = "#6a8f4a"
 [code]
 inline = "#c4784a"
 block = "#6a7880"
+keyword = "#c4784a"
+type = "#3d8a84"
+function = "#4a7a99"
+string = "#6a8f4a"
+number = "#c4944a"
+comment = "#857c72"
+diff_added = "#6a8f4a"
+diff_removed = "#b85a4a"
+diff_hunk = "#8a6a8f"
 "##;
@@ -18,6 +18,7 @@ mod semantic;
 mod toml_format;
 
 pub use bundled::list_bundled_themes;
+use toml_format::CodeColors;
 pub use toml_format::TomlTheme;
 
 // Legacy exports (for migration period)
@@ -92,6 +93,15 @@ pub struct Theme {
     // ─── Code Highlighting Colors ────────────────────────────
     pub code_inline: Color,
     pub code_block: Color,
+    pub code_keyword: Color,
+    pub code_type: Color,
+    pub code_function: Color,
+    pub code_string: Color,
+    pub code_number: Color,
+    pub code_comment: Color,
+    pub diff_added: Color,
+    pub diff_removed: Color,
+    pub diff_hunk: Color,
 
     // ─── Source for VHS export ───────────────────────────────
     #[allow(dead_code)] // Used by to_vhs_json() for demo recording export
@@ -223,6 +233,16 @@ impl Theme {
             Color::Reset
         };
 
+        // Syntax and diff slots: [code] value, else an event color
+        let slot = |pick: fn(&CodeColors) -> &Option<String>, fallback: &str| {
+            parse(
+                toml.code
+                    .as_ref()
+                    .and_then(|c| pick(c).as_deref())
+                    .unwrap_or(fallback),
+            )
+        };
+
         Self {
             name: toml.meta.name.clone(),
 
@@ -283,6 +303,18 @@ impl Theme {
                 .as_ref()
                 .map(|c| parse(&c.block))
                 .unwrap_or_else(|| parse(&toml.events.api_usage)),
+            code_keyword: slot(|c| &c.keyword, &toml.events.response),
+            code_type: slot(|c| &c.type_name, &toml.events.tool_call),
+            code_function: slot(|c| &c.function, &toml.events.request),
+            code_string: slot(|c| &c.string, &toml.events.tool_result_ok),
+            code_number: slot(|c| &c.number, &toml.events.context_compact),
+            code_comment: slot(
+                |c| &c.comment,
+                toml.ui.muted.as_ref().unwrap_or(&toml.events.api_usage),
+            ),
+            diff_added: slot(|c| &c.diff_added, &toml.events.tool_result_ok),
+            diff_removed: slot(|c| &c.diff_removed, &toml.events.tool_result_fail),
+            diff_hunk: slot(|c| &c.diff_hunk, &toml.events.request),
 
             toml_source: Some(toml),
         }
@@ -335,6 +367,15 @@ impl Theme {
             border_type: BorderType::Plain,
             code_inline: semantic.tool_call,
             code_block: semantic.api_usage,
+            code_keyword: semantic.response,
+            code_type: semantic.tool_call,
+            code_function: semantic.request,
+            code_string: semantic.tool_result_ok,
+            code_number: semantic.context_compact,
+            code_comment: semantic.api_usage,
+            diff_added: semantic.tool_result_ok,
+            diff_removed: semantic.tool_result_fail,
+            diff_hunk: semantic.request,
 
             toml_source: None,
         }
@@ -386,6 +427,15 @@ impl Theme {
             border_type: BorderType::Plain,
             code_inline: Color::Rgb(86, 182, 194),
             code_block: Color::Rgb(220, 223, 228),
+            code_keyword: Color::Rgb(198, 120, 221),
+            code_type: Color::Rgb(86, 182, 194),
+            code_function: Color::Rgb(97, 175, 239),
+            code_string: Color::Rgb(152, 195, 121),
+            code_number: Color::Rgb(229, 192, 123),
+            code_comment: Color::Rgb(127, 132, 142),
+            diff_added: Color::Rgb(152, 195, 121),
+            diff_removed: Color::Rgb(224, 108, 117),
+            diff_hunk: Color::Rgb(97, 175, 239),
 
             toml_source: None,
         }
@@ -84,12 +84,34 @@ pub struct PanelColors {
 }
 
 /// Code/syntax highlighting colors (optional)
+///
+/// `inline` and `block` are required; the syntax and diff slots fall back to
+/// event colors when missing.
 #[derive(Debug, Clone, Deserialize)]
 pub struct CodeColors {
     /// Color for `inline code` spans
     pub inline: String,
-    /// Color for fenced code blocks
+    /// Color for fenced code blocks (and plain text inside highlighted ones)
     pub block: String,
+    /// Language keywords (falls back to events.response)
+    pub keyword: Option<String>,
+    /// Type names, lifetimes, shell variables (falls back to events.tool_call)
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    /// Function/macro calls and config keys (falls back to events.request)
+    pub function: Option<String>,
+    /// String literals (falls back to events.tool_result_ok)
+    pub string: Option<String>,
+    /// Number literals (falls back to events.context_compact)
+    pub number: Option<String>,
+    /// Comments (falls back to ui.muted)
+    pub comment: Option<String>,
+    /// Added diff lines (falls back to events.tool_result_ok)
+    pub diff_added: Option<String>,
+    /// Removed diff lines (falls back to events.tool_result_fail)
+    pub diff_removed: Option<String>,
+    /// Diff hunk headers (falls back to events.request)
+    pub diff_hunk: Option<String>,
 }
 
 /// VHS export colors (optional, for demo recordings)
@@ -252,5 +274,14 @@ logs = "#a6e3a1"
         assert_eq!(theme.meta.version, 1);
         assert_eq!(theme.ui.background, "#1e1e2e");
         assert!(theme.vhs.is_none());
+        assert!(theme.code.is_none());
+
+        let with_code = format!(
+            "{}\n[code]\ninline = \"#f9e2af\"\nblock = \"#9399b2\"\ntype = \"#89dceb\"\n",
+            toml
+        );
+        let code = TomlTheme::from_str(&with_code).unwrap().code.unwrap();
+        assert_eq!(code.type_name.as_deref(), Some("#89dceb"));
+        assert!(code.keyword.is_none());
     }
 }
@@ -0,0 +1,587 @@
+//! Session timeline: where wall-clock time goes
+//!
+//! Lays a session's events out as spans on one time axis:
+//!
+//! - **Requests**: API round trips, split into time to first byte and
+//!   streaming (from the `Response` event's `ttfb` and `duration`)
+//! - **Thinking**: estimated from the share of the response's output tokens
+//!   that went to thinking, placed at the start of streaming (thinking blocks
+//!   come first; the stream itself isn't timestamped per block)
+//! - **Tools**: `ToolCall` → `ToolResult` durations; calls without a result
+//!   yet run to the latest event
+//! - **Idle**: gaps of at least [`IDLE_MIN`] with no request or tool in
+//!   flight, marked as waiting for the user when a prompt ends them
+//!
+//! The Stats view draws it as a waterfall, and [`Timeline::to_chrome_trace`]
+//! exports it as Chrome trace-event JSON for `chrome://tracing` or Perfetto.
+
+use crate::events::ProxyEvent;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Shortest gap between requests and tools shown as idle
+pub const IDLE_MIN: Duration = Duration::from_secs(1);
+
+/// What a span measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanKind {
+    Request,
+    Thinking,
+    Tool,
+    Idle,
+}
+
+impl SpanKind {
+    pub const ALL: [SpanKind; 4] = [Self::Request, Self::Thinking, Self::Tool, Self::Idle];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Request => "API",
+            Self::Thinking => "Thinking",
+            Self::Tool => "Tool",
+            Self::Idle => "Idle",
+        }
+    }
+}
+
+/// One bar of the waterfall
+#[derive(Debug, Clone, Serialize)]
+pub struct Span {
+    pub kind: SpanKind,
+    /// Model, tool name, or idle reason
+    pub name: String,
+    /// Milliseconds from the start of the timeline
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Requests: time to first byte (the rest is streaming)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_ms: Option<u64>,
+    /// Error status or failed tool
+    pub failed: bool,
+    /// Tool call still waiting for its result
+    pub open: bool,
+}
+
+impl Span {
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms.saturating_sub(self.start_ms)
+    }
+}
+
+/// Spans of one session, ordered by start
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Timeline {
+    /// Wall-clock time of offset 0
+    pub start: Option<DateTime<Utc>>,
+    pub spans: Vec<Span>,
+}
+
+/// A span while building, before times become offsets
+struct Draft {
+    kind: SpanKind,
+    name: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    ttfb: Option<Duration>,
+    failed: bool,
+    open: bool,
+}
+
+impl Draft {
+    fn new(kind: SpanKind, name: String, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            kind,
+            name,
+            start,
+            end,
+            ttfb: None,
+            failed: false,
+            open: false,
+        }
+    }
+}
+
+/// Thinking and output tokens reported after a response
+#[derive(Default)]
+struct ResponseTokens {
+    thinking: u64,
+    output: Option<u64>,
+}
+
+impl Timeline {
+    /// Build from one session's events, in the order they were recorded
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a ProxyEvent>) -> Self {
+        let mut drafts: Vec<Draft> = Vec::new();
+        let mut request_starts: HashMap<&str, (DateTime<Utc>, &str)> = HashMap::new();
+        let mut open_tools: Vec<(&str, DateTime<Utc>, &str)> = Vec::new();
+        let mut prompts: Vec<DateTime<Utc>> = Vec::new();
+        // Parsed events (thinking, usage) follow their Response
+        let mut tokens: HashMap<usize, ResponseTokens> = HashMap::new();
+        let mut last_response: Option<usize> = None;
+        let mut latest: Option<DateTime<Utc>> = None;
+
+        for event in events {
+            match event {
+                ProxyEvent::Request {
+                    id,
+                    timestamp,
+                    path,
+                    ..
+                } => {
+                    request_starts.insert(id, (*timestamp, path));
+                }
+                ProxyEvent::Response {
+                    request_id,
+                    timestamp,
+                    status,
+                    ttfb,
+                    duration,
+                    ..
+                } => {
+                    let elapsed = chrono::Duration::from_std(*duration).unwrap_or_default();
+                    let (start, path) = request_starts
+                        .get(request_id.as_str())
+                        .copied()
+                        .unwrap_or((*timestamp - elapsed, "request"));
+                    let mut draft =
+                        Draft::new(SpanKind::Request, path.to_string(), start, start + elapsed);
+                    draft.ttfb = Some((*ttfb).min(*duration));
+                    draft.failed = *status >= 400;
+                    last_response = Some(drafts.len());
+                    drafts.push(draft);
+                }
+                ProxyEvent::Thinking { token_estimate, .. } => {
+                    if let Some(idx) = last_response {
+                        tokens.entry(idx).or_default().thinking += *token_estimate as u64;
+                    }
+                }
+                ProxyEvent::ApiUsage {
+                    model,
+                    output_tokens,
+                    ..
+                } => {
+                    if let Some(idx) = last_response {
+                        let entry = tokens.entry(idx).or_default();
+                        if entry.output.is_none() {
+                            entry.output = Some(*output_tokens as u64);
+                            drafts[idx].name = model.clone();
+                        }
+                    }
+                }
+                ProxyEvent::ToolCall {
+                    id,
+                    timestamp,
+                    tool_name,
+                    ..
+                } => open_tools.push((id, *timestamp, tool_name)),
+                ProxyEvent::ToolResult {
+                    id,
+                    timestamp,
+                    tool_name,
+                    duration,
+                    success,
+                    ..
+                } => {
+                    open_tools.retain(|(open_id, _, _)| *open_id != id.as_str());
+                    let elapsed = chrono::Duration::from_std(*duration).unwrap_or_default();
+                    let mut draft = Draft::new(
+                        SpanKind::Tool,
+                        tool_name.clone(),
+                        *timestamp - elapsed,
+                        *timestamp,
+                    );
+                    draft.failed = !success;
+                    drafts.push(draft);
+                }
+                ProxyEvent::UserPrompt { timestamp, .. } => prompts.push(*timestamp),
+                _ => {}
+            }
+            if let Some(end) = drafts.last().map(|d| d.end) {
+                latest = latest.max(Some(end));
+            }
+            latest = latest.max(open_tools.last().map(|(_, ts, _)| *ts));
+        }
+
+        for (_, start, name) in open_tools {
+            let mut draft = Draft::new(
+                SpanKind::Tool,
+                name.to_string(),
+                start,
+                latest.unwrap_or(start),
+            );
+            draft.open = true;
+            drafts.push(draft);
+        }
+
+        let thinking: Vec<Draft> = tokens
+            .iter()
+            .filter(|(_, t)| t.thinking > 0)
+            .map(|(&idx, t)| thinking_draft(&drafts[idx], t))
+            .collect();
+        drafts.extend(thinking);
+
+        let idle = idle_drafts(&drafts, &prompts);
+        drafts.extend(idle);
+
+        Self::from_drafts(drafts)
+    }
+
+    fn from_drafts(mut drafts: Vec<Draft>) -> Self {
+        drafts.sort_by_key(|d| (d.start, d.kind));
+        let Some(start) = drafts.iter().map(|d| d.start).min() else {
+            return Self::default();
+        };
+        let offset = |t: DateTime<Utc>| (t - start).num_milliseconds().max(0) as u64;
+        let spans = drafts
+            .into_iter()
+            .map(|d| Span {
+                kind: d.kind,
+                name: d.name,
+                start_ms: offset(d.start),
+                end_ms: offset(d.end.max(d.start)),
+                ttfb_ms: d.ttfb.map(|t| t.as_millis() as u64),
+                failed: d.failed,
+                open: d.open,
+            })
+            .collect();
+        Self {
+            start: Some(start),
+            spans,
+        }
+    }
+
+    /// Stretch tool calls still waiting for a result up to `now` (live sessions)
+    pub fn extend_open(&mut self, now: DateTime<Utc>) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let now_ms = (now - start).num_milliseconds().max(0) as u64;
+        for span in self.spans.iter_mut().filter(|s| s.open) {
+            span.end_ms = span.end_ms.max(now_ms);
+        }
+    }
+
+    /// Offset of the last span end
+    pub fn duration_ms(&self) -> u64 {
+        self.spans.iter().map(|s| s.end_ms).max().unwrap_or(0)
+    }
+
+    /// Wall-clock time covered by a kind of span (overlaps counted once)
+    pub fn total_ms(&self, kind: SpanKind) -> u64 {
+        let mut covered = 0;
+        let mut reach = 0;
+        for span in self.spans.iter().filter(|s| s.kind == kind) {
+            let start = span.start_ms.max(reach);
+            covered += span.end_ms.saturating_sub(start);
+            reach = reach.max(span.end_ms);
+        }
+        covered
+    }
+
+    /// Chrome trace-event JSON (`chrome://tracing`, Perfetto)
+    ///
+    /// Each kind gets its own threads, with overlapping spans (parallel tools,
+    /// concurrent requests) spread over extra lanes so slices nest correctly.
+    /// Requests carry `ttfb` and `streaming` child slices.
+    pub fn to_chrome_trace(&self) -> Value {
+        let mut events = Vec::new();
+        let mut lane_ends: HashMap<SpanKind, Vec<u64>> = HashMap::new();
+
+        for span in &self.spans {
+            let lanes = lane_ends.entry(span.kind).or_default();
+            let lane = match lanes.iter().position(|&end| end <= span.start_ms) {
+                Some(free) => free,
+                None => {
+                    lanes.push(0);
+                    lanes.len() - 1
+                }
+            };
+            lanes[lane] = span.end_ms;
+            let tid = thread_id(span.kind, lane);
+
+            let mut args = json!({ "failed": span.failed });
+            if span.open {
+                args["open"] = json!(true);
+            }
+            events.push(slice(
+                &span.name,
+                span.kind,
+                tid,
+                span.start_ms,
+                span.duration_ms(),
+                args,
+            ));
+            if let Some(ttfb) = span.ttfb_ms {
+                events.push(slice(
+                    "ttfb",
+                    span.kind,
+                    tid,
+                    span.start_ms,
+                    ttfb,
+                    json!({}),
+                ));
+                events.push(slice(
+                    "streaming",
+                    span.kind,
+                    tid,
+                    span.start_ms + ttfb,
+                    span.duration_ms().saturating_sub(ttfb),
+                    json!({}),
+                ));
+            }
+        }
+
+        // Thread names and order, so lanes read top to bottom like the waterfall
+        for kind in SpanKind::ALL {
+            let lanes = lane_ends.get(&kind).map_or(0, Vec::len);
+            for lane in 0..lanes {
+                let name = match lane {
+                    0 => kind.label().to_string(),
+                    n => format!("{} #{}", kind.label(), n + 1),
+                };
+                let tid = thread_id(kind, lane);
+                events.push(json!({
+                    "name": "thread_name", "ph": "M", "pid": 1, "tid": tid,
+                    "args": { "name": name },
+                }));
+                events.push(json!({
+                    "name": "thread_sort_index", "ph": "M", "pid": 1, "tid": tid,
+                    "args": { "sort_index": tid },
+                }));
+            }
+        }
+        events.push(json!({
+            "name": "process_name", "ph": "M", "pid": 1,
+            "args": { "name": "aspy session" },
+        }));
+
+        json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+            "otherData": {
+                "start": self.start.map(|s| s.to_rfc3339()),
+            },
+        })
+    }
+}
+
+/// Thread id for a kind's lane (kinds are 100 apart)
+fn thread_id(kind: SpanKind, lane: usize) -> u64 {
+    let base = SpanKind::ALL.iter().position(|k| *k == kind).unwrap_or(0) as u64;
+    (base + 1) * 100 + lane as u64
+}
+
+/// Complete ("X") trace event; times in microseconds
+fn slice(name: &str, kind: SpanKind, tid: u64, start_ms: u64, dur_ms: u64, args: Value) -> Value {
+    json!({
+        "name": name,
+        "cat": kind.label(),
+        "ph": "X",
+        "ts": start_ms * 1000,
+        "dur": dur_ms * 1000,
+        "pid": 1,
+        "tid": tid,
+        "args": args,
+    })
+}
+
+/// Thinking share of a request's streaming time
+fn thinking_draft(request: &Draft, tokens: &ResponseTokens) -> Draft {
+    let ttfb = chrono::Duration::from_std(request.ttfb.unwrap_or_default()).unwrap_or_default();
+    let first_byte = (request.start + ttfb).min(request.end);
+    let streaming = (request.end - first_byte).num_milliseconds() as f64;
+    let share = match tokens.output {
+        Some(output) if output > 0 => (tokens.thinking as f64 / output as f64).min(1.0),
+        _ => 1.0,
+    };
+    let length = chrono::Duration::milliseconds((streaming * share) as i64);
+    Draft::new(
+        SpanKind::Thinking,
+        format!("~{} tokens", tokens.thinking),
+        first_byte,
+        first_byte + length,
+    )
+}
+
+/// Gaps with no request or tool in flight
+fn idle_drafts(drafts: &[Draft], prompts: &[DateTime<Utc>]) -> Vec<Draft> {
+    let mut busy: Vec<(DateTime<Utc>, DateTime<Utc>)> = drafts
+        .iter()
+        .filter(|d| matches!(d.kind, SpanKind::Request | SpanKind::Tool))
+        .map(|d| (d.start, d.end))
+        .collect();
+    busy.sort();
+
+    let idle_min = chrono::Duration::from_std(IDLE_MIN).unwrap_or_default();
+    let mut idle = Vec::new();
+    let mut reach: Option<DateTime<Utc>> = None;
+    for (start, end) in busy {
+        if let Some(gap_start) = reach.filter(|r| start - *r >= idle_min) {
+            // The prompt is parsed from the request that ends the gap
+            let waiting = prompts
+                .iter()
+                .any(|p| *p >= gap_start && *p <= start + idle_min);
+            let name = if waiting { "waiting for user" } else { "idle" };
+            idle.push(Draft::new(
+                SpanKind::Idle,
+                name.to_string(),
+                gap_start,
+                start,
+            ));
+        }
+        reach = reach.max(Some(end));
+    }
+    idle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        Utc.timestamp_millis_opt(1_700_000_000_000 + ms).unwrap()
+    }
+
+    fn session() -> Vec<ProxyEvent> {
+        vec![
+            ProxyEvent::UserPrompt {
+                timestamp: at(0),
+                content: "fix it".into(),
+            },
+            ProxyEvent::Request {
+                id: "r1".into(),
+                timestamp: at(0),
+                method: "POST".into(),
+                path: "/v1/messages".into(),
+                body_size: 10,
+                body: None,
+            },
+            ProxyEvent::Response {
+                request_id: "r1".into(),
+                timestamp: at(3000),
+                status: 200,
+                body_size: 10,
+                ttfb: Duration::from_millis(1000),
+                duration: Duration::from_millis(3000),
+                body: None,
+            },
+            ProxyEvent::Thinking {
+                timestamp: at(3000),
+                content: String::new(),
+                token_estimate: 50,
+            },
+            ProxyEvent::ToolCall {
+                id: "t1".into(),
+                timestamp: at(3000),
+                tool_name: "Bash".into(),
+                input: Value::Null,
+            },
+            ProxyEvent::ApiUsage {
+                timestamp: at(3000),
+                model: "claude-sonnet-4-5".into(),
+                input_tokens: 100,
+                output_tokens: 100,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 0,
+            },
+            ProxyEvent::ToolResult {
+                id: "t1".into(),
+                timestamp: at(5000),
+                tool_name: "Bash".into(),
+                output: Value::Null,
+                duration: Duration::from_millis(2000),
+                success: false,
+            },
+            ProxyEvent::UserPrompt {
+                timestamp: at(9000),
+                content: "again".into(),
+            },
+            ProxyEvent::Request {
+                id: "r2".into(),
+                timestamp: at(9000),
+                method: "POST".into(),
+                path: "/v1/messages".into(),
+                body_size: 10,
+                body: None,
+            },
+            ProxyEvent::Response {
+                request_id: "r2".into(),
+                timestamp: at(10000),
+                status: 529,
+                body_size: 10,
+                ttfb: Duration::from_millis(1000),
+                duration: Duration::from_millis(1000),
+                body: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_spans_from_events() {
+        let timeline = Timeline::from_events(&session());
+        let summary: Vec<(SpanKind, &str, u64, u64)> = timeline
+            .spans
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.start_ms, s.end_ms))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SpanKind::Request, "claude-sonnet-4-5", 0, 3000),
+                // Half the output tokens were thinking: half the 2s stream
+                (SpanKind::Thinking, "~50 tokens", 1000, 2000),
+                (SpanKind::Tool, "Bash", 3000, 5000),
+                (SpanKind::Idle, "waiting for user", 5000, 9000),
+                (SpanKind::Request, "/v1/messages", 9000, 10000),
+            ]
+        );
+        assert!(timeline.spans[2].failed && timeline.spans[4].failed);
+        assert_eq!(timeline.duration_ms(), 10000);
+        assert_eq!(timeline.total_ms(SpanKind::Idle), 4000);
+    }
+
+    #[test]
+    fn test_open_tool_and_overlaps() {
+        let mut events = session();
+        events.truncate(5); // Bash call without its result
+        events.push(ProxyEvent::ApiUsage {
+            timestamp: at(4000),
+            model: "m".into(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+        });
+        let timeline = Timeline::from_events(&events);
+        let tool = timeline
+            .spans
+            .iter()
+            .find(|s| s.kind == SpanKind::Tool)
+            .unwrap();
+        assert!(tool.open);
+        assert_eq!((tool.start_ms, tool.end_ms), (3000, 3000));
+        let mut live = timeline.clone();
+        live.extend_open(at(7000));
+        assert_eq!(live.duration_ms(), 7000);
+
+        let trace = Timeline::from_events(&session()).to_chrome_trace();
+        let slices: Vec<&Value> = trace["traceEvents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|e| e["ph"] == "X")
+            .collect();
+        // 2 requests (each with ttfb + streaming), thinking, tool, idle
+        assert_eq!(slices.len(), 9);
+        let ttfb = slices.iter().find(|e| e["name"] == "ttfb").unwrap();
+        assert_eq!(
+            (ttfb["ts"].as_u64(), ttfb["dur"].as_u64()),
+            (Some(0), Some(1_000_000))
+        );
+    }
+}
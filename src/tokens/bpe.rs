@@ -0,0 +1,312 @@
+//! Bundled BPE tokenizer approximation
+//!
+//! A real BPE tokenizer needs a merge table of ~100K entries, which we don't
+//! want to ship. This counter reproduces the two stages that dominate the
+//! count instead:
+//!
+//! 1. **Pre-tokenization** - the same split GPT-style tokenizers apply before
+//!    merging: contractions, letter runs with an optional leading space,
+//!    digit groups of up to three, punctuation runs, whitespace runs.
+//! 2. **Merging** - common words (a small bundled vocabulary of English and
+//!    code keywords) become one token; other words are split into subword
+//!    pieces at case boundaries and then by length.
+//!
+//! On code it tracks the API's counts more closely than the character-class
+//! heuristic, because identifiers and operators are sized per piece rather
+//! than per character class.
+
+use super::TokenCounter;
+
+/// Average characters per merged subword piece for words outside the vocabulary
+const CHARS_PER_PIECE: f64 = 3.6;
+
+/// Words that BPE vocabularies reliably hold as a single token
+///
+/// Deliberately short: the list only needs to cover words frequent enough to
+/// move the total. Everything else falls back to length-based splitting.
+#[rustfmt::skip]
+const COMMON_WORDS: &[&str] = &[
+    // English function words
+    "the", "be", "to", "of", "and", "a", "in", "that", "have", "i", "it", "for", "not", "on",
+    "with", "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we",
+    "say", "her", "she", "or", "an", "will", "my", "one", "all", "would", "there", "their",
+    "what", "so", "up", "out", "if", "about", "who", "get", "which", "go", "me", "when", "make",
+    "can", "like", "time", "no", "just", "him", "know", "take", "people", "into", "year",
+    "your", "good", "some", "could", "them", "see", "other", "than", "then", "now", "look",
+    "only", "come", "its", "over", "think", "also", "back", "after", "use", "two", "how",
+    "our", "work", "first", "well", "way", "even", "new", "want", "because", "any", "these",
+    "give", "day", "most", "us", "is", "are", "was", "were", "been", "has", "had", "should",
+    "here", "where", "why", "let", "need", "file", "line", "code", "error", "test", "run",
+    "add", "fix", "change", "update", "read", "write", "check", "using", "used", "call",
+    "value", "data", "type", "name", "path", "list", "set", "each", "same", "before",
+    "between", "without", "within", "while", "still", "such", "more", "very", "much",
+    "does", "did", "done", "made", "found", "next", "last", "both", "many", "own", "right",
+    "left", "start", "end", "open", "close", "show", "help", "keep",
+    // Code keywords (Rust, JS/TS, Python, shell)
+    "fn", "let", "mut", "pub", "struct", "enum", "impl", "trait", "mod", "use", "crate",
+    "self", "super", "match", "return", "true", "false", "null", "none", "some", "ok", "err",
+    "async", "await", "const", "static", "function", "var", "class", "def", "import",
+    "export", "default", "else", "elif", "for", "while", "loop", "break", "continue", "new",
+    "try", "catch", "throw", "raise", "except", "finally", "string", "str", "int", "bool",
+    "void", "char", "float", "double", "vec", "option", "result", "box", "arc", "ref",
+    "where", "type", "interface", "extends", "implements", "public", "private", "protected",
+    "echo", "cd", "ls", "git", "cargo", "npm", "src", "lib", "main", "test", "tests", "json",
+    "http", "https", "www", "com", "api", "url", "id", "key", "map", "len", "args", "config",
+];
+
+/// Counter using the bundled BPE approximation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BpeCounter;
+
+impl TokenCounter for BpeCounter {
+    fn name(&self) -> &'static str {
+        "bpe"
+    }
+
+    fn count(&self, text: &str) -> u32 {
+        pretokenize(text).iter().map(|p| piece_tokens(p)).sum()
+    }
+}
+
+/// Character class used by the pre-tokenizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Letter,
+    Digit,
+    Space,
+    Newline,
+    Punct,
+    /// Non-ASCII symbols and scripts without word spacing (CJK, emoji)
+    Wide,
+}
+
+fn classify(c: char) -> CharClass {
+    if c == '\n' || c == '\r' {
+        CharClass::Newline
+    } else if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_ascii_digit() {
+        CharClass::Digit
+    } else if c.is_alphabetic() && (c.is_ascii() || is_spaced_script(c)) {
+        CharClass::Letter
+    } else if c.is_ascii() {
+        CharClass::Punct
+    } else {
+        CharClass::Wide
+    }
+}
+
+/// Latin extensions, Greek and Cyrillic behave like ASCII words
+fn is_spaced_script(c: char) -> bool {
+    matches!(c as u32, 0x00C0..=0x052F)
+}
+
+/// A pre-tokenized piece
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Piece<'a> {
+    class: CharClass,
+    text: &'a str,
+}
+
+/// Split text into pre-tokenization pieces
+///
+/// A single leading space is folded into the following letter or punctuation
+/// run (" hello" is one piece), matching how GPT-style vocabularies store
+/// word-initial tokens.
+fn pretokenize(text: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        // Contractions ('s, 't, 're, 've, 'll, 'd, 'm) are their own piece
+        if c == '\'' {
+            if let Some(len) = contraction_len(&text[start + 1..]) {
+                let end = start + 1 + len;
+                while chars.peek().is_some_and(|&(idx, _)| idx < end) {
+                    chars.next();
+                }
+                pieces.push(Piece {
+                    class: CharClass::Letter,
+                    text: &text[start..end],
+                });
+                continue;
+            }
+        }
+
+        let mut class = classify(c);
+        let mut end = start + c.len_utf8();
+
+        // Fold a single space into a following word/punct run
+        if c == ' ' {
+            if let Some(&(idx, next)) = chars.peek() {
+                let next_class = classify(next);
+                if matches!(next_class, CharClass::Letter | CharClass::Punct) {
+                    class = next_class;
+                    end = idx + next.len_utf8();
+                    chars.next();
+                }
+            }
+        }
+
+        let mut digits = usize::from(class == CharClass::Digit);
+
+        while let Some(&(idx, next)) = chars.peek() {
+            let next_class = classify(next);
+            if next_class != class || class == CharClass::Wide {
+                break;
+            }
+            // Digits merge in groups of at most three
+            if class == CharClass::Digit {
+                if digits == 3 {
+                    break;
+                }
+                digits += 1;
+            }
+            end = idx + next.len_utf8();
+            chars.next();
+        }
+
+        pieces.push(Piece {
+            class,
+            text: &text[start..end],
+        });
+    }
+
+    pieces
+}
+
+/// Length of a contraction suffix at the start of `rest` (after the apostrophe)
+fn contraction_len(rest: &str) -> Option<usize> {
+    ["re", "ve", "ll", "s", "t", "d", "m"]
+        .iter()
+        .find_map(|suffix| {
+            let candidate = rest.get(..suffix.len())?;
+            let boundary = rest[suffix.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| !c.is_alphabetic());
+            (candidate.eq_ignore_ascii_case(suffix) && boundary).then_some(suffix.len())
+        })
+}
+
+/// Token count for a single pre-tokenized piece
+fn piece_tokens(piece: &Piece<'_>) -> u32 {
+    match piece.class {
+        CharClass::Letter => word_tokens(piece.text.trim_start()),
+        CharClass::Digit => 1,
+        // Runs of spaces (indentation) are usually a single token
+        CharClass::Space => 1,
+        // Each line break is typically its own token ("\n\n" merges)
+        CharClass::Newline => {
+            let breaks = piece.text.chars().filter(|c| *c == '\n').count() as u32;
+            breaks.div_ceil(2).max(1)
+        }
+        // Operators pair up (`::`, `->`, `==`, `);`), so ~2 chars per token
+        CharClass::Punct => {
+            let len = piece.text.trim_start().chars().count() as u32;
+            len.div_ceil(2).max(1)
+        }
+        // CJK and emoji: roughly one token per character (often more for emoji)
+        CharClass::Wide => piece.text.len().div_ceil(3) as u32,
+    }
+}
+
+/// Token count for a word (letters only)
+fn word_tokens(word: &str) -> u32 {
+    if word.is_empty() {
+        return 0;
+    }
+    split_case(word)
+        .into_iter()
+        .map(|part| {
+            let lower = part.to_lowercase();
+            if COMMON_WORDS.contains(&lower.as_str()) {
+                1
+            } else {
+                let len = part.chars().count();
+                if len <= 4 {
+                    1
+                } else {
+                    (len as f64 / CHARS_PER_PIECE).ceil() as u32
+                }
+            }
+        })
+        .sum()
+}
+
+/// Split a word at camelCase boundaries ("parseResponse" -> ["parse", "Response"])
+fn split_case(word: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut prev_lower = false;
+
+    for (idx, c) in word.char_indices() {
+        if c.is_uppercase() && prev_lower {
+            parts.push(&word[start..idx]);
+            start = idx;
+        }
+        prev_lower = c.is_lowercase();
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(BpeCounter.count(""), 0);
+    }
+
+    #[test]
+    fn test_common_words_are_single_tokens() {
+        // " the", " file", " with" all fold the leading space into the word
+        assert_eq!(BpeCounter.count("the file with"), 3);
+    }
+
+    #[test]
+    fn test_pretokenize_digits_group_by_three() {
+        let pieces = pretokenize("1234567");
+        let texts: Vec<&str> = pieces.iter().map(|p| p.text).collect();
+        assert_eq!(texts, vec!["123", "456", "7"]);
+    }
+
+    #[test]
+    fn test_contractions_are_separate_pieces() {
+        let pieces = pretokenize("don't");
+        let texts: Vec<&str> = pieces.iter().map(|p| p.text).collect();
+        assert_eq!(texts, vec!["don", "'t"]);
+        // Not a contraction: quote followed by a longer word
+        let pieces = pretokenize("'tis");
+        assert_eq!(pieces[0].text, "'");
+    }
+
+    #[test]
+    fn test_camel_case_splits() {
+        assert_eq!(split_case("parseResponse"), vec!["parse", "Response"]);
+        assert_eq!(split_case("HTTPClient"), vec!["HTTPClient"]);
+        // "read" + "File" are both vocabulary words
+        assert_eq!(BpeCounter.count("readFile"), 2);
+    }
+
+    #[test]
+    fn test_code_snippet_in_range() {
+        let code = r#"fn main() {
+    println!("Hello");
+}"#;
+        let tokens = BpeCounter.count(code);
+        assert!((8..=20).contains(&tokens), "got {}", tokens);
+    }
+
+    #[test]
+    fn test_prose_close_to_heuristic() {
+        let prose = "The quick brown fox jumps over the lazy dog. \
+                     It was the best of times, it was the worst of times.";
+        let bpe = BpeCounter.count(prose) as f64;
+        let heuristic = super::super::estimate_tokens(prose) as f64;
+        let ratio = bpe / heuristic;
+        assert!((0.6..=1.4).contains(&ratio), "ratio {}", ratio);
+    }
+}
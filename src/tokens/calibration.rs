@@ -0,0 +1,295 @@
+//! Self-calibration of token estimates against actual API usage
+//!
+//! When a request goes out, the proxy records what each counter thinks its
+//! input size is. When the matching `ApiUsage` comes back, the estimates are
+//! resolved against the real `input + cache_read + cache_creation` total and
+//! folded into per-counter drift statistics.
+//!
+//! Estimates can arrive after their usage (the count_tokens fetch races the
+//! response), so recently resolved actuals are kept and late estimates are
+//! folded in directly instead of waiting for a resolve that never comes.
+//!
+//! The report answers "how far off are our estimates right now?" and offers
+//! a correction factor that callers can apply to local counts.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Shared calibration state (proxy writes, API reads)
+pub type SharedCalibration = Arc<Mutex<Calibration>>;
+
+/// Weight of the newest sample in the moving average (~last 20 requests)
+const EWMA_ALPHA: f64 = 0.1;
+
+/// Maximum unresolved requests kept (errors and non-message calls never resolve)
+const MAX_PENDING: usize = 256;
+
+/// Resolved requests whose actual total is kept for late estimates
+const MAX_SETTLED: usize = 256;
+
+/// Estimates waiting for their `ApiUsage`
+struct PendingEstimate {
+    created: Instant,
+    estimates: Vec<(&'static str, u32)>,
+}
+
+/// Running drift statistics for one counter
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DriftStats {
+    /// Number of resolved samples
+    pub samples: u64,
+    /// Mean of actual/estimate over all samples
+    pub mean_ratio: f64,
+    /// Mean absolute percentage error over all samples
+    pub mean_abs_error_pct: f64,
+    /// Exponentially weighted actual/estimate ratio (recent behaviour)
+    pub recent_ratio: f64,
+    /// Last estimate that was resolved
+    pub last_estimate: u32,
+    /// Actual tokens for the last resolved estimate
+    pub last_actual: u32,
+}
+
+impl DriftStats {
+    fn record(&mut self, estimate: u32, actual: u32) {
+        if estimate == 0 || actual == 0 {
+            return;
+        }
+        let ratio = actual as f64 / estimate as f64;
+        let abs_error_pct = ((estimate as f64 - actual as f64) / actual as f64).abs() * 100.0;
+
+        self.samples += 1;
+        let n = self.samples as f64;
+        self.mean_ratio += (ratio - self.mean_ratio) / n;
+        self.mean_abs_error_pct += (abs_error_pct - self.mean_abs_error_pct) / n;
+        self.recent_ratio = if self.samples == 1 {
+            ratio
+        } else {
+            EWMA_ALPHA * ratio + (1.0 - EWMA_ALPHA) * self.recent_ratio
+        };
+        self.last_estimate = estimate;
+        self.last_actual = actual;
+    }
+
+    /// Signed drift of recent estimates in percent (positive = underestimating)
+    pub fn drift_pct(&self) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            (self.recent_ratio - 1.0) * 100.0
+        }
+    }
+
+    /// Multiplier to apply to this counter's estimates (1.0 until calibrated)
+    pub fn correction_factor(&self) -> f64 {
+        if self.samples == 0 {
+            1.0
+        } else {
+            self.recent_ratio
+        }
+    }
+}
+
+/// Calibration tracker for all counters
+#[derive(Default)]
+pub struct Calibration {
+    pending: HashMap<String, PendingEstimate>,
+    /// Actual input totals of recently resolved requests
+    settled: HashMap<String, u32>,
+    /// `settled` keys, oldest first (for eviction)
+    settled_order: VecDeque<String>,
+    drift: BTreeMap<&'static str, DriftStats>,
+}
+
+impl Calibration {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a counter's estimate for an outgoing request
+    ///
+    /// May be called several times per request: once per local counter, and
+    /// again when the count_tokens fetch completes. An estimate for a request
+    /// that was already resolved is scored against its actual right away.
+    pub fn record_estimate(&mut self, request_id: &str, counter: &'static str, tokens: u32) {
+        if let Some(&actual) = self.settled.get(request_id) {
+            self.drift
+                .entry(counter)
+                .or_default()
+                .record(tokens, actual);
+            return;
+        }
+        if !self.pending.contains_key(request_id) && self.pending.len() >= MAX_PENDING {
+            self.evict_oldest();
+        }
+        let entry = self
+            .pending
+            .entry(request_id.to_string())
+            .or_insert_with(|| PendingEstimate {
+                created: Instant::now(),
+                estimates: Vec::new(),
+            });
+        entry.estimates.retain(|(name, _)| *name != counter);
+        entry.estimates.push((counter, tokens));
+    }
+
+    /// Resolve a request's estimates against the actual input tokens
+    ///
+    /// Returns false if no estimates were recorded for the request.
+    pub fn resolve(&mut self, request_id: &str, actual: u32) -> bool {
+        self.settle(request_id, actual);
+        let Some(pending) = self.pending.remove(request_id) else {
+            return false;
+        };
+        for (counter, estimate) in pending.estimates {
+            let stats = self.drift.entry(counter).or_default();
+            stats.record(estimate, actual);
+            tracing::trace!(
+                counter,
+                estimate,
+                actual,
+                drift_pct = stats.drift_pct(),
+                "Token calibration sample"
+            );
+        }
+        true
+    }
+
+    /// Build a serializable report of all counters
+    pub fn report(&self) -> CalibrationReport {
+        CalibrationReport {
+            pending: self.pending.len(),
+            counters: self
+                .drift
+                .iter()
+                .map(|(name, stats)| CounterDrift {
+                    counter: name.to_string(),
+                    drift_pct: stats.drift_pct(),
+                    correction_factor: stats.correction_factor(),
+                    stats: stats.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Remember a request's actual total for estimates that arrive late
+    fn settle(&mut self, request_id: &str, actual: u32) {
+        if self
+            .settled
+            .insert(request_id.to_string(), actual)
+            .is_some()
+        {
+            return;
+        }
+        self.settled_order.push_back(request_id.to_string());
+        if self.settled_order.len() > MAX_SETTLED {
+            if let Some(oldest) = self.settled_order.pop_front() {
+                self.settled.remove(&oldest);
+            }
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some(oldest) = self
+            .pending
+            .iter()
+            .min_by_key(|(_, p)| p.created)
+            .map(|(id, _)| id.clone())
+        {
+            self.pending.remove(&oldest);
+        }
+    }
+}
+
+/// Calibration report returned by `/api/tokens/calibration`
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationReport {
+    /// Requests with estimates still waiting for usage data
+    pub pending: usize,
+    /// Per-counter drift
+    pub counters: Vec<CounterDrift>,
+}
+
+/// Drift summary for one counter
+#[derive(Debug, Clone, Serialize)]
+pub struct CounterDrift {
+    pub counter: String,
+    /// Signed recent drift in percent (positive = estimates too low)
+    pub drift_pct: f64,
+    /// Multiplier to bring estimates in line with actual usage
+    pub correction_factor: f64,
+    #[serde(flatten)]
+    pub stats: DriftStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_updates_drift() {
+        let mut cal = Calibration::new();
+        cal.record_estimate("req-1", "heuristic", 900);
+        cal.record_estimate("req-1", "bpe", 1000);
+        assert!(cal.resolve("req-1", 1000));
+
+        let heuristic = cal.drift.get("heuristic").unwrap();
+        assert_eq!(heuristic.samples, 1);
+        assert!((heuristic.drift_pct() - 11.11).abs() < 0.01);
+
+        let bpe = cal.drift.get("bpe").unwrap();
+        assert!(bpe.drift_pct().abs() < f64::EPSILON);
+        assert!((bpe.correction_factor() - 1.0).abs() < f64::EPSILON);
+
+        // Already resolved
+        assert!(!cal.resolve("req-1", 1000));
+    }
+
+    #[test]
+    fn test_record_estimate_replaces_same_counter() {
+        let mut cal = Calibration::new();
+        cal.record_estimate("req-1", "api", 10);
+        cal.record_estimate("req-1", "api", 20);
+        cal.resolve("req-1", 20);
+        assert_eq!(cal.drift.get("api").unwrap().last_estimate, 20);
+    }
+
+    #[test]
+    fn test_pending_is_bounded() {
+        let mut cal = Calibration::new();
+        for i in 0..(MAX_PENDING + 10) {
+            cal.record_estimate(&format!("req-{}", i), "heuristic", 1);
+        }
+        assert_eq!(cal.report().pending, MAX_PENDING);
+    }
+
+    #[test]
+    fn test_late_estimate_scored_against_settled_actual() {
+        let mut cal = Calibration::new();
+        cal.record_estimate("req-1", "heuristic", 1000);
+        assert!(cal.resolve("req-1", 1000));
+
+        // count_tokens finished after the usage came back
+        cal.record_estimate("req-1", "api", 800);
+        assert_eq!(cal.report().pending, 0);
+        assert_eq!(cal.drift.get("api").unwrap().last_actual, 1000);
+
+        // Settled actuals are bounded too
+        for i in 0..(MAX_SETTLED + 10) {
+            cal.resolve(&format!("req-{}", i + 2), 1);
+        }
+        assert_eq!(cal.settled.len(), MAX_SETTLED);
+        assert_eq!(cal.settled_order.len(), MAX_SETTLED);
+    }
+
+    #[test]
+    fn test_zero_samples_ignored() {
+        let mut cal = Calibration::new();
+        cal.record_estimate("req-1", "heuristic", 0);
+        cal.resolve("req-1", 100);
+        assert_eq!(cal.drift.get("heuristic").unwrap().samples, 0);
+    }
+}
@@ -0,0 +1,240 @@
+//! Upstream `/v1/messages/count_tokens` client
+//!
+//! The Messages API exposes an exact counter for a request body. Calling it
+//! costs a round-trip, so aspy never awaits it on the request path: the proxy
+//! spawns `fetch()` in the background and later consumers read the cached
+//! result through the sync `TokenCounter` interface, falling back to a local
+//! counter on a cache miss.
+
+use super::{CounterKind, TokenCounter};
+use anyhow::{Context, Result};
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Request fields the count_tokens endpoint accepts
+///
+/// Everything else (max_tokens, stream, temperature, metadata) is rejected
+/// or irrelevant to the count, so it is stripped before hashing and sending.
+const COUNTED_FIELDS: &[&str] = &[
+    "model",
+    "system",
+    "messages",
+    "tools",
+    "tool_choice",
+    "thinking",
+];
+
+/// Headers forwarded from the original request (auth + API versioning)
+const FORWARDED_HEADERS: &[&str] = &[
+    "x-api-key",
+    "authorization",
+    "anthropic-version",
+    "anthropic-beta",
+];
+
+/// Bounded FIFO cache keyed by SHA-256 of the counted payload
+struct CountCache {
+    entries: HashMap<String, u32>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl CountCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<u32> {
+        self.entries.get(key).copied()
+    }
+
+    fn insert(&mut self, key: String, tokens: u32) {
+        if self.entries.insert(key.clone(), tokens).is_none() {
+            self.order.push_back(key);
+            while self.order.len() > self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+    }
+}
+
+/// Cached client for the upstream count_tokens endpoint
+pub struct CountTokensClient {
+    client: reqwest::Client,
+    base_url: String,
+    fallback: CounterKind,
+    cache: Mutex<CountCache>,
+}
+
+impl CountTokensClient {
+    /// Create a client for `base_url` (e.g. "https://api.anthropic.com")
+    ///
+    /// `fallback` answers sync queries on a cache miss.
+    pub fn new(
+        client: reqwest::Client,
+        base_url: impl Into<String>,
+        fallback: CounterKind,
+        cache_size: usize,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            fallback,
+            cache: Mutex::new(CountCache::new(cache_size)),
+        }
+    }
+
+    /// Cached exact count for a request body, if previously fetched
+    pub fn cached(&self, body: &Value) -> Option<u32> {
+        let key = cache_key(&counted_payload(body));
+        self.cache.lock().ok()?.get(&key)
+    }
+
+    /// Fetch the exact input token count for a request body
+    ///
+    /// Returns the cached value when available. Auth and version headers are
+    /// copied from `headers` (the original client request).
+    pub async fn fetch(&self, body: &Value, headers: &HeaderMap) -> Result<u32> {
+        let payload = counted_payload(body);
+        let key = cache_key(&payload);
+
+        if let Some(tokens) = self.cache.lock().ok().and_then(|c| c.get(&key)) {
+            return Ok(tokens);
+        }
+
+        let url = format!("{}/v1/messages/count_tokens", self.base_url);
+        let mut req = self.client.post(&url).json(&payload);
+        for name in FORWARDED_HEADERS {
+            if let Some(value) = headers.get(*name) {
+                req = req.header(*name, value);
+            }
+        }
+
+        let response = req.send().await.context("count_tokens request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("count_tokens returned status {}", status);
+        }
+
+        let json: Value = response
+            .json()
+            .await
+            .context("Invalid count_tokens response")?;
+        let tokens = json
+            .get("input_tokens")
+            .and_then(|t| t.as_u64())
+            .context("count_tokens response missing input_tokens")? as u32;
+
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(key, tokens);
+        }
+        Ok(tokens)
+    }
+}
+
+impl TokenCounter for CountTokensClient {
+    fn name(&self) -> &'static str {
+        "api"
+    }
+
+    fn count(&self, text: &str) -> u32 {
+        // The endpoint sizes whole requests, not fragments
+        self.fallback.counter().count(text)
+    }
+
+    fn count_json(&self, json: &Value) -> u32 {
+        self.fallback.counter().count_json(json)
+    }
+
+    fn count_request(&self, body: &Value) -> u32 {
+        self.cached(body)
+            .unwrap_or_else(|| self.fallback.counter().count_request(body))
+    }
+}
+
+/// Strip a Messages request down to the fields count_tokens accepts
+fn counted_payload(body: &Value) -> Value {
+    let mut payload = serde_json::Map::new();
+    for field in COUNTED_FIELDS {
+        if let Some(value) = body.get(*field) {
+            payload.insert((*field).to_string(), value.clone());
+        }
+    }
+    Value::Object(payload)
+}
+
+fn cache_key(payload: &Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(payload.to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counted_payload_strips_unsupported_fields() {
+        let body = serde_json::json!({
+            "model": "claude-sonnet-4",
+            "max_tokens": 1024,
+            "stream": true,
+            "messages": [{"role": "user", "content": "hi"}]
+        });
+        let payload = counted_payload(&body);
+        assert!(payload.get("model").is_some());
+        assert!(payload.get("messages").is_some());
+        assert!(payload.get("max_tokens").is_none());
+        assert!(payload.get("stream").is_none());
+    }
+
+    #[test]
+    fn test_cache_key_ignores_stripped_fields() {
+        let a = serde_json::json!({"model": "m", "messages": [], "stream": true});
+        let b = serde_json::json!({"model": "m", "messages": [], "max_tokens": 5});
+        assert_eq!(
+            cache_key(&counted_payload(&a)),
+            cache_key(&counted_payload(&b))
+        );
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest() {
+        let mut cache = CountCache::new(2);
+        cache.insert("a".into(), 1);
+        cache.insert("b".into(), 2);
+        cache.insert("c".into(), 3);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(2));
+        assert_eq!(cache.get("c"), Some(3));
+    }
+
+    #[test]
+    fn test_count_request_falls_back_on_miss() {
+        let client = CountTokensClient::new(
+            reqwest::Client::new(),
+            "http://localhost",
+            CounterKind::Heuristic,
+            8,
+        );
+        let body = serde_json::json!({"messages": [{"role": "user", "content": "hello"}]});
+        assert_eq!(
+            client.count_request(&body),
+            CounterKind::Heuristic.counter().count_request(&body)
+        );
+
+        // Simulate a completed background fetch
+        let key = cache_key(&counted_payload(&body));
+        client.cache.lock().unwrap().insert(key, 42);
+        assert_eq!(client.count_request(&body), 42);
+    }
+}
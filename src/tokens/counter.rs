@@ -0,0 +1,228 @@
+//! Pluggable token counters
+//!
+//! `TokenCounter` abstracts over the different ways aspy can size content:
+//! the character-class heuristic, the bundled BPE approximation, and the
+//! upstream `count_tokens` endpoint (see `count_api`). Counters are sync so
+//! they can run inside the transformation pipeline; anything that needs the
+//! network fills a cache off the hot path instead.
+
+use serde_json::Value;
+
+/// Per-message framing overhead (role markers, turn separators)
+const MESSAGE_OVERHEAD: u32 = 3;
+
+/// Fixed overhead for every request (start-of-conversation framing)
+const REQUEST_OVERHEAD: u32 = 3;
+
+/// Trait for token counters
+///
+/// Implementations only need `name()` and `count()`. The JSON and request
+/// helpers have sensible defaults built on top of `count()`.
+pub trait TokenCounter: Send + Sync {
+    /// Short identifier used in logs and calibration reports
+    fn name(&self) -> &'static str;
+
+    /// Count tokens in plain text
+    fn count(&self, text: &str) -> u32;
+
+    /// Count tokens in a JSON value (serialized compactly)
+    fn count_json(&self, json: &Value) -> u32 {
+        self.count(&json.to_string())
+    }
+
+    /// Count the input tokens of an Anthropic Messages request body
+    ///
+    /// Sums system prompt, message content and tool definitions, plus a small
+    /// per-message framing overhead. This is what `ApiUsage` input tokens are
+    /// compared against during calibration.
+    fn count_request(&self, body: &Value) -> u32 {
+        count_request_with(self, body)
+    }
+}
+
+/// Which sync counter to use for transformer/augmenter estimates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CounterKind {
+    /// Character-class heuristic (`estimate_tokens`)
+    #[default]
+    Heuristic,
+    /// Bundled BPE tokenizer approximation
+    Bpe,
+}
+
+impl CounterKind {
+    /// Parse counter name from config
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "bpe" => Self::Bpe,
+            _ => Self::Heuristic, // Default to heuristic for unknown values
+        }
+    }
+
+    /// Convert to string for TOML serialization
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Heuristic => "heuristic",
+            Self::Bpe => "bpe",
+        }
+    }
+
+    /// Get the counter implementation for this kind
+    pub fn counter(&self) -> &'static dyn TokenCounter {
+        static HEURISTIC: HeuristicCounter = HeuristicCounter;
+        static BPE: super::bpe::BpeCounter = super::bpe::BpeCounter;
+        match self {
+            Self::Heuristic => &HEURISTIC,
+            Self::Bpe => &BPE,
+        }
+    }
+}
+
+/// Counter backed by the character-class heuristic
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicCounter;
+
+impl TokenCounter for HeuristicCounter {
+    fn name(&self) -> &'static str {
+        "heuristic"
+    }
+
+    fn count(&self, text: &str) -> u32 {
+        super::estimate_tokens(text)
+    }
+
+    fn count_json(&self, json: &Value) -> u32 {
+        super::estimate_json_tokens(json)
+    }
+}
+
+/// Shared implementation of `TokenCounter::count_request`
+fn count_request_with<C: TokenCounter + ?Sized>(counter: &C, body: &Value) -> u32 {
+    let mut total = REQUEST_OVERHEAD;
+
+    // System prompt: string or array of text blocks
+    match body.get("system") {
+        Some(Value::String(s)) => total += counter.count(s),
+        Some(Value::Array(blocks)) => {
+            for block in blocks {
+                total += count_block(counter, block);
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(messages) = body.get("messages").and_then(|m| m.as_array()) {
+        for msg in messages {
+            total += MESSAGE_OVERHEAD;
+            match msg.get("content") {
+                Some(Value::String(s)) => total += counter.count(s),
+                Some(Value::Array(blocks)) => {
+                    for block in blocks {
+                        total += count_block(counter, block);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Tool definitions are serialized into the prompt as JSON schema
+    if let Some(tools) = body.get("tools").and_then(|t| t.as_array()) {
+        for tool in tools {
+            total += counter.count_json(tool);
+        }
+    }
+
+    total
+}
+
+/// Count a single content block (text, thinking, tool_use, tool_result)
+fn count_block<C: TokenCounter + ?Sized>(counter: &C, block: &Value) -> u32 {
+    match block.get("type").and_then(|t| t.as_str()) {
+        Some("text") => block
+            .get("text")
+            .and_then(|t| t.as_str())
+            .map(|t| counter.count(t))
+            .unwrap_or(0),
+        Some("thinking") => block
+            .get("thinking")
+            .and_then(|t| t.as_str())
+            .map(|t| counter.count(t))
+            .unwrap_or(0),
+        Some("tool_use") => {
+            let name = block
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(|n| counter.count(n))
+                .unwrap_or(0);
+            let input = block
+                .get("input")
+                .map(|i| counter.count_json(i))
+                .unwrap_or(0);
+            name + input
+        }
+        Some("tool_result") => match block.get("content") {
+            Some(Value::String(s)) => counter.count(s),
+            Some(Value::Array(parts)) => parts.iter().map(|p| count_block(counter, p)).sum(),
+            _ => 0,
+        },
+        // Images, documents, redacted thinking: sized by the API, not by text.
+        // Leave them out rather than guessing wildly.
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_kind_roundtrip() {
+        for kind in [CounterKind::Heuristic, CounterKind::Bpe] {
+            assert_eq!(CounterKind::from_str(kind.as_str()), kind);
+            assert_eq!(kind.counter().name(), kind.as_str());
+        }
+        assert_eq!(CounterKind::from_str("unknown"), CounterKind::Heuristic);
+    }
+
+    #[test]
+    fn test_count_request_sums_all_parts() {
+        let counter = HeuristicCounter;
+        let body = serde_json::json!({
+            "system": [{"type": "text", "text": "You are a helpful assistant."}],
+            "messages": [
+                {"role": "user", "content": "Read the file please"},
+                {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "t1", "name": "Read", "input": {"file_path": "/tmp/a.rs"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "t1", "content": "fn main() {}"}
+                ]}
+            ],
+            "tools": [{"name": "Read", "input_schema": {"type": "object"}}]
+        });
+
+        let total = counter.count_request(&body);
+        let parts = counter.count("You are a helpful assistant.")
+            + counter.count("Read the file please")
+            + counter.count("Read")
+            + counter.count_json(&serde_json::json!({"file_path": "/tmp/a.rs"}))
+            + counter.count("fn main() {}")
+            + counter.count_json(&body["tools"][0]);
+        assert_eq!(total, parts + REQUEST_OVERHEAD + 3 * MESSAGE_OVERHEAD);
+    }
+
+    #[test]
+    fn test_count_request_ignores_images() {
+        let counter = HeuristicCounter;
+        let body = serde_json::json!({
+            "messages": [{"role": "user", "content": [
+                {"type": "image", "source": {"type": "base64", "data": "AAAA"}}
+            ]}]
+        });
+        assert_eq!(
+            counter.count_request(&body),
+            REQUEST_OVERHEAD + MESSAGE_OVERHEAD
+        );
+    }
+}
@@ -12,6 +12,17 @@
 //!
 //! For exact counts, use the API's `usage` response field.
 //!
+//! # Counters
+//!
+//! `estimate_tokens` is the default. The `TokenCounter` trait makes the
+//! counting strategy pluggable:
+//! - `HeuristicCounter`: the character-class heuristic below
+//! - `BpeCounter`: bundled BPE tokenizer approximation (`bpe.rs`)
+//! - `CountTokensClient`: upstream `/v1/messages/count_tokens`, cached and
+//!   fetched off the hot path (`count_api.rs`)
+//!
+//! `Calibration` compares estimates with actual `ApiUsage` and reports drift.
+//!
 //! # Usage
 //!
 //! ```ignore
@@ -21,6 +32,18 @@
 //! let count = estimate_tokens(text);
 //! ```
 
+mod bpe;
+mod calibration;
+mod count_api;
+mod counter;
+
+#[allow(unused_imports)]
+pub use bpe::BpeCounter;
+pub use calibration::{Calibration, CalibrationReport, SharedCalibration};
+pub use count_api::CountTokensClient;
+#[allow(unused_imports)]
+pub use counter::{CounterKind, HeuristicCounter, TokenCounter};
+
 /// Estimate token count for text content
 ///
 /// Uses a multi-factor heuristic:
@@ -0,0 +1,787 @@
+//! Tool call analytics
+//!
+//! Turns raw tool call/result pairs into actionable patterns:
+//!
+//! - **Repeated failures**: the same tool failing on similar input within a
+//!   session (Claude retrying something that keeps breaking)
+//! - **Edit mismatches**: `Edit` calls whose `old_string` wasn't found, or
+//!   matched more than once
+//! - **Bash failures**: commands that exited non-zero
+//! - **Rejections by tool**: which tools the user keeps saying no to
+//! - **Slowest calls**: individual calls, not per-tool averages
+//!
+//! Analysis is pure: callers build `ToolCallRecord`s from whatever source they
+//! have (lifestats rows, live TUI events) and call `analyze()`.
+
+use crate::events::ProxyEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+
+/// Known Claude Code rejection message patterns.
+/// These indicate the user rejected a tool call (not an actual error).
+const REJECTION_PATTERNS: &[&str] = &[
+    "The tool use was rejected (eg. if it was a file edit, the new_string was NOT written to the file)",
+    "The user doesn't want to take this action right now",
+];
+
+/// Edit tool error when `old_string` doesn't occur in the file
+const EDIT_NOT_FOUND: &str = "string to replace not found";
+
+/// Edit tool error when `old_string` occurs several times without replace_all
+const EDIT_AMBIGUOUS: &str = "matches of the string to replace";
+
+/// Maximum characters kept in signatures and previews
+const PREVIEW_CHARS: usize = 120;
+
+/// Calls kept by the live `ToolCallLog` (oldest dropped first)
+pub const LOG_CAPACITY: usize = 2000;
+
+/// Characters kept per input field in the live log (normalization collapses
+/// whitespace before cutting to `PREVIEW_CHARS`, so keep some slack)
+const LOG_INPUT_CHARS: usize = PREVIEW_CHARS * 4;
+
+/// Characters of a failed call's output kept in the live log
+const LOG_OUTPUT_CHARS: usize = 2000;
+
+/// Check if a tool result output indicates a user rejection
+pub fn is_user_rejection(output: &str) -> bool {
+    REJECTION_PATTERNS
+        .iter()
+        .any(|pattern| output.contains(pattern))
+}
+
+/// One tool call joined with its result (if any)
+#[derive(Debug, Clone)]
+pub struct ToolCallRecord {
+    pub call_id: String,
+    pub session_id: Option<String>,
+    /// RFC3339 timestamp of the call
+    pub timestamp: String,
+    pub tool_name: String,
+    /// Tool input (Null when tool I/O isn't stored)
+    pub input: Value,
+    /// Result text (None when no result yet or tool I/O isn't stored)
+    pub output: Option<String>,
+    pub duration_ms: Option<u64>,
+    /// None until the result arrives
+    pub success: Option<bool>,
+    pub is_rejection: bool,
+}
+
+impl ToolCallRecord {
+    fn failed(&self) -> bool {
+        self.success == Some(false)
+    }
+
+    /// Failed for a reason other than the user saying no
+    fn errored(&self) -> bool {
+        self.failed() && !self.is_rejection
+    }
+}
+
+/// Extract readable text from a tool result payload
+///
+/// Results are either a plain string or an array of content blocks; anything
+/// else is returned as compact JSON.
+pub fn output_text(output: &Value) -> String {
+    match output {
+        Value::String(s) => s.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Normalized input signature used to group "similar" calls
+///
+/// Picks the field that identifies what the call was trying to do (command,
+/// file, pattern, URL), then normalizes whitespace, case and numbers so that
+/// retries with trivial differences land in the same group.
+pub fn input_signature(tool_name: &str, input: &Value) -> String {
+    let field = |name: &str| input.get(name).and_then(|v| v.as_str()).unwrap_or("");
+    let key = match tool_name {
+        "Bash" => field("command").to_string(),
+        "Read" | "Write" | "Edit" | "MultiEdit" => field("file_path").to_string(),
+        "NotebookEdit" => field("notebook_path").to_string(),
+        "Grep" | "Glob" => format!("{} {}", field("pattern"), field("path")),
+        "WebFetch" => field("url").to_string(),
+        "WebSearch" => field("query").to_string(),
+        _ if input.is_null() => String::new(),
+        _ => input.to_string(),
+    };
+    normalize(&key)
+}
+
+/// Collapse whitespace, lowercase, and replace digit runs with `#`
+fn normalize(s: &str) -> String {
+    let mut out = String::with_capacity(s.len().min(PREVIEW_CHARS));
+    let mut last_space = true;
+    let mut last_digit = false;
+    for c in s.chars() {
+        if out.chars().count() >= PREVIEW_CHARS {
+            break;
+        }
+        if c.is_whitespace() {
+            if !last_space {
+                out.push(' ');
+            }
+            last_space = true;
+            last_digit = false;
+        } else if c.is_ascii_digit() {
+            if !last_digit {
+                out.push('#');
+            }
+            last_space = false;
+            last_digit = true;
+        } else {
+            out.extend(c.to_lowercase());
+            last_space = false;
+            last_digit = false;
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// Truncate to a single-line preview
+fn preview(s: &str) -> String {
+    let line = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= PREVIEW_CHARS {
+        line
+    } else {
+        let truncated: String = line.chars().take(PREVIEW_CHARS - 1).collect();
+        format!("{}…", truncated)
+    }
+}
+
+/// Parse the exit code from a Bash error ("Exit code 1" on its own line)
+pub fn bash_exit_code(output: &str) -> Option<i32> {
+    output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("Exit code")
+            .and_then(|rest| rest.trim_start_matches(':').trim().parse().ok())
+    })
+}
+
+/// Why an Edit call didn't apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditMismatchKind {
+    /// `old_string` not present in the file
+    NotFound,
+    /// `old_string` matched several times without `replace_all`
+    Ambiguous,
+}
+
+impl EditMismatchKind {
+    /// Classify an Edit error message
+    pub fn detect(output: &str) -> Option<Self> {
+        let lower = output.to_lowercase();
+        if lower.contains(EDIT_NOT_FOUND) {
+            Some(Self::NotFound)
+        } else if lower.contains(EDIT_AMBIGUOUS) {
+            Some(Self::Ambiguous)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not found",
+            Self::Ambiguous => "ambiguous",
+        }
+    }
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Insight Types
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// The same tool failing on similar input more than once in a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedFailure {
+    pub tool_name: String,
+    pub session_id: Option<String>,
+    /// Normalized input signature shared by the attempts
+    pub signature: String,
+    pub attempts: usize,
+    pub first_seen: String,
+    pub last_seen: String,
+    /// Preview of the most recent error
+    pub last_error: String,
+}
+
+/// An Edit call whose `old_string` didn't apply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditMismatch {
+    pub call_id: String,
+    pub session_id: Option<String>,
+    pub timestamp: String,
+    pub file_path: Option<String>,
+    pub kind: EditMismatchKind,
+    /// Preview of the `old_string` that failed to match
+    pub old_string: Option<String>,
+}
+
+/// A Bash command that exited non-zero
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BashFailure {
+    pub call_id: String,
+    pub session_id: Option<String>,
+    pub timestamp: String,
+    pub command: String,
+    pub exit_code: Option<i32>,
+    /// Preview of the error output
+    pub error: String,
+}
+
+/// Rejection counts for one tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolRejections {
+    pub tool_name: String,
+    pub rejections: usize,
+    pub calls: usize,
+    pub rejection_rate_pct: f64,
+}
+
+/// A single slow tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowCall {
+    pub call_id: String,
+    pub session_id: Option<String>,
+    pub timestamp: String,
+    pub tool_name: String,
+    pub duration_ms: u64,
+    /// Preview of what the call was doing
+    pub input: String,
+}
+
+/// Tool analytics report (returned by `/api/lifestats/tools/insights`)
+///
+/// Lists are capped at the requested limit; the `total_*` counts are not.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolInsights {
+    pub calls_analyzed: usize,
+    pub failed_calls: usize,
+    pub rejected_calls: usize,
+    pub repeated_failures: Vec<RepeatedFailure>,
+    pub total_edit_mismatches: usize,
+    pub edit_mismatches: Vec<EditMismatch>,
+    pub total_bash_failures: usize,
+    pub bash_failures: Vec<BashFailure>,
+    pub rejections_by_tool: Vec<ToolRejections>,
+    pub slowest_calls: Vec<SlowCall>,
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Analysis
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Analyze tool call records
+///
+/// Records may be in any order. `limit` caps each list in the report; the
+/// most recent edit mismatches and Bash failures are kept.
+pub fn analyze(records: &[ToolCallRecord], limit: usize) -> ToolInsights {
+    let mut sorted: Vec<&ToolCallRecord> = records.iter().collect();
+    sorted.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    let mut insights = ToolInsights {
+        calls_analyzed: sorted.len(),
+        ..Default::default()
+    };
+
+    let mut groups: HashMap<(Option<&str>, &str, String), RepeatedFailure> = HashMap::new();
+    let mut rejections: HashMap<&str, (usize, usize)> = HashMap::new();
+
+    for record in &sorted {
+        let entry = rejections.entry(record.tool_name.as_str()).or_default();
+        entry.1 += 1;
+
+        if record.is_rejection {
+            insights.rejected_calls += 1;
+            entry.0 += 1;
+        }
+        if record.failed() {
+            insights.failed_calls += 1;
+        }
+        if !record.errored() {
+            continue;
+        }
+
+        let output = record.output.as_deref().unwrap_or("");
+
+        // Group failures by session + tool + normalized input
+        let signature = input_signature(&record.tool_name, &record.input);
+        if !signature.is_empty() {
+            groups
+                .entry((
+                    record.session_id.as_deref(),
+                    record.tool_name.as_str(),
+                    signature.clone(),
+                ))
+                .and_modify(|g| {
+                    g.attempts += 1;
+                    g.last_seen = record.timestamp.clone();
+                    g.last_error = preview(output);
+                })
+                .or_insert_with(|| RepeatedFailure {
+                    tool_name: record.tool_name.clone(),
+                    session_id: record.session_id.clone(),
+                    signature,
+                    attempts: 1,
+                    first_seen: record.timestamp.clone(),
+                    last_seen: record.timestamp.clone(),
+                    last_error: preview(output),
+                });
+        }
+
+        let field = |name: &str| record.input.get(name).and_then(|v| v.as_str());
+
+        match record.tool_name.as_str() {
+            "Edit" | "MultiEdit" => {
+                if let Some(kind) = EditMismatchKind::detect(output) {
+                    insights.total_edit_mismatches += 1;
+                    insights.edit_mismatches.push(EditMismatch {
+                        call_id: record.call_id.clone(),
+                        session_id: record.session_id.clone(),
+                        timestamp: record.timestamp.clone(),
+                        file_path: field("file_path").map(String::from),
+                        kind,
+                        old_string: field("old_string").map(preview),
+                    });
+                }
+            }
+            "Bash" => {
+                insights.total_bash_failures += 1;
+                insights.bash_failures.push(BashFailure {
+                    call_id: record.call_id.clone(),
+                    session_id: record.session_id.clone(),
+                    timestamp: record.timestamp.clone(),
+                    command: field("command").map(preview).unwrap_or_default(),
+                    exit_code: bash_exit_code(output),
+                    error: preview(output),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // Keep the most recent mismatches and failures, newest first
+    insights.edit_mismatches.reverse();
+    insights.edit_mismatches.truncate(limit);
+    insights.bash_failures.reverse();
+    insights.bash_failures.truncate(limit);
+
+    let mut repeated: Vec<RepeatedFailure> =
+        groups.into_values().filter(|g| g.attempts >= 2).collect();
+    repeated.sort_by(|a, b| {
+        b.attempts
+            .cmp(&a.attempts)
+            .then_with(|| b.last_seen.cmp(&a.last_seen))
+    });
+    repeated.truncate(limit);
+    insights.repeated_failures = repeated;
+
+    let mut by_tool: Vec<ToolRejections> = rejections
+        .into_iter()
+        .filter(|(_, (rejected, _))| *rejected > 0)
+        .map(|(tool, (rejected, calls))| ToolRejections {
+            tool_name: tool.to_string(),
+            rejections: rejected,
+            calls,
+            rejection_rate_pct: rejected as f64 / calls as f64 * 100.0,
+        })
+        .collect();
+    by_tool.sort_by(|a, b| {
+        b.rejections
+            .cmp(&a.rejections)
+            .then_with(|| a.tool_name.cmp(&b.tool_name))
+    });
+    by_tool.truncate(limit);
+    insights.rejections_by_tool = by_tool;
+
+    let mut slowest: Vec<&ToolCallRecord> = sorted
+        .iter()
+        .copied()
+        .filter(|r| r.duration_ms.is_some())
+        .collect();
+    slowest.sort_by_key(|r| std::cmp::Reverse(r.duration_ms));
+    insights.slowest_calls = slowest
+        .into_iter()
+        .take(limit)
+        .map(|r| SlowCall {
+            call_id: r.call_id.clone(),
+            session_id: r.session_id.clone(),
+            timestamp: r.timestamp.clone(),
+            tool_name: r.tool_name.clone(),
+            duration_ms: r.duration_ms.unwrap_or(0),
+            input: preview(&input_signature(&r.tool_name, &r.input)),
+        })
+        .collect();
+
+    insights
+}
+
+// ═════════════════════════════════════════════════════════════════════════════
+// Live Event Log
+// ═════════════════════════════════════════════════════════════════════════════
+
+/// Builds `ToolCallRecord`s from live proxy events
+///
+/// Used by the TUI, which sees calls and results as separate events. Only the
+/// most recent [`LOG_CAPACITY`] calls are kept, and each keeps just what
+/// `analyze()` reads: the identifying input fields and the start of a failed
+/// call's output.
+#[derive(Debug, Default)]
+pub struct ToolCallLog {
+    records: VecDeque<ToolCallRecord>,
+    /// Call id → sequence number (position = seq - evicted)
+    index: HashMap<String, u64>,
+    evicted: u64,
+}
+
+impl ToolCallLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a ToolCall or ToolResult event
+    ///
+    /// Returns true when a result was applied (insights may have changed).
+    pub fn record(&mut self, event: &ProxyEvent, session_id: Option<&str>) -> bool {
+        match event {
+            ProxyEvent::ToolCall {
+                id,
+                timestamp,
+                tool_name,
+                input,
+            } => {
+                self.push(ToolCallRecord {
+                    call_id: id.clone(),
+                    session_id: session_id.map(String::from),
+                    timestamp: timestamp.to_rfc3339(),
+                    tool_name: tool_name.clone(),
+                    input: compact_input(tool_name, input),
+                    output: None,
+                    duration_ms: None,
+                    success: None,
+                    is_rejection: false,
+                });
+                false
+            }
+            ProxyEvent::ToolResult {
+                id,
+                timestamp,
+                tool_name,
+                output,
+                duration,
+                success,
+            } => {
+                let position = self.index.get(id).map(|&seq| (seq - self.evicted) as usize);
+                let position = match position {
+                    Some(position) => position,
+                    None => {
+                        // Result without a recorded call (e.g. aspy started mid-turn)
+                        self.push(ToolCallRecord {
+                            call_id: id.clone(),
+                            session_id: session_id.map(String::from),
+                            timestamp: timestamp.to_rfc3339(),
+                            tool_name: tool_name.clone(),
+                            input: Value::Null,
+                            output: None,
+                            duration_ms: None,
+                            success: None,
+                            is_rejection: false,
+                        });
+                        self.records.len() - 1
+                    }
+                };
+                let record = &mut self.records[position];
+                // Successful output is never analyzed, so don't keep it
+                if !success {
+                    let text = output_text(output);
+                    record.is_rejection = is_user_rejection(&text);
+                    record.output = Some(text.chars().take(LOG_OUTPUT_CHARS).collect());
+                }
+                record.duration_ms = Some(duration.as_millis() as u64);
+                record.success = Some(*success);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn push(&mut self, record: ToolCallRecord) {
+        if self.records.len() == LOG_CAPACITY {
+            if let Some(oldest) = self.records.pop_front() {
+                self.index.remove(&oldest.call_id);
+                self.evicted += 1;
+            }
+        }
+        let seq = self.evicted + self.records.len() as u64;
+        self.index.insert(record.call_id.clone(), seq);
+        self.records.push_back(record);
+    }
+
+    /// Recorded calls, oldest first
+    pub fn records(&mut self) -> &[ToolCallRecord] {
+        self.records.make_contiguous()
+    }
+}
+
+/// The parts of a tool input `analyze()` reads
+///
+/// Known tools keep their identifying fields (shortened); other tools keep
+/// small inputs whole and large ones as the start of their JSON text.
+fn compact_input(tool_name: &str, input: &Value) -> Value {
+    const FIELDS: &[&str] = &[
+        "command",
+        "file_path",
+        "notebook_path",
+        "pattern",
+        "path",
+        "url",
+        "query",
+        "old_string",
+    ];
+    let known = matches!(
+        tool_name,
+        "Bash"
+            | "Read"
+            | "Write"
+            | "Edit"
+            | "MultiEdit"
+            | "NotebookEdit"
+            | "Grep"
+            | "Glob"
+            | "WebFetch"
+            | "WebSearch"
+    );
+    if known {
+        let fields = FIELDS
+            .iter()
+            .filter_map(|&name| {
+                let value = input.get(name)?.as_str()?;
+                let short: String = value.chars().take(LOG_INPUT_CHARS).collect();
+                Some((name.to_string(), Value::String(short)))
+            })
+            .collect();
+        return Value::Object(fields);
+    }
+    let text = input.to_string();
+    if text.len() <= LOG_INPUT_CHARS {
+        input.clone()
+    } else {
+        Value::String(text.chars().take(LOG_INPUT_CHARS).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(
+        id: &str,
+        ts: &str,
+        tool: &str,
+        input: Value,
+        output: &str,
+        success: bool,
+    ) -> ToolCallRecord {
+        ToolCallRecord {
+            call_id: id.to_string(),
+            session_id: Some("s1".to_string()),
+            timestamp: ts.to_string(),
+            tool_name: tool.to_string(),
+            input,
+            output: Some(output.to_string()),
+            duration_ms: Some(10),
+            success: Some(success),
+            is_rejection: !success && is_user_rejection(output),
+        }
+    }
+
+    #[test]
+    fn test_input_signature_normalizes() {
+        let a = input_signature("Bash", &json!({"command": "cargo  test --test foo_123"}));
+        let b = input_signature("Bash", &json!({"command": "Cargo test --test foo_456"}));
+        assert_eq!(a, b);
+        assert_eq!(a, "cargo test --test foo_#");
+
+        let edit = input_signature(
+            "Edit",
+            &json!({"file_path": "/src/main.rs", "old_string": "x"}),
+        );
+        assert_eq!(edit, "/src/main.rs");
+    }
+
+    #[test]
+    fn test_bash_exit_code() {
+        assert_eq!(
+            bash_exit_code("Exit code 101\nerror: test failed"),
+            Some(101)
+        );
+        assert_eq!(bash_exit_code("some output\nExit code: 2"), Some(2));
+        assert_eq!(bash_exit_code("all good"), None);
+    }
+
+    #[test]
+    fn test_edit_mismatch_detection() {
+        assert_eq!(
+            EditMismatchKind::detect("String to replace not found in file.\nString: foo"),
+            Some(EditMismatchKind::NotFound)
+        );
+        assert_eq!(
+            EditMismatchKind::detect(
+                "Found 3 matches of the string to replace, but replace_all is false."
+            ),
+            Some(EditMismatchKind::Ambiguous)
+        );
+        assert_eq!(EditMismatchKind::detect("File has not been read yet"), None);
+    }
+
+    #[test]
+    fn test_analyze_patterns() {
+        let records = vec![
+            record(
+                "1",
+                "2025-01-01T00:00:01Z",
+                "Bash",
+                json!({"command": "cargo test"}),
+                "Exit code 101\nfailures",
+                false,
+            ),
+            record(
+                "2",
+                "2025-01-01T00:00:02Z",
+                "Edit",
+                json!({"file_path": "/a.rs", "old_string": "fn foo()"}),
+                "String to replace not found in file.",
+                false,
+            ),
+            record(
+                "3",
+                "2025-01-01T00:00:03Z",
+                "Bash",
+                json!({"command": "cargo  test"}),
+                "Exit code 101\nstill failing",
+                false,
+            ),
+            record(
+                "4",
+                "2025-01-01T00:00:04Z",
+                "Write",
+                json!({"file_path": "/b.rs"}),
+                REJECTION_PATTERNS[1],
+                false,
+            ),
+            record(
+                "5",
+                "2025-01-01T00:00:05Z",
+                "Read",
+                json!({"file_path": "/a.rs"}),
+                "ok",
+                true,
+            ),
+        ];
+
+        let insights = analyze(&records, 10);
+        assert_eq!(insights.calls_analyzed, 5);
+        assert_eq!(insights.failed_calls, 4);
+        assert_eq!(insights.rejected_calls, 1);
+
+        assert_eq!(insights.repeated_failures.len(), 1);
+        let repeated = &insights.repeated_failures[0];
+        assert_eq!(repeated.tool_name, "Bash");
+        assert_eq!(repeated.attempts, 2);
+        assert_eq!(repeated.last_error, "Exit code 101 still failing");
+
+        assert_eq!(insights.total_edit_mismatches, 1);
+        assert_eq!(insights.edit_mismatches[0].kind, EditMismatchKind::NotFound);
+        assert_eq!(
+            insights.edit_mismatches[0].old_string.as_deref(),
+            Some("fn foo()")
+        );
+
+        // Newest first
+        assert_eq!(insights.total_bash_failures, 2);
+        assert_eq!(insights.bash_failures[0].call_id, "3");
+        assert_eq!(insights.bash_failures[0].exit_code, Some(101));
+
+        assert_eq!(insights.rejections_by_tool.len(), 1);
+        assert_eq!(insights.rejections_by_tool[0].tool_name, "Write");
+        assert!((insights.rejections_by_tool[0].rejection_rate_pct - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tool_call_log_pairs_results() {
+        use chrono::Utc;
+        use std::time::Duration;
+
+        let mut log = ToolCallLog::new();
+        let call = ProxyEvent::ToolCall {
+            id: "t1".into(),
+            timestamp: Utc::now(),
+            tool_name: "Bash".into(),
+            input: json!({"command": "ls"}),
+        };
+        assert!(!log.record(&call, Some("s1")));
+
+        let result = ProxyEvent::ToolResult {
+            id: "t1".into(),
+            timestamp: Utc::now(),
+            tool_name: "Bash".into(),
+            output: json!([{"type": "text", "text": "Exit code 2"}]),
+            duration: Duration::from_millis(1500),
+            success: false,
+        };
+        assert!(log.record(&result, Some("s1")));
+
+        let records = log.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].duration_ms, Some(1500));
+        assert_eq!(records[0].output.as_deref(), Some("Exit code 2"));
+        assert_eq!(records[0].success, Some(false));
+    }
+
+    #[test]
+    fn test_tool_call_log_is_capped() {
+        use chrono::Utc;
+        use std::time::Duration;
+
+        let mut log = ToolCallLog::new();
+        for i in 0..LOG_CAPACITY + 5 {
+            let call = ProxyEvent::ToolCall {
+                id: format!("t{}", i),
+                timestamp: Utc::now(),
+                tool_name: "Read".into(),
+                input: json!({"file_path": "/a.rs", "content": "x".repeat(10_000)}),
+            };
+            log.record(&call, None);
+        }
+        assert_eq!(log.records().len(), LOG_CAPACITY);
+        assert_eq!(log.records()[0].call_id, "t5");
+        // Only the fields analyze() reads are kept
+        assert_eq!(log.records()[0].input, json!({"file_path": "/a.rs"}));
+
+        // Results still find their calls after eviction
+        let result = ProxyEvent::ToolResult {
+            id: format!("t{}", LOG_CAPACITY + 4),
+            timestamp: Utc::now(),
+            tool_name: "Read".into(),
+            output: json!("ok"),
+            duration: Duration::from_millis(5),
+            success: true,
+        };
+        assert!(log.record(&result, None));
+        let last = log.records().last().unwrap();
+        assert_eq!((last.success, last.output.as_deref()), (Some(true), None));
+        assert_eq!(log.records().len(), LOG_CAPACITY);
+    }
+}
@@ -5,31 +5,61 @@
 
 use super::components::detail_panel::DetailPanel;
 use super::components::events_panel::EventsPanel;
+use super::components::history_panel::{HistoryPanel, Replay};
+use super::components::lifetime_panel::{LifetimeData, LifetimePanel};
 use super::components::logs_panel::LogsPanel;
-use super::components::settings_panel::SettingsPanel;
+use super::components::settings_panel::{SettingsFocus, SettingsPanel};
 // Re-export SettingsCategory (used in settings_apply_option)
 pub use super::components::settings_panel::SettingsCategory;
 use super::components::thinking_panel::ThinkingPanel;
+use super::components::timeline_panel::TimelinePanel;
 use super::components::Toast;
 use super::input::InputHandler;
+use super::keymap::{Action, Keymap};
 use super::modal::Modal;
-use super::preset::{get_preset, Preset};
+use super::mouse::{inner_row, MouseState, Target};
+use super::preset::{export_preset, get_preset, list_presets, load_preset, Preset, PresetEntry};
 use super::scroll::FocusablePanel;
+use super::search::{self, SearchState};
 use super::streaming::StreamingStateMachine;
 use super::traits::{Handled, Interactive, Zoomable};
+use super::views::{STATS_LIFETIME_TAB, STATS_TAB_COUNT, STATS_TIMELINE_TAB};
 use crate::config::Config;
 use crate::events::{ProxyEvent, Stats, TrackedEvent};
 use crate::logging::LogBuffer;
+use crate::pipeline::alerts::AlertFeed;
+use crate::pipeline::file_activity::FileActivityLog;
+use crate::pipeline::lifestats_query::{LifestatsQuery, SearchMode, SessionSummary};
+use crate::pipeline::todos::{TodoBoard, TodoItem};
 use crate::proxy::sessions::ContextState;
 use crate::theme::{Theme, ThemeConfig};
+use crate::timeline::Timeline;
+use crate::tool_analytics::{analyze, ToolCallLog, ToolInsights};
 use crate::StreamingThinking;
-use crossterm::event::KeyEvent;
+use std::cell::RefCell;
 use std::collections::HashSet;
-use std::time::SystemTime;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long alert toasts stay up (longer than the 2s action toasts)
+const ALERT_TOAST_DURATION: Duration = Duration::from_secs(6);
 
 // Re-export StreamingState for backward compatibility with ui.rs
 pub use super::streaming::StreamingState;
 
+/// Entries per list in the Stats > Tools insights
+const TOOL_INSIGHTS_LIMIT: usize = 8;
+
+/// Minimum time between tool insight recomputations
+const TOOL_INSIGHTS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lifestats matches shown by history search
+const HISTORY_SEARCH_LIMIT: usize = 25;
+
+/// Past sessions listed in the History view
+const HISTORY_SESSION_LIMIT: usize = 500;
+
 /// Active view in the TUI
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum View {
@@ -37,11 +67,32 @@ pub enum View {
     Events,
     Stats,
     Settings,
+    History,
 }
 
 // Note: SettingsCategory, SettingsFocus live in components/settings_panel.rs
 // SettingsFocus used only by component; SettingsCategory re-exported for settings_apply_option
 
+/// What a `filtered_indices` result was computed for
+#[derive(Debug, Clone, PartialEq)]
+struct FilterKey {
+    query: Option<String>,
+    session: Option<String>,
+    /// Replayed session id (None for live events)
+    replay: Option<String>,
+    events: usize,
+}
+
+impl FilterKey {
+    /// Same filter over a longer event list (events are only ever appended)
+    fn extends_to(&self, next: &Self) -> bool {
+        self.query == next.query
+            && self.session == next.session
+            && self.replay == next.replay
+            && self.events <= next.events
+    }
+}
+
 /// Topic info extracted from Haiku's summarization
 #[derive(Debug, Clone, Default)]
 pub struct TopicInfo {
@@ -85,12 +136,36 @@ pub struct App {
     /// All proxy events received this session (tool calls, responses, etc.)
     pub events: Vec<TrackedEvent>,
 
+    /// Lowercased search text of each event, parallel to `events`
+    event_text: Vec<String>,
+
+    /// Last `filtered_indices` result and what it was computed for
+    filter_cache: RefCell<Option<(FilterKey, Arc<[usize]>)>>,
+
     /// Accumulated statistics (tokens, costs, tool calls, etc.)
     pub stats: Stats,
 
     /// Context window state for TUI display (mirrors selected session or global)
     pub context_state: ContextState,
 
+    /// Tool call/result pairs for analytics
+    tool_log: ToolCallLog,
+
+    /// Tool failure patterns (recomputed on tick after results arrive)
+    pub tool_insights: ToolInsights,
+
+    /// A result arrived since `tool_insights` was computed
+    tool_insights_dirty: bool,
+
+    /// When `tool_insights` was last computed
+    tool_insights_at: Option<Instant>,
+
+    /// Files touched by tool calls (Stats > Files)
+    pub file_activity: FileActivityLog,
+
+    /// Latest TodoWrite plan per session (Events > Plan)
+    pub todo_board: TodoBoard,
+
     /// Shared statistics (synced for HTTP API access)
     shared_stats: crate::proxy::api::SharedStats,
 
@@ -120,7 +195,7 @@ pub struct App {
     // Navigation & Selection
     // Where the user is in the UI and what they're looking at
     // ─────────────────────────────────────────────────────────────────────────
-    /// Active view (Events, Stats, Settings)
+    /// Active view (Events, Stats, Settings, History)
     pub view: View,
 
     /// Currently focused panel (receives keyboard input)
@@ -132,12 +207,24 @@ pub struct App {
     /// Toast notification (copy confirmation, errors) - auto-dismisses
     pub toast: Option<Toast>,
 
-    /// Selected tab in Stats view (0=Overview, 1=Models, 2=Tokens, 3=Tools, 4=Trends)
+    /// Selected tab in Stats view (0=Overview, 1=Models, 2=Tokens, 3=Tools, 4=Trends, 5=Files)
     pub stats_selected_tab: usize,
 
     /// Whether the focused panel is currently zoomed (expanded to full content area)
     pub zoomed: bool,
 
+    /// Events view split dragged with the mouse (first panel's percent, until the preset changes)
+    pub split_percent: Option<u16>,
+
+    /// Events search prompt and applied query (`/`)
+    pub search: SearchState,
+
+    /// Past session opened from the History view (Events view shows it read-only)
+    pub replay: Option<Replay>,
+
+    /// Request id marked as the diff base (`m`)
+    pub diff_base: Option<String>,
+
     // ─────────────────────────────────────────────────────────────────────────
     // Appearance & Animation
     // Visual presentation: theme, layout, streaming indicators
@@ -154,6 +241,9 @@ pub struct App {
     /// Layout preset (panel arrangement: classic, reasoning, debug)
     pub preset: Preset,
 
+    /// Presets listed in Settings (built-in + ~/.config/aspy/presets, reloaded on entry)
+    pub presets: Vec<PresetEntry>,
+
     /// Animation frame counter (for spinners, dots)
     pub animation_frame: usize,
 
@@ -161,9 +251,15 @@ pub struct App {
     // Input Handling
     // Keyboard event processing and debouncing
     // ─────────────────────────────────────────────────────────────────────────
+    /// Active key bindings (defaults + [keybindings])
+    pub keymap: Keymap,
+
     /// Input handler (tracks pressed keys, prevents double-triggers)
     input_handler: InputHandler,
 
+    /// Clickable areas from the last frame, double-click and drag state
+    pub mouse: MouseState,
+
     // ─────────────────────────────────────────────────────────────────────────
     // Delegated Subsystems
     // Complex state that's managed by dedicated structs (component pattern)
@@ -184,12 +280,30 @@ pub struct App {
     /// This includes navigation, theme selection, and layout preset selection
     pub settings_panel: SettingsPanel,
 
+    /// History panel component (past session list, sort, filter)
+    pub history_panel: HistoryPanel,
+
+    /// Stats Timeline tab (zoom, pan, row scroll)
+    pub timeline_panel: TimelinePanel,
+
+    /// Stats Lifetime tab (latest lifestats snapshot, load in flight)
+    pub lifetime_panel: LifetimePanel,
+
     /// Streaming state machine (idle → thinking → generating)
     streaming_sm: StreamingStateMachine,
 
     /// Real-time streaming thinking content (shared with proxy)
     pub streaming_thinking: Option<StreamingThinking>,
 
+    /// Fired alerts waiting to be shown (shared with the alerts processor)
+    pub alert_feed: Option<AlertFeed>,
+
+    /// Lifestats connection for history search and the History view (opened on first use)
+    history: Option<Arc<LifestatsQuery>>,
+
+    /// Past session being loaded for replay on a background thread
+    replay_pending: Option<Receiver<Result<Replay, String>>>,
+
     // ─────────────────────────────────────────────────────────────────────────
     // Lifecycle
     // Application lifecycle state
@@ -235,14 +349,34 @@ impl App {
         let theme = Theme::by_name_with_config(&config.theme, &theme_config);
         let preset = get_preset(&config.preset);
 
+        // Bad or conflicting bindings don't stop startup - log them and say so
+        let (keymap, keymap_problems) = Keymap::from_config(&config.keybindings);
+        for problem in &keymap_problems {
+            tracing::warn!("{}", problem);
+        }
+        let toast = match keymap_problems.len() {
+            0 => None,
+            1 => Some(format!("⚠ {}", keymap_problems[0])),
+            n => Some(format!("⚠ {} keybinding problems - see logs", n)),
+        }
+        .map(|message| Toast::new(message).with_duration(ALERT_TOAST_DURATION));
+
         // Initialize context state with limit from config
         let context_state = ContextState::with_limit(config.context_limit);
 
         Self {
             events: Vec::new(),
+            event_text: Vec::new(),
+            filter_cache: RefCell::new(None),
             should_quit: false,
             stats: Stats::default(),
             context_state,
+            tool_log: ToolCallLog::new(),
+            tool_insights: ToolInsights::default(),
+            tool_insights_dirty: false,
+            tool_insights_at: None,
+            file_activity: FileActivityLog::new(),
+            todo_board: TodoBoard::new(),
             shared_stats,
             shared_events,
             start_time: SystemTime::now(),
@@ -251,7 +385,12 @@ impl App {
             thinking_panel: ThinkingPanel::new(),
             detail_panel: DetailPanel::new(),
             settings_panel: SettingsPanel::new(),
-            input_handler: InputHandler::default(),
+            history_panel: HistoryPanel::new(),
+            timeline_panel: TimelinePanel::default(),
+            lifetime_panel: LifetimePanel::default(),
+            input_handler: InputHandler::for_keymap(&keymap),
+            keymap,
+            mouse: MouseState::default(),
             log_buffer,
             active_sessions: Vec::new(),
             selected_session: None,
@@ -261,15 +400,23 @@ impl App {
             focused: FocusablePanel::default(),
             stats_selected_tab: 0, // Default to Overview tab
             zoomed: false,
+            split_percent: None,
+            search: SearchState::default(),
+            replay: None,
+            diff_base: None,
             theme,
             theme_config,
             config,
             streaming_sm: StreamingStateMachine::new(),
             animation_frame: 0,
             streaming_thinking: None,
+            alert_feed: None,
+            history: None,
+            replay_pending: None,
             modal: None,
-            toast: None,
+            toast,
             preset,
+            presets: Vec::new(),
         }
     }
 
@@ -307,7 +454,12 @@ impl App {
         let session = self.effective_session();
 
         // First try streaming content (real-time) for this session
-        if let Some(ref streaming) = self.streaming_thinking {
+        // A replayed session is finished - only its stored thinking applies
+        if let Some(streaming) = self
+            .streaming_thinking
+            .as_ref()
+            .filter(|_| self.replay.is_none())
+        {
             if let Ok(guard) = streaming.lock() {
                 // Try session-specific content first
                 if let Some(session_key) = session {
@@ -328,8 +480,8 @@ impl App {
             }
         }
 
-        // Fall back to last completed thinking from filtered events (session-aware)
-        self.filtered_events()
+        // Fall back to last completed thinking from the session's events
+        self.session_events()
             .iter()
             .rev() // Search from most recent
             .find_map(|tracked| {
@@ -352,12 +504,20 @@ impl App {
         self.modal = None; // Close any modal when switching views
         self.focused = FocusablePanel::Events;
         self.detail_panel.reset();
+        self.replay = None; // Leaving the replayed session returns to live events
+
+        // When entering History, reload the session list
+        if view == View::History {
+            self.refresh_history();
+        }
 
         // When entering Settings, scroll theme list to current theme
+        // and pick up preset files added or edited since last time
         if view == View::Settings {
             let themes = Theme::list_available();
             self.settings_panel
                 .scroll_to_current_theme(&themes, &self.theme.name);
+            self.refresh_presets();
         }
     }
 
@@ -418,19 +578,143 @@ impl App {
                 }
             }
             SettingsCategory::Layout => {
-                // Apply selected preset
-                let preset_names = ["classic", "reasoning", "debug"];
-                if let Some(&preset_name) =
-                    preset_names.get(self.settings_panel.layout_option_index)
-                {
-                    self.preset = get_preset(preset_name);
-                    self.config.preset = preset_name.to_string();
-                    self.settings_panel.mark_dirty();
+                // Apply selected preset (user files are re-read, so edits apply)
+                let Some(entry) = self.presets.get(self.settings_panel.layout_option_index) else {
+                    return;
+                };
+                match load_preset(&entry.name) {
+                    Ok(preset) => {
+                        self.config.preset = preset.name.clone();
+                        self.preset = preset;
+                        self.split_percent = None;
+                        // Keep focus on a panel the new layout can reach
+                        if !self.preset.focus_order.contains(&self.focused) {
+                            self.focused = FocusablePanel::Events;
+                        }
+                        self.settings_panel.mark_dirty();
+                    }
+                    Err(e) => self.show_toast(format!("✗ {:#}", e)),
                 }
+                self.refresh_presets();
             }
         }
     }
 
+    /// Reload the Settings preset list
+    pub fn refresh_presets(&mut self) {
+        self.presets = list_presets();
+        self.settings_panel.sync_presets(self.presets.len());
+    }
+
+    /// Waterfall of the current session (or the replayed one)
+    pub fn session_timeline(&self) -> Timeline {
+        let mut timeline =
+            Timeline::from_events(self.session_events().into_iter().map(|e| &e.event));
+        // A live session's unfinished tool calls are still running
+        if self.replay.is_none() {
+            timeline.extend_open(chrono::Utc::now());
+        }
+        timeline
+    }
+
+    /// Write the session timeline as Chrome trace JSON to `<log_dir>/traces/`
+    pub fn export_trace(&mut self) {
+        let timeline = self.session_timeline();
+        if timeline.spans.is_empty() {
+            self.show_toast("Nothing to export yet");
+            return;
+        }
+        let session = match &self.replay {
+            Some(replay) => replay.session.id.clone(),
+            None => self.effective_session().unwrap_or("all").to_string(),
+        };
+        let session: String = session
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let dir = self.config.log_dir.join("traces");
+        let path = dir.join(format!(
+            "trace-{}-{}.json",
+            session,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let written = std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&path, timeline.to_chrome_trace().to_string()));
+        match written {
+            Ok(()) => self.show_toast(format!("✓ Exported {}", path.display())),
+            Err(e) => self.show_toast(format!("✗ Export failed: {}", e)),
+        }
+    }
+
+    /// Recompute tool failure patterns if results arrived (throttled)
+    ///
+    /// Called on every tick, so a burst of results costs one analysis.
+    pub fn refresh_tool_insights(&mut self) {
+        let due = self
+            .tool_insights_at
+            .is_none_or(|at| at.elapsed() >= TOOL_INSIGHTS_INTERVAL);
+        if self.tool_insights_dirty && due {
+            self.tool_insights = analyze(self.tool_log.records(), TOOL_INSIGHTS_LIMIT);
+            self.tool_insights_dirty = false;
+            self.tool_insights_at = Some(Instant::now());
+        }
+    }
+
+    /// Collect a finished lifetime load, and start one when the visible tab is stale
+    ///
+    /// Called on every tick; queries run on a background thread.
+    pub fn poll_lifetime(&mut self) {
+        self.lifetime_panel.poll();
+        if self.view == View::Stats
+            && self.stats_selected_tab == STATS_LIFETIME_TAB
+            && !self.lifetime_panel.is_loading()
+            && self.lifetime_panel.is_stale()
+        {
+            self.refresh_lifetime();
+        }
+    }
+
+    /// Load lifetime stats from the lifestats database off the UI thread
+    fn refresh_lifetime(&mut self) {
+        if !self.config.lifestats.enabled {
+            self.lifetime_panel.error =
+                Some("disabled ([lifestats] enabled = false in config)".to_string());
+            return;
+        }
+        let db_path = self.config.lifestats.db_path.clone();
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("lifetime-stats".into())
+            .spawn(move || {
+                // Opening the pool would create an empty database
+                let result = if db_path.exists() {
+                    LifestatsQuery::new(&db_path)
+                        .and_then(|query| LifetimeData::load(&query))
+                        .map_err(|e| format!("{:#}", e))
+                } else {
+                    Err(format!("no database at {}", db_path.display()))
+                };
+                let _ = tx.send(result);
+            });
+        match spawned {
+            Ok(_) => self.lifetime_panel.start_load(rx),
+            Err(e) => self.lifetime_panel.error = Some(e.to_string()),
+        }
+    }
+
+    /// Save the current layout (including a dragged split) as a user preset
+    pub fn export_layout(&mut self) {
+        let mut preset = self.preset.clone();
+        if let Some(percent) = self.split_percent {
+            preset = preset.with_split(percent);
+        }
+        match export_preset(&preset) {
+            Ok(path) => self.show_toast(format!("✓ Exported {}", path.display())),
+            Err(e) => self.show_toast(format!("✗ Export failed: {:#}", e)),
+        }
+        self.refresh_presets();
+    }
+
     /// Save settings to config file if any changes were made
     pub fn save_settings_if_dirty(&mut self) {
         if self.settings_panel.is_dirty() {
@@ -475,11 +759,51 @@ impl App {
         // First, populate historical ring buffers for sparklines
         self.stats.update_history(event);
 
+        // Pair tool calls with results; failure patterns refresh on tick
+        if self
+            .tool_log
+            .record(event, tracked_event.session_id.as_deref())
+        {
+            self.tool_insights_dirty = true;
+        }
+
+        self.file_activity
+            .record(event, tracked_event.session_id.as_deref());
+        self.todo_board
+            .record(event, tracked_event.user_id.as_deref());
+
         // Then, handle aggregate stats and TUI-specific state updates
         match event {
-            ProxyEvent::Request { .. } => {
+            ProxyEvent::ConfigReloaded {
+                success,
+                message,
+                theme,
+                use_theme_background,
+                ..
+            } => {
+                if let Some(background) = use_theme_background {
+                    self.config.use_theme_background = *background;
+                    self.theme_config.use_theme_background = *background;
+                }
+                if let Some(name) = theme {
+                    self.config.theme = name.clone();
+                }
+                if theme.is_some() || use_theme_background.is_some() {
+                    self.theme = Theme::by_name_with_config(&self.config.theme, &self.theme_config);
+                }
+                if *success {
+                    self.show_toast(format!("⟳ Config reloaded: {}", message));
+                } else {
+                    let headline = message.lines().next().unwrap_or_default();
+                    self.show_toast(format!("✗ Config reload failed: {}", headline));
+                }
+            }
+            ProxyEvent::Request { body, .. } => {
                 self.stats.total_requests += 1;
                 self.streaming_sm.on_request();
+                if let Some(body) = body {
+                    self.context_state.update_from_request(body);
+                }
             }
             ProxyEvent::Response {
                 status, ttfb, body, ..
@@ -609,6 +933,8 @@ impl App {
         }
 
         // Store the full TrackedEvent (includes user_id, session_id for filtering)
+        self.event_text
+            .push(search::searchable_text(&tracked_event));
         self.events.push(tracked_event);
         // In auto-follow mode (None), we don't need to track selection
         // The view will always show the latest events
@@ -717,11 +1043,31 @@ impl App {
             .or_else(|| self.active_sessions.first().map(|s| s.as_str()))
     }
 
-    /// Get filtered events for current session
+    /// Events behind the Events view: the replayed session's, or live ones
+    pub fn shown_events(&self) -> &[TrackedEvent] {
+        match &self.replay {
+            Some(replay) => &replay.events,
+            None => &self.events,
+        }
+    }
+
+    /// TodoWrite plan for the Plan panel (replayed session or selected session)
+    pub fn current_plan(&self) -> Option<&[TodoItem]> {
+        match &self.replay {
+            Some(replay) => Some(replay.plan.as_slice()).filter(|items| !items.is_empty()),
+            None => self.todo_board.plan(self.effective_session()),
+        }
+    }
+
+    /// Get events for current session
     ///
     /// Returns references to events matching the currently selected session.
-    /// If no session is selected, returns all events.
-    pub fn filtered_events(&self) -> Vec<&TrackedEvent> {
+    /// If no session is selected, returns all events. A replayed session's
+    /// events are returned as-is.
+    pub fn session_events(&self) -> Vec<&TrackedEvent> {
+        if let Some(replay) = &self.replay {
+            return replay.events.iter().collect();
+        }
         match self.effective_session() {
             Some(session) => self
                 .events
@@ -732,6 +1078,72 @@ impl App {
         }
     }
 
+    /// Search text parallel to `shown_events()`
+    fn shown_text(&self) -> &[String] {
+        match &self.replay {
+            Some(replay) => &replay.search_text,
+            None => &self.event_text,
+        }
+    }
+
+    /// Indices into `shown_events()` of the Events list (session + search query)
+    ///
+    /// Memoized: reused while the query, session and source are unchanged, and
+    /// extended with just the new events when only the event count grew.
+    fn filtered_indices(&self) -> Arc<[usize]> {
+        let session = self.effective_session().filter(|_| self.replay.is_none());
+        let query = self.search.query.as_ref();
+        let key = FilterKey {
+            query: query.map(|q| q.raw.clone()),
+            session: session.map(String::from),
+            replay: self.replay.as_ref().map(|r| r.session.id.clone()),
+            events: self.shown_events().len(),
+        };
+
+        let mut cache = self.filter_cache.borrow_mut();
+        let (start, mut indices) = match cache.as_ref() {
+            Some((cached, indices)) if *cached == key => return indices.clone(),
+            Some((cached, indices)) if cached.extends_to(&key) => (cached.events, indices.to_vec()),
+            _ => (0, Vec::new()),
+        };
+
+        let events = self.shown_events();
+        let text = self.shown_text();
+        indices.extend((start..events.len()).filter(|&i| {
+            (session.is_none() || events[i].user_id.as_deref() == session)
+                && query.is_none_or(|q| q.matches(&events[i], &text[i]))
+        }));
+        let indices: Arc<[usize]> = indices.into();
+        *cache = Some((key, indices.clone()));
+        indices
+    }
+
+    /// Get the events shown in the Events list
+    ///
+    /// The current session's events, narrowed by the search query if one is
+    /// applied. `events_panel.selected` indexes into this list.
+    pub fn filtered_events(&self) -> Vec<&TrackedEvent> {
+        let events = self.shown_events();
+        self.filtered_indices()
+            .iter()
+            .map(|&i| &events[i])
+            .collect()
+    }
+
+    /// Number of entries in the Events list (no per-event work when memoized)
+    pub fn filtered_count(&self) -> usize {
+        self.filtered_indices().len()
+    }
+
+    /// Index into `shown_events()` of the selected list entry (latest in auto-follow)
+    pub fn selected_event_index(&self) -> Option<usize> {
+        let indices = self.filtered_indices();
+        match self.events_panel.selected {
+            Some(selected) => indices.get(selected).copied(),
+            None => indices.last().copied(),
+        }
+    }
+
     /// Cycle to next session (wraps around)
     pub fn next_session(&mut self) {
         if self.active_sessions.len() <= 1 {
@@ -766,6 +1178,383 @@ impl App {
         }
     }
 
+    // ─────────────────────────────────────────────────────────────
+    // Search
+    // ─────────────────────────────────────────────────────────────
+
+    /// The `/` prompt for the current view (History filters sessions)
+    pub fn active_search(&self) -> &SearchState {
+        match self.view {
+            View::History => &self.history_panel.filter,
+            _ => &self.search,
+        }
+    }
+
+    fn active_search_mut(&mut self) -> &mut SearchState {
+        match self.view {
+            View::History => &mut self.history_panel.filter,
+            _ => &mut self.search,
+        }
+    }
+
+    /// Put the list back at its start after the query changes
+    fn reset_search_selection(&mut self) {
+        match self.view {
+            View::History => self.history_panel.reset_selection(),
+            _ => self.events_panel.selected = None,
+        }
+    }
+
+    /// Open the `/` prompt
+    pub fn open_search(&mut self) {
+        self.active_search_mut().open();
+        self.reset_search_selection();
+    }
+
+    /// Edit the prompt text (the query re-parses as you type)
+    pub fn edit_search(&mut self, edit: impl FnOnce(&mut SearchState)) {
+        edit(self.active_search_mut());
+        self.reset_search_selection();
+    }
+
+    /// Close the prompt; Enter keeps the query, Esc restores the previous one
+    pub fn close_search(&mut self, keep: bool) {
+        if keep {
+            self.active_search_mut().commit();
+        } else {
+            self.active_search_mut().cancel();
+        }
+        self.reset_search_selection();
+    }
+
+    /// Drop the applied query (Esc); returns whether there was one
+    pub fn clear_search(&mut self) -> bool {
+        let cleared = self.active_search_mut().clear();
+        if cleared {
+            self.reset_search_selection();
+            self.show_toast("Search cleared");
+        }
+        cleared
+    }
+
+    /// Select the next (n) or previous (N) match, wrapping around
+    pub fn search_step(&mut self, forward: bool) {
+        if self.search.query.is_none() {
+            return;
+        }
+        let count = self.filtered_count();
+        if count == 0 {
+            self.show_toast("No matches");
+            return;
+        }
+        self.focused = FocusablePanel::Events;
+        self.events_panel.sync_events(count);
+        let last = count - 1;
+        self.events_panel.selected = Some(match (self.events_panel.selected, forward) {
+            (None, true) => 0,
+            (None, false) => last,
+            (Some(i), true) if i >= last => 0,
+            (Some(i), true) => i + 1,
+            (Some(0), false) => last,
+            (Some(i), false) => i - 1,
+        });
+    }
+
+    /// Run the query's text against lifestats and show the results modal
+    pub fn search_history(&mut self) {
+        self.search.commit();
+        let Some(query) = self.search.query.clone() else {
+            return;
+        };
+        let Some(fts) = query.fts_query() else {
+            self.show_toast("History search needs words to look for");
+            return;
+        };
+        if !self.config.lifestats.enabled {
+            self.show_toast("History search needs [lifestats] enabled");
+            return;
+        }
+        let Err(e) = self.open_history() else {
+            return self.run_history_search(query, fts);
+        };
+        self.show_toast(format!("✗ Lifestats unavailable: {}", e));
+    }
+
+    fn run_history_search(&mut self, query: search::SearchQuery, fts: String) {
+        let Some(history) = &self.history else {
+            return;
+        };
+
+        let client = self.effective_session().map(str::to_string);
+        let results = match &client {
+            Some(client) => {
+                history.recover_user_context(client, &fts, HISTORY_SEARCH_LIMIT, SearchMode::Raw)
+            }
+            None => history.recover_context(&fts, HISTORY_SEARCH_LIMIT, SearchMode::Raw),
+        };
+        match results {
+            Ok(matches) => {
+                let matches: Vec<_> = matches
+                    .into_iter()
+                    .filter(|m| query.matches_history(m))
+                    .collect();
+                self.detail_panel.reset();
+                self.detail_panel.set_content(search::format_history(
+                    &query,
+                    &matches,
+                    client.as_deref(),
+                ));
+                self.modal = Some(Modal::search_results());
+            }
+            Err(e) => self.show_toast(format!("✗ History search failed: {}", e)),
+        }
+    }
+
+    // ─────────────────────────────────────────────────────────────
+    // History
+    // Past sessions from lifestats, replayed read-only in the Events view
+    // ─────────────────────────────────────────────────────────────
+
+    /// Open the lifestats connection on first use
+    fn open_history(&mut self) -> anyhow::Result<()> {
+        if self.history.is_none() {
+            self.history = Some(Arc::new(LifestatsQuery::new(
+                &self.config.lifestats.db_path,
+            )?));
+        }
+        Ok(())
+    }
+
+    /// Reload the History view's session list off the UI thread
+    pub fn refresh_history(&mut self) {
+        if !self.config.lifestats.enabled || self.history_panel.is_loading() {
+            return;
+        }
+        if let Err(e) = self.open_history() {
+            self.history_panel.error = Some(format!("Lifestats unavailable: {}", e));
+            return;
+        }
+        let Some(history) = self.history.clone() else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("history-sessions".into())
+            .spawn(move || {
+                let result = history
+                    .list_sessions(HISTORY_SESSION_LIMIT)
+                    .map_err(|e| format!("{:#}", e));
+                let _ = tx.send(result);
+            });
+        match spawned {
+            Ok(_) => self.history_panel.start_load(rx),
+            Err(e) => self.history_panel.error = Some(e.to_string()),
+        }
+    }
+
+    /// Start loading the selected past session for the Events view (read-only)
+    pub fn open_replay(&mut self) {
+        if self.replay_pending.is_some() {
+            return;
+        }
+        let Some(session) = self.history_panel.selected_session().cloned() else {
+            return;
+        };
+        let Some(history) = self.history.clone() else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("history-replay".into())
+            .spawn(move || {
+                let result = history
+                    .get_session_events(&session.id)
+                    .map(|events| {
+                        // A missing plan shouldn't stop the replay
+                        let plan = history.get_todo_plan(&session.id).unwrap_or_default();
+                        let search_text = events.iter().map(search::searchable_text).collect();
+                        Replay {
+                            session,
+                            events,
+                            search_text,
+                            plan,
+                        }
+                    })
+                    .map_err(|e| format!("{:#}", e));
+                let _ = tx.send(result);
+            });
+        match spawned {
+            Ok(_) => self.replay_pending = Some(rx),
+            Err(e) => self.show_toast(format!("✗ Failed to load session: {}", e)),
+        }
+    }
+
+    /// Collect finished History loads (session list, replayed session)
+    pub fn poll_history(&mut self) {
+        self.history_panel.poll();
+
+        let Some(rx) = &self.replay_pending else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err("session loader stopped".to_string()),
+        };
+        self.replay_pending = None;
+        // The user moved on while it loaded
+        if self.view != View::History {
+            return;
+        }
+        match result {
+            Ok(replay) => {
+                let count = replay.events.len();
+                self.set_view(View::Events);
+                self.replay = Some(replay);
+                self.events_panel.selected = None;
+                self.show_toast(format!(
+                    "📜 Loaded {} events (read-only, Esc to go back)",
+                    count
+                ));
+            }
+            Err(e) => self.show_toast(format!("✗ Failed to load session: {}", e)),
+        }
+    }
+
+    /// Leave the replayed session for the History list; returns whether one was open
+    pub fn close_replay(&mut self) -> bool {
+        if self.replay.is_none() {
+            return false;
+        }
+        self.set_view(View::History);
+        true
+    }
+
+    // ─────────────────────────────────────────────────────────────
+    // Diff
+    // `m` marks a base, `d` compares it with the selection
+    // ─────────────────────────────────────────────────────────────
+
+    /// Mark the selected request (Events) or session (History) as the diff base
+    pub fn mark_diff_base(&mut self) {
+        if self.view == View::History {
+            let Some(id) = self.history_panel.selected_session().map(|s| s.id.clone()) else {
+                return;
+            };
+            if self.history_panel.marked.as_ref() == Some(&id) {
+                self.history_panel.marked = None;
+                return self.show_toast("⇄ Diff base cleared");
+            }
+            self.history_panel.marked = Some(id);
+            return self.show_toast("⇄ Session marked - select another and press d");
+        }
+
+        match self.selected_request() {
+            Some((id, _)) if self.diff_base.as_deref() == Some(id.as_str()) => {
+                self.diff_base = None;
+                self.show_toast("⇄ Diff base cleared");
+            }
+            Some((id, _)) => {
+                self.diff_base = Some(id);
+                self.show_toast("⇄ Request marked - select another and press d");
+            }
+            None => self.show_toast("Select a request with a captured body to mark"),
+        }
+    }
+
+    /// Open the diff of the marked base against the selection
+    ///
+    /// Without a mark, requests compare against the previous request.
+    pub fn open_diff(&mut self) {
+        let markdown = if self.view == View::History {
+            match self.session_diff() {
+                Ok(md) => md,
+                Err(msg) => return self.show_toast(msg),
+            }
+        } else {
+            match self.request_diff() {
+                Ok(md) => md,
+                Err(msg) => return self.show_toast(msg),
+            }
+        };
+        self.detail_panel.reset();
+        self.detail_panel.set_content(markdown);
+        self.modal = Some(Modal::diff());
+    }
+
+    /// Id and body of the selected Request event
+    fn selected_request(&self) -> Option<(String, serde_json::Value)> {
+        let idx = self.selected_event_index()?;
+        match &self.shown_events().get(idx)?.event {
+            ProxyEvent::Request {
+                id,
+                body: Some(body),
+                ..
+            } => Some((id.clone(), body.clone())),
+            _ => None,
+        }
+    }
+
+    fn request_diff(&self) -> Result<String, &'static str> {
+        let (id_b, body_b) = self
+            .selected_request()
+            .ok_or("Select a request with a captured body to diff")?;
+        let events = self.shown_events();
+        let position = events
+            .iter()
+            .position(|e| matches!(&e.event, ProxyEvent::Request { id, .. } if *id == id_b));
+
+        let base = match &self.diff_base {
+            Some(base) if *base != id_b => events
+                .iter()
+                .find(|e| matches!(&e.event, ProxyEvent::Request { id, .. } if id == base)),
+            // No mark: the previous request from the same client
+            _ => position.and_then(|pos| {
+                let client = &events[pos].user_id;
+                events[..pos].iter().rev().find(|e| {
+                    e.user_id == *client
+                        && matches!(&e.event, ProxyEvent::Request { body: Some(_), .. })
+                })
+            }),
+        };
+        let Some(TrackedEvent {
+            event:
+                ProxyEvent::Request {
+                    id: id_a,
+                    body: Some(body_a),
+                    ..
+                },
+            ..
+        }) = base
+        else {
+            return Err("No earlier request to compare - mark one with m");
+        };
+
+        let diff = crate::diff::RequestDiff::between(body_a, &body_b);
+        Ok(diff.to_markdown(&request_label(events, id_a), &request_label(events, &id_b)))
+    }
+
+    fn session_diff(&self) -> Result<String, &'static str> {
+        let b = self
+            .history_panel
+            .selected_session()
+            .ok_or("Select a session to diff")?;
+        let a = self
+            .history_panel
+            .marked
+            .as_ref()
+            .filter(|id| **id != b.id)
+            .and_then(|id| self.history_panel.session(id))
+            .ok_or("Mark a different session with m first")?;
+        Ok(crate::diff::SessionDiff::between(
+            &session_label(a),
+            &session_stats(a),
+            &session_label(b),
+            &session_stats(b),
+        )
+        .to_markdown())
+    }
+
     /// Get the count of active sessions
     pub fn session_count(&self) -> usize {
         self.active_sessions.len()
@@ -775,42 +1564,147 @@ impl App {
     // Selection & Scrolling (Trait-based dispatch)
     // ─────────────────────────────────────────────────────────────
 
-    /// Dispatch a key event to the currently focused panel via the Interactive trait
+    /// Dispatch an action to the currently focused panel via the Interactive trait
     ///
-    /// This is the primary dispatch mechanism for keyboard events.
-    /// Each panel implements Interactive::handle_key() with its own behavior.
+    /// This is the primary dispatch mechanism for keyboard input.
+    /// Each panel implements Interactive::handle_action() with its own behavior.
     ///
-    /// Returns Handled::Yes if the panel consumed the event, Handled::No if not.
-    pub fn dispatch_to_focused(&mut self, key: KeyEvent) -> Handled {
+    /// Returns Handled::Yes if the panel consumed the action, Handled::No if not.
+    pub fn dispatch_to_focused(&mut self, action: Action) -> Handled {
         // Settings view has its own panel structure
         if self.view == View::Settings {
-            return self.dispatch_to_settings(key);
+            return self.dispatch_to_settings(action);
+        }
+        if self.view == View::History {
+            return self.history_panel.handle_action(action);
+        }
+        if self.view == View::Stats && self.stats_selected_tab == STATS_TIMELINE_TAB {
+            return self.timeline_panel.handle_action(action);
+        }
+        if self.view == View::Stats && self.stats_selected_tab == STATS_LIFETIME_TAB {
+            return self.lifetime_panel.handle_action(action);
         }
 
         // Events/Stats view: dispatch based on focused panel
-        match self.focused {
+        self.dispatch_to_panel(self.focused, action)
+    }
+
+    /// Send an action to one of the Events view panels (focused or under the mouse)
+    fn dispatch_to_panel(&mut self, panel: FocusablePanel, action: Action) -> Handled {
+        match panel {
             FocusablePanel::Events => {
                 // Use filtered count (current session) not total count (all sessions)
-                self.events_panel.sync_events(self.filtered_events().len());
-                self.events_panel.handle_key(key)
+                self.events_panel.sync_events(self.filtered_count());
+                self.events_panel.handle_action(action)
             }
-            FocusablePanel::Thinking => self.thinking_panel.handle_key(key),
+            FocusablePanel::Thinking => self.thinking_panel.handle_action(action),
             FocusablePanel::Logs => {
                 self.logs_panel.entry_count = self.log_buffer.get_all().len();
-                self.logs_panel.handle_key(key)
+                self.logs_panel.handle_action(action)
             }
         }
     }
 
-    /// Dispatch key events within Settings view
+    // ─────────────────────────────────────────────────────────────
+    // Mouse
+    // Positions resolve against the hit map recorded by the last draw
+    // ─────────────────────────────────────────────────────────────
+
+    /// Focus and select whatever was drawn at a position (left click)
+    ///
+    /// Returns true if the click selected an item a double-click can open.
+    pub fn click_at(&mut self, column: u16, row: u16) -> bool {
+        let Some((target, area)) = self.mouse.hits.target_at(column, row) else {
+            return false;
+        };
+        match target {
+            // Logs is drawn in every view but only takes focus in Events
+            Target::Panel(_) if self.view != View::Events => false,
+            Target::Panel(panel) => {
+                self.focused = panel;
+                let handled = match panel {
+                    FocusablePanel::Events => {
+                        self.events_panel.sync_events(self.filtered_count());
+                        self.events_panel.handle_click(area, column, row)
+                    }
+                    FocusablePanel::Thinking => self.thinking_panel.handle_click(area, column, row),
+                    FocusablePanel::Logs => {
+                        self.logs_panel.entry_count = self.log_buffer.get_all().len();
+                        self.logs_panel.handle_click(area, column, row)
+                    }
+                };
+                handled.was_handled()
+            }
+            Target::StatsTab(tab) => {
+                self.stats_selected_tab = tab;
+                false
+            }
+            Target::SettingsCategories => {
+                // Selecting a category is all a click does; nothing to activate
+                if let Some(r) = inner_row(area, row) {
+                    self.settings_panel.click_category(r);
+                }
+                false
+            }
+            Target::SettingsOptions => {
+                inner_row(area, row).is_some_and(|r| self.settings_panel.click_option(r))
+            }
+            Target::History => self
+                .history_panel
+                .handle_click(area, column, row)
+                .was_handled(),
+        }
+    }
+
+    /// Scroll whatever is under the pointer (mouse wheel), else the focused panel
+    pub fn scroll_at(&mut self, column: u16, row: u16, action: Action) {
+        match self
+            .mouse
+            .hits
+            .target_at(column, row)
+            .map(|(target, _)| target)
+        {
+            Some(Target::Panel(panel)) => {
+                self.dispatch_to_panel(panel, action);
+            }
+            Some(Target::StatsTab(_)) => {
+                // Wheel over the tab bar flips through tabs
+                self.stats_selected_tab = if action == Action::Up {
+                    self.stats_selected_tab.saturating_sub(1)
+                } else {
+                    (self.stats_selected_tab + 1).min(STATS_TAB_COUNT - 1)
+                };
+            }
+            Some(Target::SettingsCategories) => {
+                self.settings_panel.focus = SettingsFocus::Categories;
+                self.dispatch_to_settings(action);
+            }
+            Some(Target::SettingsOptions) => {
+                self.settings_panel.focus = SettingsFocus::Options;
+                self.dispatch_to_settings(action);
+            }
+            Some(Target::History) | None => {
+                self.dispatch_to_focused(action);
+            }
+        }
+    }
+
+    /// Move the Events view divider to follow a drag
+    pub fn drag_split(&mut self, column: u16, row: u16) {
+        if let Some(split) = self.mouse.hits.split {
+            self.split_percent = Some(split.percent_at(column, row));
+        }
+    }
+
+    /// Dispatch actions within Settings view
     /// Now fully delegated to SettingsPanel component
-    fn dispatch_to_settings(&mut self, key: KeyEvent) -> Handled {
+    fn dispatch_to_settings(&mut self, action: Action) -> Handled {
         // Sync theme count before handling (for proper bounds)
         let themes = Theme::list_available();
         self.settings_panel.sync_themes(themes.len(), 20); // viewport hint
 
-        // Delegate all key handling to the component
-        self.settings_panel.handle_key(key)
+        // Delegate all action handling to the component
+        self.settings_panel.handle_action(action)
     }
 
     // ─────────────────────────────────────────────────────────────
@@ -822,6 +1716,32 @@ impl App {
         self.toast = Some(Toast::new(message));
     }
 
+    /// Show pending alert toasts
+    ///
+    /// Returns true if any alert asked for the terminal bell (the caller
+    /// rings it between frames).
+    pub fn drain_alerts(&mut self) -> bool {
+        let Some(feed) = &self.alert_feed else {
+            return false;
+        };
+        let notices: Vec<_> = match feed.lock() {
+            Ok(mut feed) => feed.drain(..).collect(),
+            Err(_) => return false,
+        };
+
+        let mut bell = false;
+        for notice in notices {
+            bell |= notice.bell;
+            if notice.toast {
+                self.toast = Some(
+                    Toast::new(format!("⚠ {}: {}", notice.alert.rule, notice.alert.message))
+                        .with_duration(ALERT_TOAST_DURATION),
+                );
+            }
+        }
+        bell
+    }
+
     /// Clear the toast if it has expired
     pub fn clear_expired_toast(&mut self) {
         if let Some(ref toast) = self.toast {
@@ -847,7 +1767,8 @@ impl App {
         match self.focused {
             FocusablePanel::Events => {
                 // Delegate to component
-                self.events_panel.copy_text_with_events(&self.events)
+                self.events_panel
+                    .copy_text_with_events(&self.filtered_events())
             }
             FocusablePanel::Thinking => {
                 // Copy current thinking content
@@ -868,7 +1789,8 @@ impl App {
     pub fn copy_current_jsonl(&self) -> Option<String> {
         // JSONL only makes sense for events (modal handles its own copy)
         if self.focused == FocusablePanel::Events {
-            self.events_panel.copy_data_with_events(&self.events)
+            self.events_panel
+                .copy_data_with_events(&self.filtered_events())
         } else {
             None
         }
@@ -902,6 +1824,21 @@ impl App {
         if self.view == View::Settings {
             return self.settings_panel.focus_hint();
         }
+        if self.view == View::History && self.modal.is_none() {
+            return self.history_panel.focus_hint();
+        }
+        if self.view == View::Stats
+            && self.stats_selected_tab == STATS_TIMELINE_TAB
+            && self.modal.is_none()
+        {
+            return self.timeline_panel.focus_hint();
+        }
+        if self.view == View::Stats
+            && self.stats_selected_tab == STATS_LIFETIME_TAB
+            && self.modal.is_none()
+        {
+            return self.lifetime_panel.focus_hint();
+        }
 
         // Modal captures focus when open
         if self.modal.is_some() {
@@ -1004,3 +1941,49 @@ impl Default for App {
         Self::new()
     }
 }
+
+/// `14:02:11 dev-1 req_abc12345` for diff headers
+fn request_label(events: &[TrackedEvent], request_id: &str) -> String {
+    events
+        .iter()
+        .find(|e| matches!(&e.event, ProxyEvent::Request { id, .. } if id == request_id))
+        .map(|e| {
+            format!(
+                "{} {} `{}`",
+                e.tracked_at
+                    .with_timezone(&chrono::Local)
+                    .format("%H:%M:%S"),
+                e.user_id.as_deref().unwrap_or("-"),
+                request_id
+            )
+        })
+        .unwrap_or_else(|| format!("`{}`", request_id))
+}
+
+/// `dev-1 2026-01-05 14:02` for diff headers
+fn session_label(s: &SessionSummary) -> String {
+    format!(
+        "{} {}",
+        s.user_id.as_deref().unwrap_or("-"),
+        s.started_at
+            .map(|t| t
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string())
+            .unwrap_or_else(|| s.id.clone())
+    )
+}
+
+/// Aggregate stats compared by session diffs
+fn session_stats(s: &SessionSummary) -> serde_json::Value {
+    serde_json::json!({
+        "duration_secs": s.duration().map(|d| d.num_seconds()),
+        "cost_usd": s.cost_usd,
+        "tokens": s.tokens,
+        "prompts": s.prompts,
+        "tool_calls": s.tool_calls,
+        "compactions": s.compactions,
+        "project": s.cwd,
+        "git_branch": s.git_branch,
+    })
+}
@@ -17,6 +17,7 @@ use ratatui::{
 /// - Current tokens / limit with percentage
 /// - Color-coded fill based on usage level
 /// - Special "compact pending" state when over limit
+/// - Estimated attachment (image/document) share, when any
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
     let ctx = &app.context_state;
 
@@ -35,19 +36,27 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
             app.theme.context_bar_fill
         };
 
+        let attachments = if ctx.attachment_tokens > 0 {
+            format!(" · 📎 ~{}", format_number(ctx.attachment_tokens))
+        } else {
+            String::new()
+        };
+
         let label = if over_limit {
             // Don't show embarrassing >100%, signal compact is pending
             format!(
-                "Context: {} / {} (~100%, compact pending)",
+                "Context: {} / {} (~100%, compact pending){}",
                 format_number(ctx.current_tokens),
                 format_number(ctx.limit),
+                attachments
             )
         } else {
             format!(
-                "Context: {} / {} ({:.1}%)",
+                "Context: {} / {} ({:.1}%){}",
                 format_number(ctx.current_tokens),
                 format_number(ctx.limit),
-                pct
+                pct,
+                attachments
             )
         };
         (label, pct.min(100.0), color) // Cap display at 100%
@@ -4,11 +4,11 @@
 //! - Formatted event information
 //! - Scroll support for long content
 //! - Copy support for event data
+use crate::tui::keymap::Action;
 use crate::tui::scroll::ScrollState;
 use crate::tui::traits::{
     Component, ComponentId, Copyable, Handled, Interactive, RenderContext, Scrollable,
 };
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
     style::Style,
@@ -143,29 +143,29 @@ impl Copyable for DetailPanel {
 }
 
 impl Interactive for DetailPanel {
-    fn handle_key(&mut self, key: KeyEvent) -> Handled {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    fn handle_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 self.scroll_up();
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Action::Down => {
                 self.scroll_down();
                 Handled::Yes
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::Top => {
                 self.scroll_to_top();
                 Handled::Yes
             }
-            KeyCode::End | KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.scroll_to_bottom();
                 Handled::Yes
             }
-            KeyCode::PageUp => {
+            Action::PageUp => {
                 self.page_up();
                 Handled::Yes
             }
-            KeyCode::PageDown => {
+            Action::PageDown => {
                 self.page_down();
                 Handled::Yes
             }
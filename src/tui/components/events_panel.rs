@@ -15,16 +15,19 @@
 use super::scrollbar::{render_scrollbar_raw, ScrollbarStyle};
 use crate::events::{ProxyEvent, TrackedEvent};
 use crate::theme::Theme;
+use crate::tui::keymap::Action;
+use crate::tui::mouse::inner_row;
 use crate::tui::scroll::{FocusablePanel, ScrollState};
+use crate::tui::search::SearchQuery;
 use crate::tui::traits::{
     Component, ComponentId, Copyable, Handled, Interactive, RenderContext, Scrollable, Selectable,
     Zoomable,
 };
 use crate::tui::views::format_event_line;
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem},
     Frame,
 };
@@ -45,6 +48,9 @@ pub struct EventsPanel {
     /// Public so App can sync it before delegating operations
     pub event_count: usize,
 
+    /// First visible row in selection mode (kept so a clicked row stays put)
+    offset: usize,
+
     /// Scroll state (unused for EventsPanel - exists for trait compliance)
     /// EventsPanel uses selection-based scrolling, not ScrollState
     _scroll: ScrollState,
@@ -56,6 +62,7 @@ impl EventsPanel {
         Self {
             selected: None, // Auto-follow by default
             event_count: 0,
+            offset: 0,
             _scroll: ScrollState::new(), // Unused - for trait compliance
         }
     }
@@ -91,8 +98,11 @@ impl EventsPanel {
                 total.saturating_sub(height)
             }
             Some(idx) => {
-                // Selection mode: keep selected item visible
-                if idx >= height {
+                // Selection mode: keep the last offset while the selection is on screen
+                let offset = self.offset.min(total.saturating_sub(height));
+                if idx >= offset && idx < offset + height {
+                    offset
+                } else if idx >= height {
                     idx.saturating_sub(height - 1)
                 } else {
                     0
@@ -106,6 +116,15 @@ impl EventsPanel {
         (start, end)
     }
 
+    /// Remember the visible window (call each frame before rendering)
+    ///
+    /// Scrolls only as far as needed to keep the selection visible, so rows
+    /// don't jump when one is selected.
+    pub fn sync_viewport(&mut self, total: usize, height: usize) {
+        self.sync_events(total);
+        self.offset = self.visible_range(total, height).0;
+    }
+
     /// Render the events panel with owned events slice (backward compatibility)
     ///
     /// This method is kept for backward compatibility with code that passes
@@ -122,12 +141,13 @@ impl EventsPanel {
     ) {
         // Convert to references for unified rendering
         let refs: Vec<&TrackedEvent> = events.iter().collect();
-        self.render_events_inner(f, area, &refs, theme, focused);
+        self.render_events_inner(f, area, &refs, theme, focused, None);
     }
 
     /// Render with pre-filtered event references (for multi-session support)
     ///
     /// Takes a slice of references - useful when events have been filtered.
+    /// With a search query, matching text is highlighted.
     pub fn render_with_filtered_events(
         &self,
         f: &mut Frame,
@@ -135,8 +155,9 @@ impl EventsPanel {
         events: &[&TrackedEvent],
         theme: &Theme,
         focused: bool,
+        search: Option<&SearchQuery>,
     ) {
-        self.render_events_inner(f, area, events, theme, focused);
+        self.render_events_inner(f, area, events, theme, focused, search);
     }
 
     /// Internal rendering implementation (works with references)
@@ -147,6 +168,7 @@ impl EventsPanel {
         events: &[&TrackedEvent],
         theme: &Theme,
         focused: bool,
+        search: Option<&SearchQuery>,
     ) {
         let height = area.height.saturating_sub(2) as usize;
         let (start, end) = self.visible_range(events.len(), height);
@@ -199,12 +221,18 @@ impl EventsPanel {
                     base_style
                 };
 
-                ListItem::new(line).style(style)
+                match search {
+                    Some(query) => {
+                        let ranges = query.highlight_ranges(&line);
+                        ListItem::new(highlight_line(&line, &ranges)).style(style)
+                    }
+                    None => ListItem::new(line).style(style),
+                }
             })
             .collect();
 
         // Title shows mode: count only (auto-follow) or position/count [select]
-        let title = if events.is_empty() {
+        let mut title = if events.is_empty() {
             " Events ".to_string()
         } else if let Some(idx) = self.selected {
             // Selection mode: show position
@@ -213,6 +241,9 @@ impl EventsPanel {
             // Auto-follow mode: just show count
             format!(" Events ({}) ", events.len())
         };
+        if let Some(query) = search {
+            title.push_str(&format!("[/{}] ", query.raw));
+        }
 
         let border_color = theme.panel_border(FocusablePanel::Events, focused);
         let list = List::new(items).block(
@@ -286,7 +317,7 @@ impl Scrollable for EventsPanel {
             None => {
                 // Enter selection mode at last item, then move up
                 let last = self.event_count.saturating_sub(1);
-                self.selected = Some(last.saturating_sub(1).max(0));
+                self.selected = Some(last.saturating_sub(1));
             }
             Some(idx) if idx > 0 => {
                 self.selected = Some(idx - 1);
@@ -362,21 +393,21 @@ impl Copyable for EventsPanel {
 }
 
 impl Interactive for EventsPanel {
-    fn handle_key(&mut self, key: KeyEvent) -> Handled {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    fn handle_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 self.scroll_up();
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Action::Down => {
                 self.scroll_down();
                 Handled::Yes
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::Top => {
                 self.scroll_to_top();
                 Handled::Yes
             }
-            KeyCode::End | KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.scroll_to_bottom();
                 // Explicitly select last item so selection highlight is visible
                 if self.event_count > 0 {
@@ -384,7 +415,7 @@ impl Interactive for EventsPanel {
                 }
                 Handled::Yes
             }
-            KeyCode::PageUp => {
+            Action::PageUp => {
                 // Page up: move selection up by ~10 items
                 if let Some(idx) = self.selected {
                     self.selected = Some(idx.saturating_sub(10));
@@ -393,7 +424,7 @@ impl Interactive for EventsPanel {
                 }
                 Handled::Yes
             }
-            KeyCode::PageDown => {
+            Action::PageDown => {
                 // Page down: move selection down by ~10 items
                 let last = self.event_count.saturating_sub(1);
                 if let Some(idx) = self.selected {
@@ -403,7 +434,7 @@ impl Interactive for EventsPanel {
                 }
                 Handled::Yes
             }
-            KeyCode::Esc => {
+            Action::Back => {
                 // Clear selection if any, return to auto-follow
                 if self.selected.is_some() {
                     self.selected = None;
@@ -416,12 +447,28 @@ impl Interactive for EventsPanel {
         }
     }
 
+    fn handle_click(&mut self, area: Rect, _column: u16, row: u16) -> Handled {
+        let Some(row) = inner_row(area, row) else {
+            return Handled::No;
+        };
+        let (start, end) =
+            self.visible_range(self.event_count, area.height.saturating_sub(2) as usize);
+        if start + row >= end {
+            return Handled::No;
+        }
+        self.offset = start;
+        self.selected = Some(start + row);
+        Handled::Yes
+    }
+
     fn focusable(&self) -> bool {
         true
     }
 
     fn focus_hint(&self) -> Option<&'static str> {
-        Some("↑↓:select  g/G:top/end  Enter:detail  y:copy  z:zoom  Esc:follow")
+        Some(
+            "↑↓:select  g/G:top/end  Enter:detail  /:search  n/N:match  m/d:diff  y:copy  z:zoom  Esc:follow",
+        )
     }
 }
 
@@ -434,14 +481,14 @@ impl Zoomable for EventsPanel {
 // Helper methods for copy operations that need event data
 impl EventsPanel {
     /// Get formatted text for the selected event (for clipboard)
-    pub fn copy_text_with_events(&self, events: &[TrackedEvent]) -> Option<String> {
+    pub fn copy_text_with_events(&self, events: &[&TrackedEvent]) -> Option<String> {
         self.selected
             .and_then(|idx| events.get(idx))
-            .map(format_event_line)
+            .map(|tracked| format_event_line(tracked))
     }
 
     /// Get JSONL for the selected event (for clipboard)
-    pub fn copy_data_with_events(&self, events: &[TrackedEvent]) -> Option<String> {
+    pub fn copy_data_with_events(&self, events: &[&TrackedEvent]) -> Option<String> {
         self.selected
             .and_then(|idx| events.get(idx))
             .and_then(|tracked| serde_json::to_string(&tracked.event).ok())
@@ -452,6 +499,32 @@ impl EventsPanel {
 // Helpers
 // ============================================================================
 
+/// Split a list line into spans, reversing the byte ranges of search matches
+///
+/// Unmatched spans carry no style of their own, so the item's style (event
+/// color or selection) shows through.
+fn highlight_line(line: &str, ranges: &[std::ops::Range<usize>]) -> Line<'static> {
+    let matched = Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD);
+    let mut spans = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut pos = 0;
+    for range in ranges {
+        // The ellipsis can cut a match short
+        let end = range.end.min(line.len());
+        if range.start >= end {
+            break;
+        }
+        if range.start > pos {
+            spans.push(Span::raw(line[pos..range.start].to_string()));
+        }
+        spans.push(Span::styled(line[range.start..end].to_string(), matched));
+        pos = end;
+    }
+    if pos < line.len() {
+        spans.push(Span::raw(line[pos..].to_string()));
+    }
+    Line::from(spans)
+}
+
 /// Get appropriate color style for an event
 fn event_color_style(event: &ProxyEvent, theme: &Theme) -> Style {
     match event {
@@ -489,9 +562,24 @@ fn event_color_style(event: &ProxyEvent, theme: &Theme) -> Style {
         ProxyEvent::RequestTransformed { .. } => Style::default()
             .fg(theme.api_usage)
             .add_modifier(Modifier::DIM),
+        ProxyEvent::ResponseTransformed { .. } => Style::default()
+            .fg(theme.api_usage)
+            .add_modifier(Modifier::DIM),
+        ProxyEvent::PolicyDecision { decision, .. } if decision == "deny" => Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::BOLD),
+        ProxyEvent::PolicyDecision { .. } => Style::default()
+            .fg(theme.api_usage)
+            .add_modifier(Modifier::DIM),
         ProxyEvent::ResponseAugmented { .. } => Style::default()
             .fg(theme.api_usage)
             .add_modifier(Modifier::DIM),
+        ProxyEvent::ConfigReloaded { success: false, .. } => Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::BOLD),
+        ProxyEvent::ConfigReloaded { .. } => Style::default()
+            .fg(theme.api_usage)
+            .add_modifier(Modifier::DIM),
     }
 }
 
@@ -519,6 +607,7 @@ pub fn render_filtered(
     events: &[&TrackedEvent],
     theme: &Theme,
     focused: bool,
+    search: Option<&SearchQuery>,
 ) {
-    events_panel.render_with_filtered_events(f, area, events, theme, focused);
+    events_panel.render_with_filtered_events(f, area, events, theme, focused, search);
 }
@@ -0,0 +1,166 @@
+// Files tab panel for stats view
+//
+// Displays file activity extracted from tool call inputs:
+// - Table of most-touched files with read/edit/write/search counts
+// - Files re-read without changes in between (wasted context)
+
+use crate::pipeline::file_activity::FileActivityLog;
+use crate::theme::Theme;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+    Frame,
+};
+
+/// Maximum files listed in the table
+const TOP_FILES: usize = 50;
+
+/// Panel displaying file activity
+pub struct FilesTabPanel;
+
+impl FilesTabPanel {
+    /// Render the panel to a frame
+    pub fn render(frame: &mut Frame, area: Rect, files: &FileActivityLog, theme: &Theme) {
+        if files.is_empty() {
+            let placeholder = Paragraph::new("No file activity yet")
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" Files ")
+                        .border_style(theme.border),
+                )
+                .style(Style::default().fg(theme.muted));
+            frame.render_widget(placeholder, area);
+            return;
+        }
+
+        // Top files on the left (65%), redundant reads on the right (35%)
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(area);
+
+        // === Table: Most-Touched Files ===
+        Self::render_top_files(frame, chunks[0], files, theme);
+
+        // === List: Redundant Reads ===
+        Self::render_redundant_reads(frame, chunks[1], files, theme);
+    }
+
+    fn render_top_files(frame: &mut Frame, area: Rect, files: &FileActivityLog, theme: &Theme) {
+        // Path column gets whatever the fixed-width count columns leave over
+        let path_width = area.width.saturating_sub(2 + 5 * 7) as usize;
+
+        let header = Row::new(["Path", "Read", "Edit", "Write", "Search", "Sess"]).style(
+            Style::default()
+                .fg(theme.foreground)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let count_cell = |n: u32, color: Color| {
+            if n == 0 {
+                Span::styled("-", Style::default().fg(theme.muted))
+            } else {
+                Span::styled(n.to_string(), Style::default().fg(color))
+            }
+        };
+
+        let rows: Vec<Row> = files
+            .top_files(TOP_FILES)
+            .into_iter()
+            .map(|(path, counts)| {
+                Row::new(vec![
+                    Line::from(Span::styled(
+                        shorten_path(path, path_width),
+                        Style::default().fg(theme.foreground),
+                    )),
+                    Line::from(count_cell(counts.reads, Color::Cyan)),
+                    Line::from(count_cell(counts.edits, Color::Yellow)),
+                    Line::from(count_cell(counts.writes, Color::Green)),
+                    Line::from(count_cell(counts.searches, Color::Magenta)),
+                    Line::from(count_cell(counts.sessions.len() as u32, theme.muted)),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(10),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(6),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Most-Touched Files ")
+                .border_style(theme.border),
+        );
+
+        frame.render_widget(table, area);
+    }
+
+    fn render_redundant_reads(
+        frame: &mut Frame,
+        area: Rect,
+        files: &FileActivityLog,
+        theme: &Theme,
+    ) {
+        let limit = area.height.saturating_sub(2) as usize;
+        let reads = files.redundant_reads(limit);
+        let path_width = area.width.saturating_sub(12) as usize;
+
+        let lines: Vec<Line> = if reads.is_empty() {
+            vec![Line::from(Span::styled(
+                "No files re-read without changes",
+                Style::default().fg(theme.muted),
+            ))]
+        } else {
+            reads
+                .iter()
+                .map(|r| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{:>3}x ", r.redundant),
+                            Style::default().fg(if r.redundant >= 3 {
+                                Color::Red
+                            } else {
+                                Color::Yellow
+                            }),
+                        ),
+                        Span::styled(
+                            shorten_path(&r.path, path_width),
+                            Style::default().fg(theme.foreground),
+                        ),
+                    ])
+                })
+                .collect()
+        };
+
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Redundant Reads ")
+                .border_style(theme.border),
+        );
+
+        frame.render_widget(paragraph, area);
+    }
+}
+
+/// Keep the end of a path (the file name matters most), prefixing "…"
+fn shorten_path(path: &str, max: usize) -> String {
+    let len = path.chars().count();
+    if len <= max || max < 2 {
+        return path.to_string();
+    }
+    let tail: String = path.chars().skip(len - (max - 1)).collect();
+    format!("…{}", tail)
+}
@@ -40,3 +40,18 @@ pub fn format_compact_number(n: u64) -> String {
         n.to_string()
     }
 }
+
+/// Model id without the family prefix and release date
+///
+/// # Examples
+/// ```ignore
+/// assert_eq!(short_model_name("claude-opus-4-5-20251101"), "opus-4-5");
+/// assert_eq!(short_model_name("gpt-4o"), "gpt-4o");
+/// ```
+pub fn short_model_name(model: &str) -> &str {
+    let name = model.strip_prefix("claude-").unwrap_or(model);
+    match name.rsplit_once('-') {
+        Some((model, date)) if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) => model,
+        _ => name,
+    }
+}
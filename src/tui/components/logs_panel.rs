@@ -14,12 +14,13 @@
 use super::scrollbar::{render_scrollbar, ScrollbarStyle};
 use crate::logging::{LogEntry, LogLevel};
 use crate::theme::Theme;
+use crate::tui::keymap::Action;
+use crate::tui::mouse::inner_row;
 use crate::tui::scroll::ScrollState;
 use crate::tui::traits::{
     Component, ComponentId, Copyable, Handled, Interactive, RenderContext, Scrollable, Selectable,
     Zoomable,
 };
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
@@ -258,39 +259,39 @@ impl Copyable for LogsPanel {
 }
 
 impl Interactive for LogsPanel {
-    fn handle_key(&mut self, key: KeyEvent) -> Handled {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    fn handle_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 self.select_previous();
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Action::Down => {
                 self.select_next();
                 Handled::Yes
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::Top => {
                 self.scroll_to_top();
                 if self.entry_count > 0 {
                     self.selected = Some(0);
                 }
                 Handled::Yes
             }
-            KeyCode::End | KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.scroll_to_bottom();
                 if self.entry_count > 0 {
                     self.selected = Some(self.entry_count.saturating_sub(1));
                 }
                 Handled::Yes
             }
-            KeyCode::PageUp => {
+            Action::PageUp => {
                 self.page_up();
                 Handled::Yes
             }
-            KeyCode::PageDown => {
+            Action::PageDown => {
                 self.page_down();
                 Handled::Yes
             }
-            KeyCode::Esc => {
+            Action::Back => {
                 // Clear selection if any, return to auto-follow
                 if self.selected.is_some() {
                     self.selected = None;
@@ -304,6 +305,18 @@ impl Interactive for LogsPanel {
         }
     }
 
+    fn handle_click(&mut self, area: Rect, _column: u16, row: u16) -> Handled {
+        let Some(row) = inner_row(area, row) else {
+            return Handled::No;
+        };
+        let (start, end) = self.scroll.visible_range();
+        if start + row >= end {
+            return Handled::No;
+        }
+        self.selected = Some(start + row);
+        Handled::Yes
+    }
+
     fn focusable(&self) -> bool {
         true
     }
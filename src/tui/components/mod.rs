@@ -11,15 +11,20 @@
 pub mod context_bar;
 pub mod detail_panel;
 pub mod events_panel;
+pub mod files_tab_panel;
 pub mod formatters;
+pub mod history_panel;
+pub mod lifetime_panel;
 pub mod logs_panel;
 pub mod models_tab_panel;
+pub mod plan_panel;
 pub mod scrollbar;
 pub mod session_gauges_panel;
 pub mod settings_panel;
 pub mod status_bar;
 pub mod theme_list_panel;
 pub mod thinking_panel;
+pub mod timeline_panel;
 pub mod title_bar;
 pub mod toast;
 pub mod tokens_tab_panel;
@@ -56,4 +61,4 @@ pub fn render_logs_panel(f: &mut Frame, area: Rect, app: &mut App) {
 }
 
 // Re-export formatters for shared use
-pub use formatters::{format_compact_number, format_number};
+pub use formatters::{format_compact_number, format_number, short_model_name};
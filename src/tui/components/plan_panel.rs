@@ -0,0 +1,89 @@
+// Plan panel for events view
+//
+// Shows the selected session's current TodoWrite plan above the thinking
+// panel: one line per item with a status marker, completed items dimmed.
+// Only rendered while the session has a plan.
+
+use crate::pipeline::todos::{TodoCounts, TodoItem, TodoStatus};
+use crate::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// Share of the column the plan may take before it scrolls off
+const MAX_HEIGHT_PERCENT: u16 = 40;
+
+/// Height the panel wants inside a column of `available` rows
+///
+/// Items plus borders, capped so the panel below keeps most of the space.
+pub fn height_for(items: &[TodoItem], available: u16) -> u16 {
+    let wanted = items.len() as u16 + 2;
+    let cap = (available * MAX_HEIGHT_PERCENT / 100).max(3);
+    wanted.min(cap)
+}
+
+/// Render the plan panel
+pub fn render(f: &mut Frame, area: Rect, items: &[TodoItem], theme: &Theme) {
+    let counts = TodoCounts::of(items);
+    let width = area.width.saturating_sub(6) as usize;
+
+    // Keep the in-progress item visible when the list is longer than the panel
+    let visible = area.height.saturating_sub(2) as usize;
+    let focus = items
+        .iter()
+        .position(|i| i.status == TodoStatus::InProgress)
+        .unwrap_or(0);
+    let skip = (focus + 1).saturating_sub(visible);
+
+    let lines: Vec<Line> = items
+        .iter()
+        .skip(skip)
+        .map(|item| {
+            let (marker, style) = match item.status {
+                TodoStatus::Completed => (
+                    "✓",
+                    Style::default()
+                        .fg(theme.muted)
+                        .add_modifier(Modifier::CROSSED_OUT),
+                ),
+                TodoStatus::InProgress => (
+                    "▶",
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                TodoStatus::Pending => ("○", Style::default().fg(theme.foreground)),
+            };
+            // In-progress items read better in their present-tense form
+            let text = match (item.status, &item.active_form) {
+                (TodoStatus::InProgress, Some(active)) => active.as_str(),
+                _ => item.content.as_str(),
+            };
+            let text: String = if text.chars().count() > width {
+                let cut: String = text.chars().take(width.saturating_sub(1)).collect();
+                format!("{}…", cut)
+            } else {
+                text.to_string()
+            };
+            Line::from(vec![
+                Span::styled(format!(" {} ", marker), style),
+                Span::styled(text, style),
+            ])
+        })
+        .collect();
+
+    let title = format!(" 📋 Plan {}/{} ", counts.completed, counts.total());
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(theme.border_type)
+            .border_style(Style::default().fg(theme.border))
+            .title(title),
+    );
+
+    f.render_widget(paragraph, area);
+}
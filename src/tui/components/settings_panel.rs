@@ -9,8 +9,8 @@
 //! Follows the "components own their state" pattern from CLAUDE.md.
 
 use super::theme_list_panel::ThemeListPanel;
+use crate::tui::keymap::Action;
 use crate::tui::traits::{Component, ComponentId, Handled, Interactive, RenderContext};
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
     style::Style,
@@ -79,6 +79,9 @@ pub struct SettingsPanel {
     /// (Appearance uses ThemeListPanel.selected instead)
     pub layout_option_index: usize,
 
+    /// Presets listed in the Layout category (built-in + user)
+    preset_count: usize,
+
     /// Track if settings changed (for save on exit)
     pub dirty: bool,
 
@@ -92,6 +95,7 @@ impl SettingsPanel {
             category: SettingsCategory::default(),
             focus: SettingsFocus::default(),
             layout_option_index: 0,
+            preset_count: 0,
             dirty: false,
             theme_list: ThemeListPanel::new(),
         }
@@ -145,32 +149,70 @@ impl SettingsPanel {
         self.theme_list.sync_themes(theme_count, viewport_height);
     }
 
+    /// Sync preset list length (built-in + user presets)
+    pub fn sync_presets(&mut self, preset_count: usize) {
+        self.preset_count = preset_count;
+        self.layout_option_index = self.layout_option_index.min(preset_count.saturating_sub(1));
+    }
+
     /// Scroll to and select the current theme (call when entering Settings)
     pub fn scroll_to_current_theme(&mut self, themes: &[String], current_theme: &str) {
         self.theme_list.scroll_to_theme(themes, current_theme);
     }
 
-    /// Handle key input for Layout options (up/down selection)
-    fn handle_layout_key(&mut self, key: KeyEvent) -> Handled {
-        const PRESET_COUNT: usize = 3; // classic, reasoning, debug
+    /// Select a category by its row in the category list (mouse click)
+    pub fn click_category(&mut self, row: usize) -> bool {
+        let category = match row {
+            0 => SettingsCategory::Appearance,
+            1 => SettingsCategory::Layout,
+            _ => return false,
+        };
+        self.category = category;
+        self.focus = SettingsFocus::Categories;
+        true
+    }
 
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    /// Select an option by its row in the options list (mouse click)
+    pub fn click_option(&mut self, row: usize) -> bool {
+        let selected = match self.category {
+            SettingsCategory::Appearance => match self.theme_list.item_at(row) {
+                Some(index) => {
+                    self.theme_list.selected = index;
+                    true
+                }
+                None => false,
+            },
+            SettingsCategory::Layout if row < self.preset_count => {
+                self.layout_option_index = row;
+                true
+            }
+            SettingsCategory::Layout => false,
+        };
+        if selected {
+            self.focus = SettingsFocus::Options;
+        }
+        selected
+    }
+
+    /// Handle key input for Layout options (up/down selection)
+    fn handle_layout_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 self.layout_option_index = self.layout_option_index.saturating_sub(1);
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if self.layout_option_index < PRESET_COUNT - 1 {
+            Action::Down => {
+                if self.layout_option_index + 1 < self.preset_count {
                     self.layout_option_index += 1;
                 }
                 Handled::Yes
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::Top => {
                 self.layout_option_index = 0;
                 Handled::Yes
             }
-            KeyCode::End | KeyCode::Char('G') => {
-                self.layout_option_index = PRESET_COUNT - 1;
+            Action::Bottom => {
+                self.layout_option_index = self.preset_count.saturating_sub(1);
                 Handled::Yes
             }
             _ => Handled::No,
@@ -178,13 +220,13 @@ impl SettingsPanel {
     }
 
     /// Handle key input for category navigation
-    fn handle_category_key(&mut self, key: KeyEvent) -> Handled {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    fn handle_category_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 self.prev_category();
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Action::Down => {
                 self.next_category();
                 Handled::Yes
             }
@@ -227,12 +269,12 @@ impl Component for SettingsPanel {
 }
 
 impl Interactive for SettingsPanel {
-    fn handle_key(&mut self, key: KeyEvent) -> Handled {
+    fn handle_action(&mut self, action: Action) -> Handled {
         match self.focus {
-            SettingsFocus::Categories => self.handle_category_key(key),
+            SettingsFocus::Categories => self.handle_category_action(action),
             SettingsFocus::Options => match self.category {
-                SettingsCategory::Appearance => self.theme_list.handle_key(key),
-                SettingsCategory::Layout => self.handle_layout_key(key),
+                SettingsCategory::Appearance => self.theme_list.handle_action(action),
+                SettingsCategory::Layout => self.handle_layout_action(action),
             },
         }
     }
@@ -246,7 +288,7 @@ impl Interactive for SettingsPanel {
             SettingsFocus::Categories => Some("↑↓:category  Tab/→:options"),
             SettingsFocus::Options => match self.category {
                 SettingsCategory::Appearance => Some("↑↓:select  Enter:apply  Tab/←:back"),
-                SettingsCategory::Layout => Some("↑↓:select  Enter:apply  Tab/←:back"),
+                SettingsCategory::Layout => Some("↑↓:select  Enter:apply  x:export  Tab/←:back"),
             },
         }
     }
@@ -3,7 +3,7 @@
 // Renders statistics at the bottom: uptime, requests, tools, success rate, cost.
 
 use super::formatters::format_compact_number;
-use crate::tui::app::App;
+use crate::tui::app::{App, View};
 use crate::tui::layout::Breakpoint;
 use ratatui::prelude::Alignment;
 use ratatui::{
@@ -19,6 +19,11 @@ use ratatui::{
 /// - Wide: Full format with labels
 /// - Narrow: Compact icon-based format
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    if let Some(input) = &app.active_search().input {
+        render_search_prompt(f, area, app, input);
+        return;
+    }
+
     let stats = &app.stats;
     let bp = Breakpoint::from_width(area.width);
 
@@ -114,3 +119,33 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
 
     f.render_widget(status, area);
 }
+
+/// Render the `/` search prompt in place of the statistics
+///
+/// Parse errors replace the prompt text so they're visible while typing.
+fn render_search_prompt(f: &mut Frame, area: Rect, app: &App, input: &str) {
+    let (text, color) = match &app.active_search().error {
+        Some(err) => (format!(" /{}▏  {}", input, err), app.theme.error),
+        None => (format!(" /{}▏", input), app.theme.foreground),
+    };
+
+    // Tab runs the query against lifestats (Events view only)
+    let hint = if app.view == View::History {
+        "Enter:apply  Ctrl+U:clear  Esc:cancel"
+    } else {
+        "Enter:apply  Tab:history  Ctrl+U:clear  Esc:cancel"
+    };
+
+    let prompt = Paragraph::new(text)
+        .style(Style::default().fg(color))
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(app.theme.highlight))
+                .title_alignment(Alignment::Center)
+                .title_style(Style::default().fg(app.theme.muted))
+                .title(hint),
+        );
+
+    f.render_widget(prompt, area);
+}
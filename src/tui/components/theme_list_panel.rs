@@ -4,11 +4,11 @@
 //! Implements the trait system: Component, Scrollable, Selectable, Interactive.
 
 use crate::theme::Theme;
+use crate::tui::keymap::Action;
 use crate::tui::scroll::ScrollState;
 use crate::tui::traits::{
     Component, ComponentId, Handled, Interactive, RenderContext, Scrollable, Selectable,
 };
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
@@ -89,6 +89,19 @@ impl ThemeListPanel {
         }
     }
 
+    /// Item index at a row of the list (0 = first row under the border)
+    pub fn item_at(&self, row: usize) -> Option<usize> {
+        let end = (self.offset + self.viewport).min(self.total_items());
+        let themes_shown = end.min(self.theme_count).saturating_sub(self.offset);
+        if row < themes_shown {
+            return Some(self.offset + row);
+        }
+        // Background toggle sits after a spacer row when themes are shown above it
+        let toggle_row = themes_shown + usize::from(themes_shown > 0);
+        let toggle_shown = self.include_bg_toggle && self.theme_count < end;
+        (toggle_shown && row == toggle_row).then_some(self.theme_count)
+    }
+
     /// Total item count (themes + optional bg toggle)
     fn total_items(&self) -> usize {
         if self.include_bg_toggle {
@@ -275,16 +288,16 @@ impl Selectable for ThemeListPanel {
 }
 
 impl Interactive for ThemeListPanel {
-    fn handle_key(&mut self, key: KeyEvent) -> Handled {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    fn handle_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 if self.selected > 0 {
                     self.selected -= 1;
                     self.ensure_visible();
                 }
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Action::Down => {
                 let max = self.total_items().saturating_sub(1);
                 if self.selected < max {
                     self.selected += 1;
@@ -292,23 +305,23 @@ impl Interactive for ThemeListPanel {
                 }
                 Handled::Yes
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::Top => {
                 self.selected = 0;
                 self.ensure_visible();
                 Handled::Yes
             }
-            KeyCode::End | KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.selected = self.total_items().saturating_sub(1);
                 self.ensure_visible();
                 Handled::Yes
             }
-            KeyCode::PageUp => {
+            Action::PageUp => {
                 let page = self.viewport.max(1);
                 self.selected = self.selected.saturating_sub(page);
                 self.ensure_visible();
                 Handled::Yes
             }
-            KeyCode::PageDown => {
+            Action::PageDown => {
                 let page = self.viewport.max(1);
                 let max = self.total_items().saturating_sub(1);
                 self.selected = (self.selected + page).min(max);
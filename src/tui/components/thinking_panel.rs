@@ -7,13 +7,13 @@
 
 use super::scrollbar::{render_scrollbar, ScrollbarStyle};
 use crate::theme::Theme;
+use crate::tui::keymap::Action;
 use crate::tui::markdown;
 use crate::tui::scroll::{FocusablePanel, ScrollState};
 use crate::tui::streaming::StreamingState;
 use crate::tui::traits::{
     Component, ComponentId, Copyable, Handled, Interactive, RenderContext, Scrollable, Zoomable,
 };
-use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::Rect,
     style::Style,
@@ -173,29 +173,29 @@ impl Copyable for ThinkingPanel {
 }
 
 impl Interactive for ThinkingPanel {
-    fn handle_key(&mut self, key: KeyEvent) -> Handled {
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    fn handle_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 self.scroll_up();
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Action::Down => {
                 self.scroll_down();
                 Handled::Yes
             }
-            KeyCode::Home | KeyCode::Char('g') => {
+            Action::Top => {
                 self.scroll_to_top();
                 Handled::Yes
             }
-            KeyCode::End | KeyCode::Char('G') => {
+            Action::Bottom => {
                 self.scroll_to_bottom();
                 Handled::Yes
             }
-            KeyCode::PageUp => {
+            Action::PageUp => {
                 self.page_up();
                 Handled::Yes
             }
-            KeyCode::PageDown => {
+            Action::PageDown => {
                 self.page_down();
                 Handled::Yes
             }
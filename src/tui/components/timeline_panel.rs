@@ -0,0 +1,416 @@
+//! Timeline panel component (Stats view, Timeline tab)
+//!
+//! Draws a session's [`Timeline`] as a waterfall: one row per span, bars on a
+//! shared time axis. Requests show time to first byte (`░`) then streaming
+//! (`█`); thinking, tools and idle gaps get their own rows.
+//!
+//! Zoom halves or doubles the visible window around its center, and panning
+//! moves it by a quarter. At the right edge the window stays pinned to the
+//! latest activity, and rows follow new spans like the Events list.
+
+use super::short_model_name;
+use crate::theme::Theme;
+use crate::timeline::{Span as TimelineSpan, SpanKind, Timeline};
+use crate::tui::keymap::Action;
+use crate::tui::scroll::ScrollState;
+use crate::tui::traits::{Component, ComponentId, Handled, Interactive, RenderContext};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// Width of the kind / name / duration column
+const LABEL_WIDTH: usize = 34;
+
+/// Deepest zoom (window = session / 2^MAX_ZOOM)
+const MAX_ZOOM: u32 = 12;
+
+/// Narrowest window, however long the session
+const MIN_WINDOW_MS: u64 = 100;
+
+/// Waterfall state: zoom, horizontal window and row scroll
+#[derive(Debug, Default)]
+pub struct TimelinePanel {
+    /// Window is the session length / 2^zoom
+    pub zoom: u32,
+    /// Window start, or None to stay pinned to the latest activity
+    pub offset_ms: Option<u64>,
+    pub scroll: ScrollState,
+    /// Session length at the last render (pan and zoom clamp against it)
+    duration_ms: u64,
+}
+
+impl TimelinePanel {
+    /// Visible span of time
+    fn window_ms(&self) -> u64 {
+        (self.duration_ms >> self.zoom).max(MIN_WINDOW_MS)
+    }
+
+    /// Window start, clamped to the session
+    fn start_ms(&self) -> u64 {
+        let last_start = self.duration_ms.saturating_sub(self.window_ms());
+        self.offset_ms
+            .map_or(last_start, |offset| offset.min(last_start))
+    }
+
+    /// Move the window start; reaching the end pins it there again
+    fn set_start(&mut self, start: u64) {
+        let last_start = self.duration_ms.saturating_sub(self.window_ms());
+        self.offset_ms = (start < last_start).then_some(start);
+    }
+
+    /// Change zoom, keeping the middle of the window in place
+    fn zoom_to(&mut self, zoom: u32) {
+        let center = self.start_ms() + self.window_ms() / 2;
+        let pinned = self.offset_ms.is_none();
+        self.zoom = zoom;
+        if !pinned {
+            self.set_start(center.saturating_sub(self.window_ms() / 2));
+        }
+    }
+
+    fn pan(&mut self, forward: bool) {
+        let step = (self.window_ms() / 4).max(1);
+        let start = self.start_ms();
+        self.set_start(if forward {
+            start + step
+        } else {
+            start.saturating_sub(step)
+        });
+    }
+
+    /// Render the waterfall for `timeline`
+    pub fn render_timeline(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        timeline: &Timeline,
+        theme: &Theme,
+    ) {
+        self.duration_ms = timeline.duration_ms();
+        while self.zoom > 0 && self.duration_ms >> self.zoom < MIN_WINDOW_MS {
+            self.zoom -= 1;
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(theme.border_type)
+            .border_style(Style::default().fg(theme.border))
+            .title(summary_title(timeline))
+            .title_bottom(
+                Line::from(format!(
+                    " {} of {} · zoom {}x ",
+                    format_ms(self.window_ms().min(self.duration_ms)),
+                    format_ms(self.duration_ms),
+                    1u64 << self.zoom
+                ))
+                .right_aligned(),
+            );
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        if timeline.spans.is_empty() {
+            let empty = Paragraph::new(
+                "No requests or tool calls in this session yet - the waterfall fills in as they complete",
+            )
+            .style(Style::default().fg(theme.muted));
+            f.render_widget(empty, inner);
+            return;
+        }
+
+        // Axis row, then one row per span
+        let viewport = inner.height.saturating_sub(1) as usize;
+        self.scroll
+            .update_dimensions(timeline.spans.len(), viewport);
+        let (first, last) = self.scroll.visible_range();
+
+        let bar_width = (inner.width as usize).saturating_sub(LABEL_WIDTH + 1);
+        let window = Window {
+            start_ms: self.start_ms(),
+            window_ms: self.window_ms(),
+            width: bar_width,
+        };
+
+        let mut lines = vec![axis_line(&window, theme)];
+        lines.extend(
+            timeline.spans[first..last.min(timeline.spans.len())]
+                .iter()
+                .map(|span| span_line(span, &window, theme)),
+        );
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+/// Visible time range mapped onto bar columns
+struct Window {
+    start_ms: u64,
+    window_ms: u64,
+    width: usize,
+}
+
+impl Window {
+    /// Column of a time, unclamped (may fall outside 0..width)
+    fn column(&self, ms: u64) -> i64 {
+        ((ms as f64 - self.start_ms as f64) * self.width as f64 / self.window_ms as f64).floor()
+            as i64
+    }
+
+    /// Columns covered by `start..end`, at least one when visible
+    fn columns(&self, start_ms: u64, end_ms: u64) -> Option<(usize, usize)> {
+        let (from, to) = (self.column(start_ms), self.column(end_ms));
+        if to < 0 || from >= self.width as i64 {
+            return None;
+        }
+        let from = from.max(0) as usize;
+        let to = (to.max(from as i64 + 1) as usize).min(self.width);
+        Some((from, to))
+    }
+}
+
+/// Title: total time and where it went
+fn summary_title(timeline: &Timeline) -> String {
+    let total = timeline.duration_ms();
+    let parts: Vec<String> = SpanKind::ALL
+        .iter()
+        .map(|&kind| {
+            let ms = timeline.total_ms(kind);
+            let percent = (ms * 100).checked_div(total).unwrap_or(0);
+            format!("{} {} ({}%)", kind.label(), format_ms(ms), percent)
+        })
+        .collect();
+    format!(" Timeline {} · {} ", format_ms(total), parts.join(" · "))
+}
+
+/// Time labels along the bar column
+fn axis_line(window: &Window, theme: &Theme) -> Line<'static> {
+    let mut axis = vec![' '; window.width];
+    // A tick every ~12 columns, on a round step
+    let target = window.window_ms * 12 / window.width.max(1) as u64;
+    let step = tick_step(target.max(1));
+    let mut tick = window.start_ms.div_ceil(step) * step;
+    while tick <= window.start_ms + window.window_ms {
+        let column = window.column(tick);
+        let label = format!("│{}", format_ms(tick));
+        if column >= 0 && column as usize + label.chars().count() <= window.width {
+            for (i, c) in label.chars().enumerate() {
+                axis[column as usize + i] = c;
+            }
+        }
+        tick += step;
+    }
+    Line::from(vec![
+        Span::styled(
+            format!("{:<width$} ", "", width = LABEL_WIDTH),
+            Style::default(),
+        ),
+        Span::styled(
+            axis.into_iter().collect::<String>(),
+            Style::default().fg(theme.muted),
+        ),
+    ])
+}
+
+/// Smallest round step (1, 2, 5 × 10^n ms, then seconds and minutes) ≥ target
+fn tick_step(target: u64) -> u64 {
+    const STEPS: [u64; 18] = [
+        1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000, 15_000, 30_000, 60_000,
+        300_000, 600_000,
+    ];
+    STEPS
+        .iter()
+        .copied()
+        .find(|&step| step >= target)
+        .unwrap_or_else(|| target.div_ceil(3_600_000) * 3_600_000)
+}
+
+/// One waterfall row: label column, then the bar
+fn span_line(span: &TimelineSpan, window: &Window, theme: &Theme) -> Line<'static> {
+    let color = match span.kind {
+        _ if span.failed => theme.error,
+        SpanKind::Request => theme.request,
+        SpanKind::Thinking => theme.thinking,
+        SpanKind::Tool => theme.tool_call,
+        SpanKind::Idle => theme.muted,
+    };
+    let duration = match span.open {
+        true => format!("{}…", format_ms(span.duration_ms())),
+        false => format_ms(span.duration_ms()),
+    };
+    // Thinking belongs to the request above it
+    let indent = if span.kind == SpanKind::Thinking {
+        "  "
+    } else {
+        ""
+    };
+    let name_width = LABEL_WIDTH - 9 - 8 - indent.len();
+    // Model ids without the family prefix and date: `opus-4-5`
+    let name: String = short_model_name(&span.name)
+        .chars()
+        .take(name_width)
+        .collect();
+    let label = format!(
+        "{}{:<8} {:<name_width$} {:>7} ",
+        indent,
+        span.kind.label(),
+        name,
+        duration,
+    );
+
+    let mut bar: Vec<Span<'static>> = Vec::new();
+    let mut column = 0;
+    let mut push =
+        |bar: &mut Vec<Span<'static>>, from: usize, to: usize, fill: char, style: Style| {
+            if from > column {
+                bar.push(Span::raw(" ".repeat(from - column)));
+            }
+            if to > from.max(column) {
+                let width = to - from.max(column);
+                bar.push(Span::styled(fill.to_string().repeat(width), style));
+                column = to;
+            }
+        };
+    let style = Style::default().fg(color);
+    match (span.kind, span.ttfb_ms) {
+        (SpanKind::Request, Some(ttfb)) => {
+            let first_byte = span.start_ms + ttfb;
+            if let Some((from, to)) = window.columns(span.start_ms, first_byte) {
+                push(&mut bar, from, to, '░', style);
+            }
+            if let Some((from, to)) = window.columns(first_byte, span.end_ms) {
+                push(&mut bar, from, to, '█', style);
+            }
+        }
+        (SpanKind::Idle, _) => {
+            if let Some((from, to)) = window.columns(span.start_ms, span.end_ms) {
+                push(&mut bar, from, to, '·', style);
+            }
+        }
+        _ => {
+            if let Some((from, to)) = window.columns(span.start_ms, span.end_ms) {
+                push(&mut bar, from, to, '█', style);
+            }
+        }
+    }
+
+    let label_style = match span.kind {
+        SpanKind::Idle => Style::default().fg(theme.muted),
+        _ if span.failed => Style::default().fg(theme.error),
+        _ => Style::default().fg(theme.foreground),
+    };
+    let mut spans = vec![
+        Span::styled(label, label_style),
+        Span::styled("│", Style::default().fg(theme.border)),
+    ];
+    spans.extend(bar);
+    if span.open {
+        spans.push(Span::styled(
+            "▸",
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// `850ms`, `12.4s`, `3m 05s`, `1h 02m`
+fn format_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else if ms >= 1000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+impl Component for TimelinePanel {
+    fn id(&self) -> ComponentId {
+        ComponentId::Timeline
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect, ctx: &RenderContext) {
+        // Placeholder - actual rendering is done by render_timeline()
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(ctx.theme.border_type)
+            .border_style(Style::default().fg(ctx.theme.border))
+            .title(" Timeline ");
+
+        f.render_widget(block, area);
+    }
+}
+
+impl Interactive for TimelinePanel {
+    fn handle_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => self.scroll.scroll_up(),
+            Action::Down => self.scroll.scroll_down(),
+            Action::Top => self.scroll.scroll_to_top(),
+            Action::Bottom => self.scroll.scroll_to_bottom(),
+            Action::PageUp => self.scroll.page_up(),
+            Action::PageDown => self.scroll.page_down(),
+            Action::TimelineZoomIn => self.zoom_to((self.zoom + 1).min(MAX_ZOOM)),
+            Action::TimelineZoomOut => self.zoom_to(self.zoom.saturating_sub(1)),
+            Action::TimelinePanLeft => self.pan(false),
+            Action::TimelinePanRight => self.pan(true),
+            _ => return Handled::No,
+        }
+        Handled::Yes
+    }
+
+    fn focus_hint(&self) -> Option<&'static str> {
+        Some("↑↓:scroll  +/-:zoom  h/l:pan  x:export trace  Tab:next tab")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_and_pan_window() {
+        let mut panel = TimelinePanel {
+            duration_ms: 80_000,
+            ..Default::default()
+        };
+        assert_eq!((panel.start_ms(), panel.window_ms()), (0, 80_000));
+
+        // Zooming while pinned keeps the latest activity in view
+        panel.handle_action(Action::TimelineZoomIn);
+        assert_eq!((panel.start_ms(), panel.window_ms()), (40_000, 40_000));
+
+        // Pan back a quarter window, then zoom in around the middle
+        panel.handle_action(Action::TimelinePanLeft);
+        assert_eq!(panel.start_ms(), 30_000);
+        panel.handle_action(Action::TimelineZoomIn);
+        assert_eq!((panel.start_ms(), panel.window_ms()), (40_000, 20_000));
+
+        // Panning past the end pins the window again
+        for _ in 0..10 {
+            panel.handle_action(Action::TimelinePanRight);
+        }
+        assert_eq!(panel.offset_ms, None);
+        assert_eq!(panel.start_ms(), 60_000);
+    }
+
+    #[test]
+    fn test_window_columns() {
+        let window = Window {
+            start_ms: 1_000,
+            window_ms: 10_000,
+            width: 100,
+        };
+        assert_eq!(window.columns(2_000, 3_000), Some((10, 20)));
+        // Short spans still get a column; off-screen ones none
+        assert_eq!(window.columns(5_000, 5_001), Some((40, 41)));
+        assert_eq!(window.columns(0, 500), None);
+        assert_eq!(window.columns(0, 50_000), Some((0, 100)));
+        assert_eq!(tick_step(700), 1_000);
+        assert_eq!(format_ms(185_000), "3m 05s");
+    }
+}
@@ -17,7 +17,8 @@ use ratatui::{
 /// - App name ("Aspy")
 /// - Streaming indicator (spinner + state) when active
 /// - Conversation topic (extracted from Haiku summarization)
-/// - Session indicator (right side, when multiple sessions active)
+/// - Session indicator (right side, when multiple sessions active, or the
+///   replayed session from the History view)
 /// - Zoom indicator (right side, hidden on small screens)
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
     let bp = Breakpoint::from_width(area.width);
@@ -42,7 +43,14 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
     };
 
     // Build session indicator (show when we have sessions)
-    let session_indicator = if let Some(session) = app.effective_session() {
+    // A replayed past session replaces the live one
+    let session_indicator = if let Some(replay) = &app.replay {
+        let short_id: String = replay.session.id.chars().take(8).collect();
+        match &replay.session.user_id {
+            Some(client) => format!("📜 [{} {}] read-only ", client, short_id),
+            None => format!("📜 [{}] read-only ", short_id),
+        }
+    } else if let Some(session) = app.effective_session() {
         let count = app.session_count();
         if count > 1 {
             // Multiple sessions: show name and count
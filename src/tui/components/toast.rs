@@ -32,6 +32,12 @@ impl Toast {
         }
     }
 
+    /// Override how long the toast stays visible
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
     /// Check if the toast has expired and should be removed
     pub fn is_expired(&self) -> bool {
         self.created_at.elapsed() >= self.duration
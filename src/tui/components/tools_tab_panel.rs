@@ -3,15 +3,18 @@
 // Displays tool usage statistics using:
 // - BarChart showing call frequency by tool name
 // - Duration analysis showing average execution time
+// - Insights list: repeated failures, Edit mismatches, Bash exits,
+//   rejections by tool, slowest individual calls
 // - Success rate indicator
 
 use crate::events::Stats;
 use crate::theme::Theme;
+use crate::tool_analytics::ToolInsights;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
@@ -20,25 +23,41 @@ pub struct ToolsTabPanel;
 
 impl ToolsTabPanel {
     /// Render the panel to a frame
-    pub fn render(frame: &mut Frame, area: Rect, stats: &Stats, theme: &Theme) {
-        // Split into frequency chart (60%), duration chart (30%), and summary (10%)
-        let chunks = Layout::default()
+    pub fn render(
+        frame: &mut Frame,
+        area: Rect,
+        stats: &Stats,
+        insights: &ToolInsights,
+        theme: &Theme,
+    ) {
+        // Split into main area and a 3-line summary
+        let rows = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(60),
-                Constraint::Percentage(30),
-                Constraint::Percentage(10),
-            ])
+            .constraints([Constraint::Min(10), Constraint::Length(3)])
             .split(area);
 
+        // Charts on the left (55%), insights on the right (45%)
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(rows[0]);
+
+        let charts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(columns[0]);
+
         // === BarChart: Tool Call Frequency ===
-        Self::render_frequency_chart(frame, chunks[0], stats, theme);
+        Self::render_frequency_chart(frame, charts[0], stats, theme);
 
         // === BarChart: Average Duration ===
-        Self::render_duration_chart(frame, chunks[1], stats, theme);
+        Self::render_duration_chart(frame, charts[1], stats, theme);
+
+        // === Insights: Failure Patterns ===
+        Self::render_insights(frame, columns[1], insights, theme);
 
         // === Summary: Success Rate ===
-        Self::render_summary(frame, chunks[2], stats, theme);
+        Self::render_summary(frame, rows[1], stats, insights, theme);
     }
 
     fn render_frequency_chart(frame: &mut Frame, area: Rect, stats: &Stats, theme: &Theme) {
@@ -126,7 +145,7 @@ impl ToolsTabPanel {
             .collect();
 
         // Sort by duration (descending)
-        durations.sort_by(|a, b| b.1.cmp(&a.1));
+        durations.sort_by_key(|d| std::cmp::Reverse(d.1));
 
         // Take top 10
         let top_durations: Vec<_> = durations.iter().take(10).collect();
@@ -163,7 +182,111 @@ impl ToolsTabPanel {
         frame.render_widget(chart, area);
     }
 
-    fn render_summary(frame: &mut Frame, area: Rect, stats: &Stats, theme: &Theme) {
+    fn render_insights(frame: &mut Frame, area: Rect, insights: &ToolInsights, theme: &Theme) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Insights ")
+            .border_style(theme.border);
+
+        if insights.calls_analyzed == 0 {
+            let placeholder = Paragraph::new("No tool results yet")
+                .block(block)
+                .style(Style::default().fg(theme.muted));
+            frame.render_widget(placeholder, area);
+            return;
+        }
+
+        let heading = |title: &str| {
+            Line::from(Span::styled(
+                title.to_string(),
+                Style::default()
+                    .fg(theme.foreground)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        };
+        let none = || Line::from(Span::styled("  none", Style::default().fg(theme.muted)));
+        let detail = |label: String, label_color: Color, text: &str| {
+            Line::from(vec![
+                Span::styled(format!("  {:<9} ", label), Style::default().fg(label_color)),
+                Span::styled(text.to_string(), Style::default().fg(theme.foreground)),
+            ])
+        };
+
+        let mut lines = vec![heading("Repeated failures")];
+        if insights.repeated_failures.is_empty() {
+            lines.push(none());
+        }
+        for f in &insights.repeated_failures {
+            lines.push(detail(
+                format!("{}x {}", f.attempts, f.tool_name),
+                Color::Red,
+                &f.signature,
+            ));
+        }
+
+        lines.push(Line::default());
+        lines.push(heading("Edit mismatches"));
+        if insights.edit_mismatches.is_empty() {
+            lines.push(none());
+        }
+        for m in &insights.edit_mismatches {
+            lines.push(detail(
+                m.kind.as_str().to_string(),
+                Color::Yellow,
+                m.file_path.as_deref().unwrap_or("(unknown file)"),
+            ));
+        }
+
+        lines.push(Line::default());
+        lines.push(heading("Bash failures"));
+        if insights.bash_failures.is_empty() {
+            lines.push(none());
+        }
+        for b in &insights.bash_failures {
+            let code = b
+                .exit_code
+                .map(|c| format!("exit {}", c))
+                .unwrap_or_else(|| "error".to_string());
+            lines.push(detail(code, Color::Red, &b.command));
+        }
+
+        lines.push(Line::default());
+        lines.push(heading("Rejected by you"));
+        if insights.rejections_by_tool.is_empty() {
+            lines.push(none());
+        }
+        for r in &insights.rejections_by_tool {
+            lines.push(detail(
+                r.tool_name.clone(),
+                Color::Magenta,
+                &format!(
+                    "{} of {} ({:.0}%)",
+                    r.rejections, r.calls, r.rejection_rate_pct
+                ),
+            ));
+        }
+
+        lines.push(Line::default());
+        lines.push(heading("Slowest calls"));
+        for c in &insights.slowest_calls {
+            lines.push(detail(
+                format!("{:.1}s", c.duration_ms as f64 / 1000.0),
+                Self::duration_color(c.duration_ms),
+                &format!("{} {}", c.tool_name, c.input),
+            ));
+        }
+
+        let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_summary(
+        frame: &mut Frame,
+        area: Rect,
+        stats: &Stats,
+        insights: &ToolInsights,
+        theme: &Theme,
+    ) {
         let total_calls = stats.total_tool_calls;
         let failed_calls = stats.failed_tool_calls;
         let success_rate = if total_calls == 0 {
@@ -190,6 +313,27 @@ impl ToolsTabPanel {
                 format!("{:.1}%", success_rate),
                 Style::default().fg(success_color),
             ),
+            Span::styled("  |  Rejected: ", Style::default().fg(theme.foreground)),
+            Span::styled(
+                format!("{}", insights.rejected_calls),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::styled(
+                "  |  Edit Mismatches: ",
+                Style::default().fg(theme.foreground),
+            ),
+            Span::styled(
+                format!("{}", insights.total_edit_mismatches),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(
+                "  |  Bash Failures: ",
+                Style::default().fg(theme.foreground),
+            ),
+            Span::styled(
+                format!("{}", insights.total_bash_failures),
+                Style::default().fg(Color::Red),
+            ),
         ])];
 
         let summary = Paragraph::new(text).block(
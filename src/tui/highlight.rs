@@ -0,0 +1,703 @@
+// Syntax highlighting for fenced code blocks
+//
+// A small line-oriented lexer rather than a grammar engine: each language is
+// a table of keywords, builtin types, comment and string delimiters, and the
+// lexer colors identifiers, literals and comments from it. That covers what
+// shows up in thinking and tool I/O (Rust, TypeScript/JavaScript, Python,
+// shell, Go, TOML, YAML) without a parser dependency. Unified diffs color
+// whole lines (added, removed, hunk headers).
+//
+// Block comments and multi-line strings carry over between lines, so feed a
+// block's lines through one Highlighter in order. JSON keeps its own
+// highlighter in markdown.rs.
+
+use crate::theme::Theme;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Span;
+use std::path::Path;
+
+/// Languages with highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Rust,
+    /// TypeScript and JavaScript
+    TypeScript,
+    Python,
+    Shell,
+    Go,
+    Toml,
+    Yaml,
+    Diff,
+}
+
+impl Lang {
+    /// From a code fence info string (`rust`, `ts`, `bash`, `diff`, ...)
+    pub fn from_tag(tag: &str) -> Option<Lang> {
+        let tag = tag.split([' ', ',', '{']).next().unwrap_or("");
+        Some(match tag.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Lang::Rust,
+            "typescript" | "ts" | "tsx" | "javascript" | "js" | "jsx" | "mjs" | "cjs" => {
+                Lang::TypeScript
+            }
+            "python" | "py" => Lang::Python,
+            "sh" | "bash" | "zsh" | "shell" | "console" | "shellscript" => Lang::Shell,
+            "go" | "golang" => Lang::Go,
+            "toml" => Lang::Toml,
+            "yaml" | "yml" => Lang::Yaml,
+            "diff" | "patch" | "udiff" => Lang::Diff,
+            _ => return None,
+        })
+    }
+
+    /// From a file path's extension (Write tool content, Read results)
+    pub fn from_path(path: &str) -> Option<Lang> {
+        let path = Path::new(path);
+        match path.file_name().and_then(|n| n.to_str()) {
+            Some(".bashrc" | ".zshrc" | ".profile") => return Some(Lang::Shell),
+            Some("Cargo.lock") => return Some(Lang::Toml),
+            _ => {}
+        }
+        Self::from_tag(path.extension()?.to_str()?)
+    }
+
+    /// Canonical fence tag
+    pub fn tag(self) -> &'static str {
+        match self {
+            Lang::Rust => "rust",
+            Lang::TypeScript => "typescript",
+            Lang::Python => "python",
+            Lang::Shell => "sh",
+            Lang::Go => "go",
+            Lang::Toml => "toml",
+            Lang::Yaml => "yaml",
+            Lang::Diff => "diff",
+        }
+    }
+
+    /// Lexer table (diffs are colored by line instead)
+    fn spec(self) -> Option<&'static Spec> {
+        Some(match self {
+            Lang::Rust => &RUST,
+            Lang::TypeScript => &TYPESCRIPT,
+            Lang::Python => &PYTHON,
+            Lang::Shell => &SHELL,
+            Lang::Go => &GO,
+            Lang::Toml => &TOML,
+            Lang::Yaml => &YAML,
+            Lang::Diff => return None,
+        })
+    }
+}
+
+/// What the lexer needs to know about a language
+struct Spec {
+    keywords: &'static [&'static str],
+    /// Builtin types and constants
+    types: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Single-line string quotes
+    quotes: &'static [char],
+    /// Delimiters of strings that may span lines (`"""`, template literals)
+    long_strings: &'static [&'static str],
+    /// `CamelCase` identifiers are types
+    capitalized_types: bool,
+    /// Rust: `'a` is a lifetime unless it closes like a char literal
+    lifetimes: bool,
+    /// Shell: `$VAR` / `${VAR}` expansions
+    variables: bool,
+    /// Config files: the leading word before this separator is a key
+    key_separator: Option<char>,
+}
+
+const RUST: Spec = Spec {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+    types: &[
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+        "i64", "i128", "isize", "f32", "f64",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    long_strings: &[],
+    capitalized_types: true,
+    lifetimes: true,
+    variables: false,
+    key_separator: None,
+};
+
+const TYPESCRIPT: Spec = Spec {
+    keywords: &[
+        "abstract",
+        "as",
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "declare",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "get",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "keyof",
+        "let",
+        "new",
+        "null",
+        "of",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "return",
+        "set",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    types: &[
+        "any", "bigint", "boolean", "never", "number", "object", "string", "symbol", "unknown",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    long_strings: &["`"],
+    capitalized_types: true,
+    lifetimes: false,
+    variables: false,
+    key_separator: None,
+};
+
+const PYTHON: Spec = Spec {
+    keywords: &[
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+        "if", "import", "in", "is", "lambda", "match", "nonlocal", "not", "or", "pass", "raise",
+        "return", "self", "try", "while", "with", "yield",
+    ],
+    types: &[
+        "bool",
+        "bytes",
+        "dict",
+        "float",
+        "frozenset",
+        "int",
+        "list",
+        "object",
+        "set",
+        "str",
+        "tuple",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    long_strings: &["\"\"\"", "'''"],
+    capitalized_types: true,
+    lifetimes: false,
+    variables: false,
+    key_separator: None,
+};
+
+const SHELL: Spec = Spec {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "readonly", "return", "select", "then", "until", "while",
+    ],
+    types: &[],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    long_strings: &[],
+    capitalized_types: false,
+    lifetimes: false,
+    variables: true,
+    key_separator: None,
+};
+
+const GO: Spec = Spec {
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "false",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "nil",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "true",
+        "type",
+        "var",
+    ],
+    types: &[
+        "any", "bool", "byte", "error", "float32", "float64", "int", "int8", "int16", "int32",
+        "int64", "rune", "string", "uint", "uint8", "uint16", "uint32", "uint64", "uintptr",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    long_strings: &["`"],
+    capitalized_types: true,
+    lifetimes: false,
+    variables: false,
+    key_separator: None,
+};
+
+const TOML: Spec = Spec {
+    keywords: &["true", "false"],
+    types: &[],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    long_strings: &["\"\"\"", "'''"],
+    capitalized_types: false,
+    lifetimes: false,
+    variables: false,
+    key_separator: Some('='),
+};
+
+const YAML: Spec = Spec {
+    keywords: &["true", "false", "null", "yes", "no"],
+    types: &[],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    long_strings: &[],
+    capitalized_types: false,
+    lifetimes: false,
+    variables: false,
+    key_separator: Some(':'),
+};
+
+/// Token classes, each mapped to a theme slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Plain,
+    Keyword,
+    Type,
+    Function,
+    String,
+    Number,
+    Comment,
+    Added,
+    Removed,
+    Hunk,
+}
+
+impl Token {
+    fn style(self, theme: &Theme) -> Style {
+        match self {
+            Token::Plain => Style::default().fg(theme.code_block),
+            Token::Keyword => Style::default().fg(theme.code_keyword),
+            Token::Type => Style::default().fg(theme.code_type),
+            Token::Function => Style::default().fg(theme.code_function),
+            Token::String => Style::default().fg(theme.code_string),
+            Token::Number => Style::default().fg(theme.code_number),
+            Token::Comment => Style::default()
+                .fg(theme.code_comment)
+                .add_modifier(Modifier::ITALIC),
+            Token::Added => Style::default().fg(theme.diff_added),
+            Token::Removed => Style::default().fg(theme.diff_removed),
+            Token::Hunk => Style::default()
+                .fg(theme.diff_hunk)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+/// Highlights one code block line by line
+pub struct Highlighter {
+    lang: Lang,
+    /// Unclosed block comment or long string: (closing delimiter, token)
+    open: Option<(&'static str, Token)>,
+}
+
+impl Highlighter {
+    pub fn new(lang: Lang) -> Self {
+        Self { lang, open: None }
+    }
+
+    /// Styled spans for the next line of the block
+    pub fn line(&mut self, line: &str, theme: &Theme) -> Vec<Span<'static>> {
+        self.tokens(line)
+            .into_iter()
+            .map(|(text, token)| Span::styled(text, token.style(theme)))
+            .collect()
+    }
+
+    /// Split a line into (text, token) runs, adjacent runs merged
+    fn tokens(&mut self, line: &str) -> Vec<(String, Token)> {
+        let Some(spec) = self.lang.spec() else {
+            return vec![(line.to_string(), diff_token(line))];
+        };
+        let mut out = Runs::default();
+        let mut rest = line;
+
+        // Continue a block comment / long string from an earlier line
+        if let Some((close, token)) = self.open {
+            match rest.find(close) {
+                Some(end) => {
+                    let end = end + close.len();
+                    out.push(&rest[..end], token);
+                    rest = &rest[end..];
+                    self.open = None;
+                }
+                None => {
+                    out.push(rest, token);
+                    return out.0;
+                }
+            }
+        }
+
+        // Config key at the start of the line (`key =`, `key:`, `- key:`)
+        if let Some(separator) = spec.key_separator {
+            let body = rest.trim_start_matches([' ', '\t', '-']);
+            let indent = &rest[..rest.len() - body.len()];
+            if body.starts_with('[') && separator == '=' {
+                out.push(indent, Token::Plain);
+                let (header, tail) = split_comment(body, spec);
+                out.push(header, Token::Keyword);
+                out.push(tail, Token::Comment);
+                return out.0;
+            }
+            if let Some(end) = body.find(separator) {
+                let key = body[..end].trim_end();
+                let plain_key = !key.is_empty() && !key.contains([' ', '#', '"', '\'']);
+                if plain_key || is_quoted(key) {
+                    out.push(indent, Token::Plain);
+                    out.push(key, Token::Function);
+                    rest = &body[key.len()..];
+                }
+            }
+        }
+
+        let mut prev: Option<char> = None;
+        while !rest.is_empty() {
+            let c = rest.chars().next().unwrap_or(' ');
+            let word_start = !prev.is_some_and(|p| p.is_alphanumeric() || p == '_');
+
+            // Comments (in shell, `#` only after whitespace: `a#b` and `$#` aren't)
+            if spec.line_comments.iter().any(|m| rest.starts_with(m))
+                && !(spec.variables && prev.is_some_and(|p| !p.is_whitespace()))
+            {
+                out.push(rest, Token::Comment);
+                break;
+            }
+            if let Some((open, close)) = spec.block_comment {
+                if rest.starts_with(open) {
+                    match rest[open.len()..].find(close) {
+                        Some(end) => {
+                            let end = open.len() + end + close.len();
+                            out.push(&rest[..end], Token::Comment);
+                            rest = &rest[end..];
+                            prev = Some('/');
+                            continue;
+                        }
+                        None => {
+                            out.push(rest, Token::Comment);
+                            self.open = Some((close, Token::Comment));
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // Strings
+            if let Some(delim) = spec.long_strings.iter().find(|d| rest.starts_with(**d)) {
+                match rest[delim.len()..].find(*delim) {
+                    Some(end) => {
+                        let end = delim.len() + end + delim.len();
+                        out.push(&rest[..end], Token::String);
+                        rest = &rest[end..];
+                        prev = Some('"');
+                        continue;
+                    }
+                    None => {
+                        out.push(rest, Token::String);
+                        self.open = Some((delim, Token::String));
+                        break;
+                    }
+                }
+            }
+            if spec.lifetimes && c == '\'' {
+                if let Some(end) = char_literal_end(rest) {
+                    out.push(&rest[..end], Token::String);
+                    rest = &rest[end..];
+                } else {
+                    let end = 1 + ident_len(&rest[1..]);
+                    out.push(&rest[..end], Token::Type);
+                    rest = &rest[end..];
+                }
+                prev = Some('\'');
+                continue;
+            }
+            if spec.quotes.contains(&c) {
+                // Shell single quotes have no escapes
+                let end = string_end(rest, c, !(spec.variables && c == '\''));
+                out.push(&rest[..end], Token::String);
+                rest = &rest[end..];
+                prev = Some(c);
+                continue;
+            }
+
+            // Shell expansions
+            if spec.variables && c == '$' {
+                let end = match rest[1..].chars().next() {
+                    Some('{') => rest.find('}').map_or(rest.len(), |i| i + 1),
+                    Some(ch) if ch.is_alphanumeric() || ch == '_' => 1 + ident_len(&rest[1..]),
+                    Some('@' | '#' | '?' | '$' | '!' | '*') => 2,
+                    _ => 1,
+                };
+                out.push(&rest[..end], Token::Type);
+                rest = &rest[end..];
+                prev = Some('}');
+                continue;
+            }
+
+            // Numbers
+            if c.is_ascii_digit() && word_start {
+                let end = rest
+                    .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '.'))
+                    .unwrap_or(rest.len());
+                out.push(&rest[..end], Token::Number);
+                rest = &rest[end..];
+                prev = Some('0');
+                continue;
+            }
+
+            // Identifiers
+            if c.is_alphabetic() || c == '_' {
+                let end = ident_len(rest);
+                let word = &rest[..end];
+                let next = rest[end..].chars().next();
+                let token = if spec.keywords.contains(&word) {
+                    Token::Keyword
+                } else if spec.types.contains(&word)
+                    || (spec.capitalized_types && word.starts_with(char::is_uppercase))
+                {
+                    Token::Type
+                } else if next == Some('(') || (spec.lifetimes && next == Some('!')) {
+                    Token::Function
+                } else {
+                    Token::Plain
+                };
+                out.push(word, token);
+                rest = &rest[end..];
+                prev = Some('a');
+                continue;
+            }
+
+            out.push(&rest[..c.len_utf8()], Token::Plain);
+            rest = &rest[c.len_utf8()..];
+            prev = Some(c);
+        }
+        out.0
+    }
+}
+
+/// Accumulated (text, token) runs
+#[derive(Default)]
+struct Runs(Vec<(String, Token)>);
+
+impl Runs {
+    fn push(&mut self, text: &str, token: Token) {
+        if text.is_empty() {
+            return;
+        }
+        match self.0.last_mut() {
+            Some((last, t)) if *t == token => last.push_str(text),
+            _ => self.0.push((text.to_string(), token)),
+        }
+    }
+}
+
+/// Byte length of the identifier at the start of `s`
+fn ident_len(s: &str) -> usize {
+    s.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(s.len())
+}
+
+/// End of a quoted string starting at `s[0]` (the line's end if unclosed)
+fn string_end(s: &str, quote: char, escapes: bool) -> usize {
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if escapes => escaped = true,
+            c if c == quote => return i + c.len_utf8(),
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+/// Diff lines are colored whole
+fn diff_token(line: &str) -> Token {
+    const HEADERS: [&str; 5] = ["@@", "+++", "---", "diff ", "index "];
+    if HEADERS.iter().any(|h| line.starts_with(h)) {
+        Token::Hunk
+    } else if line.starts_with('+') {
+        Token::Added
+    } else if line.starts_with('-') {
+        Token::Removed
+    } else {
+        Token::Plain
+    }
+}
+
+/// End of a Rust char literal (`'x'`, `'\n'`, `'\u{1F600}'`) at the start of `s`
+fn char_literal_end(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1);
+    let (_, first) = chars.next()?;
+    if first == '\\' {
+        return s[2..].find('\'').map(|i| i + 3).filter(|&end| end <= 12);
+    }
+    match chars.next() {
+        Some((i, '\'')) => Some(i + 1),
+        _ => None,
+    }
+}
+
+fn is_quoted(key: &str) -> bool {
+    key.len() >= 2
+        && ((key.starts_with('"') && key.ends_with('"'))
+            || (key.starts_with('\'') && key.ends_with('\'')))
+}
+
+/// Split a TOML table header from a trailing comment
+fn split_comment<'a>(s: &'a str, spec: &Spec) -> (&'a str, &'a str) {
+    spec.line_comments
+        .iter()
+        .filter_map(|m| s.find(m))
+        .min()
+        .map_or((s, ""), |i| s.split_at(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(lang: Lang, lines: &[&str]) -> Vec<Vec<(String, Token)>> {
+        let mut h = Highlighter::new(lang);
+        lines.iter().map(|l| h.tokens(l)).collect()
+    }
+
+    fn kinds(runs: &[(String, Token)]) -> Vec<(&str, Token)> {
+        runs.iter()
+            .filter(|(t, _)| !t.trim().is_empty())
+            .map(|(t, k)| (t.trim(), *k))
+            .collect()
+    }
+
+    #[test]
+    fn test_lang_lookup() {
+        assert_eq!(Lang::from_tag("tsx"), Some(Lang::TypeScript));
+        assert_eq!(Lang::from_tag("rust,ignore"), Some(Lang::Rust));
+        assert_eq!(Lang::from_path("/src/main.rs"), Some(Lang::Rust));
+        assert_eq!(Lang::from_path("/home/u/.zshrc"), Some(Lang::Shell));
+        assert_eq!(Lang::from_tag("cobol"), None);
+    }
+
+    #[test]
+    fn test_rust_line() {
+        let lines = tokens(
+            Lang::Rust,
+            &["fn parse<'a>(s: &'a str) -> Option<u8> { let c = 'x'; println!(\"{}\", 42) } // done"],
+        );
+        let runs = kinds(&lines[0]);
+        assert!(runs.contains(&("fn", Token::Keyword)));
+        assert!(runs.contains(&("'a", Token::Type)));
+        assert!(runs.contains(&("Option", Token::Type)));
+        assert!(runs.contains(&("'x'", Token::String)));
+        assert!(runs.contains(&("println", Token::Function)));
+        assert!(runs.contains(&("\"{}\"", Token::String)));
+        assert!(runs.contains(&("42", Token::Number)));
+        assert_eq!(runs.last(), Some(&("// done", Token::Comment)));
+    }
+
+    #[test]
+    fn test_state_carries_across_lines() {
+        let lines = tokens(
+            Lang::TypeScript,
+            &["const a = 1; /* start", "still comment */ f(`x", "y`)"],
+        );
+        assert_eq!(kinds(&lines[0]).last(), Some(&("/* start", Token::Comment)));
+        assert_eq!(kinds(&lines[1])[0], ("still comment */", Token::Comment));
+        assert_eq!(kinds(&lines[1])[1], ("f", Token::Function));
+        assert_eq!(kinds(&lines[2])[0], ("y`", Token::String));
+    }
+
+    #[test]
+    fn test_shell_and_config() {
+        let lines = tokens(Lang::Shell, &["echo \"$HOME\" ${PATH} # note $#"]);
+        let sh = kinds(&lines[0]);
+        assert!(sh.contains(&("${PATH}", Token::Type)));
+        assert_eq!(sh.last(), Some(&("# note $#", Token::Comment)));
+
+        let toml = tokens(Lang::Toml, &["[server] # main", "port = 8080"]);
+        assert_eq!(
+            kinds(&toml[0]),
+            [("[server]", Token::Keyword), ("# main", Token::Comment)]
+        );
+        assert_eq!(kinds(&toml[1])[0], ("port", Token::Function));
+        assert_eq!(kinds(&toml[1]).last(), Some(&("8080", Token::Number)));
+
+        let diff = tokens(Lang::Diff, &["@@ -1 +1 @@", "-old", "+new", " same"]);
+        let diff_kinds: Vec<Token> = diff.iter().map(|l| l[0].1).collect();
+        assert_eq!(
+            diff_kinds,
+            [Token::Hunk, Token::Removed, Token::Added, Token::Plain]
+        );
+    }
+}
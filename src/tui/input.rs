@@ -4,6 +4,7 @@
 // - State-change only keys (trigger once per press)
 // - Repeatable keys (trigger on press, then repeat while held)
 
+use super::keymap::{Keymap, REGISTRY};
 use crossterm::event::KeyCode;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -161,69 +162,28 @@ impl InputHandler {
         }
     }
 
-    /// Get default configuration for common keys
-    pub fn with_default_config() -> Self {
+    /// Behaviors for every key bound in the keymap. A key shared by a
+    /// repeatable action and a one-shot action repeats (e.g. → scrolls the
+    /// detail modal and switches panels).
+    pub fn for_keymap(keymap: &Keymap) -> Self {
         let mut handler = Self::new();
-
-        // Navigation keys - repeatable
-        handler.configure_keys(
-            &[KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right],
-            KeyBehavior::navigation(),
-        );
-
-        // Vim keys - repeatable
-        handler.configure_keys(
-            &[
-                KeyCode::Char('j'),
-                KeyCode::Char('k'),
-                KeyCode::Char('h'),
-                KeyCode::Char('l'),
-            ],
-            KeyBehavior::navigation(),
-        );
-
-        // Page navigation - fast repeatable
-        handler.configure_keys(
-            &[
-                KeyCode::PageUp,
-                KeyCode::PageDown,
-                KeyCode::Home,
-                KeyCode::End,
-            ],
-            KeyBehavior::fast_navigation(),
-        );
-
-        // Action keys - state change only (trigger once per press)
-        handler.configure_keys(
-            &[
-                // Core actions
-                KeyCode::Enter,
-                KeyCode::Esc,
-                KeyCode::Tab,
-                KeyCode::BackTab,
-                KeyCode::Char(' '),
-                // Quit
-                KeyCode::Char('q'),
-                KeyCode::Char('Q'),
-                // View switching
-                KeyCode::F(1),
-                KeyCode::F(2),
-                KeyCode::F(3),
-                KeyCode::Char('e'),
-                KeyCode::Char('E'),
-                KeyCode::Char('s'),
-                KeyCode::Char('S'),
-                // Clipboard
-                KeyCode::Char('y'),
-                KeyCode::Char('Y'),
-                // Help
-                KeyCode::Char('?'),
-            ],
-            KeyBehavior::StateChange,
-        );
-
+        let mut bound: Vec<_> = REGISTRY
+            .iter()
+            .map(|d| (d.action.behavior(), keymap.chords(d.action)))
+            .collect();
+        bound.sort_by_key(|(behavior, _)| matches!(behavior, KeyBehavior::Repeatable { .. }));
+
+        for (behavior, chords) in bound {
+            let codes: Vec<KeyCode> = chords.iter().map(|chord| chord.code).collect();
+            handler.configure_keys(&codes, behavior);
+        }
         handler
     }
+
+    /// Behaviors for the default keybindings
+    pub fn with_default_config() -> Self {
+        Self::for_keymap(&Keymap::default())
+    }
 }
 
 impl Default for InputHandler {
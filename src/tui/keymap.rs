@@ -0,0 +1,507 @@
+// Keybindings - named actions, their default keys and [keybindings] overrides
+//
+// Every rebindable key goes through here. The input layers (modal, global,
+// view, focused component) ask the Keymap which actions a key event triggers
+// and act on the first one they handle; components implement
+// Interactive::handle_action instead of matching KeyCodes. A binding changed
+// in config.toml therefore applies everywhere, and the Help modal is built
+// from the same registry.
+//
+// Text entry (the search prompt) and the Stats view number keys are fixed.
+//
+// Actions belong to a scope. Two actions may share a key only if their scopes
+// can't both be active - e.g. `o` sorts the History table and opens an
+// attachment in the detail modal. Anything else is reported as a conflict.
+
+use super::input::KeyBehavior;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Everything a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Views
+    ViewEvents,
+    ViewStats,
+    ViewSettings,
+    ViewHistory,
+    // Navigation (lists, panels, modal content)
+    Up,
+    Down,
+    Top,
+    Bottom,
+    PageUp,
+    PageDown,
+    // General
+    Activate,
+    Back,
+    FocusNext,
+    FocusPrev,
+    OpenSearch,
+    MarkDiff,
+    OpenDiff,
+    CopyReadable,
+    CopyJsonl,
+    PrevSession,
+    NextSession,
+    Help,
+    Quit,
+    // Events view
+    ToggleZoom,
+    SearchNext,
+    SearchPrev,
+    // History view
+    CycleSort,
+    ReverseSort,
+    // Settings view
+    ExportLayout,
+    // Stats view (Timeline tab)
+    TimelineZoomIn,
+    TimelineZoomOut,
+    TimelinePanLeft,
+    TimelinePanRight,
+    ExportTrace,
+    // Stats view (Lifetime tab)
+    LifetimePeriod,
+    RefreshLifetime,
+    // Detail modal
+    CloseModal,
+    ScrollLeft,
+    ScrollRight,
+    ScrollLeftmost,
+    OpenAttachment,
+}
+
+/// Where an action can fire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Any view, no modal open
+    Global,
+    /// Focused panel or list, and modal content
+    Navigation,
+    Events,
+    History,
+    Settings,
+    Stats,
+    /// A modal is open (it captures all input)
+    Modal,
+}
+
+impl Scope {
+    /// Whether both scopes can be active for the same key press
+    fn overlaps(self, other: Scope) -> bool {
+        use Scope::*;
+        match (self, other) {
+            (a, b) if a == b => true,
+            (Modal, Navigation) | (Navigation, Modal) => true,
+            (Modal, _) | (_, Modal) => false,
+            (Global | Navigation, _) | (_, Global | Navigation) => true,
+            // Two different views
+            _ => false,
+        }
+    }
+}
+
+/// Registry entry: config name, scope, default keys and Help text
+pub struct ActionDef {
+    pub action: Action,
+    pub name: &'static str,
+    pub scope: Scope,
+    /// Help modal section
+    pub section: &'static str,
+    pub keys: &'static [&'static str],
+    pub help: &'static str,
+}
+
+const fn def(
+    action: Action,
+    name: &'static str,
+    scope: Scope,
+    section: &'static str,
+    keys: &'static [&'static str],
+    help: &'static str,
+) -> ActionDef {
+    ActionDef {
+        action,
+        name,
+        scope,
+        section,
+        keys,
+        help,
+    }
+}
+
+/// All actions, in `Action` order (Help shows them in this order)
+#[rustfmt::skip]
+pub const REGISTRY: &[ActionDef] = &[
+    def(Action::ViewEvents, "view_events", Scope::Global, "Views", &["F1", "e", "E"], "Events (main view)"),
+    def(Action::ViewStats, "view_stats", Scope::Global, "Views", &["F2", "s", "S"], "Statistics"),
+    def(Action::ViewSettings, "view_settings", Scope::Global, "Views", &["F3"], "Settings"),
+    def(Action::ViewHistory, "view_history", Scope::Global, "Views", &["F4", "H"], "History (past sessions)"),
+    def(Action::Up, "up", Scope::Navigation, "Navigation", &["Up", "k"], "Move / scroll up"),
+    def(Action::Down, "down", Scope::Navigation, "Navigation", &["Down", "j"], "Move / scroll down"),
+    def(Action::Top, "top", Scope::Navigation, "Navigation", &["Home", "g"], "Jump to start"),
+    def(Action::Bottom, "bottom", Scope::Navigation, "Navigation", &["End", "G"], "Jump to end"),
+    def(Action::PageUp, "page_up", Scope::Navigation, "Navigation", &["PageUp"], "Page up"),
+    def(Action::PageDown, "page_down", Scope::Navigation, "Navigation", &["PageDown"], "Page down"),
+    def(Action::Activate, "activate", Scope::Global, "General", &["Enter"], "Open / apply / replay"),
+    def(Action::Back, "back", Scope::Global, "General", &["Esc"], "Clear / go back"),
+    def(Action::FocusNext, "focus_next", Scope::Global, "General", &["Tab", "Right"], "Next panel / pane / tab"),
+    def(Action::FocusPrev, "focus_prev", Scope::Global, "General", &["BackTab", "Left"], "Previous panel / tab"),
+    def(Action::OpenSearch, "open_search", Scope::Global, "General", &["/"], "Search / filter sessions"),
+    def(Action::MarkDiff, "mark_diff", Scope::Global, "General", &["m"], "Mark diff base"),
+    def(Action::OpenDiff, "open_diff", Scope::Global, "General", &["d"], "Diff against mark"),
+    def(Action::CopyReadable, "copy_readable", Scope::Global, "General", &["y"], "Copy (text)"),
+    def(Action::CopyJsonl, "copy_jsonl", Scope::Global, "General", &["Y"], "Copy (JSONL)"),
+    def(Action::PrevSession, "prev_session", Scope::Global, "General", &["["], "Previous session"),
+    def(Action::NextSession, "next_session", Scope::Global, "General", &["]"], "Next session"),
+    def(Action::Help, "help", Scope::Global, "General", &["?"], "Toggle this help"),
+    def(Action::Quit, "quit", Scope::Global, "General", &["q", "Q"], "Quit"),
+    def(Action::ToggleZoom, "toggle_zoom", Scope::Events, "Events View", &["z"], "Zoom focused panel"),
+    def(Action::SearchNext, "search_next", Scope::Events, "Events View", &["n"], "Next match"),
+    def(Action::SearchPrev, "search_prev", Scope::Events, "Events View", &["N"], "Previous match"),
+    def(Action::CycleSort, "cycle_sort", Scope::History, "History View", &["o"], "Sort column"),
+    def(Action::ReverseSort, "reverse_sort", Scope::History, "History View", &["O"], "Reverse sort"),
+    def(Action::ExportLayout, "export_layout", Scope::Settings, "Settings View", &["x"], "Export layout preset"),
+    def(Action::TimelineZoomIn, "timeline_zoom_in", Scope::Stats, "Stats Timeline", &["+", "="], "Zoom in"),
+    def(Action::TimelineZoomOut, "timeline_zoom_out", Scope::Stats, "Stats Timeline", &["-"], "Zoom out"),
+    def(Action::TimelinePanLeft, "timeline_pan_left", Scope::Stats, "Stats Timeline", &["h", "<"], "Pan earlier"),
+    def(Action::TimelinePanRight, "timeline_pan_right", Scope::Stats, "Stats Timeline", &["l", ">"], "Pan later"),
+    def(Action::ExportTrace, "export_trace", Scope::Stats, "Stats Timeline", &["x"], "Export Chrome trace"),
+    def(Action::LifetimePeriod, "lifetime_period", Scope::Stats, "Stats Lifetime", &["p"], "Day / week / month"),
+    def(Action::RefreshLifetime, "refresh_lifetime", Scope::Stats, "Stats Lifetime", &["r"], "Reload from lifestats"),
+    def(Action::CloseModal, "close_modal", Scope::Modal, "Detail View", &["Esc", "q"], "Close"),
+    def(Action::ScrollLeft, "scroll_left", Scope::Modal, "Detail View", &["Left", "h"], "Scroll left"),
+    def(Action::ScrollRight, "scroll_right", Scope::Modal, "Detail View", &["Right", "l"], "Scroll right"),
+    def(Action::ScrollLeftmost, "scroll_leftmost", Scope::Modal, "Detail View", &["0"], "Scroll to left edge"),
+    def(Action::OpenAttachment, "open_attachment", Scope::Modal, "Detail View", &["o"], "Open attachment"),
+];
+
+impl Action {
+    /// Registry entry for this action
+    pub fn def(self) -> &'static ActionDef {
+        &REGISTRY[self as usize]
+    }
+
+    pub fn name(self) -> &'static str {
+        self.def().name
+    }
+
+    /// Action by config name
+    pub fn from_name(name: &str) -> Option<Action> {
+        REGISTRY.iter().find(|d| d.name == name).map(|d| d.action)
+    }
+
+    /// Hold-to-repeat behavior for keys bound to this action
+    pub fn behavior(self) -> KeyBehavior {
+        match self {
+            Action::Up
+            | Action::Down
+            | Action::ScrollLeft
+            | Action::ScrollRight
+            | Action::TimelinePanLeft
+            | Action::TimelinePanRight => KeyBehavior::navigation(),
+            Action::Top | Action::Bottom | Action::PageUp | Action::PageDown => {
+                KeyBehavior::fast_navigation()
+            }
+            _ => KeyBehavior::StateChange,
+        }
+    }
+}
+
+/// A key with modifiers, e.g. `ctrl+d`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Normalized so the same physical key compares equal across terminals:
+    /// Shift is implied by the character (`Q`) and Shift+Tab is BackTab
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self {
+            code,
+            modifiers: modifiers
+                & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parse config syntax: `q`, `Q`, `F1`, `ctrl+d`, `alt+Enter`, `PageDown`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (modifier_part, key) = match text.strip_suffix('+') {
+            // A literal `+` key (`+` or `ctrl++`)
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+                (rest.strip_suffix('+').unwrap_or(rest), "+")
+            }
+            _ => match text.rsplit_once('+') {
+                Some((mods, key)) => (mods, key),
+                None => ("", text),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in modifier_part.split('+').filter(|p| !p.is_empty()) {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier `{}` in `{}`", part, text)),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => return Err("empty key".to_string()),
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{}`", key)),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::BackTab => write!(f, "S-Tab"),
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// A `[keybindings]` entry that couldn't be used, or a key bound twice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapProblem {
+    /// Config key the problem belongs to (an action name)
+    pub action: String,
+    pub message: String,
+}
+
+impl fmt::Display for KeymapProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "keybindings.{}: {}", self.action, self.message)
+    }
+}
+
+/// Active key bindings (defaults with `[keybindings]` applied)
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Chords per action, indexed like REGISTRY
+    chords: Vec<Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let chords = REGISTRY
+            .iter()
+            .map(|d| {
+                d.keys
+                    .iter()
+                    .map(|k| KeyChord::parse(k).expect("default key parses"))
+                    .collect()
+            })
+            .collect();
+        Self { chords }
+    }
+}
+
+impl Keymap {
+    /// Defaults with config overrides (an entry replaces that action's keys;
+    /// an empty list unbinds it). Problems are returned, not fatal: bad
+    /// entries are skipped and conflicting keys stay bound.
+    pub fn from_config(overrides: &BTreeMap<String, Vec<String>>) -> (Self, Vec<KeymapProblem>) {
+        let mut keymap = Self::default();
+        let mut problems = Vec::new();
+
+        for (name, keys) in overrides {
+            let Some(action) = Action::from_name(name) else {
+                problems.push(KeymapProblem {
+                    action: name.clone(),
+                    message: "unknown action".to_string(),
+                });
+                continue;
+            };
+            let mut chords = Vec::new();
+            for key in keys {
+                match KeyChord::parse(key) {
+                    Ok(chord) if !chords.contains(&chord) => chords.push(chord),
+                    Ok(_) => {}
+                    Err(message) => problems.push(KeymapProblem {
+                        action: name.clone(),
+                        message,
+                    }),
+                }
+            }
+            keymap.chords[action as usize] = chords;
+        }
+
+        // Blame the entry the user wrote, so `config --check` can point at it
+        for (a, b, chord) in keymap.conflicts() {
+            let (action, other) = if overrides.contains_key(b.name()) {
+                (b, a)
+            } else {
+                (a, b)
+            };
+            problems.push(KeymapProblem {
+                action: action.name().to_string(),
+                message: format!("`{}` is also bound to {}", chord, other.name()),
+            });
+        }
+        (keymap, problems)
+    }
+
+    /// Keys bound to two actions whose scopes can be active together
+    pub fn conflicts(&self) -> Vec<(Action, Action, KeyChord)> {
+        let mut conflicts = Vec::new();
+        for (i, a) in REGISTRY.iter().enumerate() {
+            for (j, b) in REGISTRY.iter().enumerate().skip(i + 1) {
+                if !a.scope.overlaps(b.scope) {
+                    continue;
+                }
+                for &chord in self.chords[i].iter().filter(|c| self.chords[j].contains(c)) {
+                    conflicts.push((a.action, b.action, chord));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Actions a key event triggers, in registry order
+    pub fn actions(&self, key: &KeyEvent) -> Vec<Action> {
+        let chord = KeyChord::from_event(key);
+        REGISTRY
+            .iter()
+            .zip(&self.chords)
+            .filter(|(_, chords)| chords.contains(&chord))
+            .map(|(d, _)| d.action)
+            .collect()
+    }
+
+    pub fn chords(&self, action: Action) -> &[KeyChord] {
+        &self.chords[action as usize]
+    }
+
+    /// Keys for an action as shown in Help, e.g. `↑, k`
+    pub fn label(&self, action: Action) -> String {
+        self.chords(action)
+            .iter()
+            .map(KeyChord::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_order_and_defaults() {
+        for (i, d) in REGISTRY.iter().enumerate() {
+            assert_eq!(d.action as usize, i, "{} out of order", d.name);
+        }
+        assert!(Keymap::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_chord_parsing() {
+        let chord = |s: &str| KeyChord::parse(s).unwrap();
+        assert_eq!(
+            chord("Q"),
+            KeyChord::new(KeyCode::Char('Q'), KeyModifiers::SHIFT)
+        );
+        assert_eq!(chord("shift+q"), chord("Q"));
+        assert_eq!(chord("shift+tab"), chord("BackTab"));
+        assert_eq!(
+            chord("Ctrl+d"),
+            KeyChord::new(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(chord("+").code, KeyCode::Char('+'));
+        assert_eq!(chord("ctrl++").modifiers, KeyModifiers::CONTROL);
+        assert_eq!(chord("f12").code, KeyCode::F(12));
+        assert!(KeyChord::parse("hyper+x").is_err());
+        assert!(KeyChord::parse("PageUpp").is_err());
+        assert_eq!(chord("pgup").to_string(), "PgUp");
+    }
+
+    #[test]
+    fn test_overrides_and_conflicts() {
+        let overrides: BTreeMap<String, Vec<String>> = [
+            ("toggle_zoom", vec!["ctrl+z"]),
+            ("copy_jsonl", vec!["q"]),
+            ("cycle_sort", vec!["x"]),
+            ("warp", vec!["w"]),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.into_iter().map(String::from).collect()))
+        .collect();
+        let (keymap, problems) = Keymap::from_config(&overrides);
+
+        let ctrl_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.actions(&ctrl_z), vec![Action::ToggleZoom]);
+        let z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE);
+        assert!(keymap.actions(&z).is_empty());
+
+        let messages: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+        assert!(messages.contains(&"keybindings.warp: unknown action".to_string()));
+        // Global quit and copy_jsonl collide; History's `x` and Settings' `x` don't
+        assert!(messages.contains(&"keybindings.copy_jsonl: `q` is also bound to quit".to_string()));
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+    }
+}
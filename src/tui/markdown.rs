@@ -6,9 +6,11 @@
 // - Any component needing rich text with wrapping
 //
 // Uses pulldown-cmark to parse markdown and convert to styled ratatui Spans.
-// Supports: headings, inline code, fenced code blocks (with JSON highlighting),
+// Supports: headings, inline code, fenced code blocks (JSON highlighting here,
+// other languages and diffs via highlight.rs),
 // bold, italic, strikethrough, lists, blockquotes, tables, links, XML tags.
 
+use super::highlight::{Highlighter, Lang};
 use crate::theme::Theme;
 use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use ratatui::style::{Modifier, Style};
@@ -23,11 +25,7 @@ pub enum StyledSegment {
     /// Inline code: `like this`
     InlineCode(String),
     /// Fenced code block with optional language
-    CodeBlock {
-        #[allow(dead_code)] // Future: syntax highlighting
-        lang: Option<String>,
-        code: String,
-    },
+    CodeBlock { lang: Option<String>, code: String },
     /// Soft break (single newline in source)
     SoftBreak,
     /// Hard break (explicit line break)
@@ -648,8 +646,12 @@ pub fn segments_to_lines(
                 flush_line(&mut lines, &mut current_spans);
                 current_width = 0;
 
-                // Check if this is JSON - if so, use syntax highlighting
+                // JSON has its own highlighter; other known languages use highlight.rs
                 let is_json = lang.as_ref().map(|l| l == "json").unwrap_or(false);
+                let mut highlighter = lang
+                    .as_deref()
+                    .and_then(Lang::from_tag)
+                    .map(Highlighter::new);
 
                 for line in code.lines() {
                     if is_json {
@@ -657,6 +659,10 @@ pub fn segments_to_lines(
                         let mut spans = vec![Span::raw("  ")]; // Indent
                         spans.extend(highlight_json_line(line, theme));
                         lines.push(Line::from(spans));
+                    } else if let Some(highlighter) = highlighter.as_mut() {
+                        let mut spans = vec![Span::raw("  ")]; // Indent
+                        spans.extend(highlighter.line(line, theme));
+                        lines.push(Line::from(spans));
                     } else {
                         // Default: code_block color with dim modifier
                         lines.push(Line::from(Span::styled(
@@ -9,12 +9,17 @@
 pub mod app;
 pub mod clipboard;
 pub mod components;
+pub mod highlight;
 pub mod input;
+pub mod keymap;
 pub mod layout;
 pub mod markdown;
 pub mod modal;
+pub mod mouse;
 pub mod preset;
+pub mod preset_toml;
 pub mod scroll;
+pub mod search;
 pub mod streaming;
 pub mod traits;
 pub mod ui;
@@ -23,20 +28,22 @@ pub mod views;
 use crate::config::Config;
 use crate::events::TrackedEvent;
 use crate::logging::{LogBuffer, LogLevel};
+use crate::storage::attachments;
 use crate::StreamingThinking;
 use anyhow::{Context, Result};
-use app::{App, View};
+use app::{App, SettingsCategory, View};
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
-        KeyModifiers, MouseEvent, MouseEventKind,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
     },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use keymap::Action;
 use modal::{Modal, ModalAction};
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
+use std::io::{self, Write};
 use std::time::Duration;
 use tokio::sync::mpsc;
 use traits::{Copyable, Handled, Scrollable};
@@ -53,30 +60,35 @@ pub async fn run_tui(
     streaming_thinking: StreamingThinking,
     shared_stats: crate::proxy::api::SharedStats,
     shared_events: crate::proxy::api::SharedEvents,
+    alert_feed: Option<crate::pipeline::alerts::AlertFeed>,
 ) -> Result<()> {
     // Set up terminal
     enable_raw_mode().context("Failed to enable raw mode")?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
-        .context("Failed to setup terminal")?;
+    execute!(stdout, EnterAlternateScreen).context("Failed to setup terminal")?;
+    // Mouse capture disables the terminal's own text selection, so it's optional
+    let mouse = config.mouse;
+    if mouse {
+        execute!(stdout, EnableMouseCapture).context("Failed to enable mouse capture")?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
 
     // Create app state with config (initializes theme, preset from config)
     let mut app = App::with_config(log_buffer, config, shared_stats, shared_events);
     app.streaming_thinking = Some(streaming_thinking);
+    app.alert_feed = alert_feed;
 
     // Run the event loop
     let result = run_event_loop(&mut terminal, &mut app, &mut event_rx).await;
 
     // Restore terminal
     disable_raw_mode().context("Failed to disable raw mode")?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )
-    .context("Failed to restore terminal")?;
+    if mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)
+            .context("Failed to disable mouse capture")?;
+    }
+    execute!(terminal.backend_mut(), LeaveAlternateScreen).context("Failed to restore terminal")?;
     terminal.show_cursor().context("Failed to show cursor")?;
 
     result
@@ -123,6 +135,22 @@ async fn run_event_loop(
             _ = tick_interval.tick() => {
                 // Advance animation frame for spinners
                 app.tick_animation();
+
+                // Lifetime tab: pick up finished loads, reload when stale
+                app.poll_lifetime();
+
+                // History view: session list and replays load in the background
+                app.poll_history();
+
+                // Tool failure patterns, batched off the event path
+                app.refresh_tool_insights();
+
+                // Alerts: toasts render next frame, the bell goes out now
+                if app.drain_alerts() {
+                    let backend = terminal.backend_mut();
+                    let _ = backend.write_all(b"\x07");
+                    let _ = backend.flush();
+                }
             }
 
             // Proxy events
@@ -142,180 +170,56 @@ async fn run_event_loop(
 
 /// Handle keyboard input
 /// Layered dispatch: Modal → Global → View-specific → Component
+///
+/// The keymap turns the key into the actions bound to it (registry order);
+/// each layer acts on the first action it handles.
 fn handle_key_event(app: &mut App, key_event: KeyEvent) {
+    let actions = app.keymap.actions(&key_event);
+
     // Layer 1: Modal captures all input when active
-    if handle_modal_input(app, &key_event) {
+    if handle_modal_input(app, &key_event, &actions) {
         return;
     }
 
-    // Layer 2: Global keys (work regardless of view)
-    if handle_global_keys(app, &key_event) {
+    // Layer 1b: Search prompt captures typing while open
+    if handle_search_input(app, &key_event) {
         return;
     }
 
     let key = key_event.code;
 
-    // Layer 3: View-specific action keys (use InputHandler for debounce)
     match key_event.kind {
         KeyEventKind::Press => {
-            match key {
-                KeyCode::Esc => {
-                    if app.handle_key_press(key) {
-                        // Esc priority: zoom > panel selection > view navigation
-                        if app.zoomed {
-                            // Exit zoom mode
-                            app.exit_zoom();
-                            return;
-                        }
-                        // Esc: let focused panel handle it (clear selection)
-                        // If panel didn't handle it, fall back to view navigation
-                        let key_event = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
-                        if app.dispatch_to_focused(key_event) == Handled::No {
-                            // Panel had nothing to clear - go back to Events view
-                            if app.view != View::Events {
-                                if app.view == View::Settings {
-                                    app.save_settings_if_dirty();
-                                }
-                                app.set_view(View::Events);
-                            }
-                        }
-                    }
+            for &action in &actions {
+                // Layer 2: Global actions (work regardless of view)
+                // Layer 3: View-specific actions (use InputHandler for debounce)
+                if handle_global_action(app, action, key) || handle_view_action(app, action, key) {
                     return;
                 }
-                KeyCode::Enter => {
-                    if app.handle_key_press(key) {
-                        match app.view {
-                            View::Events => {
-                                // Only open detail when focused on Events panel
-                                if app.focused == scroll::FocusablePanel::Events {
-                                    // Get index: use selected if in selection mode,
-                                    // otherwise use last event (auto-follow mode)
-                                    let idx = app
-                                        .events_panel
-                                        .selected
-                                        .or_else(|| app.events.len().checked_sub(1));
-
-                                    if let Some(idx) = idx {
-                                        app.detail_panel.reset();
-                                        // Populate cached content for clipboard copy
-                                        if let Some(tracked) = app.events.get(idx) {
-                                            let renderable = format_event_detail(tracked);
-                                            app.detail_panel
-                                                .set_content(renderable.as_str().to_string());
-                                        }
-                                        app.modal = Some(Modal::detail(idx));
-                                    }
-                                } else if app.focused == scroll::FocusablePanel::Logs {
-                                    // Logs panel: dispatch Enter to the component
-                                    let entries = app.log_buffer.get_all();
-                                    app.logs_panel.entry_count = entries.len();
-                                    if let Some(idx) = app
-                                        .logs_panel
-                                        .selected
-                                        .or_else(|| entries.len().checked_sub(1))
-                                    {
-                                        // Open log detail modal
-                                        if let Some(entry) = entries.get(idx) {
-                                            app.detail_panel.reset();
-                                            // Format like events: emoji heading, bold labels, separator, content
-                                            let level_icon = match entry.level {
-                                                LogLevel::Error => "❌",
-                                                LogLevel::Warn => "⚠",
-                                                LogLevel::Info => "ℹ",
-                                                LogLevel::Debug => "🔍",
-                                                LogLevel::Trace => "📍",
-                                            };
-                                            let content = format!(
-                                                "## {} System Log\n\n\
-                                                **Timestamp:** {}  \n\
-                                                **Level:** `{:?}`  \n\
-                                                **Target:** `{}`\n\n\
-                                                ---\n\n\
-                                                {}",
-                                                level_icon,
-                                                entry.timestamp.to_rfc3339(),
-                                                entry.level,
-                                                entry.target,
-                                                entry.message
-                                            );
-                                            app.detail_panel.set_content(content);
-                                            app.modal = Some(Modal::log_detail());
-                                        }
-                                    }
-                                }
-                            }
-                            View::Settings => app.settings_apply_option(),
-                            _ => {}
-                        }
-                    }
-                    return;
-                }
-                KeyCode::Tab | KeyCode::Right => {
-                    if app.handle_key_press(key) {
-                        match app.view {
-                            View::Events => {
-                                if key_event.modifiers.contains(KeyModifiers::SHIFT) {
-                                    app.focus_prev();
-                                } else {
-                                    app.focus_next();
-                                }
-                            }
-                            View::Settings => app.settings_toggle_focus(),
-                            View::Stats => {
-                                // Navigate to next tab (wraps around)
-                                app.stats_selected_tab = (app.stats_selected_tab + 1) % 5;
-                            }
-                        }
-                    }
-                    return;
-                }
-                // Backtab or Left arrow - go back
-                KeyCode::BackTab | KeyCode::Left => {
-                    if app.handle_key_press(key) {
-                        match app.view {
-                            View::Events => app.focus_prev(),
-                            View::Settings => app.settings_toggle_focus(),
-                            View::Stats => {
-                                // Navigate to previous tab (wraps around)
-                                app.stats_selected_tab = if app.stats_selected_tab == 0 {
-                                    4
-                                } else {
-                                    app.stats_selected_tab - 1
-                                };
-                            }
-                        }
-                    }
-                    return;
-                }
-                // 'z' - toggle zoom for focused panel (Events view only)
-                KeyCode::Char('z') => {
-                    if app.handle_key_press(key) && app.view == View::Events {
-                        app.toggle_zoom();
-                    }
-                    return;
-                }
-                // Number keys 1-5 for direct tab selection in Stats view
-                KeyCode::Char('1'..='5') => {
-                    if app.handle_key_press(key) && app.view == View::Stats {
-                        // Map '1' -> tab 0, '2' -> tab 1, etc.
-                        if let KeyCode::Char(c) = key {
-                            app.stats_selected_tab = (c as usize) - ('1' as usize);
-                        }
-                    }
-                    return;
+            }
+
+            // Number keys 1-8 for direct tab selection in Stats view (not rebindable)
+            if let KeyCode::Char(c @ '1'..='8') = key {
+                if app.handle_key_press(key) && app.view == View::Stats {
+                    // Map '1' -> tab 0, '2' -> tab 1, etc.
+                    app.stats_selected_tab = (c as usize) - ('1' as usize);
                 }
-                _ => {}
+                return;
             }
 
             // Navigation keys - use state tracking for hold-to-repeat
-            if !app.handle_key_press(key) {
+            if actions.is_empty() || !app.handle_key_press(key) {
                 return;
             }
 
             // Dispatch to focused panel via Interactive trait
             // All views (Events, Stats, Settings) route through dispatch_to_focused()
-            // Settings uses dispatch_to_settings() → settings_panel.handle_key()
-            app.dispatch_to_focused(key_event);
+            // Settings uses dispatch_to_settings() → settings_panel.handle_action()
+            for &action in &actions {
+                if app.dispatch_to_focused(action).was_handled() {
+                    break;
+                }
+            }
         }
         KeyEventKind::Release => {
             app.handle_key_release(key);
@@ -324,35 +228,276 @@ fn handle_key_event(app: &mut App, key_event: KeyEvent) {
     }
 }
 
+/// Handle view-specific actions - returns true if handled
+/// Actions that don't apply to the current view fall through to the focused panel
+fn handle_view_action(app: &mut App, action: Action, key: KeyCode) -> bool {
+    match action {
+        Action::Back => {
+            if app.handle_key_press(key) {
+                // Esc priority: zoom > panel selection > search > replay > view navigation
+                if app.zoomed {
+                    // Exit zoom mode
+                    app.exit_zoom();
+                    return true;
+                }
+                // Let focused panel handle it (clear selection)
+                // If panel didn't handle it, fall back to view navigation
+                if app.dispatch_to_focused(Action::Back) == Handled::No && !app.clear_search() {
+                    // Panel had nothing to clear - go back to Events view
+                    if app.view != View::Events {
+                        if app.view == View::Settings {
+                            app.save_settings_if_dirty();
+                        }
+                        app.set_view(View::Events);
+                    } else {
+                        // Replayed session: back to the History list
+                        app.close_replay();
+                    }
+                }
+            }
+            true
+        }
+        Action::Activate => {
+            if app.handle_key_press(key) {
+                match app.view {
+                    View::Events => open_focused_detail(app),
+                    View::Settings => app.settings_apply_option(),
+                    View::History => app.open_replay(),
+                    _ => {}
+                }
+            }
+            true
+        }
+        Action::FocusNext => {
+            if app.handle_key_press(key) {
+                match app.view {
+                    View::Events => app.focus_next(),
+                    View::Settings => app.settings_toggle_focus(),
+                    View::Stats => {
+                        // Navigate to next tab (wraps around)
+                        app.stats_selected_tab =
+                            (app.stats_selected_tab + 1) % views::STATS_TAB_COUNT;
+                    }
+                    View::History => {}
+                }
+            }
+            true
+        }
+        Action::FocusPrev => {
+            if app.handle_key_press(key) {
+                match app.view {
+                    View::Events => app.focus_prev(),
+                    View::Settings => app.settings_toggle_focus(),
+                    View::Stats => {
+                        // Navigate to previous tab (wraps around)
+                        app.stats_selected_tab = if app.stats_selected_tab == 0 {
+                            views::STATS_TAB_COUNT - 1
+                        } else {
+                            app.stats_selected_tab - 1
+                        };
+                    }
+                    View::History => {}
+                }
+            }
+            true
+        }
+        // Toggle zoom for focused panel (Events view only)
+        Action::ToggleZoom => {
+            if app.handle_key_press(key) && app.view == View::Events {
+                app.toggle_zoom();
+            }
+            true
+        }
+        // Open search prompt (Events view) or session filter (History view)
+        Action::OpenSearch => {
+            if app.handle_key_press(key) && matches!(app.view, View::Events | View::History) {
+                app.open_search();
+            }
+            true
+        }
+        // Next/previous search match
+        Action::SearchNext | Action::SearchPrev
+            if app.view == View::Events && app.search.query.is_some() =>
+        {
+            if app.handle_key_press(key) {
+                app.search_step(action == Action::SearchNext);
+            }
+            true
+        }
+        // Mark diff base / diff against it (Events and History views)
+        Action::MarkDiff | Action::OpenDiff if matches!(app.view, View::Events | View::History) => {
+            if app.handle_key_press(key) {
+                if action == Action::MarkDiff {
+                    app.mark_diff_base();
+                } else {
+                    app.open_diff();
+                }
+            }
+            true
+        }
+        // Export the current layout as a user preset (Settings > Layout)
+        Action::ExportTrace
+            if app.view == View::Stats && app.stats_selected_tab == views::STATS_TIMELINE_TAB =>
+        {
+            if app.handle_key_press(key) {
+                app.export_trace();
+            }
+            true
+        }
+        Action::ExportLayout
+            if app.view == View::Settings
+                && app.settings_panel.category == SettingsCategory::Layout =>
+        {
+            if app.handle_key_press(key) {
+                app.export_layout();
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Open the detail modal for the focused panel's selection (Enter, double-click)
+///
+/// Events opens the selected (or latest) event, Logs the selected (or latest) entry.
+fn open_focused_detail(app: &mut App) {
+    // Only open detail when focused on Events panel
+    if app.focused == scroll::FocusablePanel::Events {
+        // Get index: use selected if in selection mode,
+        // otherwise use last event (auto-follow mode)
+        if let Some(idx) = app.selected_event_index() {
+            app.detail_panel.reset();
+            // Populate cached content for clipboard copy
+            if let Some(tracked) = app.shown_events().get(idx) {
+                let renderable = format_event_detail(tracked);
+                app.detail_panel
+                    .set_content(renderable.as_str().to_string());
+            }
+            app.modal = Some(Modal::detail(idx));
+        }
+    } else if app.focused == scroll::FocusablePanel::Logs {
+        // Logs panel: dispatch Enter to the component
+        let entries = app.log_buffer.get_all();
+        app.logs_panel.entry_count = entries.len();
+        if let Some(idx) = app
+            .logs_panel
+            .selected
+            .or_else(|| entries.len().checked_sub(1))
+        {
+            // Open log detail modal
+            if let Some(entry) = entries.get(idx) {
+                app.detail_panel.reset();
+                // Format like events: emoji heading, bold labels, separator, content
+                let level_icon = match entry.level {
+                    LogLevel::Error => "❌",
+                    LogLevel::Warn => "⚠",
+                    LogLevel::Info => "ℹ",
+                    LogLevel::Debug => "🔍",
+                    LogLevel::Trace => "📍",
+                };
+                let content = format!(
+                    "## {} System Log\n\n\
+                    **Timestamp:** {}  \n\
+                    **Level:** `{:?}`  \n\
+                    **Target:** `{}`\n\n\
+                    ---\n\n\
+                    {}",
+                    level_icon,
+                    entry.timestamp.to_rfc3339(),
+                    entry.level,
+                    entry.target,
+                    entry.message
+                );
+                app.detail_panel.set_content(content);
+                app.modal = Some(Modal::log_detail());
+            }
+        }
+    }
+}
+
 /// Handle mouse input
+///
+/// Positions resolve against the clickable areas recorded by the last draw.
 fn handle_mouse_event(app: &mut App, mouse_event: MouseEvent) {
+    let (column, row) = (mouse_event.column, mouse_event.row);
     match mouse_event.kind {
-        MouseEventKind::ScrollUp => {
-            // If modal is open, scroll the detail panel directly
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            let up = mouse_event.kind == MouseEventKind::ScrollUp;
             if app.modal.is_some() {
-                app.detail_panel.scroll_up();
+                // If modal is open, scroll the detail panel directly
+                if up {
+                    app.detail_panel.scroll_up();
+                } else {
+                    app.detail_panel.scroll_down();
+                }
             } else {
-                // Synthesize Up key event for trait dispatch
-                let key_event = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
-                app.dispatch_to_focused(key_event);
+                // Synthesize Up/Down for the panel under the pointer
+                let action = if up { Action::Up } else { Action::Down };
+                app.scroll_at(column, row, action);
             }
         }
-        MouseEventKind::ScrollDown => {
-            // If modal is open, scroll the detail panel directly
-            if app.modal.is_some() {
-                app.detail_panel.scroll_down();
-            } else {
-                // Synthesize Down key event for trait dispatch
-                let key_event = KeyEvent::new(KeyCode::Down, KeyModifiers::NONE);
-                app.dispatch_to_focused(key_event);
+        MouseEventKind::Down(MouseButton::Left) if app.modal.is_none() => {
+            if app.view == View::Events
+                && app
+                    .mouse
+                    .hits
+                    .split
+                    .is_some_and(|split| split.grabs(column, row))
+            {
+                app.mouse.dragging = true;
+                return;
             }
+            let double = app.mouse.click(column, row);
+            if app.click_at(column, row) && double {
+                match app.view {
+                    View::Events => open_focused_detail(app),
+                    View::Settings => app.settings_apply_option(),
+                    View::History => app.open_replay(),
+                    View::Stats => {}
+                }
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) if app.mouse.dragging => {
+            app.drag_split(column, row);
+        }
+        MouseEventKind::Up(MouseButton::Left) => app.mouse.dragging = false,
+        _ => {}
+    }
+}
+
+/// Handle search prompt input - returns true if the prompt absorbed the input
+///
+/// Typing bypasses the InputHandler debounce so repeated letters register.
+fn handle_search_input(app: &mut App, key_event: &KeyEvent) -> bool {
+    if !app.active_search().is_editing() {
+        return false;
+    }
+
+    // Keep InputHandler in sync (the '/' that opened the prompt is still held)
+    if key_event.kind == KeyEventKind::Release {
+        app.handle_key_release(key_event.code);
+        return true;
+    }
+    if key_event.kind != KeyEventKind::Press {
+        return true;
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.close_search(false),
+        KeyCode::Enter => app.close_search(true),
+        KeyCode::Tab if app.view == View::Events => app.search_history(),
+        KeyCode::Backspace => app.edit_search(|s| s.pop()),
+        KeyCode::Char('u') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.edit_search(|s| s.clear_input())
         }
+        KeyCode::Char(c) => app.edit_search(|s| s.push(c)),
         _ => {}
     }
+    true
 }
 
 /// Handle modal input - returns true if modal absorbed the input
-fn handle_modal_input(app: &mut App, key_event: &KeyEvent) -> bool {
+fn handle_modal_input(app: &mut App, key_event: &KeyEvent, actions: &[Action]) -> bool {
     let Some(ref mut modal) = app.modal else {
         return false;
     };
@@ -368,7 +513,13 @@ fn handle_modal_input(app: &mut App, key_event: &KeyEvent) -> bool {
         return true; // Modal absorbs other non-press events (Repeat, etc.)
     }
 
-    match modal.handle_input(key_event.code) {
+    let modal_action = actions
+        .iter()
+        .map(|&action| modal.handle_input(action))
+        .find(|result| !matches!(result, ModalAction::None))
+        .unwrap_or(ModalAction::None);
+
+    match modal_action {
         ModalAction::None => {}
         ModalAction::Close => {
             app.detail_panel.reset();
@@ -394,7 +545,7 @@ fn handle_modal_input(app: &mut App, key_event: &KeyEvent) -> bool {
         }
         ModalAction::CopyJsonl => {
             if let Some(idx) = modal.event_index() {
-                if let Some(event) = app.events.get(idx) {
+                if let Some(event) = app.shown_events().get(idx) {
                     if let Ok(json) = serde_json::to_string(event) {
                         if clipboard::copy_to_clipboard(&json).is_ok() {
                             app.show_toast("✓ Copied JSONL to clipboard");
@@ -405,31 +556,38 @@ fn handle_modal_input(app: &mut App, key_event: &KeyEvent) -> bool {
                 }
             }
         }
+        ModalAction::OpenAttachment => {
+            let latest = modal
+                .event_index()
+                .and_then(|idx| app.shown_events().get(idx))
+                .and_then(|tracked| views::event_attachments(&tracked.event).pop());
+            match latest {
+                Some(found) => match attachments::open(&found.reference.path) {
+                    Ok(()) => app.show_toast(format!("📎 Opened {}", found.reference.media_type)),
+                    Err(_) => app.show_toast("✗ Failed to open attachment"),
+                },
+                None => app.show_toast("No attachments in this event"),
+            }
+        }
     }
 
     true // Modal absorbed the input
 }
 
-/// Handle global keys - returns true if handled
-/// Global keys work the same regardless of current view
+/// Handle global actions - returns true if handled
+/// Global actions work the same regardless of current view
 /// Uses InputHandler for debounce (StateChange behavior = trigger once per press)
-fn handle_global_keys(app: &mut App, key_event: &KeyEvent) -> bool {
-    if key_event.kind != KeyEventKind::Press {
-        return false;
-    }
-
-    let key = key_event.code;
-
-    match key {
+fn handle_global_action(app: &mut App, action: Action, key: KeyCode) -> bool {
+    match action {
         // Quit
-        KeyCode::Char('q') | KeyCode::Char('Q') => {
+        Action::Quit => {
             if app.handle_key_press(key) {
                 app.should_quit = true;
             }
             true
         }
-        // View switching - F-keys (primary) and letter shortcuts
-        KeyCode::F(1) | KeyCode::Char('e') | KeyCode::Char('E') => {
+        // View switching
+        Action::ViewEvents => {
             if app.handle_key_press(key) {
                 if app.view == View::Settings {
                     app.save_settings_if_dirty();
@@ -438,7 +596,7 @@ fn handle_global_keys(app: &mut App, key_event: &KeyEvent) -> bool {
             }
             true
         }
-        KeyCode::F(2) | KeyCode::Char('s') | KeyCode::Char('S') => {
+        Action::ViewStats => {
             if app.handle_key_press(key) {
                 if app.view == View::Settings {
                     app.save_settings_if_dirty();
@@ -447,21 +605,30 @@ fn handle_global_keys(app: &mut App, key_event: &KeyEvent) -> bool {
             }
             true
         }
-        KeyCode::F(3) => {
+        Action::ViewSettings => {
             if app.handle_key_press(key) {
                 app.set_view(View::Settings);
             }
             true
         }
+        Action::ViewHistory => {
+            if app.handle_key_press(key) {
+                if app.view == View::Settings {
+                    app.save_settings_if_dirty();
+                }
+                app.set_view(View::History);
+            }
+            true
+        }
         // Help modal
-        KeyCode::Char('?') => {
+        Action::Help => {
             if app.handle_key_press(key) {
                 app.modal = Some(Modal::help());
             }
             true
         }
-        // Copy to clipboard: y = readable, Y = JSONL
-        KeyCode::Char('y') => {
+        // Copy to clipboard: readable or JSONL
+        Action::CopyReadable => {
             if app.handle_key_press(key) {
                 if let Some(text) = app.copy_current_readable() {
                     if clipboard::copy_to_clipboard(&text).is_ok() {
@@ -473,7 +640,7 @@ fn handle_global_keys(app: &mut App, key_event: &KeyEvent) -> bool {
             }
             true
         }
-        KeyCode::Char('Y') => {
+        Action::CopyJsonl => {
             if app.handle_key_press(key) {
                 if let Some(json) = app.copy_current_jsonl() {
                     if clipboard::copy_to_clipboard(&json).is_ok() {
@@ -485,14 +652,14 @@ fn handle_global_keys(app: &mut App, key_event: &KeyEvent) -> bool {
             }
             true
         }
-        // Session switching: [ = previous, ] = next
-        KeyCode::Char('[') => {
+        // Session switching
+        Action::PrevSession => {
             if app.handle_key_press(key) {
                 app.prev_session();
             }
             true
         }
-        KeyCode::Char(']') => {
+        Action::NextSession => {
             if app.handle_key_press(key) {
                 app.next_session();
             }
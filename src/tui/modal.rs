@@ -3,7 +3,7 @@
 // Self-contained modal dialogs that handle their own input and return actions.
 // App just holds Option<Modal>, input routing acts on returned ModalAction.
 
-use crossterm::event::KeyCode;
+use super::keymap::Action;
 
 /// Actions returned by modal input handling
 #[derive(Debug, Clone)]
@@ -34,6 +34,8 @@ pub enum ModalAction {
     CopyReadable,
     /// Copy content (JSONL format)
     CopyJsonl,
+    /// Open the event's latest attachment in the system viewer
+    OpenAttachment,
 }
 
 /// Available modal types
@@ -46,6 +48,10 @@ pub enum Modal {
     Detail(usize),
     /// Log entry detail view - content cached in DetailPanel
     LogDetail,
+    /// Lifestats history search results - content cached in DetailPanel
+    SearchResults,
+    /// Request or session diff - content cached in DetailPanel
+    Diff,
 }
 
 impl Modal {
@@ -64,32 +70,45 @@ impl Modal {
         Modal::LogDetail
     }
 
-    /// Handle keyboard input, return action for caller to execute
-    pub fn handle_input(&mut self, key: KeyCode) -> ModalAction {
+    /// Create a history search results modal (content cached in DetailPanel)
+    pub fn search_results() -> Self {
+        Modal::SearchResults
+    }
+
+    /// Create a diff modal (content cached in DetailPanel)
+    pub fn diff() -> Self {
+        Modal::Diff
+    }
+
+    /// Handle a keymap action, return action for caller to execute
+    pub fn handle_input(&mut self, action: Action) -> ModalAction {
         match self {
-            Modal::Help => match key {
-                KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => ModalAction::Close,
-                _ => ModalAction::None,
-            },
-            Modal::Detail(_) | Modal::LogDetail => match key {
-                KeyCode::Esc | KeyCode::Char('q') => ModalAction::Close,
-                // Vertical scroll
-                KeyCode::Up | KeyCode::Char('k') => ModalAction::ScrollUp,
-                KeyCode::Down | KeyCode::Char('j') => ModalAction::ScrollDown,
-                KeyCode::PageUp => ModalAction::PageUp,
-                KeyCode::PageDown => ModalAction::PageDown,
-                // Horizontal scroll (for structured content only)
-                KeyCode::Left | KeyCode::Char('h') => ModalAction::ScrollLeft,
-                KeyCode::Right | KeyCode::Char('l') => ModalAction::ScrollRight,
-                // Jump positions
-                KeyCode::Home | KeyCode::Char('g') => ModalAction::ScrollTop,
-                KeyCode::End | KeyCode::Char('G') => ModalAction::ScrollBottom,
-                KeyCode::Char('0') => ModalAction::ScrollLeftmost,
-                // Copy
-                KeyCode::Char('y') => ModalAction::CopyReadable,
-                KeyCode::Char('Y') => ModalAction::CopyJsonl,
+            Modal::Help => match action {
+                Action::CloseModal | Action::Help => ModalAction::Close,
                 _ => ModalAction::None,
             },
+            Modal::Detail(_) | Modal::LogDetail | Modal::SearchResults | Modal::Diff => {
+                match action {
+                    Action::CloseModal => ModalAction::Close,
+                    // Vertical scroll
+                    Action::Up => ModalAction::ScrollUp,
+                    Action::Down => ModalAction::ScrollDown,
+                    Action::PageUp => ModalAction::PageUp,
+                    Action::PageDown => ModalAction::PageDown,
+                    // Horizontal scroll (for structured content only)
+                    Action::ScrollLeft => ModalAction::ScrollLeft,
+                    Action::ScrollRight => ModalAction::ScrollRight,
+                    // Jump positions
+                    Action::Top => ModalAction::ScrollTop,
+                    Action::Bottom => ModalAction::ScrollBottom,
+                    Action::ScrollLeftmost => ModalAction::ScrollLeftmost,
+                    // Copy
+                    Action::CopyReadable => ModalAction::CopyReadable,
+                    Action::CopyJsonl => ModalAction::CopyJsonl,
+                    Action::OpenAttachment => ModalAction::OpenAttachment,
+                    _ => ModalAction::None,
+                }
+            }
         }
     }
 
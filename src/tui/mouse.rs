@@ -0,0 +1,219 @@
+// Mouse support - hit testing, double-clicks and split dragging
+//
+// Renderers record where clickable things landed in a HitMap each frame, and
+// mouse events are resolved against the map from the last draw. Layout stays
+// the renderers' business (preset, zoom, breakpoints); the mouse handler only
+// asks "what was drawn at this cell?".
+//
+// Disabled with `mouse = false` in config.toml - mouse capture stops the
+// terminal's own text selection from working.
+
+use super::scroll::FocusablePanel;
+use ratatui::layout::{Direction, Position, Rect};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+/// Two clicks on the same cell within this window make a double-click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Smallest share either side of a dragged split can shrink to (percent)
+const MIN_SPLIT_PERCENT: u16 = 15;
+
+/// What was drawn at a screen position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// A focusable panel (Events view panels, or Logs in the shell footer)
+    Panel(FocusablePanel),
+    /// A tab in the Stats view tab bar
+    StatsTab(usize),
+    /// Settings category list
+    SettingsCategories,
+    /// Settings options list (themes or presets)
+    SettingsOptions,
+    /// History session table
+    History,
+}
+
+/// The divider between the two panels of the Events view layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Split {
+    /// Area shared by both panels
+    pub area: Rect,
+    pub direction: Direction,
+    /// Column (horizontal) or row (vertical) where the second panel starts
+    pub at: u16,
+}
+
+impl Split {
+    /// Whether a position is on the divider (either of the touching borders)
+    pub fn grabs(&self, column: u16, row: u16) -> bool {
+        if !self.area.contains(Position::new(column, row)) {
+            return false;
+        }
+        let pos = match self.direction {
+            Direction::Horizontal => column,
+            Direction::Vertical => row,
+        };
+        pos == self.at || pos + 1 == self.at
+    }
+
+    /// First panel's share (percent) with the divider dragged to a position
+    pub fn percent_at(&self, column: u16, row: u16) -> u16 {
+        let (pos, start, len) = match self.direction {
+            Direction::Horizontal => (column, self.area.x, self.area.width),
+            Direction::Vertical => (row, self.area.y, self.area.height),
+        };
+        if len == 0 {
+            return 50;
+        }
+        let percent = u32::from(pos.saturating_sub(start)) * 100 / u32::from(len);
+        (percent as u16).clamp(MIN_SPLIT_PERCENT, 100 - MIN_SPLIT_PERCENT)
+    }
+}
+
+/// Clickable areas from the last frame
+#[derive(Debug, Default)]
+pub struct HitMap {
+    regions: Vec<(Rect, Target)>,
+
+    /// Events view divider (only when two panels are side by side or stacked)
+    pub split: Option<Split>,
+}
+
+impl HitMap {
+    /// Forget the previous frame (called at the start of each draw)
+    pub fn clear(&mut self) {
+        self.regions.clear();
+        self.split = None;
+    }
+
+    /// Record a clickable area; later areas win where they overlap
+    pub fn add(&mut self, area: Rect, target: Target) {
+        self.regions.push((area, target));
+    }
+
+    /// Topmost target at a position, with the area it was drawn in
+    pub fn target_at(&self, column: u16, row: u16) -> Option<(Target, Rect)> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|(area, target)| (*target, *area))
+    }
+}
+
+/// Mouse state carried between events
+#[derive(Debug, Default)]
+pub struct MouseState {
+    /// Where things were drawn last frame
+    pub hits: HitMap,
+
+    /// Last left click (time and cell) for double-click detection
+    last_click: Option<(Instant, u16, u16)>,
+
+    /// The Events view divider is being dragged
+    pub dragging: bool,
+}
+
+impl MouseState {
+    /// Record a left click; returns true if it completes a double-click
+    pub fn click(&mut self, column: u16, row: u16) -> bool {
+        self.click_at(Instant::now(), column, row)
+    }
+
+    fn click_at(&mut self, now: Instant, column: u16, row: u16) -> bool {
+        let double = self.last_click.is_some_and(|(at, c, r)| {
+            c == column && r == row && now.duration_since(at) <= DOUBLE_CLICK
+        });
+        // A third click starts a new pair rather than chaining doubles
+        self.last_click = if double {
+            None
+        } else {
+            Some((now, column, row))
+        };
+        double
+    }
+}
+
+/// Row inside a bordered list (0 = first row under the top border)
+pub fn inner_row(area: Rect, row: u16) -> Option<usize> {
+    let first = area.y + 1;
+    let last = area.bottom().saturating_sub(1);
+    (row >= first && row < last).then(|| usize::from(row - first))
+}
+
+/// Areas covered by each tab of a bordered ratatui `Tabs`
+///
+/// Mirrors its layout: one cell of padding either side of a title and a
+/// one-cell divider between tabs. Each area spans the full bar height so the
+/// border rows are clickable too.
+pub fn tab_areas(area: Rect, titles: &[&str]) -> Vec<Rect> {
+    let mut x = area.x + 1;
+    let right = area.right().saturating_sub(1);
+    titles
+        .iter()
+        .map(|title| {
+            let width = (title.width() as u16 + 2).min(right.saturating_sub(x));
+            let tab = Rect::new(x, area.y, width, area.height);
+            x = (x + width + 1).min(right);
+            tab
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_click_window() {
+        let mut mouse = MouseState::default();
+        let start = Instant::now();
+        assert!(!mouse.click_at(start, 10, 5));
+        assert!(mouse.click_at(start + Duration::from_millis(200), 10, 5));
+        // Third click starts over
+        assert!(!mouse.click_at(start + Duration::from_millis(300), 10, 5));
+        // Too slow, or on another cell
+        assert!(!mouse.click_at(start + Duration::from_millis(900), 10, 5));
+        assert!(!mouse.click_at(start + Duration::from_millis(950), 11, 5));
+    }
+
+    #[test]
+    fn test_split_drag() {
+        let split = Split {
+            area: Rect::new(0, 3, 100, 20),
+            direction: Direction::Horizontal,
+            at: 65,
+        };
+        assert!(split.grabs(64, 10));
+        assert!(split.grabs(65, 10));
+        assert!(!split.grabs(66, 10));
+        assert!(!split.grabs(65, 1)); // Above the content area
+        assert_eq!(split.percent_at(40, 10), 40);
+        assert_eq!(split.percent_at(2, 10), MIN_SPLIT_PERCENT);
+        assert_eq!(split.percent_at(99, 10), 100 - MIN_SPLIT_PERCENT);
+    }
+
+    #[test]
+    fn test_hit_map_and_tabs() {
+        let mut hits = HitMap::default();
+        hits.add(
+            Rect::new(0, 0, 50, 10),
+            Target::Panel(FocusablePanel::Events),
+        );
+        hits.add(Rect::new(0, 8, 50, 2), Target::Panel(FocusablePanel::Logs));
+        assert_eq!(
+            hits.target_at(3, 9).map(|(t, _)| t),
+            Some(Target::Panel(FocusablePanel::Logs))
+        );
+        assert_eq!(hits.target_at(60, 9), None);
+
+        assert_eq!(inner_row(Rect::new(0, 4, 10, 5), 5), Some(0));
+        assert_eq!(inner_row(Rect::new(0, 4, 10, 5), 4), None); // Border
+        assert_eq!(inner_row(Rect::new(0, 4, 10, 5), 8), None);
+
+        let tabs = tab_areas(Rect::new(0, 0, 80, 3), &[" 1│Overview ", " 2│Models "]);
+        assert_eq!(tabs[0], Rect::new(1, 0, 14, 3));
+        assert_eq!(tabs[1], Rect::new(16, 0, 12, 3));
+    }
+}
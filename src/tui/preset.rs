@@ -6,9 +6,26 @@
 // - LayoutSlot: a panel with sizing constraints
 // - Layout: a collection of slots arranged in a direction
 // - Preset: a named configuration (shell + per-view layouts)
+//
+// Built-in presets are defined below; user presets are TOML files in
+// ~/.config/aspy/presets/ (see preset_toml.rs for the format).
 
 use super::layout::Breakpoint;
+use super::preset_toml::TomlPreset;
+use anyhow::{bail, Context, Result};
 use ratatui::layout::Constraint;
+use std::path::PathBuf;
+
+/// Built-in presets and their Settings descriptions, in display order
+pub const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    ("classic", "Side-by-side events and thinking"),
+    ("reasoning", "Thinking-first, larger reasoning panel"),
+    ("debug", "Expanded logs for debugging"),
+    ("minimal", "Events only, no thinking panel"),
+    ("focus", "Thinking dominates, narrow events strip"),
+    ("compact", "Stacked panels for narrow terminals"),
+    ("balanced", "Equal events and thinking split"),
+];
 
 /// All renderable panels in the TUI
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -471,19 +488,183 @@ impl Preset {
     }
 }
 
-/// Get preset by name
-pub fn get_preset(name: &str) -> Preset {
+impl Preset {
+    /// Copy with the Events view split fixed at a percentage (mouse-dragged split)
+    ///
+    /// Responsive size overrides on the two panels are dropped so the split
+    /// holds at every width.
+    pub fn with_split(mut self, percent: u16) -> Self {
+        let slots = &mut self.events_view.layout.slots;
+        if let [first, second] = slots.as_mut_slice() {
+            for (slot, size) in [(first, percent), (second, 100 - percent)] {
+                slot.size = SizeConstraint::Percent(size);
+                if let Some(rule) = &mut slot.responsive {
+                    rule.overrides.clear();
+                }
+            }
+        }
+        self
+    }
+}
+
+/// Built-in preset by name (case-insensitive)
+fn builtin(name: &str) -> Option<Preset> {
     match name.to_lowercase().as_str() {
-        "reasoning" => Preset::reasoning(),
-        "debug" => Preset::debug(),
-        "minimal" => Preset::minimal(),
-        "focus" => Preset::focus(),
-        "compact" => Preset::compact(),
-        "balanced" => Preset::balanced(),
-        _ => Preset::classic(), // Default fallback
+        "classic" => Some(Preset::classic()),
+        "reasoning" => Some(Preset::reasoning()),
+        "debug" => Some(Preset::debug()),
+        "minimal" => Some(Preset::minimal()),
+        "focus" => Some(Preset::focus()),
+        "compact" => Some(Preset::compact()),
+        "balanced" => Some(Preset::balanced()),
+        _ => None,
     }
 }
 
+/// User presets directory (~/.config/aspy/presets)
+pub fn presets_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".config").join("aspy").join("presets"))
+}
+
+/// A preset offered in Settings
+#[derive(Debug, Clone)]
+pub struct PresetEntry {
+    pub name: String,
+    pub description: String,
+    /// File for user presets (None for built-ins)
+    pub path: Option<PathBuf>,
+    /// Why a user preset failed to load (listed, but can't be applied)
+    pub error: Option<String>,
+}
+
+/// List built-in presets followed by user presets (sorted by name)
+///
+/// User presets are loaded to validate them, so a broken file shows up with
+/// its error instead of silently disappearing.
+pub fn list_presets() -> Vec<PresetEntry> {
+    let mut entries: Vec<PresetEntry> = BUILTIN_PRESETS
+        .iter()
+        .map(|(name, description)| PresetEntry {
+            name: name.to_string(),
+            description: description.to_string(),
+            path: None,
+            error: None,
+        })
+        .collect();
+
+    let mut user: Vec<PresetEntry> = user_preset_files()
+        .into_iter()
+        .map(|(name, path)| match load_file(&name, &path) {
+            Ok((_, description)) => PresetEntry {
+                name,
+                description: description.unwrap_or_default(),
+                path: Some(path),
+                error: None,
+            },
+            Err(e) => PresetEntry {
+                name,
+                description: String::new(),
+                path: Some(path),
+                error: Some(format!("{:#}", e)),
+            },
+        })
+        .collect();
+    user.sort_by(|a, b| a.name.cmp(&b.name));
+    entries.extend(user);
+    entries
+}
+
+/// `*.toml` files in the presets directory, as (name, path)
+fn user_preset_files() -> Vec<(String, PathBuf)> {
+    let Some(Ok(dir)) = presets_dir().map(std::fs::read_dir) else {
+        return Vec::new();
+    };
+    dir.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some((name, path))
+        })
+        .collect()
+}
+
+/// Names of user preset files (unvalidated; for config checks)
+pub fn user_preset_names() -> Vec<String> {
+    user_preset_files()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Load and validate a user preset file, returning it with its description
+fn load_file(name: &str, path: &std::path::Path) -> Result<(Preset, Option<String>)> {
+    if builtin(name).is_some() {
+        bail!("`{}` is a built-in preset name; rename the file", name);
+    }
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let toml = TomlPreset::from_str(&contents)?;
+    let description = toml.description.clone();
+    Ok((toml.into_preset(name)?, description))
+}
+
+/// Load a preset by name: built-ins first, then ~/.config/aspy/presets/<name>.toml
+pub fn load_preset(name: &str) -> Result<Preset> {
+    if let Some(preset) = builtin(name) {
+        return Ok(preset);
+    }
+    let path = presets_dir()
+        .map(|dir| dir.join(format!("{}.toml", name)))
+        .filter(|path| path.exists())
+        .with_context(|| format!("no preset named `{}`", name))?;
+    let (preset, _) =
+        load_file(name, &path).with_context(|| format!("invalid preset {}", path.display()))?;
+    Ok(preset)
+}
+
+/// Get preset by name, falling back to classic if it can't be loaded
+pub fn get_preset(name: &str) -> Preset {
+    load_preset(name).unwrap_or_else(|e| {
+        tracing::warn!("Layout preset: {:#}; using classic", e);
+        Preset::classic()
+    })
+}
+
+/// Write a preset to the presets directory under an unused name
+///
+/// Exporting `classic` writes `classic-custom.toml` (then `classic-custom-2.toml`, ...).
+pub fn export_preset(preset: &Preset) -> Result<PathBuf> {
+    let dir = presets_dir().context("no home directory")?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+
+    // Re-exporting an export shouldn't stack suffixes
+    let base = match preset.name.rsplit_once("-custom") {
+        Some((base, rest))
+            if rest.is_empty()
+                || rest
+                    .strip_prefix('-')
+                    .is_some_and(|n| n.parse::<u32>().is_ok()) =>
+        {
+            base
+        }
+        _ => preset.name.as_str(),
+    };
+    let path = (1..)
+        .map(|n| match n {
+            1 => format!("{}-custom", base),
+            n => format!("{}-custom-{}", base, n),
+        })
+        .map(|name| dir.join(format!("{}.toml", name)))
+        .find(|path| !path.exists())
+        .expect("unbounded range");
+
+    let description = format!("Exported from {}", preset.name);
+    let toml = TomlPreset::from_preset(preset, Some(description)).to_toml()?;
+    std::fs::write(&path, toml).with_context(|| format!("writing {}", path.display()))?;
+    Ok(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -496,6 +677,22 @@ mod tests {
         assert_eq!(preset.shell.footer.len(), 3); // Logs, ContextBar, Status
     }
 
+    #[test]
+    fn builtin_names_resolve() {
+        for (name, _) in BUILTIN_PRESETS {
+            assert_eq!(load_preset(name).unwrap().name, *name);
+        }
+        assert!(load_preset("no-such-preset-anywhere").is_err());
+    }
+
+    #[test]
+    fn with_split_overrides_responsive_sizes() {
+        let preset = Preset::classic().with_split(70);
+        let resolved = preset.events_view.layout.resolve(Breakpoint::Wide);
+        assert_eq!(resolved[0].1, Constraint::Percentage(70));
+        assert_eq!(resolved[1].1, Constraint::Percentage(30));
+    }
+
     #[test]
     fn responsive_visibility() {
         let slot = LayoutSlot::new(Panel::Thinking, SizeConstraint::Percent(35))
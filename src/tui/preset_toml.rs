@@ -0,0 +1,538 @@
+// TOML format for user layout presets
+//
+// Presets live in ~/.config/aspy/presets/<name>.toml; the file name is the
+// preset name. A file describes the shell (panels above and below the view
+// content) and how the Events view arranges its panels:
+//
+//   description = "Big logs, thinking beside events"
+//
+//   [shell]
+//   header = [{ panel = "title", size = 3 }]
+//   footer = [
+//       { panel = "logs", size = "min:12" },
+//       { panel = "context_bar", size = 1 },
+//       { panel = "status", size = 2 },
+//   ]
+//
+//   [views.events]
+//   direction = "horizontal"
+//   slots = [
+//       { panel = "events", size = "60%" },
+//       { panel = "thinking", size = "40%", hide_below = "normal", sizes = { ultra_wide = "30%" } },
+//   ]
+//
+// Sizes are a line/column count, "N%", "min:N" or "fill". `sizes` overrides
+// the size from a breakpoint up (compact, normal, wide, ultra_wide) and
+// `hide_below` drops the panel on narrower terminals. Everything is checked
+// when the file loads - a preset that fails is listed in Settings with its
+// error instead of being applied.
+
+use super::layout::Breakpoint;
+use super::preset::{
+    Layout, LayoutDirection, LayoutSlot, Panel, Preset, ResponsiveRule, ShellConfig,
+    SizeConstraint, ViewLayout,
+};
+use super::scroll::FocusablePanel;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Root structure for TOML preset files
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlPreset {
+    /// Shown next to the name in Settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub shell: TomlShell,
+    pub views: TomlViews,
+    /// Tab focus order (default: Events view panels in layout order, then logs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<Vec<PanelName>>,
+}
+
+/// Panels framing every view
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlShell {
+    #[serde(default)]
+    pub header: Vec<TomlSlot>,
+    #[serde(default)]
+    pub footer: Vec<TomlSlot>,
+}
+
+/// Per-view panel arrangements
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlViews {
+    pub events: TomlLayout,
+}
+
+/// Panels laid out in one direction
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlLayout {
+    #[serde(default)]
+    pub direction: TomlDirection,
+    pub slots: Vec<TomlSlot>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TomlDirection {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// A panel with its size and responsive overrides
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlSlot {
+    pub panel: PanelName,
+    pub size: TomlSize,
+    /// Hide the panel on terminals narrower than this breakpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_below: Option<BreakpointName>,
+    /// Size from a breakpoint up (the widest matching breakpoint wins)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sizes: BTreeMap<BreakpointName, TomlSize>,
+}
+
+/// Panels a preset can place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelName {
+    Title,
+    Logs,
+    ContextBar,
+    Status,
+    Events,
+    Thinking,
+}
+
+/// Breakpoint names (ordered narrowest first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakpointName {
+    Compact,
+    Normal,
+    Wide,
+    UltraWide,
+}
+
+/// A size as written: a plain number is a fixed line/column count
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum TomlSize {
+    Lines(u16),
+    Text(String),
+}
+
+impl PanelName {
+    fn panel(self) -> Panel {
+        match self {
+            PanelName::Title => Panel::Title,
+            PanelName::Logs => Panel::Logs,
+            PanelName::ContextBar => Panel::ContextBar,
+            PanelName::Status => Panel::Status,
+            PanelName::Events => Panel::Events,
+            PanelName::Thinking => Panel::Thinking,
+        }
+    }
+
+    fn from_panel(panel: Panel) -> Option<Self> {
+        match panel {
+            Panel::Title => Some(PanelName::Title),
+            Panel::Logs => Some(PanelName::Logs),
+            Panel::ContextBar => Some(PanelName::ContextBar),
+            Panel::Status => Some(PanelName::Status),
+            Panel::Events => Some(PanelName::Events),
+            Panel::Thinking => Some(PanelName::Thinking),
+            Panel::Stats | Panel::Settings => None,
+        }
+    }
+
+    fn focusable(self) -> Option<FocusablePanel> {
+        match self {
+            PanelName::Events => Some(FocusablePanel::Events),
+            PanelName::Thinking => Some(FocusablePanel::Thinking),
+            PanelName::Logs => Some(FocusablePanel::Logs),
+            _ => None,
+        }
+    }
+
+    fn from_focusable(panel: FocusablePanel) -> Self {
+        match panel {
+            FocusablePanel::Events => PanelName::Events,
+            FocusablePanel::Thinking => PanelName::Thinking,
+            FocusablePanel::Logs => PanelName::Logs,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            PanelName::Title => "title",
+            PanelName::Logs => "logs",
+            PanelName::ContextBar => "context_bar",
+            PanelName::Status => "status",
+            PanelName::Events => "events",
+            PanelName::Thinking => "thinking",
+        }
+    }
+
+    fn in_shell(self) -> bool {
+        !matches!(self, PanelName::Events | PanelName::Thinking)
+    }
+}
+
+impl BreakpointName {
+    fn breakpoint(self) -> Breakpoint {
+        match self {
+            BreakpointName::Compact => Breakpoint::Compact,
+            BreakpointName::Normal => Breakpoint::Normal,
+            BreakpointName::Wide => Breakpoint::Wide,
+            BreakpointName::UltraWide => Breakpoint::UltraWide,
+        }
+    }
+
+    fn from_breakpoint(bp: Breakpoint) -> Self {
+        match bp {
+            Breakpoint::Compact => BreakpointName::Compact,
+            Breakpoint::Normal => BreakpointName::Normal,
+            Breakpoint::Wide => BreakpointName::Wide,
+            Breakpoint::UltraWide => BreakpointName::UltraWide,
+        }
+    }
+}
+
+impl TomlSize {
+    fn parse(&self) -> Result<SizeConstraint> {
+        let size = match self {
+            TomlSize::Lines(n) => SizeConstraint::Fixed(*n),
+            TomlSize::Text(text) => {
+                let text = text.trim();
+                if text == "fill" {
+                    SizeConstraint::Fill
+                } else if let Some(percent) = text.strip_suffix('%') {
+                    match percent.trim().parse() {
+                        Ok(p @ 1..=100) => SizeConstraint::Percent(p),
+                        _ => bail!("`{}` is not a percentage between 1% and 100%", text),
+                    }
+                } else if let Some(min) = text.strip_prefix("min:") {
+                    match min.trim().parse() {
+                        Ok(n) => SizeConstraint::Min(n),
+                        Err(_) => bail!("`{}` needs a line count after `min:`", text),
+                    }
+                } else if let Ok(n) = text.parse() {
+                    SizeConstraint::Fixed(n)
+                } else {
+                    bail!(
+                        "`{}` is not a size (use a number, \"N%\", \"min:N\" or \"fill\")",
+                        text
+                    )
+                }
+            }
+        };
+        if matches!(size, SizeConstraint::Fixed(0)) {
+            bail!("size must be at least 1");
+        }
+        Ok(size)
+    }
+
+    fn from_size(size: SizeConstraint) -> Self {
+        match size {
+            SizeConstraint::Fixed(n) => TomlSize::Lines(n),
+            SizeConstraint::Percent(p) => TomlSize::Text(format!("{}%", p)),
+            SizeConstraint::Min(n) => TomlSize::Text(format!("min:{}", n)),
+            SizeConstraint::Fill => TomlSize::Text("fill".to_string()),
+        }
+    }
+}
+
+impl TomlSlot {
+    fn to_slot(&self, place: &str) -> Result<LayoutSlot> {
+        let context = |e: anyhow::Error| e.context(format!("{} `{}`", place, self.panel.as_str()));
+        let mut slot = LayoutSlot::new(self.panel.panel(), self.size.parse().map_err(context)?);
+        if self.hide_below.is_some() || !self.sizes.is_empty() {
+            // ResponsiveRule checks overrides in order, so widest first
+            let mut overrides = Vec::new();
+            for (bp, size) in self.sizes.iter().rev() {
+                overrides.push((bp.breakpoint(), size.parse().map_err(context)?));
+            }
+            slot = slot.with_responsive(ResponsiveRule {
+                min_breakpoint: self.hide_below.map(BreakpointName::breakpoint),
+                overrides,
+            });
+        }
+        Ok(slot)
+    }
+
+    fn from_slot(slot: &LayoutSlot) -> Option<Self> {
+        let rule = slot.responsive.as_ref();
+        Some(Self {
+            panel: PanelName::from_panel(slot.panel)?,
+            size: TomlSize::from_size(slot.size),
+            hide_below: rule
+                .and_then(|r| r.min_breakpoint)
+                .map(BreakpointName::from_breakpoint),
+            sizes: rule
+                .map(|r| {
+                    r.overrides
+                        .iter()
+                        .map(|(bp, size)| {
+                            (
+                                BreakpointName::from_breakpoint(*bp),
+                                TomlSize::from_size(*size),
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl TomlPreset {
+    pub fn from_str(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Validate and build the preset (`name` comes from the file name)
+    pub fn into_preset(self, name: &str) -> Result<Preset> {
+        let mut placed: Vec<PanelName> = Vec::new();
+        let mut place = |panel: PanelName, allowed: bool, section: &str| -> Result<()> {
+            if !allowed {
+                bail!("panel `{}` can't go in {}", panel.as_str(), section);
+            }
+            if placed.contains(&panel) {
+                bail!("panel `{}` is placed more than once", panel.as_str());
+            }
+            placed.push(panel);
+            Ok(())
+        };
+
+        for slot in self.shell.header.iter().chain(&self.shell.footer) {
+            place(slot.panel, slot.panel.in_shell(), "the shell")?;
+        }
+        let slots = &self.views.events.slots;
+        if slots.is_empty() {
+            bail!("views.events needs at least one slot");
+        }
+        for slot in slots {
+            place(slot.panel, !slot.panel.in_shell(), "views.events")?;
+        }
+        if !placed.contains(&PanelName::Events) {
+            bail!("views.events must include the `events` panel");
+        }
+
+        let header = build_slots(&self.shell.header, "shell.header")?;
+        let footer = build_slots(&self.shell.footer, "shell.footer")?;
+        let events = build_slots(slots, "views.events")?;
+
+        // Percentages that can't fit would be silently squashed by the solver
+        let percent: u16 = events
+            .iter()
+            .map(|slot| match slot.size {
+                SizeConstraint::Percent(p) => p,
+                _ => 0,
+            })
+            .sum();
+        if percent > 100 {
+            bail!("views.events sizes add up to {}%", percent);
+        }
+
+        let focus_order = match &self.focus {
+            Some(focus) => {
+                let mut order = Vec::new();
+                for panel in focus {
+                    let Some(focusable) = panel.focusable() else {
+                        bail!("focus: panel `{}` can't take focus", panel.as_str());
+                    };
+                    if !placed.contains(panel) {
+                        bail!("focus: panel `{}` isn't in this preset", panel.as_str());
+                    }
+                    if order.contains(&focusable) {
+                        bail!("focus: panel `{}` is listed twice", panel.as_str());
+                    }
+                    order.push(focusable);
+                }
+                if order.is_empty() {
+                    bail!("focus must list at least one panel");
+                }
+                order
+            }
+            // Events view panels in layout order, then the logs panel
+            None => slots
+                .iter()
+                .map(|slot| slot.panel)
+                .chain(placed.contains(&PanelName::Logs).then_some(PanelName::Logs))
+                .filter_map(PanelName::focusable)
+                .collect(),
+        };
+
+        let direction = match self.views.events.direction {
+            TomlDirection::Horizontal => LayoutDirection::Horizontal,
+            TomlDirection::Vertical => LayoutDirection::Vertical,
+        };
+
+        Ok(Preset {
+            name: name.to_string(),
+            shell: ShellConfig { header, footer },
+            events_view: ViewLayout {
+                layout: Layout {
+                    direction,
+                    slots: events,
+                },
+            },
+            stats_view: ViewLayout {
+                layout: Layout::vertical(vec![LayoutSlot::new(Panel::Stats, SizeConstraint::Fill)]),
+            },
+            settings_view: None,
+            focus_order,
+        })
+    }
+
+    /// Describe an existing preset (export)
+    pub fn from_preset(preset: &Preset, description: Option<String>) -> Self {
+        let slots = |slots: &[LayoutSlot]| slots.iter().filter_map(TomlSlot::from_slot).collect();
+        Self {
+            description,
+            shell: TomlShell {
+                header: slots(&preset.shell.header),
+                footer: slots(&preset.shell.footer),
+            },
+            views: TomlViews {
+                events: TomlLayout {
+                    direction: match preset.events_view.layout.direction {
+                        LayoutDirection::Horizontal => TomlDirection::Horizontal,
+                        LayoutDirection::Vertical => TomlDirection::Vertical,
+                    },
+                    slots: slots(&preset.events_view.layout.slots),
+                },
+            },
+            focus: Some(
+                preset
+                    .focus_order
+                    .iter()
+                    .map(|&p| PanelName::from_focusable(p))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
+
+fn build_slots(slots: &[TomlSlot], place: &str) -> Result<Vec<LayoutSlot>> {
+    slots.iter().map(|slot| slot.to_slot(place)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+description = "Big logs"
+
+[shell]
+header = [{ panel = "title", size = 3 }]
+footer = [
+    { panel = "logs", size = "min:12" },
+    { panel = "context_bar", size = 1 },
+    { panel = "status", size = "2" },
+]
+
+[views.events]
+direction = "vertical"
+slots = [
+    { panel = "thinking", size = "40%", hide_below = "normal", sizes = { wide = "35%", ultra_wide = "30%" } },
+    { panel = "events", size = "fill" },
+]
+"#;
+
+    #[test]
+    fn test_parse_preset() {
+        let preset = TomlPreset::from_str(SAMPLE)
+            .unwrap()
+            .into_preset("big-logs")
+            .unwrap();
+        assert_eq!(preset.name, "big-logs");
+        assert!(matches!(
+            preset.shell.footer[0].size,
+            SizeConstraint::Min(12)
+        ));
+        assert_eq!(
+            preset.events_view.layout.direction,
+            LayoutDirection::Vertical
+        );
+        // Default focus: events view order, then logs
+        assert_eq!(
+            preset.focus_order,
+            vec![
+                FocusablePanel::Thinking,
+                FocusablePanel::Events,
+                FocusablePanel::Logs
+            ]
+        );
+
+        let layout = &preset.events_view.layout;
+        assert_eq!(layout.resolve(Breakpoint::Compact).len(), 1);
+        let wide = layout.resolve(Breakpoint::UltraWide);
+        assert_eq!(wide[0].1, ratatui::layout::Constraint::Percentage(30));
+        let normal = layout.resolve(Breakpoint::Normal);
+        assert_eq!(normal[0].1, ratatui::layout::Constraint::Percentage(40));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let error = |source: &str| {
+            TomlPreset::from_str(source)
+                .map_err(anyhow::Error::from)
+                .and_then(|p| p.into_preset("bad"))
+                .map(|_| ())
+                .map_err(|e| format!("{:#}", e))
+                .unwrap_err()
+        };
+        let base = |events: &str| format!("[shell]\n[views.events]\nslots = [{}]\n", events);
+
+        assert!(error(&base(r#"{ panel = "thinking", size = 1 }"#))
+            .contains("must include the `events` panel"));
+        assert!(error(&base(r#"{ panel = "status", size = 1 }"#))
+            .contains("`status` can't go in views.events"));
+        assert!(error(&base(
+            r#"{ panel = "events", size = "70%" }, { panel = "thinking", size = "40%" }"#
+        ))
+        .contains("add up to 110%"));
+        assert!(error(&base(r#"{ panel = "events", size = "big" }"#)).contains("not a size"));
+        assert!(error(&base(r#"{ panel = "event", size = 1 }"#)).contains("unknown variant"));
+        assert!(error(&format!(
+            "focus = [\"logs\"]\n{}",
+            base(r#"{ panel = "events", size = "fill" }"#)
+        ))
+        .contains("`logs` isn't in this preset"));
+    }
+
+    #[test]
+    fn test_builtin_round_trip() {
+        for name in ["classic", "reasoning", "debug", "compact"] {
+            let builtin = super::super::preset::get_preset(name);
+            let source = TomlPreset::from_preset(&builtin, None).to_toml().unwrap();
+            let loaded = TomlPreset::from_str(&source)
+                .unwrap()
+                .into_preset(name)
+                .unwrap();
+            assert_eq!(loaded.focus_order, builtin.focus_order, "{}", name);
+            for bp in [Breakpoint::Compact, Breakpoint::Wide] {
+                assert_eq!(
+                    loaded.events_view.layout.resolve(bp),
+                    builtin.events_view.layout.resolve(bp),
+                    "{}",
+                    name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,601 @@
+// Event search for the Events view
+//
+// `/` opens a prompt in the status bar. The query filters the event list as
+// you type and highlights matching text; n/N step through the matches. Tab
+// sends the same query to lifestats FTS and shows matches from past sessions
+// in a modal.
+//
+// Query syntax - different keys are ANDed, repeats of one key are ORed:
+//
+//   type:ToolCall     event type (case-insensitive substring, so type:tool
+//                     matches ToolCall and ToolResult)
+//   tool:Bash         tool name (tool calls, results, policy decisions)
+//   status:500        response status; also 5xx, >=400, <300
+//   since:10m         relative (s/m/h/d) or local time of day (since:14:30)
+//   until:14:45       upper bound, same formats (alias: before)
+//   "cargo test"      phrase in prompts, responses, thinking, tool I/O, errors
+//   cargo             bare words match the same way
+//
+// Keys that aren't recognised (`http://...`) are treated as text.
+//
+// The History view reuses the prompt to filter past sessions: text terms match
+// the session ID, client, working directory and branch; since/until bound the
+// start time.
+
+use crate::events::{ProxyEvent, TrackedEvent};
+use crate::pipeline::lifestats_query::{ContextMatch, MatchType, SessionSummary};
+use crate::proxy::api::event_type_name;
+use crate::tui::views::format_event_line;
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
+use std::ops::Range;
+
+/// Status code condition from a `status:` term
+#[derive(Debug, Clone, PartialEq)]
+enum StatusFilter {
+    Exact(u16),
+    /// `5xx` → 5
+    Class(u16),
+    AtLeast(u16),
+    AtMost(u16),
+}
+
+impl StatusFilter {
+    fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid status `{}` (try 500, 5xx, >=400)", value);
+        let number = |s: &str| s.parse::<u16>().map_err(|_| invalid());
+
+        if let Some(rest) = value.strip_prefix(">=") {
+            Ok(Self::AtLeast(number(rest)?))
+        } else if let Some(rest) = value.strip_prefix("<=") {
+            Ok(Self::AtMost(number(rest)?))
+        } else if let Some(rest) = value.strip_prefix('>') {
+            Ok(Self::AtLeast(number(rest)?.saturating_add(1)))
+        } else if let Some(rest) = value.strip_prefix('<') {
+            Ok(Self::AtMost(number(rest)?.saturating_sub(1)))
+        } else if let Some(class) = value.to_lowercase().strip_suffix("xx") {
+            Ok(Self::Class(number(class)?))
+        } else {
+            Ok(Self::Exact(number(value)?))
+        }
+    }
+
+    fn matches(&self, status: u16) -> bool {
+        match self {
+            Self::Exact(s) => status == *s,
+            Self::Class(c) => status / 100 == *c,
+            Self::AtLeast(s) => status >= *s,
+            Self::AtMost(s) => status <= *s,
+        }
+    }
+}
+
+/// A parsed search query
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// What the user typed (shown in the Events title, restored on reopen)
+    pub raw: String,
+    /// Lowercased `type:` values
+    types: Vec<String>,
+    /// Lowercased `tool:` values
+    tools: Vec<String>,
+    statuses: Vec<StatusFilter>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    /// Lowercased words and phrases
+    text: Vec<String>,
+}
+
+/// Split on whitespace, keeping quoted phrases together
+///
+/// Returns each token and whether it opened with a quote (a quoted token is
+/// always text, even if it contains a colon).
+fn tokenize(input: &str) -> Vec<(String, bool)> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                if current.is_empty() && !in_quotes {
+                    quoted = true;
+                }
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push((std::mem::take(&mut current), quoted));
+                }
+                quoted = false;
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push((current, quoted));
+    }
+    tokens
+}
+
+/// `10m` / `2h` ago, or a local time of day today
+fn parse_time(value: &str, now: DateTime<Local>) -> Result<DateTime<Utc>, String> {
+    let invalid = || format!("invalid time `{}` (try 10m, 2h, 14:30)", value);
+
+    if let Ok(time) = NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+    {
+        return Local
+            .from_local_datetime(&now.date_naive().and_time(time))
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+            .ok_or_else(invalid);
+    }
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let ago = match unit {
+        "s" => Duration::seconds(amount),
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        _ => return Err(invalid()),
+    };
+    Ok((now - ago).with_timezone(&Utc))
+}
+
+impl SearchQuery {
+    /// Parse a query; `Ok(None)` for a blank one
+    pub fn parse(input: &str) -> Result<Option<Self>, String> {
+        let now = Local::now();
+        let mut query = Self {
+            raw: input.trim().to_string(),
+            ..Self::default()
+        };
+
+        for (token, quoted) in tokenize(input) {
+            let Some((key, value)) = token.split_once(':').filter(|_| !quoted) else {
+                query.text.push(token.to_lowercase());
+                continue;
+            };
+            if value.is_empty() {
+                // Still typing `tool:`
+                continue;
+            }
+            match key.to_lowercase().as_str() {
+                "type" => query.types.push(value.to_lowercase()),
+                "tool" => query.tools.push(value.to_lowercase()),
+                "status" => query.statuses.push(StatusFilter::parse(value)?),
+                "since" | "after" => query.since = Some(parse_time(value, now)?),
+                "until" | "before" => query.until = Some(parse_time(value, now)?),
+                _ => query.text.push(token.to_lowercase()),
+            }
+        }
+
+        let is_empty = query.types.is_empty()
+            && query.tools.is_empty()
+            && query.statuses.is_empty()
+            && query.since.is_none()
+            && query.until.is_none()
+            && query.text.is_empty();
+        Ok((!is_empty).then_some(query))
+    }
+
+    fn matches_type(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.types.is_empty() || self.types.iter().any(|t| name.contains(t))
+    }
+
+    fn matches_time(&self, timestamp: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| timestamp >= since)
+            && self.until.is_none_or(|until| timestamp <= until)
+    }
+
+    /// Whether an event satisfies every term
+    ///
+    /// `text` is the event's [`searchable_text`], computed once when it's stored.
+    pub fn matches(&self, tracked: &TrackedEvent, text: &str) -> bool {
+        let event = &tracked.event;
+        if !self.matches_type(event_type_name(event)) {
+            return false;
+        }
+        if !self.tools.is_empty() {
+            let Some(tool) = tool_name(event) else {
+                return false;
+            };
+            let tool = tool.to_lowercase();
+            if !self.tools.iter().any(|t| tool.contains(t)) {
+                return false;
+            }
+        }
+        if !self.statuses.is_empty() {
+            let ProxyEvent::Response { status, .. } = event else {
+                return false;
+            };
+            if !self.statuses.iter().any(|s| s.matches(*status)) {
+                return false;
+            }
+        }
+        if !self.matches_time(tracked.event_timestamp()) {
+            return false;
+        }
+        if self.text.is_empty() {
+            return true;
+        }
+        self.text.iter().all(|term| text.contains(term.as_str()))
+    }
+
+    /// Byte ranges of text terms in a rendered line (sorted, non-overlapping)
+    ///
+    /// ASCII case-insensitive, which keeps byte offsets valid for `line`.
+    pub fn highlight_ranges(&self, line: &str) -> Vec<Range<usize>> {
+        let haystack = line.to_ascii_lowercase();
+        let mut ranges: Vec<Range<usize>> = self
+            .text
+            .iter()
+            .flat_map(|term| {
+                let term = term.to_ascii_lowercase();
+                haystack
+                    .match_indices(&term)
+                    .map(|(start, m)| start..start + m.len())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        ranges.sort_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+
+    /// Text terms as an FTS5 query (each term quoted, implicitly ANDed)
+    ///
+    /// None when the query has no text - FTS can't search by type or status.
+    pub fn fts_query(&self) -> Option<String> {
+        if self.text.is_empty() {
+            return None;
+        }
+        let terms: Vec<String> = self
+            .text
+            .iter()
+            .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+            .collect();
+        Some(terms.join(" "))
+    }
+
+    /// Whether a lifestats match passes the type and time terms
+    pub fn matches_history(&self, m: &ContextMatch) -> bool {
+        let name = match m.match_type {
+            MatchType::Thinking => "Thinking",
+            MatchType::UserPrompt => "UserPrompt",
+            MatchType::AssistantResponse => "AssistantResponse",
+        };
+        let in_range = DateTime::parse_from_rfc3339(&m.timestamp)
+            .map(|t| self.matches_time(t.with_timezone(&Utc)))
+            .unwrap_or(true);
+        self.matches_type(name) && in_range
+    }
+
+    /// Whether a past session passes the text and time terms (History view)
+    pub fn matches_session(&self, session: &SessionSummary) -> bool {
+        if let Some(started) = session.started_at {
+            if !self.matches_time(started) {
+                return false;
+            }
+        }
+        let haystack = [
+            Some(session.id.as_str()),
+            session.user_id.as_deref(),
+            session.cwd.as_deref(),
+            session.git_branch.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .to_lowercase();
+        self.text
+            .iter()
+            .all(|term| haystack.contains(term.as_str()))
+    }
+}
+
+fn tool_name(event: &ProxyEvent) -> Option<&str> {
+    match event {
+        ProxyEvent::ToolCall { tool_name, .. }
+        | ProxyEvent::ToolResult { tool_name, .. }
+        | ProxyEvent::PolicyDecision { tool_name, .. } => Some(tool_name),
+        _ => None,
+    }
+}
+
+/// The list line plus the event's full text content, lowercased for matching
+pub fn searchable_text(tracked: &TrackedEvent) -> String {
+    let mut text = format_event_line(tracked);
+    let mut push = |s: &str| {
+        text.push('\n');
+        text.push_str(s);
+    };
+    match &tracked.event {
+        ProxyEvent::ToolCall { input, .. } => push(&input.to_string()),
+        ProxyEvent::ToolResult { output, .. } => match output.as_str() {
+            Some(s) => push(s),
+            None => push(&output.to_string()),
+        },
+        ProxyEvent::Error {
+            message, context, ..
+        } => {
+            push(message);
+            if let Some(context) = context {
+                push(context);
+            }
+        }
+        ProxyEvent::Thinking { content, .. }
+        | ProxyEvent::UserPrompt { content, .. }
+        | ProxyEvent::AssistantResponse { content, .. } => push(content),
+        ProxyEvent::RequestTransformed { modifications, .. }
+        | ProxyEvent::ResponseTransformed { modifications, .. } => push(&modifications.join("\n")),
+        ProxyEvent::PolicyDecision { rule, reason, .. } => {
+            push(reason);
+            if let Some(rule) = rule {
+                push(rule);
+            }
+        }
+        ProxyEvent::ConfigReloaded { message, .. } => push(message),
+        _ => {}
+    }
+    text.to_lowercase()
+}
+
+/// Longest excerpt shown per history match
+const HISTORY_EXCERPT_CHARS: usize = 600;
+
+/// Markdown for the history results modal
+pub fn format_history(
+    query: &SearchQuery,
+    matches: &[ContextMatch],
+    client: Option<&str>,
+) -> String {
+    let scope = client
+        .map(|c| format!("client `{}`", c))
+        .unwrap_or_else(|| "all clients".to_string());
+    let mut out = format!(
+        "## 🔎 History: `{}`\n\n**{} match{}** in lifestats ({})\n\n---\n",
+        query.raw,
+        matches.len(),
+        if matches.len() == 1 { "" } else { "es" },
+        scope
+    );
+    if matches.is_empty() {
+        out.push_str("\nNo past thinking, prompts or responses match.\n");
+    }
+
+    for m in matches {
+        let (icon, label) = match m.match_type {
+            MatchType::Thinking => ("💭", "Thinking"),
+            MatchType::UserPrompt => ("👤", "Prompt"),
+            MatchType::AssistantResponse => ("🤖", "Response"),
+        };
+        let when = DateTime::parse_from_rfc3339(&m.timestamp)
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| m.timestamp.clone());
+        let session = m
+            .session_id
+            .as_deref()
+            .map(|s| format!(" · session `{}`", s.chars().take(8).collect::<String>()))
+            .unwrap_or_default();
+
+        let mut excerpt: String = m.content.chars().take(HISTORY_EXCERPT_CHARS).collect();
+        if m.content.chars().count() > HISTORY_EXCERPT_CHARS {
+            excerpt.push('…');
+        }
+        out.push_str(&format!(
+            "\n### {} {} · {}{}\n\n{}\n",
+            icon, label, when, session, excerpt
+        ));
+    }
+    out
+}
+
+/// Search prompt and applied query
+#[derive(Debug, Default)]
+pub struct SearchState {
+    /// Prompt text while `/` is open (None = prompt closed)
+    pub input: Option<String>,
+    /// Query filtering the Events list
+    pub query: Option<SearchQuery>,
+    /// Why the current input doesn't parse (the last valid query stays applied)
+    pub error: Option<String>,
+    /// Query in effect when the prompt opened (restored on Esc)
+    previous: Option<SearchQuery>,
+}
+
+impl SearchState {
+    /// Open the prompt, pre-filled with the applied query
+    pub fn open(&mut self) {
+        self.previous = self.query.clone();
+        self.input = Some(
+            self.query
+                .as_ref()
+                .map(|q| q.raw.clone())
+                .unwrap_or_default(),
+        );
+        self.error = None;
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.input.is_some()
+    }
+
+    pub fn push(&mut self, c: char) {
+        if let Some(input) = &mut self.input {
+            input.push(c);
+            self.update();
+        }
+    }
+
+    pub fn pop(&mut self) {
+        if let Some(input) = &mut self.input {
+            input.pop();
+            self.update();
+        }
+    }
+
+    pub fn clear_input(&mut self) {
+        if let Some(input) = &mut self.input {
+            input.clear();
+            self.update();
+        }
+    }
+
+    /// Re-parse the prompt (live filtering)
+    fn update(&mut self) {
+        let Some(input) = &self.input else {
+            return;
+        };
+        match SearchQuery::parse(input) {
+            Ok(query) => {
+                self.query = query;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Close the prompt, keeping the query
+    pub fn commit(&mut self) {
+        self.input = None;
+        self.previous = None;
+        self.error = None;
+    }
+
+    /// Close the prompt, restoring the previous query
+    pub fn cancel(&mut self) {
+        self.input = None;
+        self.query = self.previous.take();
+        self.error = None;
+    }
+
+    /// Drop the applied query; returns whether there was one
+    pub fn clear(&mut self) -> bool {
+        self.query.take().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tracked(event: ProxyEvent) -> TrackedEvent {
+        TrackedEvent::anonymous(event)
+    }
+
+    fn query(input: &str) -> SearchQuery {
+        SearchQuery::parse(input).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_terms() {
+        let q = query(r#"type:ToolCall tool:Bash "cargo test" status:5xx"#);
+        assert_eq!(q.types, vec!["toolcall"]);
+        assert_eq!(q.tools, vec!["bash"]);
+        assert_eq!(q.text, vec!["cargo test"]);
+        assert_eq!(q.statuses, vec![StatusFilter::Class(5)]);
+
+        assert_eq!(
+            query("status:>=400").statuses,
+            vec![StatusFilter::AtLeast(400)]
+        );
+        assert_eq!(
+            query("status:<300").statuses,
+            vec![StatusFilter::AtMost(299)]
+        );
+        // Unknown keys and quoted colons are text
+        assert_eq!(query("http://x").text, vec!["http://x"]);
+        assert_eq!(query(r#""tool:Bash""#).text, vec!["tool:bash"]);
+
+        assert!(SearchQuery::parse("  ").unwrap().is_none());
+        assert!(SearchQuery::parse("tool:").unwrap().is_none());
+        assert!(SearchQuery::parse("status:abc").is_err());
+        assert!(SearchQuery::parse("since:soon").is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        let call = tracked(ProxyEvent::ToolCall {
+            id: "toolu_0123456789".to_string(),
+            timestamp: Utc::now(),
+            tool_name: "Bash".to_string(),
+            input: json!({"command": "cargo test --workspace"}),
+        });
+        let response = tracked(ProxyEvent::Response {
+            request_id: "req".to_string(),
+            timestamp: Utc::now() - Duration::hours(2),
+            status: 529,
+            body_size: 0,
+            ttfb: std::time::Duration::ZERO,
+            duration: std::time::Duration::ZERO,
+            body: None,
+        });
+
+        let matches =
+            |q: &str, event: &TrackedEvent| query(q).matches(event, &searchable_text(event));
+
+        assert!(matches(r#"type:ToolCall tool:bash "Cargo Test""#, &call));
+        assert!(matches("type:tool", &call));
+        assert!(!matches("tool:Read", &call));
+        assert!(!matches("cargo build", &call));
+        assert!(!matches("status:5xx", &call));
+
+        assert!(matches("status:5xx", &response));
+        assert!(matches("status:>=500 until:1h", &response));
+        assert!(!matches("status:5xx since:1h", &response));
+        assert!(!matches("status:429", &response));
+    }
+
+    #[test]
+    fn test_highlight_ranges_merge() {
+        let q = query("cargo argo TEST");
+        let line = "[12:00:00] 🔧 Cargo test";
+        let start = line.find("Cargo").unwrap();
+        assert_eq!(
+            q.highlight_ranges(line),
+            vec![start..start + 5, start + 6..start + 10]
+        );
+    }
+
+    #[test]
+    fn test_fts_query_and_prompt() {
+        assert_eq!(
+            query(r#"type:Thinking "solarized theme" vomit"#).fts_query(),
+            Some(r#""solarized theme" "vomit""#.to_string())
+        );
+        assert_eq!(query("status:500").fts_query(), None);
+
+        let mut state = SearchState::default();
+        state.open();
+        for c in "tool:Bash".chars() {
+            state.push(c);
+        }
+        assert!(state.query.is_some());
+        state.commit();
+
+        state.open();
+        state.clear_input();
+        assert!(state.query.is_none());
+        state.cancel();
+        assert_eq!(
+            state.query.as_ref().map(|q| q.raw.as_str()),
+            Some("tool:Bash")
+        );
+    }
+}
@@ -39,6 +39,12 @@ pub enum ComponentId {
     StatusBar,
     /// Context usage bar (non-focusable)
     ContextBar,
+    /// Past session list (History view)
+    History,
+    /// Session waterfall (Stats view, Timeline tab)
+    Timeline,
+    /// Usage across all sessions (Stats view, Lifetime tab)
+    Lifetime,
 }
 
 impl ComponentId {
@@ -1,10 +1,12 @@
 //! Interactive trait for components that handle keyboard input
 //!
 //! Components that can receive and process keyboard events implement
-//! this trait. The App routes input to the focused component.
+//! this trait. The App resolves keys to actions through the keymap and
+//! routes them to the focused component.
 
 use super::Component;
-use crossterm::event::KeyEvent;
+use crate::tui::keymap::Action;
+use ratatui::layout::Rect;
 
 /// Result of handling a key event
 ///
@@ -47,16 +49,17 @@ impl From<bool> for Handled {
 
 /// Trait for components that handle keyboard input
 ///
-/// When a key event arrives, the App routes it to the focused component.
-/// The component decides whether to handle it or let it bubble up.
+/// When a key event arrives, the App looks up the actions bound to it
+/// (see `tui::keymap`) and routes them to the focused component. The
+/// component decides whether to handle each one or let it bubble up.
 ///
 /// # Event Flow
 ///
 /// ```text
-/// KeyEvent
+/// KeyEvent ──► Keymap ──► [Action]
 ///    │
 ///    ▼
-/// App (global handlers: ?, q, F1-F3)
+/// App (global handlers: help, quit, view switching)
 ///    │
 ///    │ if not handled
 ///    ▼
@@ -71,19 +74,17 @@ impl From<bool> for Handled {
 ///
 /// ```ignore
 /// impl Interactive for EventsPanel {
-///     fn handle_key(&mut self, key: KeyEvent) -> Handled {
-///         use crossterm::event::KeyCode;
-///
-///         match key.code {
-///             KeyCode::Up | KeyCode::Char('k') => {
+///     fn handle_action(&mut self, action: Action) -> Handled {
+///         match action {
+///             Action::Up => {
 ///                 self.select_previous();
 ///                 Handled::Yes
 ///             }
-///             KeyCode::Down | KeyCode::Char('j') => {
+///             Action::Down => {
 ///                 self.select_next();
 ///                 Handled::Yes
 ///             }
-///             KeyCode::Enter => {
+///             Action::Activate => {
 ///                 self.toggle_detail();
 ///                 Handled::Yes
 ///             }
@@ -96,11 +97,20 @@ impl From<bool> for Handled {
 /// Note: Currently unused as trait bound - intentional infrastructure for future event routing
 #[allow(dead_code)]
 pub trait Interactive: Component {
-    /// Handle a key event
+    /// Handle an action triggered by a key bound in the keymap
     ///
-    /// Returns `Handled::Yes` if the component consumed the event,
+    /// Returns `Handled::Yes` if the component consumed the action,
     /// `Handled::No` if it should bubble up to the App.
-    fn handle_key(&mut self, key: KeyEvent) -> Handled;
+    fn handle_action(&mut self, action: Action) -> Handled;
+
+    /// Handle a left click
+    ///
+    /// `area` is where the component was drawn last frame and `column`/`row`
+    /// are screen coordinates inside it. Default ignores clicks (the App
+    /// still moves focus to the component).
+    fn handle_click(&mut self, _area: Rect, _column: u16, _row: u16) -> Handled {
+        Handled::No
+    }
 
     /// Whether this component can receive focus
     ///
@@ -135,35 +145,33 @@ pub trait Interactive: Component {
 /// Note: Currently unused - intentional infrastructure for future simplified scroll handling
 #[allow(dead_code)]
 pub trait ScrollableInteractive: Interactive + super::Scrollable {
-    /// Handle common scroll keys
+    /// Handle common scroll actions
     ///
-    /// Call this from `handle_key()` for standard scroll behavior.
-    /// Returns `Handled::Yes` for: Up, Down, k, j, Home, End, PageUp, PageDown
-    fn handle_scroll_keys(&mut self, key: KeyEvent) -> Handled {
-        use crossterm::event::KeyCode;
-
-        match key.code {
-            KeyCode::Up | KeyCode::Char('k') => {
+    /// Call this from `handle_action()` for standard scroll behavior.
+    /// Returns `Handled::Yes` for: Up, Down, Top, Bottom, PageUp, PageDown
+    fn handle_scroll_action(&mut self, action: Action) -> Handled {
+        match action {
+            Action::Up => {
                 self.scroll_up();
                 Handled::Yes
             }
-            KeyCode::Down | KeyCode::Char('j') => {
+            Action::Down => {
                 self.scroll_down();
                 Handled::Yes
             }
-            KeyCode::Home => {
+            Action::Top => {
                 self.scroll_to_top();
                 Handled::Yes
             }
-            KeyCode::End => {
+            Action::Bottom => {
                 self.scroll_to_bottom();
                 Handled::Yes
             }
-            KeyCode::PageUp => {
+            Action::PageUp => {
                 self.page_up();
                 Handled::Yes
             }
-            KeyCode::PageDown => {
+            Action::PageDown => {
                 self.page_down();
                 Handled::Yes
             }
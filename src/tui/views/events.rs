@@ -9,8 +9,10 @@
 // API traffic in real-time.
 
 use crate::events::{ProxyEvent, TrackedEvent};
+use crate::storage::attachments::{self, FoundAttachment};
 use crate::tui::app::App;
 use crate::tui::layout::Breakpoint;
+use crate::tui::mouse::{Split, Target};
 use crate::tui::preset::{LayoutDirection, Panel};
 use crate::tui::scroll::FocusablePanel;
 use ratatui::{
@@ -18,8 +20,11 @@ use ratatui::{
     Frame,
 };
 
+use std::collections::HashSet;
+
 // Import shared utilities from components
 use super::super::components::format_number;
+use super::tool_detail::{format_tool_input, format_tool_output};
 
 // ============================================================================
 // Detail content types
@@ -55,6 +60,7 @@ impl RenderableContent {
 pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
     // When zoomed, render only the focused panel at full size
     if app.zoomed {
+        app.mouse.hits.add(area, Target::Panel(app.focused));
         match app.focused {
             FocusablePanel::Events => render_list_view(f, area, app),
             FocusablePanel::Thinking => render_thinking_panel(f, area, app),
@@ -73,8 +79,14 @@ pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
         LayoutDirection::Vertical => Direction::Vertical,
     };
 
-    // Build constraints from resolved layout
-    let constraints: Vec<Constraint> = resolved.iter().map(|(_, c)| *c).collect();
+    // Build constraints from resolved layout (a split dragged with the mouse wins)
+    let constraints: Vec<Constraint> = match app.split_percent {
+        Some(percent) if resolved.len() == 2 => vec![
+            Constraint::Percentage(percent),
+            Constraint::Percentage(100 - percent),
+        ],
+        _ => resolved.iter().map(|(_, c)| *c).collect(),
+    };
 
     // Split area based on preset layout
     let chunks = Layout::default()
@@ -86,11 +98,33 @@ pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
     // Note: Detail view is now a modal, not a split panel
     for (i, (panel, _)) in resolved.iter().enumerate() {
         match panel {
-            Panel::Events => render_list_view(f, chunks[i], app),
-            Panel::Thinking => render_thinking_panel(f, chunks[i], app),
+            Panel::Events => {
+                app.mouse
+                    .hits
+                    .add(chunks[i], Target::Panel(FocusablePanel::Events));
+                render_list_view(f, chunks[i], app)
+            }
+            Panel::Thinking => {
+                app.mouse
+                    .hits
+                    .add(chunks[i], Target::Panel(FocusablePanel::Thinking));
+                render_thinking_panel(f, chunks[i], app)
+            }
             _ => {} // Other panels not used in events_view
         }
     }
+
+    // Two panels: their shared border can be dragged to resize
+    if chunks.len() == 2 {
+        app.mouse.hits.split = Some(Split {
+            area,
+            direction,
+            at: match direction {
+                Direction::Horizontal => chunks[1].x,
+                Direction::Vertical => chunks[1].y,
+            },
+        });
+    }
 }
 
 // ============================================================================
@@ -98,10 +132,13 @@ pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
 // ============================================================================
 
 /// Render the main list view showing all events
-fn render_list_view(f: &mut Frame, area: Rect, app: &App) {
+fn render_list_view(f: &mut Frame, area: Rect, app: &mut App) {
     use super::super::components::events_panel;
 
-    // Get filtered events for the selected session
+    // Get filtered events for the selected session (and search query)
+    let count = app.filtered_count();
+    app.events_panel
+        .sync_viewport(count, area.height.saturating_sub(2) as usize);
     let filtered = app.filtered_events();
 
     // Delegate to EventsPanel component with filtered events
@@ -112,6 +149,7 @@ fn render_list_view(f: &mut Frame, area: Rect, app: &App) {
         &filtered,
         &app.theme,
         app.is_focused(FocusablePanel::Events),
+        app.search.query.as_ref(),
     );
 }
 
@@ -120,7 +158,30 @@ fn render_list_view(f: &mut Frame, area: Rect, app: &App) {
 // ============================================================================
 
 /// Render the thinking panel using the ThinkingPanel component
+///
+/// When the selected (or replayed) session has a TodoWrite plan, the plan panel takes the
+/// top of the column and thinking gets the rest.
 fn render_thinking_panel(f: &mut Frame, area: Rect, app: &mut App) {
+    use super::super::components::plan_panel;
+
+    let plan_height = app
+        .current_plan()
+        .map(|items| plan_panel::height_for(items, area.height));
+
+    let area = match plan_height {
+        Some(height) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(height), Constraint::Min(3)])
+                .split(area);
+            if let Some(items) = app.current_plan() {
+                plan_panel::render(f, chunks[0], items, &app.theme);
+            }
+            chunks[1]
+        }
+        None => area,
+    };
+
     super::super::components::thinking_panel::render(f, area, app);
 }
 
@@ -390,6 +451,39 @@ pub(crate) fn format_event_line(tracked: &TrackedEvent) -> String {
                 mods_preview
             )
         }
+        ProxyEvent::ResponseTransformed {
+            timestamp,
+            transformer,
+            modifications,
+        } => {
+            format!(
+                "[{}] {}✂ Rewrite [{}]: {}",
+                timestamp.format("%H:%M:%S"),
+                user_prefix,
+                transformer,
+                modifications.join(", ")
+            )
+        }
+        ProxyEvent::PolicyDecision {
+            timestamp,
+            tool_name,
+            decision,
+            rule,
+            enforcement,
+            ..
+        } => {
+            let icon = if decision == "deny" { "⛔" } else { "✓" };
+            format!(
+                "[{}] {}{} Policy {} {} [{}] ({})",
+                timestamp.format("%H:%M:%S"),
+                user_prefix,
+                icon,
+                decision,
+                tool_name,
+                rule.as_deref().unwrap_or("default"),
+                enforcement
+            )
+        }
         ProxyEvent::ResponseAugmented {
             timestamp,
             augmenter,
@@ -403,6 +497,23 @@ pub(crate) fn format_event_line(tracked: &TrackedEvent) -> String {
                 tokens_injected
             )
         }
+        ProxyEvent::ConfigReloaded {
+            timestamp,
+            trigger,
+            success,
+            message,
+            ..
+        } => {
+            let icon = if *success { "⟳" } else { "✗" };
+            format!(
+                "[{}] {}{} Config reload [{}]: {}",
+                timestamp.format("%H:%M:%S"),
+                user_prefix,
+                icon,
+                trigger,
+                message
+            )
+        }
     }
 }
 
@@ -436,6 +547,49 @@ fn format_tracking_header(tracked: &TrackedEvent) -> String {
     }
 }
 
+/// Attachments referenced by a request body or tool result, newest last
+///
+/// Conversations resend the same files every turn, so duplicates collapse
+/// to their last occurrence.
+pub(crate) fn event_attachments(event: &ProxyEvent) -> Vec<FoundAttachment> {
+    let value = match event {
+        ProxyEvent::Request {
+            body: Some(body), ..
+        } => body,
+        ProxyEvent::ToolResult { output, .. } => output,
+        _ => return Vec::new(),
+    };
+    let mut found = attachments::references(value);
+    let mut seen = HashSet::new();
+    found.reverse();
+    found.retain(|a| seen.insert(a.reference.sha256.clone()));
+    found.reverse();
+    found
+}
+
+/// Markdown section listing an event's attachments (empty if none)
+fn format_attachments(event: &ProxyEvent) -> String {
+    let found = event_attachments(event);
+    if found.is_empty() {
+        return String::new();
+    }
+    let items: Vec<String> = found
+        .iter()
+        .map(|a| {
+            format!(
+                "- **{}** {}  \n  `{}`",
+                a.kind.as_str(),
+                a.reference.summary(),
+                a.reference.path.display()
+            )
+        })
+        .collect();
+    format!(
+        "\n\n### 📎 Attachments\n\n{}\n\n*Press `o` to open the latest attachment*",
+        items.join("\n")
+    )
+}
+
 /// Format a tracked event as detailed content for the detail view
 ///
 /// Returns `RenderableContent` to indicate how the content should be displayed:
@@ -457,11 +611,12 @@ pub(crate) fn format_event_detail(tracked: &TrackedEvent) -> RenderableContent {
             **Timestamp:** {}  \n\
             **Tool:** `{}`\n\n\
             ---\n\n\
-            ```json\n{}\n```",
+            {}```json\n{}\n```",
             tracking_header,
             id,
             timestamp.to_rfc3339(),
             tool_name,
+            format_tool_input(tool_name, input),
             serde_json::to_string_pretty(input).unwrap_or_else(|_| "N/A".to_string())
         )),
         ProxyEvent::ToolResult {
@@ -479,9 +634,9 @@ pub(crate) fn format_event_detail(tracked: &TrackedEvent) -> RenderableContent {
                 **Timestamp:** {}  \n\
                 **Tool:** `{}`  \n\
                 **Success:** {}  \n\
-                **Duration:** {:.2}s\n\n\
+                **Duration:** {:.2}s{}\n\n\
                 ---\n\n\
-                ```json\n{}\n```",
+                {}```json\n{}\n```",
                 tracking_header,
                 status_icon,
                 id,
@@ -489,6 +644,8 @@ pub(crate) fn format_event_detail(tracked: &TrackedEvent) -> RenderableContent {
                 tool_name,
                 success,
                 duration.as_secs_f64(),
+                format_attachments(event),
+                format_tool_output(tool_name, output, *success),
                 serde_json::to_string_pretty(output).unwrap_or_else(|_| "N/A".to_string())
             ))
         }
@@ -516,13 +673,14 @@ pub(crate) fn format_event_detail(tracked: &TrackedEvent) -> RenderableContent {
                 **Timestamp:** {}  \n\
                 **Method:** {}  \n\
                 **Path:** {}  \n\
-                **Body Size:** {} bytes{}",
+                **Body Size:** {} bytes{}{}",
                 tracking_header,
                 id,
                 timestamp.to_rfc3339(),
                 method,
                 path,
                 body_size,
+                format_attachments(event),
                 body_content
             ))
         }
@@ -807,6 +965,63 @@ pub(crate) fn format_event_detail(tracked: &TrackedEvent) -> RenderableContent {
                 modifications_section
             ))
         }
+        ProxyEvent::ResponseTransformed {
+            timestamp,
+            transformer,
+            modifications,
+        } => {
+            let mods_list = modifications
+                .iter()
+                .map(|m| format!("- {}", m))
+                .collect::<Vec<_>>()
+                .join("\n");
+            RenderableContent::Markdown(format!(
+                "{}## ✂ Response Transformed\n\n\
+                **Timestamp:** {}  \n\
+                **Transformer:** `{}`\n\n\
+                ---\n\n\
+                ### Modifications\n\n{}\n\n\
+                *Aspy rewrote this API response before returning it to the client. \
+                The recorded response is the original.*",
+                tracking_header,
+                timestamp.to_rfc3339(),
+                transformer,
+                mods_list
+            ))
+        }
+        ProxyEvent::PolicyDecision {
+            timestamp,
+            tool_name,
+            tool_id,
+            decision,
+            rule,
+            enforcement,
+            reason,
+        } => {
+            let heading = if decision == "deny" {
+                "⛔ Policy Denied"
+            } else {
+                "✓ Policy Allowed"
+            };
+            RenderableContent::Markdown(format!(
+                "{}## {}\n\n\
+                **Timestamp:** {}  \n\
+                **Tool:** `{}`  \n\
+                **Tool ID:** `{}`  \n\
+                **Rule:** `{}`  \n\
+                **Enforcement:** {}\n\n\
+                ---\n\n\
+                {}",
+                tracking_header,
+                heading,
+                timestamp.to_rfc3339(),
+                tool_name,
+                tool_id.as_deref().unwrap_or("(tool definition)"),
+                rule.as_deref().unwrap_or("default"),
+                enforcement,
+                reason
+            ))
+        }
         ProxyEvent::ResponseAugmented {
             timestamp,
             augmenter,
@@ -822,5 +1037,30 @@ pub(crate) fn format_event_detail(tracked: &TrackedEvent) -> RenderableContent {
             augmenter,
             tokens_injected
         )),
+        ProxyEvent::ConfigReloaded {
+            timestamp,
+            trigger,
+            success,
+            message,
+            theme,
+            ..
+        } => RenderableContent::Markdown(format!(
+            "{}## {}\n\n\
+            **Timestamp:** {}  \n\
+            **Trigger:** {}  \n\
+            **Theme:** {}\n\n\
+            ---\n\n\
+            {}",
+            tracking_header,
+            if *success {
+                "⟳ Config Reloaded"
+            } else {
+                "✗ Config Reload Failed (current config kept)"
+            },
+            timestamp.to_rfc3339(),
+            trigger,
+            theme.as_deref().unwrap_or("(unchanged)"),
+            message
+        )),
     }
 }
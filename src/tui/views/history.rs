@@ -0,0 +1,16 @@
+// History view - past sessions from lifestats
+//
+// A sortable, filterable table of sessions with a details box for the
+// selected one. Enter replays the session in the Events view (read-only).
+
+use crate::tui::app::App;
+use crate::tui::mouse::Target;
+use ratatui::{layout::Rect, Frame};
+
+/// Main render function for the History view
+pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
+    app.mouse.hits.add(area, Target::History);
+    let lifestats_enabled = app.config.lifestats.enabled;
+    app.history_panel
+        .render_sessions(f, area, &app.theme, lifestats_enabled);
+}
@@ -4,19 +4,31 @@
 // - Events: Main view showing proxy events, thinking panel, detail view
 // - Stats: Session analytics with model/token/tool breakdowns
 // - Settings: Configuration UI for themes and presets
+// - History: Past sessions from lifestats (replayed in Events, read-only)
+//
+// tool_detail holds the per-tool sections of the Events detail modal.
 //
 // This module dispatches to the appropriate view based on app state.
 
 mod events;
+mod history;
 mod modal;
 mod settings;
 mod stats;
+mod tool_detail;
 
 // Re-export formatters for clipboard operations (crate-internal)
-pub(crate) use events::{format_event_detail, format_event_line};
+pub(crate) use events::{event_attachments, format_event_detail, format_event_line};
+pub(crate) use stats::{
+    LIFETIME_TAB as STATS_LIFETIME_TAB, TAB_COUNT as STATS_TAB_COUNT,
+    TIMELINE_TAB as STATS_TIMELINE_TAB,
+};
 
 use super::app::{App, View};
+use super::layout::Breakpoint;
+use super::mouse::Target;
 use super::preset::Panel;
+use super::scroll::FocusablePanel;
 use crate::tui::components;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::Style;
@@ -27,6 +39,9 @@ use ratatui::Frame;
 ///
 /// Builds the shell layout from the preset, then dispatches to the appropriate view.
 pub fn draw(f: &mut Frame, app: &mut App) {
+    // Clickable areas are recorded as this frame renders
+    app.mouse.hits.clear();
+
     // Apply theme background to entire frame (respects use_theme_background toggle)
     let bg_block = Block::default().style(Style::default().bg(app.theme.background));
     f.render_widget(bg_block, f.area());
@@ -34,15 +49,16 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     // Build shell layout from preset
     // Structure: [header panels...] [content slot] [footer panels...]
     let shell = &app.preset.shell;
+    let bp = Breakpoint::from_width(f.area().width);
 
     // Collect constraints: headers + content + footers
     // When zoomed, skip Logs and ContextBar to maximize content area
     let mut constraints: Vec<Constraint> = Vec::new();
     let mut panel_map: Vec<Option<Panel>> = Vec::new();
 
-    // Add header slots
-    for slot in &shell.header {
-        constraints.push(slot.size.to_constraint());
+    // Add header slots (responsive rules can hide or resize them)
+    for slot in shell.header.iter().filter(|slot| slot.visible_at(bp)) {
+        constraints.push(slot.constraint_at(bp));
         panel_map.push(Some(slot.panel));
     }
 
@@ -51,12 +67,12 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     panel_map.push(None); // None = content slot
 
     // Add footer slots (skip Logs/ContextBar when zoomed)
-    for slot in &shell.footer {
+    for slot in shell.footer.iter().filter(|slot| slot.visible_at(bp)) {
         // When zoomed, only include Status bar in footer
         if app.zoomed && matches!(slot.panel, Panel::Logs | Panel::ContextBar) {
             continue;
         }
-        constraints.push(slot.size.to_constraint());
+        constraints.push(slot.constraint_at(bp));
         panel_map.push(Some(slot.panel));
     }
 
@@ -70,7 +86,12 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     for (i, panel) in panel_map.iter().enumerate() {
         match panel {
             Some(Panel::Title) => components::render_title(f, chunks[i], app),
-            Some(Panel::Logs) => components::render_logs_panel(f, chunks[i], app),
+            Some(Panel::Logs) => {
+                app.mouse
+                    .hits
+                    .add(chunks[i], Target::Panel(FocusablePanel::Logs));
+                components::render_logs_panel(f, chunks[i], app)
+            }
             Some(Panel::ContextBar) => components::render_context_bar(f, chunks[i], app),
             Some(Panel::Status) => components::render_status(f, chunks[i], app),
             None => content_area = Some(chunks[i]), // Content slot
@@ -84,6 +105,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             View::Events => events::render(f, area, app),
             View::Stats => stats::render(f, area, app),
             View::Settings => settings::render(f, area, app),
+            View::History => history::render(f, area, app),
         }
     }
 
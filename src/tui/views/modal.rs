@@ -3,28 +3,42 @@
 // Modals are rendered on top of the main content:
 // - Help modal: keyboard shortcuts and current config
 // - Detail modal: event details (full screen overlay)
+// - Log detail / history search: cached markdown in the DetailPanel
 
 use crate::tui::app::App;
 use crate::tui::components::scrollbar::{render_scrollbar_raw, ScrollbarStyle};
+use crate::tui::keymap::{Action, REGISTRY};
 use crate::tui::markdown;
 use crate::tui::modal::Modal;
 use crate::tui::traits::{Copyable, Scrollable};
 use ratatui::{
-    layout::Rect,
-    style::{Modifier, Style},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
 use super::events::RenderableContent;
+use super::STATS_TAB_COUNT;
 
 /// Render a modal dialog as a centered overlay
 pub fn render(f: &mut Frame, modal: &Modal, app: &mut App) {
     match modal {
         Modal::Help => render_help(f, app),
         Modal::Detail(event_idx) => render_detail(f, app, *event_idx),
-        Modal::LogDetail => render_log_detail(f, app),
+        Modal::LogDetail => {
+            let border = app.theme.panel_logs;
+            render_cached_markdown(f, app, "Log Details", border)
+        }
+        Modal::SearchResults => {
+            let border = app.theme.highlight;
+            render_cached_markdown(f, app, "History Search", border)
+        }
+        Modal::Diff => {
+            let border = app.theme.highlight;
+            render_cached_markdown(f, app, "Diff", border)
+        }
     }
 }
 
@@ -36,6 +50,9 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
 }
 
 /// Render the help modal overlay
+///
+/// Keys come from the active keymap, so rebinding in [keybindings] shows up
+/// here. Sections flow into two columns when the terminal is wide enough.
 fn render_help(f: &mut Frame, app: &App) {
     // Styles
     let key_style = Style::default().fg(app.theme.tool_call);
@@ -45,50 +62,81 @@ fn render_help(f: &mut Frame, app: &App) {
         .add_modifier(Modifier::BOLD);
     let divider_style = Style::default().fg(app.theme.border);
 
+    // (key, description) rows per section: bound actions in registry order,
+    // then the fixed keys
+    let mut sections: Vec<(&str, Vec<(String, &str)>)> = Vec::new();
+    for def in REGISTRY {
+        let keys = app.keymap.label(def.action);
+        if keys.is_empty() {
+            continue;
+        }
+        match sections.last_mut() {
+            Some((section, rows)) if *section == def.section => rows.push((keys, def.help)),
+            _ => sections.push((def.section, vec![(keys, def.help)])),
+        }
+    }
+    sections.push((
+        "Stats View",
+        vec![(format!("1-{}", STATS_TAB_COUNT), "Jump to tab")],
+    ));
+    sections.push((
+        "Mouse",
+        vec![
+            ("Click".to_string(), "Focus panel, select row/tab"),
+            ("Dbl-click".to_string(), "Open detail / apply"),
+            ("Wheel".to_string(), "Scroll panel under pointer"),
+            ("Drag".to_string(), "Resize split (Events view)"),
+        ],
+    ));
+
+    let rows = sections.iter().flat_map(|(_, rows)| rows);
+    let key_width = rows
+        .clone()
+        .map(|(key, _)| key.chars().count() + 2)
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    let desc_width = rows
+        .map(|(_, desc)| desc.chars().count())
+        .max()
+        .unwrap_or(0);
+    let column_width = (4 + key_width + desc_width + 2) as u16;
+
     // Helper to create a keybind line: "    key         description"
     let kb = |key: &str, desc: &str| -> Line {
         Line::from(vec![
             Span::raw("    "),
-            Span::styled(format!("{:<12}", key), key_style),
+            Span::styled(format!("{:<width$}", key, width = key_width), key_style),
             Span::styled(desc.to_string(), desc_style),
         ])
     };
+    let section_lines = |(name, rows): &(&str, Vec<(String, &str)>)| -> Vec<Line> {
+        let mut lines = vec![Line::from(Span::styled(
+            format!("  {}", name),
+            header_style,
+        ))];
+        lines.extend(rows.iter().map(|(key, desc)| kb(key, desc)));
+        lines.push(Line::raw(""));
+        lines
+    };
+
+    // Split sections where the line count is closest to half
+    let total: usize = sections.iter().map(|(_, rows)| rows.len() + 2).sum();
+    let two_columns = f.area().width >= column_width * 2 + 2;
+    let mut columns: Vec<Vec<Line>> = vec![vec![Line::raw("")]];
+    for section in &sections {
+        let current = columns.last().map(Vec::len).unwrap_or(0);
+        if two_columns && columns.len() == 1 && current + section.1.len() / 2 > total / 2 {
+            columns.push(vec![Line::raw("")]);
+        }
+        if let Some(column) = columns.last_mut() {
+            column.extend(section_lines(section));
+        }
+    }
 
-    let content = Text::from(vec![
-        Line::raw(""),
-        Line::from(Span::styled("  Views", header_style)),
-        kb("F1, e", "Events (main view)"),
-        kb("F2, s", "Statistics"),
-        kb("F3", "Settings"),
-        Line::raw(""),
-        Line::from(Span::styled("  Navigation", header_style)),
-        kb("↑/↓, j/k", "Scroll list / detail"),
-        kb("Enter", "Open detail / apply"),
-        kb("Esc", "Close / go back"),
-        kb("Home/End", "Jump to start/end"),
-        Line::raw(""),
-        Line::from(Span::styled("  Settings Navigation", header_style)),
-        kb("Tab/→", "Switch pane focus"),
-        kb("↑/↓", "Navigate options"),
-        kb("Enter", "Apply selection"),
-        Line::raw(""),
-        Line::from(Span::styled("  Events View", header_style)),
-        kb("Tab", "Cycle panel focus"),
-        kb("Shift+Tab", "Focus previous panel"),
-        Line::raw(""),
-        Line::from(Span::styled("  Clipboard", header_style)),
-        kb("y", "Copy to clipboard (text)"),
-        kb("Y", "Copy to clipboard (JSONL)"),
-        Line::raw(""),
-        Line::from(Span::styled("  General", header_style)),
-        kb("?", "Toggle this help"),
-        kb("q", "Quit"),
-        Line::raw(""),
-        Line::from(Span::styled("  Mouse", header_style)),
-        kb("Scroll", "Navigate events"),
-        Line::raw(""),
+    let footer = vec![
         Line::from(Span::styled(
-            "  ──────────────────────────────────",
+            format!("  {}", "─".repeat(column_width.saturating_sub(4) as usize)),
             divider_style,
         )),
         Line::from(vec![
@@ -97,28 +145,43 @@ fn render_help(f: &mut Frame, app: &App) {
             Span::styled("  |  Preset: ", desc_style),
             Span::styled(&app.preset.name, key_style),
         ]),
-    ]);
+    ];
 
     // Calculate modal size
-    let width = 44;
-    let height = 34;
+    let body_height = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let width = column_width * columns.len() as u16 + 2;
+    let height = (body_height + footer.len() + 2) as u16;
     let area = centered_rect(width, height, f.area());
 
     // Clear the area behind the modal
     f.render_widget(Clear, area);
 
-    let paragraph = Paragraph::new(content)
-        .style(Style::default().bg(app.theme.background))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(app.theme.highlight))
-                .border_type(app.theme.border_type)
-                .title(" Help ")
-                .title_bottom(Line::from(" Press ? or Esc to close ").centered()),
-        );
-
-    f.render_widget(paragraph, area);
+    let close_keys: Vec<String> = [Action::Help, Action::CloseModal]
+        .iter()
+        .filter_map(|&action| app.keymap.chords(action).first())
+        .map(|chord| chord.to_string())
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.highlight))
+        .border_type(app.theme.border_type)
+        .title(" Help ")
+        .title_bottom(
+            Line::from(format!(" Press {} to close ", close_keys.join(" or "))).centered(),
+        )
+        .style(Style::default().bg(app.theme.background));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let [body_area, footer_area] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(footer.len() as u16)])
+            .areas(inner);
+    let column_areas =
+        Layout::horizontal(vec![Constraint::Length(column_width); columns.len()]).split(body_area);
+    for (lines, column_area) in columns.into_iter().zip(column_areas.iter()) {
+        f.render_widget(Paragraph::new(Text::from(lines)), *column_area);
+    }
+    f.render_widget(Paragraph::new(Text::from(footer)), footer_area);
 }
 
 /// Render the detail modal overlay
@@ -130,7 +193,7 @@ fn render_detail(f: &mut Frame, app: &mut App, event_idx: usize) {
     use super::format_event_detail;
 
     // Get event if it exists
-    let Some(event) = app.events.get(event_idx) else {
+    let Some(event) = app.shown_events().get(event_idx) else {
         // Event no longer exists (rare race condition) - close modal
         return;
     };
@@ -371,9 +434,10 @@ fn render_horizontal_scrollbar(
     f.render_widget(scrollbar_widget, scrollbar_area);
 }
 
-/// Render the log detail modal overlay
+/// Render a modal over markdown content cached in the DetailPanel
+/// (log entry details, history search results)
 /// Uses markdown rendering with text wrapping (no horizontal scroll)
-fn render_log_detail(f: &mut Frame, app: &mut App) {
+fn render_cached_markdown(f: &mut Frame, app: &mut App, title: &str, border: Color) {
     // Get cached content from detail_panel
     let content = app.detail_panel.copy_text().unwrap_or_default();
 
@@ -419,8 +483,8 @@ fn render_log_detail(f: &mut Frame, app: &mut App) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(app.theme.border_type)
-                .border_style(Style::default().fg(app.theme.panel_logs))
-                .title(format!(" Log Details{} ", scroll_info))
+                .border_style(Style::default().fg(border))
+                .title(format!(" {}{} ", title, scroll_info))
                 .title_bottom(Line::from(" ↑↓:scroll  g/G:top/end  y:copy  Esc:close ").centered()),
         )
         .scroll((v_start as u16, 0));
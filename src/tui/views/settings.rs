@@ -2,12 +2,14 @@
 //
 // Two-panel layout:
 // - Left: Category navigation (Appearance, Layout)
-// - Right: Options for selected category (themes, presets)
+// - Right: Options for selected category (themes, presets - built-in and
+//   user presets from ~/.config/aspy/presets)
 
 use crate::theme::Theme;
 use crate::tui::app::App;
 use crate::tui::components::settings_panel::{SettingsCategory, SettingsFocus};
 use crate::tui::components::theme_list_panel::ThemeRenderContext;
+use crate::tui::mouse::Target;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -15,13 +17,6 @@ use ratatui::{
     Frame,
 };
 
-/// Available presets with their descriptions
-const PRESET_LIST: &[(&str, &str)] = &[
-    ("classic", "Side-by-side events and thinking"),
-    ("reasoning", "Thinking-first, larger reasoning panel"),
-    ("debug", "Expanded logs for debugging"),
-];
-
 /// Main render function for the Settings view
 pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
     // Split into left nav (fixed) and right content (fill)
@@ -30,6 +25,9 @@ pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
         .constraints([Constraint::Length(22), Constraint::Min(30)])
         .split(area);
 
+    app.mouse.hits.add(chunks[0], Target::SettingsCategories);
+    app.mouse.hits.add(chunks[1], Target::SettingsOptions);
+
     render_categories(f, chunks[0], app);
     render_options(f, chunks[1], app);
 }
@@ -116,7 +114,7 @@ fn render_theme_options(f: &mut Frame, area: Rect, app: &mut App, is_focused: bo
         .render_with_context(f, area, &ctx);
 }
 
-/// Render preset selection options
+/// Render preset selection options (built-ins, then user presets)
 fn render_preset_options(
     f: &mut Frame,
     area: Rect,
@@ -124,11 +122,12 @@ fn render_preset_options(
     is_focused: bool,
     border_color: Color,
 ) {
-    let items: Vec<ListItem> = PRESET_LIST
+    let items: Vec<ListItem> = app
+        .presets
         .iter()
         .enumerate()
-        .map(|(i, (name, desc))| {
-            let is_current = *name == app.preset.name;
+        .map(|(i, entry)| {
+            let is_current = entry.name == app.preset.name;
             let is_highlighted = is_focused && i == app.settings_panel.layout_option_index;
 
             let prefix = if is_current { " ● " } else { "   " };
@@ -142,20 +141,27 @@ fn render_preset_options(
                 Style::default()
                     .fg(app.theme.tool_result_ok)
                     .add_modifier(Modifier::BOLD)
+            } else if entry.error.is_some() {
+                Style::default().fg(app.theme.tool_result_fail)
             } else {
                 Style::default().fg(app.theme.foreground)
             };
 
-            // Show name and description
-            let text = format!("{}{:<12} {}", prefix, name, desc);
+            // Show name and description (user presets marked, broken ones with their error)
+            let marker = if entry.path.is_some() { "◆" } else { " " };
+            let desc = match &entry.error {
+                Some(error) => format!("⚠ {}", error),
+                None => entry.description.clone(),
+            };
+            let text = format!("{}{:<16} {} {}", prefix, entry.name, marker, desc);
             ListItem::new(text).style(style)
         })
         .collect();
 
     let title = if is_focused {
-        " Layout Preset (↑↓ select, Enter apply) "
+        " Layout Preset (↑↓ select, Enter apply, x export) "
     } else {
-        " Layout Preset "
+        " Layout Preset (◆ user) "
     };
 
     let list = List::new(items).block(
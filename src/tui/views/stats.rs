@@ -1,19 +1,23 @@
 // Stats view - tabbed dashboard with rich visualizations
 //
-// Displays a 5-tab dashboard:
+// Displays an 8-tab dashboard:
 // - Overview: Session gauges + summary
 // - Models: API call distribution with BarChart and sparkline
 // - Tokens: Token usage breakdown with grouped bars
 // - Tools: Tool call frequency and duration analysis
 // - Trends: Sparklines grid showing trends over time
+// - Files: Most-touched files and redundant reads
+// - Timeline: Waterfall of requests, thinking, tools and idle gaps
+// - Lifetime: All-session usage from lifestats (heatmap, model mix, top lists)
 
 use crate::tui::{
     app::App,
     components::{
-        models_tab_panel::ModelsTabPanel, session_gauges_panel::SessionGaugesPanel,
-        tokens_tab_panel::TokensTabPanel, tools_tab_panel::ToolsTabPanel,
-        trends_tab_panel::TrendsTabPanel,
+        files_tab_panel::FilesTabPanel, models_tab_panel::ModelsTabPanel,
+        session_gauges_panel::SessionGaugesPanel, tokens_tab_panel::TokensTabPanel,
+        tools_tab_panel::ToolsTabPanel, trends_tab_panel::TrendsTabPanel,
     },
+    mouse::{self, Target},
 };
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -26,8 +30,29 @@ use ratatui::{
 // Import shared formatters from components
 use super::super::components::{format_compact_number, format_number};
 
+/// Tab bar titles (number keys select them)
+const TAB_TITLES: [&str; 8] = [
+    " 1│Overview ",
+    " 2│Models ",
+    " 3│Tokens ",
+    " 4│Tools ",
+    " 5│Trends ",
+    " 6│Files ",
+    " 7│Timeline ",
+    " 8│Lifetime ",
+];
+
+/// Number of tabs in the Stats view (for Tab/number-key navigation)
+pub const TAB_COUNT: usize = TAB_TITLES.len();
+
+/// Index of the Timeline tab (it takes its own keys)
+pub const TIMELINE_TAB: usize = 6;
+
+/// Index of the Lifetime tab (loads lifestats in the background while shown)
+pub const LIFETIME_TAB: usize = 7;
+
 /// Main render function for the Stats view
-pub fn render(f: &mut Frame, area: Rect, app: &App) {
+pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
     // Split into tab bar (3 lines) and content area
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -35,6 +60,12 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         .split(area);
 
     // === Tab Bar ===
+    for (tab, tab_area) in mouse::tab_areas(chunks[0], &TAB_TITLES)
+        .into_iter()
+        .enumerate()
+    {
+        app.mouse.hits.add(tab_area, Target::StatsTab(tab));
+    }
     render_tab_bar(f, chunks[0], app);
 
     // === Tab Content ===
@@ -43,15 +74,7 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
 
 /// Render the tab navigation bar
 fn render_tab_bar(f: &mut Frame, area: Rect, app: &App) {
-    let tab_titles = vec![
-        " 1│Overview ",
-        " 2│Models ",
-        " 3│Tokens ",
-        " 4│Tools ",
-        " 5│Trends ",
-    ];
-
-    let tabs = Tabs::new(tab_titles)
+    let tabs = Tabs::new(TAB_TITLES)
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -69,13 +92,20 @@ fn render_tab_bar(f: &mut Frame, area: Rect, app: &App) {
 }
 
 /// Render the content of the selected tab
-fn render_tab_content(f: &mut Frame, area: Rect, app: &App) {
+fn render_tab_content(f: &mut Frame, area: Rect, app: &mut App) {
     match app.stats_selected_tab {
         0 => render_overview_tab(f, area, app),
         1 => ModelsTabPanel::render(f, area, &app.stats, &app.theme),
         2 => TokensTabPanel::render(f, area, &app.stats, &app.theme),
-        3 => ToolsTabPanel::render(f, area, &app.stats, &app.theme),
+        3 => ToolsTabPanel::render(f, area, &app.stats, &app.tool_insights, &app.theme),
         4 => TrendsTabPanel::render(f, area, &app.stats, &app.theme),
+        5 => FilesTabPanel::render(f, area, &app.file_activity, &app.theme),
+        TIMELINE_TAB => {
+            let timeline = app.session_timeline();
+            app.timeline_panel
+                .render_timeline(f, area, &timeline, &app.theme);
+        }
+        LIFETIME_TAB => app.lifetime_panel.render_lifetime(f, area, &app.theme),
         _ => {
             // Fallback for invalid tab index
             let msg = Paragraph::new("Invalid tab selected")
@@ -0,0 +1,515 @@
+// Tool renderers - per-tool sections for the event detail modal
+//
+// format_event_detail shows every ToolCall / ToolResult as raw JSON. A
+// renderer adds a readable section above it: Edit as a diff, Bash as the
+// command and its exit status, TodoWrite as a checklist, and so on.
+//
+// Renderers are looked up by tool_name in RENDERERS: an exact name first, then
+// the longest matching prefix (`mcp__*` covers every MCP server's tools). To
+// support a new tool, add an entry; `none_output` leaves results as JSON only.
+
+use crate::diff::unified_diff;
+use crate::pipeline::todos::{parse_todo_write, TodoCounts, TodoStatus};
+use crate::storage::attachments::format_size;
+use crate::tui::highlight::Lang;
+use serde_json::Value;
+
+/// Lines of output shown before the rest is summarized
+const PREVIEW_LINES: usize = 40;
+
+/// Entries shown in Grep/Glob result lists
+const LIST_ENTRIES: usize = 50;
+
+/// Readable markdown for one tool's calls and results
+pub(crate) struct ToolRenderer {
+    /// Tool name, or a name prefix when it ends with `*`
+    pub pattern: &'static str,
+    /// Section for a ToolCall: (tool_name, input)
+    pub input: fn(&str, &Value) -> String,
+    /// Section for a ToolResult: (tool_name, output, success)
+    pub output: fn(&str, &Value, bool) -> String,
+}
+
+#[rustfmt::skip]
+const RENDERERS: &[ToolRenderer] = &[
+    ToolRenderer { pattern: "Edit",      input: edit_input,       output: none_output },
+    ToolRenderer { pattern: "MultiEdit", input: multi_edit_input, output: none_output },
+    ToolRenderer { pattern: "Write",     input: write_input,      output: none_output },
+    ToolRenderer { pattern: "Bash",      input: bash_input,       output: bash_output },
+    ToolRenderer { pattern: "Read",      input: read_input,       output: read_output },
+    ToolRenderer { pattern: "Grep",      input: grep_input,       output: list_output },
+    ToolRenderer { pattern: "Glob",      input: glob_input,       output: list_output },
+    ToolRenderer { pattern: "WebFetch",  input: web_fetch_input,  output: web_fetch_output },
+    ToolRenderer { pattern: "TodoWrite", input: todo_input,       output: none_output },
+    ToolRenderer { pattern: "Task",      input: task_input,       output: task_output },
+    ToolRenderer { pattern: "mcp__*",    input: mcp_input,        output: text_output },
+];
+
+/// Renderer for a tool: exact name first, then the longest matching prefix
+pub(crate) fn renderer_for(tool_name: &str) -> Option<&'static ToolRenderer> {
+    RENDERERS
+        .iter()
+        .find(|r| r.pattern == tool_name)
+        .or_else(|| {
+            RENDERERS
+                .iter()
+                .filter_map(|r| Some((r, r.pattern.strip_suffix('*')?)))
+                .filter(|(_, prefix)| tool_name.starts_with(prefix))
+                .max_by_key(|(_, prefix)| prefix.len())
+                .map(|(r, _)| r)
+        })
+}
+
+/// Readable section for a tool call's input (empty when no renderer applies)
+pub(crate) fn format_tool_input(tool_name: &str, input: &Value) -> String {
+    renderer_for(tool_name)
+        .map(|r| (r.input)(tool_name, input))
+        .unwrap_or_default()
+}
+
+/// Readable section for a tool result's output (empty when no renderer applies)
+pub(crate) fn format_tool_output(tool_name: &str, output: &Value, success: bool) -> String {
+    renderer_for(tool_name)
+        .map(|r| (r.output)(tool_name, output, success))
+        .unwrap_or_default()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Markdown helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Fenced code block, with a fence longer than any backtick run in the code
+fn fenced(lang: &str, code: &str) -> String {
+    let fence = "`".repeat((longest_backtick_run(code) + 1).max(3));
+    format!(
+        "{}{}\n{}\n{}\n\n",
+        fence,
+        lang,
+        code.trim_end_matches('\n'),
+        fence
+    )
+}
+
+/// Inline code span that survives backticks in its content
+fn code_span(text: &str) -> String {
+    let ticks = "`".repeat(longest_backtick_run(text) + 1);
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{ticks}{pad}{text}{pad}{ticks}")
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// First `max` lines of `text` and how many were left out
+fn head(text: &str, max: usize) -> (String, usize) {
+    let total = text.lines().count();
+    let shown: Vec<&str> = text.lines().take(max).collect();
+    (shown.join("\n"), total.saturating_sub(max))
+}
+
+/// Fenced preview of `text`, noting the lines that didn't fit
+fn preview(lang: &str, text: &str) -> String {
+    let (shown, omitted) = head(text, PREVIEW_LINES);
+    let mut out = fenced(lang, &shown);
+    if omitted > 0 {
+        out.push_str(&format!("*… {} more lines*\n\n", omitted));
+    }
+    out
+}
+
+/// Plain text of a tool result: a string, or the text blocks of a content array
+fn output_text(output: &Value) -> String {
+    match output {
+        Value::String(s) => s.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .map(|block| match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => block
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                Some(other) => format!("[{}]", other),
+                None => block.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+/// Bullet list of an input's scalar parameters, except `skip`
+fn params(input: &Value, skip: &[&str]) -> String {
+    let Some(map) = input.as_object() else {
+        return String::new();
+    };
+    let items: Vec<String> = map
+        .iter()
+        .filter(|(key, _)| !skip.contains(&key.as_str()))
+        .filter_map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(_) | Value::Bool(_) => value.to_string(),
+                _ => return None,
+            };
+            Some(format!("- **{}:** {}", key, code_span(&value)))
+        })
+        .collect();
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n\n", items.join("\n"))
+    }
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Renderers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn none_output(_: &str, _: &Value, _: bool) -> String {
+    String::new()
+}
+
+fn text_output(_: &str, output: &Value, _: bool) -> String {
+    let text = output_text(output);
+    if text.trim().is_empty() {
+        return String::new();
+    }
+    format!("### Output\n\n{}", preview("", &text))
+}
+
+fn edit_diff(edit: &Value) -> String {
+    fenced(
+        "diff",
+        &unified_diff(
+            str_field(edit, "old_string"),
+            str_field(edit, "new_string"),
+            3,
+        ),
+    )
+}
+
+fn edit_input(_: &str, input: &Value) -> String {
+    let all = if input.get("replace_all").and_then(|v| v.as_bool()) == Some(true) {
+        " (all occurrences)"
+    } else {
+        ""
+    };
+    format!(
+        "### ± {}{}\n\n{}",
+        code_span(str_field(input, "file_path")),
+        all,
+        edit_diff(input)
+    )
+}
+
+fn multi_edit_input(_: &str, input: &Value) -> String {
+    let edits = input
+        .get("edits")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let diffs: String = edits.iter().map(edit_diff).collect();
+    format!(
+        "### ± {} ({} edits)\n\n{}",
+        code_span(str_field(input, "file_path")),
+        edits.len(),
+        diffs
+    )
+}
+
+fn write_input(_: &str, input: &Value) -> String {
+    let path = str_field(input, "file_path");
+    let content = str_field(input, "content");
+    let lang = Lang::from_path(path).map(Lang::tag).unwrap_or("");
+    format!(
+        "### ✎ {} ({} lines)\n\n{}",
+        code_span(path),
+        content.lines().count(),
+        preview(lang, content)
+    )
+}
+
+fn bash_input(_: &str, input: &Value) -> String {
+    let description = match str_field(input, "description") {
+        "" => String::new(),
+        d => format!("*{}*\n\n", d),
+    };
+    format!(
+        "### $ Command\n\n{}{}{}",
+        description,
+        fenced("sh", str_field(input, "command")),
+        params(input, &["command", "description"])
+    )
+}
+
+/// Bash output: `{stdout, stderr}` as Claude Code records it, or the text the
+/// API sees, where failures start with "Exit code N"
+fn bash_output(_: &str, output: &Value, success: bool) -> String {
+    let (stdout, stderr) = match output {
+        Value::Object(_) if output.get("stdout").is_some() => (
+            str_field(output, "stdout").to_string(),
+            str_field(output, "stderr").to_string(),
+        ),
+        _ => (output_text(output), String::new()),
+    };
+    let (status, stdout) = match stdout.strip_prefix("Exit code ") {
+        Some(rest) => {
+            let (code, rest) = rest.split_once('\n').unwrap_or((rest, ""));
+            (format!("exit {}", code.trim()), rest.to_string())
+        }
+        None if success => ("exit 0".to_string(), stdout),
+        None => ("failed".to_string(), stdout),
+    };
+
+    let icon = if success { "✓" } else { "✗" };
+    let mut out = format!("### {} Bash: {}\n\n", icon, status);
+    for (label, text) in [("stdout", &stdout), ("stderr", &stderr)] {
+        if !text.trim().is_empty() {
+            out.push_str(&format!(
+                "**{}** ({} lines)\n\n{}",
+                label,
+                text.lines().count(),
+                preview("", text)
+            ));
+        }
+    }
+    out
+}
+
+fn read_input(_: &str, input: &Value) -> String {
+    let offset = input.get("offset").and_then(|v| v.as_u64());
+    let limit = input.get("limit").and_then(|v| v.as_u64());
+    let range = match (offset, limit) {
+        (None, None) => "whole file".to_string(),
+        (offset, Some(limit)) => {
+            let start = offset.unwrap_or(1).max(1);
+            format!("lines {}–{}", start, start + limit.saturating_sub(1))
+        }
+        (Some(offset), None) => format!("from line {}", offset),
+    };
+    format!(
+        "### 📄 {}\n\n**Range:** {}\n\n",
+        code_span(str_field(input, "file_path")),
+        range
+    )
+}
+
+/// Read output is `cat -n` style (`    12→code`): report the line range and
+/// preview the code without the numbers
+fn read_output(_: &str, output: &Value, _: bool) -> String {
+    let text = output_text(output);
+    let numbered: Vec<(u64, &str)> = text
+        .lines()
+        .filter_map(|line| {
+            let (number, code) = line.split_once('→')?;
+            Some((number.trim().parse().ok()?, code))
+        })
+        .collect();
+
+    match (numbered.first(), numbered.last()) {
+        (Some((first, _)), Some((last, _))) => {
+            let code: Vec<&str> = numbered.iter().map(|(_, code)| *code).collect();
+            format!(
+                "### 📄 Lines {}–{} ({} lines)\n\n{}",
+                first,
+                last,
+                numbered.len(),
+                preview("", &code.join("\n"))
+            )
+        }
+        _ => text_output("Read", output, true),
+    }
+}
+
+fn grep_input(_: &str, input: &Value) -> String {
+    format!(
+        "### 🔍 {}\n\n{}",
+        code_span(str_field(input, "pattern")),
+        params(input, &["pattern"])
+    )
+}
+
+fn glob_input(_: &str, input: &Value) -> String {
+    format!(
+        "### 🗂 {}\n\n{}",
+        code_span(str_field(input, "pattern")),
+        params(input, &["pattern"])
+    )
+}
+
+/// Grep/Glob output: one match per line, after an optional "Found N files"
+fn list_output(_: &str, output: &Value, _: bool) -> String {
+    let text = output_text(output);
+    let mut lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let summary = match lines.first() {
+        Some(first) if first.starts_with("Found ") || first.starts_with("No ") => {
+            lines.remove(0).to_string()
+        }
+        _ => format!("{} results", lines.len()),
+    };
+
+    let mut out = format!("### Results: {}\n\n", summary);
+    if lines.is_empty() {
+        return out;
+    }
+    let items: Vec<String> = lines
+        .iter()
+        .take(LIST_ENTRIES)
+        .map(|line| format!("- {}", code_span(line)))
+        .collect();
+    out.push_str(&items.join("\n"));
+    out.push_str("\n\n");
+    if lines.len() > LIST_ENTRIES {
+        out.push_str(&format!("*… {} more*\n\n", lines.len() - LIST_ENTRIES));
+    }
+    out
+}
+
+fn web_fetch_input(_: &str, input: &Value) -> String {
+    let url = str_field(input, "url");
+    let prompt = match str_field(input, "prompt") {
+        "" => String::new(),
+        p => format!("> {}\n\n", p.lines().collect::<Vec<_>>().join("\n> ")),
+    };
+    format!("### 🌐 {}\n\n{}", code_span(url), prompt)
+}
+
+fn web_fetch_output(_: &str, output: &Value, _: bool) -> String {
+    let text = output_text(output);
+    format!(
+        "### 🌐 Fetched {} ({} lines)\n\n{}",
+        format_size(text.len() as u64),
+        text.lines().count(),
+        preview("", &text)
+    )
+}
+
+fn todo_input(_: &str, input: &Value) -> String {
+    let Some(items) = parse_todo_write(input) else {
+        return String::new();
+    };
+    let counts = TodoCounts::of(&items);
+    let lines: Vec<String> = items
+        .iter()
+        .map(|item| match (item.status, &item.active_form) {
+            (TodoStatus::Completed, _) => format!("- ✓ ~~{}~~", item.content),
+            (TodoStatus::InProgress, Some(active)) => format!("- ▶ **{}**", active),
+            (TodoStatus::InProgress, None) => format!("- ▶ **{}**", item.content),
+            (TodoStatus::Pending, _) => format!("- ○ {}", item.content),
+        })
+        .collect();
+    format!(
+        "### ☑ Plan ({}/{} done)\n\n{}\n\n",
+        counts.completed,
+        counts.total(),
+        lines.join("\n")
+    )
+}
+
+fn task_input(_: &str, input: &Value) -> String {
+    let agent = match str_field(input, "subagent_type") {
+        "" => "general-purpose",
+        agent => agent,
+    };
+    format!(
+        "### 🤖 Subagent {}\n\n**{}**\n\n{}",
+        code_span(agent),
+        str_field(input, "description"),
+        preview("markdown", str_field(input, "prompt"))
+    )
+}
+
+/// Subagent report: its size, and the start of it
+fn task_output(_: &str, output: &Value, success: bool) -> String {
+    let text = output_text(output);
+    let icon = if success { "✓" } else { "✗" };
+    format!(
+        "### {} Subagent report ({} lines, {} words)\n\n{}",
+        icon,
+        text.lines().count(),
+        text.split_whitespace().count(),
+        preview("markdown", &text)
+    )
+}
+
+/// MCP tools are named `mcp__<server>__<tool>`
+fn mcp_input(tool_name: &str, input: &Value) -> String {
+    let name = tool_name.trim_start_matches("mcp__");
+    let (server, tool) = name.split_once("__").unwrap_or(("?", name));
+    format!(
+        "### 🔌 {} · {}\n\n{}",
+        code_span(server),
+        code_span(tool),
+        params(input, &[])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_renderer_lookup() {
+        assert_eq!(renderer_for("Bash").unwrap().pattern, "Bash");
+        assert_eq!(
+            renderer_for("mcp__github__create_issue").unwrap().pattern,
+            "mcp__*"
+        );
+        // Exact names only: "Tasks" is not "Task"
+        assert!(renderer_for("Tasks").is_none());
+        assert!(format_tool_input("NotebookEdit", &json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_bash_output_exit_status() {
+        let failed = json!("Exit code 2\nls: cannot access 'x'");
+        let out = format_tool_output("Bash", &failed, false);
+        assert!(out.contains("✗ Bash: exit 2"));
+        assert!(out.contains("ls: cannot access 'x'"));
+        assert!(!out.contains("Exit code"));
+
+        let split = json!({"stdout": "ok\n", "stderr": "warning: unused\n"});
+        let out = format_tool_output("Bash", &split, true);
+        assert!(out.contains("✓ Bash: exit 0"));
+        assert!(out.contains("**stderr** (1 lines)"));
+    }
+
+    #[test]
+    fn test_read_output_line_range() {
+        let output = json!([{"type": "text", "text": "    10→fn main() {\n    11→}\n"}]);
+        let out = format_tool_output("Read", &output, true);
+        assert!(out.contains("Lines 10–11 (2 lines)"));
+        assert!(out.contains("fn main() {\n}"));
+        assert!(!out.contains('→'));
+    }
+
+    #[test]
+    fn test_list_and_todo_sections() {
+        let found = json!("Found 2 files\nsrc/a.rs\nsrc/`b`.rs");
+        let out = format_tool_output("Glob", &found, true);
+        assert!(out.contains("Results: Found 2 files"));
+        assert!(out.contains("- `src/a.rs`"));
+        assert!(out.contains("- ``src/`b`.rs``"));
+
+        let todos = json!({"todos": [
+            {"content": "Parse", "status": "completed"},
+            {"content": "Render", "status": "in_progress", "activeForm": "Rendering"},
+            {"content": "Ship", "status": "pending"}
+        ]});
+        let out = format_tool_input("TodoWrite", &todos);
+        assert!(out.contains("(1/3 done)"));
+        assert!(out.contains("- ✓ ~~Parse~~\n- ▶ **Rendering**\n- ○ Ship"));
+    }
+}